@@ -0,0 +1,8 @@
+//! hook messages dao-cw721 forwards to dao-gov and the types they carry,
+//! shared so both contracts (and their tooling) encode hooks the same way
+
+mod msg;
+mod types;
+
+pub use crate::msg::Cw721HookMsg;
+pub use crate::types::{OrderBy, PollLink, PollStatus, PollType, VoteOption, VotingExtension};
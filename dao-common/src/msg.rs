@@ -0,0 +1,142 @@
+use cosmwasm_std::{Binary, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{PollLink, PollType, VoteOption};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw721HookMsg {
+    Exit {},
+    /// burn part of balance (free of vested balance and locks), member stays
+    Withdraw {
+        amount: Uint128,
+    },
+    /// register secp256k1 public key (33 or 65 bytes) signing relayed ballots
+    RegisterVoteKey {
+        pubkey: Binary,
+    },
+    /// bind membership to the current NFT holder (required after transfer if bind_wallet)
+    /// votes in polls still in voting period are withdrawn (kept within vote_freeze_window)
+    Rebind {},
+    DelegateVote {
+        delegator: String,
+    },
+    UnDelegateVote {},
+    /*
+    {"add_alias": {
+        "namespace": "osmosis",
+        "address": "osmo1..."
+        }
+    }
+    */
+    AddAlias {
+        namespace: String,
+        address: String,
+    },
+    RemoveAlias {
+        namespace: String,
+    },
+    CreatePoll {
+        title: String,
+        /// full text, loaded only by single poll queries
+        description: String,
+        /// short text (at most 280 characters) shown in poll lists
+        /// defaults to the start of description
+        #[serde(default)]
+        summary: Option<String>,
+        link: Option<String>, // legacy, stored as link labeled "link"
+        links: Option<Vec<PollLink>>,
+        /// draft polls can't be voted on until published by creator
+        #[serde(default)]
+        draft: bool,
+        /// multiple choice poll if given (binary yes/no poll otherwise)
+        options: Option<Vec<String>>,
+        #[serde(default)]
+        poll_type: PollType,
+        /// voting opens once this poll passed (poll is rejected if it doesn't)
+        #[serde(default)]
+        depends_on: Option<u64>,
+    },
+    PublishPoll {
+        poll_id: u64,
+    },
+    /// creator can cancel own in-progress poll before anyone voted
+    WithdrawPoll {
+        poll_id: u64,
+    },
+    /// creator can extend own poll until it reaches quorum
+    ExtendPoll {
+        poll_id: u64,
+        additional_blocks: u64,
+    },
+    /*
+    {"cast_vote": {
+        "poll_id": 2,
+        "vote": "yes",
+        "amount": "100000"
+        }
+    }
+    */
+    CastVote {
+        poll_id: u64,
+        vote: VoteOption,
+        /// balance burned to amplify own vote by 2 * isqrt(conviction)
+        /// burned balance is not refunded even if the vote is cancelled
+        #[serde(default)]
+        conviction: Option<Uint128>,
+    },
+    CastChoiceVote {
+        poll_id: u64,
+        option_index: u32,
+    },
+    /// vote on several polls at once (fails as a whole if any vote fails)
+    CastVotes {
+        votes: Vec<(u64, VoteOption)>,
+    },
+    /// votes cast on behalf of delegators are cancelled as well
+    CancelVote {
+        poll_id: u64,
+    },
+    /// delegated member votes personally on a single poll (stays delegated otherwise)
+    /// replaces the vote cast on my behalf by delegatee
+    VoteOverride {
+        poll_id: u64,
+        vote: VoteOption,
+    },
+    /*
+    {"end_poll": {
+        "poll_id": 2
+        }
+    }
+    */
+    EndPoll {
+        poll_id: u64,
+    },
+}
+
+impl Cw721HookMsg {
+    /// variant name as it is serialized (hook and hook_kind attributes of receive_cw721)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cw721HookMsg::Exit { .. } => "exit",
+            Cw721HookMsg::Withdraw { .. } => "withdraw",
+            Cw721HookMsg::RegisterVoteKey { .. } => "register_vote_key",
+            Cw721HookMsg::Rebind { .. } => "rebind",
+            Cw721HookMsg::DelegateVote { .. } => "delegate_vote",
+            Cw721HookMsg::UnDelegateVote { .. } => "un_delegate_vote",
+            Cw721HookMsg::AddAlias { .. } => "add_alias",
+            Cw721HookMsg::RemoveAlias { .. } => "remove_alias",
+            Cw721HookMsg::CreatePoll { .. } => "create_poll",
+            Cw721HookMsg::PublishPoll { .. } => "publish_poll",
+            Cw721HookMsg::WithdrawPoll { .. } => "withdraw_poll",
+            Cw721HookMsg::ExtendPoll { .. } => "extend_poll",
+            Cw721HookMsg::CastVote { .. } => "cast_vote",
+            Cw721HookMsg::CastChoiceVote { .. } => "cast_choice_vote",
+            Cw721HookMsg::CastVotes { .. } => "cast_votes",
+            Cw721HookMsg::CancelVote { .. } => "cancel_vote",
+            Cw721HookMsg::VoteOverride { .. } => "vote_override",
+            Cw721HookMsg::EndPoll { .. } => "end_poll",
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use cosmwasm_std::{Decimal, Order, StdError};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderBy {
+    Asc,
+    Desc,
+}
+
+impl From<OrderBy> for Order {
+    fn from(o: OrderBy) -> Order {
+        if o == OrderBy::Asc {
+            Order::Ascending
+        } else {
+            Order::Descending
+        }
+    }
+}
+
+/// signal polls are non-binding temperature checks (may use lower quorum/threshold)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollType {
+    Signal,
+    Binding,
+}
+
+impl Default for PollType {
+    fn default() -> Self {
+        PollType::Binding
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollLink {
+    pub label: String,
+    pub url: String,
+}
+
+impl PollLink {
+    /// legacy single link is converted to a link labeled "link"
+    pub fn from_legacy(url: String) -> Self {
+        PollLink {
+            label: "link".to_string(),
+            url,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollStatus {
+    Draft,   // visible only to creator until published
+    Pending, // waiting for depends_on poll to pass
+    InProgress,
+    Passed,
+    Rejected,
+    Executed,
+    Failed,
+    Cancelled, // withdrawn by creator before any vote
+}
+
+impl PollStatus {
+    /// every status (each has its own poll indexer)
+    pub const ALL: [PollStatus; 8] = [
+        PollStatus::Draft,
+        PollStatus::Pending,
+        PollStatus::InProgress,
+        PollStatus::Passed,
+        PollStatus::Rejected,
+        PollStatus::Executed,
+        PollStatus::Failed,
+        PollStatus::Cancelled,
+    ];
+}
+
+impl fmt::Display for PollStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteOption {
+    Yes,
+    No,
+    Choice(u32), // option index of multiple choice poll
+}
+
+impl fmt::Display for VoteOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VoteOption::Yes => write!(f, "yes"),
+            VoteOption::No => write!(f, "no"),
+            VoteOption::Choice(index) => write!(f, "choice_{}", index),
+        }
+    }
+}
+
+/// parse vote option from its string form (inverse of to_string)
+impl FromStr for VoteOption {
+    type Err = StdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "yes" => Ok(VoteOption::Yes),
+            "no" => Ok(VoteOption::No),
+            _ => match s.strip_prefix("choice_").map(|index| index.parse::<u32>()) {
+                Some(Ok(index)) => Ok(VoteOption::Choice(index)),
+                _ => Err(StdError::parse_err(
+                    "VoteOption",
+                    format!("unknown vote option: {}", s),
+                )),
+            },
+        }
+    }
+}
+
+impl TryFrom<&str> for VoteOption {
+    type Error = StdError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// extension of membership NFTs read by the gov contract (other fields are ignored)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct VotingExtension {
+    /// weight of the holder's own votes (1 if missing)
+    #[serde(default)]
+    pub multiplier: Option<Decimal>,
+}
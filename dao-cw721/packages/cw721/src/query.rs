@@ -62,6 +62,8 @@ pub enum Cw721QueryMsg {
 pub struct OwnerOfResponse {
     /// Owner of the token
     pub owner: String,
+    /// If set this address is approved to transfer the token
+    pub approvals: Vec<Approval>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -3,11 +3,10 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use crate::{
-    AllNftInfoResponse, ApprovedForAllResponse, ContractInfoResponse, NftInfoResponse,
+    AllNftInfoResponse, ApprovedForAllResponse, ContractInfoResponse, Expiration, NftInfoResponse,
     NumTokensResponse, OwnerOfResponse, TokensResponse,
 };
 use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
-use cw0::Expiration;
 
 // TODO: move this somewhere else... ideally cosmwasm-std
 pub trait CustomMsg: Clone + std::fmt::Debug + PartialEq + JsonSchema {}
@@ -37,6 +36,42 @@ where
         token_id: String,
     ) -> Result<Response<C>, Self::Err>;
 
+    fn approve(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    ) -> Result<Response<C>, Self::Err>;
+
+    fn revoke(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        spender: String,
+        token_id: String,
+    ) -> Result<Response<C>, Self::Err>;
+
+    fn approve_all(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        operator: String,
+        expires: Option<Expiration>,
+    ) -> Result<Response<C>, Self::Err>;
+
+    fn revoke_all(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        operator: String,
+    ) -> Result<Response<C>, Self::Err>;
+
     fn execute_dao(
         &self,
         deps: DepsMut,
@@ -54,6 +89,15 @@ where
         owner: Option<String>,
         gov_contract: Option<String>,
     ) -> Result<Response<C>, Self::Err>;
+
+    fn update_contract_info(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        name: Option<String>,
+        symbol: Option<String>,
+    ) -> Result<Response<C>, Self::Err>;
 }
 
 pub trait Cw721Query<T>
@@ -0,0 +1,671 @@
+#![cfg(test)]
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    coin, from_slice, to_binary, BankMsg, Binary, Coin, CosmosMsg, Empty, OwnedDeps, Response,
+    Uint128,
+};
+use cw721::{Cw721Query, Cw721ReceiveMsg, Expiration};
+use dao_common::PollType;
+
+use crate::msg::{Cw721HookMsg, VoteOption};
+use crate::state::{TransferFee, BPS_DENOMINATOR};
+use crate::{
+    ContractError, Cw721Contract, DecodeHookResponse, ExecuteMsg, Extension, InstantiateMsg,
+    MintMsg, QueryMsg,
+};
+
+const OWNER: &str = "owner0000";
+const GOV: &str = "gov0000";
+const HOLDER: &str = "holder0000";
+const BUYER: &str = "buyer0000";
+const OPERATOR: &str = "operator0000";
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+fn setup_contract(deps: &mut MockDeps) -> Cw721Contract<'static, Extension, Empty> {
+    let contract = Cw721Contract::default();
+    let msg = InstantiateMsg {
+        name: "dao-cw721".to_string(),
+        symbol: "dcc".to_string(),
+        gov_contract: GOV.to_string(),
+        owner: OWNER.to_string(),
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg)
+        .unwrap();
+    contract
+}
+
+fn mint(deps: &mut MockDeps, contract: &Cw721Contract<Extension, Empty>, token_id: &str) {
+    let msg = ExecuteMsg::Mint(MintMsg {
+        token_id: token_id.to_string(),
+        owner: HOLDER.to_string(),
+        token_uri: None,
+        extension: None,
+    });
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg)
+        .unwrap();
+}
+
+fn exec(
+    deps: &mut MockDeps,
+    contract: &Cw721Contract<Extension, Empty>,
+    sender: &str,
+    funds: &[Coin],
+    msg: ExecuteMsg<Extension>,
+) -> Result<Response, ContractError> {
+    contract.execute(deps.as_mut(), mock_env(), mock_info(sender, funds), msg)
+}
+
+/// 2.5% fee in uluna on token 1, priced by the owner at price
+fn setup_priced(price: u128) -> (MockDeps, Cw721Contract<'static, Extension, Empty>) {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(&mut deps);
+    mint(&mut deps, &contract, "1");
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        gov_contract: None,
+        transfer_fee: Some(TransferFee {
+            bps: 250,
+            denom: "uluna".to_string(),
+        }),
+    };
+    exec(&mut deps, &contract, OWNER, &[], msg).unwrap();
+    let msg = ExecuteMsg::SetSalePrice {
+        token_id: "1".to_string(),
+        price: Uint128::new(price),
+    };
+    exec(&mut deps, &contract, OWNER, &[], msg).unwrap();
+    (deps, contract)
+}
+
+fn transfer_msg() -> ExecuteMsg<Extension> {
+    ExecuteMsg::TransferNft {
+        recipient: BUYER.to_string(),
+        token_id: "1".to_string(),
+    }
+}
+
+#[test]
+fn transfer_fee_rounds_up() {
+    let fee = TransferFee {
+        bps: 250,
+        denom: "uluna".to_string(),
+    };
+    assert_eq!(fee.amount(Uint128::new(1_000)), Uint128::new(25));
+    assert_eq!(fee.amount(Uint128::new(1_001)), Uint128::new(26));
+    assert_eq!(fee.amount(Uint128::new(1)), Uint128::new(1));
+    assert_eq!(fee.amount(Uint128::zero()), Uint128::zero());
+    assert_eq!(
+        fee.amount(Uint128::new(BPS_DENOMINATOR * 4)),
+        Uint128::new(1_000)
+    );
+}
+
+#[test]
+fn transfer_pays_fee_on_owner_set_price() {
+    let (mut deps, contract) = setup_priced(1_000);
+
+    let err = exec(&mut deps, &contract, HOLDER, &[], transfer_msg()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TransferFeeRequired {
+            required: coin(25, "uluna")
+        }
+    );
+    let err = exec(
+        &mut deps,
+        &contract,
+        HOLDER,
+        &[coin(24, "uluna")],
+        transfer_msg(),
+    );
+    assert!(err.is_err());
+
+    let res = exec(
+        &mut deps,
+        &contract,
+        HOLDER,
+        &[coin(25, "uluna")],
+        transfer_msg(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: GOV.to_string(),
+            amount: vec![coin(25, "uluna")],
+        })
+    );
+    let token = contract.tokens.load(&deps.storage, "1").unwrap();
+    assert_eq!(token.owner.as_str(), BUYER);
+}
+
+#[test]
+fn caller_cannot_pass_sale_price() {
+    let (mut deps, contract) = setup_priced(1_000);
+    // a sale_price field is not part of the message any more, so it can't lower the fee
+    let msg: ExecuteMsg<Extension> = from_slice(
+        br#"{"transfer_nft":{"recipient":"buyer0000","token_id":"1","sale_price":"0"}}"#,
+    )
+    .unwrap();
+    let err = exec(&mut deps, &contract, HOLDER, &[], msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TransferFeeRequired {
+            required: coin(25, "uluna")
+        }
+    );
+    let price = contract.sale_prices.load(&deps.storage, "1").unwrap();
+    assert_eq!(price, Uint128::new(1_000));
+}
+
+#[test]
+fn funds_in_other_denoms_are_rejected() {
+    let (mut deps, contract) = setup_priced(1_000);
+    let funds = [coin(25, "uluna"), coin(1, "uusd")];
+    let err = exec(&mut deps, &contract, HOLDER, &funds, transfer_msg()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::UnexpectedFunds {
+            denom: "uusd".to_string()
+        }
+    );
+}
+
+#[test]
+fn funds_without_fee_are_rejected() {
+    let (mut deps, contract) = setup_priced(0);
+    let err = exec(
+        &mut deps,
+        &contract,
+        HOLDER,
+        &[coin(1, "uluna")],
+        transfer_msg(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::UnexpectedFunds {
+            denom: "uluna".to_string()
+        }
+    );
+    let res = exec(&mut deps, &contract, HOLDER, &[], transfer_msg()).unwrap();
+    assert!(res.messages.is_empty());
+}
+
+#[test]
+fn only_owner_sets_sale_price() {
+    let (mut deps, contract) = setup_priced(1_000);
+    let msg = ExecuteMsg::SetSalePrice {
+        token_id: "1".to_string(),
+        price: Uint128::zero(),
+    };
+    let err = exec(&mut deps, &contract, HOLDER, &[], msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+/// raw json of the DecodeHook answer for hook
+fn decoded(hook: &Cw721HookMsg) -> String {
+    let deps = mock_dependencies(&[]);
+    let contract = Cw721Contract::<Extension, Empty>::default();
+    let msg = QueryMsg::DecodeHook {
+        msg: to_binary(hook).unwrap(),
+    };
+    let res = contract.query(deps.as_ref(), mock_env(), msg).unwrap();
+    String::from_utf8(res.to_vec()).unwrap()
+}
+
+#[test]
+fn decode_hook_summarizes_every_variant() {
+    let cases = vec![
+        (Cw721HookMsg::Exit {}, r#"{"kind":"exit"}"#),
+        (
+            Cw721HookMsg::Withdraw {
+                amount: Uint128::from(5u128),
+            },
+            r#"{"kind":"withdraw","amount":"5"}"#,
+        ),
+        (
+            Cw721HookMsg::RegisterVoteKey {
+                pubkey: Binary::from(vec![2u8; 33]),
+            },
+            r#"{"kind":"register_vote_key"}"#,
+        ),
+        (Cw721HookMsg::Rebind {}, r#"{"kind":"rebind"}"#),
+        (
+            Cw721HookMsg::DelegateVote {
+                delegator: "2".to_string(),
+            },
+            r#"{"kind":"delegate_vote","delegatee":"2"}"#,
+        ),
+        (
+            Cw721HookMsg::UnDelegateVote {},
+            r#"{"kind":"un_delegate_vote"}"#,
+        ),
+        (
+            Cw721HookMsg::AddAlias {
+                namespace: "osmosis".to_string(),
+                address: "osmo1".to_string(),
+            },
+            r#"{"kind":"add_alias","namespace":"osmosis","address":"osmo1"}"#,
+        ),
+        (
+            Cw721HookMsg::RemoveAlias {
+                namespace: "osmosis".to_string(),
+            },
+            r#"{"kind":"remove_alias","namespace":"osmosis"}"#,
+        ),
+        (
+            Cw721HookMsg::CreatePoll {
+                title: "budget".to_string(),
+                description: "long text".to_string(),
+                summary: None,
+                link: None,
+                links: None,
+                draft: true,
+                options: None,
+                poll_type: PollType::default(),
+                depends_on: None,
+            },
+            r#"{"kind":"create_poll","title":"budget","draft":true}"#,
+        ),
+        (
+            Cw721HookMsg::PublishPoll { poll_id: 7 },
+            r#"{"kind":"publish_poll","poll_id":7}"#,
+        ),
+        (
+            Cw721HookMsg::WithdrawPoll { poll_id: 7 },
+            r#"{"kind":"withdraw_poll","poll_id":7}"#,
+        ),
+        (
+            Cw721HookMsg::ExtendPoll {
+                poll_id: 7,
+                additional_blocks: 50,
+            },
+            r#"{"kind":"extend_poll","poll_id":7,"additional_blocks":50}"#,
+        ),
+        (
+            Cw721HookMsg::CastVote {
+                poll_id: 7,
+                vote: VoteOption::Yes,
+                conviction: None,
+            },
+            r#"{"kind":"cast_vote","poll_id":7,"vote":"yes"}"#,
+        ),
+        (
+            Cw721HookMsg::CastVote {
+                poll_id: 7,
+                vote: VoteOption::No,
+                conviction: Some(Uint128::from(9u128)),
+            },
+            r#"{"kind":"cast_vote","poll_id":7,"vote":"no","amount":"9"}"#,
+        ),
+        (
+            Cw721HookMsg::CastChoiceVote {
+                poll_id: 7,
+                option_index: 2,
+            },
+            r#"{"kind":"cast_choice_vote","poll_id":7,"vote":{"choice":2}}"#,
+        ),
+        (
+            Cw721HookMsg::CastVotes {
+                votes: vec![(1, VoteOption::Yes), (2, VoteOption::No)],
+            },
+            r#"{"kind":"cast_votes","votes":[[1,"yes"],[2,"no"]]}"#,
+        ),
+        (
+            Cw721HookMsg::CancelVote { poll_id: 7 },
+            r#"{"kind":"cancel_vote","poll_id":7}"#,
+        ),
+        (
+            Cw721HookMsg::VoteOverride {
+                poll_id: 7,
+                vote: VoteOption::No,
+            },
+            r#"{"kind":"vote_override","poll_id":7,"vote":"no"}"#,
+        ),
+        (
+            Cw721HookMsg::EndPoll { poll_id: 7 },
+            r#"{"kind":"end_poll","poll_id":7}"#,
+        ),
+    ];
+    for (hook, expected) in cases.iter() {
+        assert_eq!(decoded(hook), *expected);
+    }
+}
+
+#[test]
+fn decode_hook_reports_malformed_payload() {
+    let contract = Cw721Contract::<Extension, Empty>::default();
+    let deps = mock_dependencies(&[]);
+    for payload in [&br#"{"cast_vote":{"poll_id":"seven"}}"#[..], b"not json"].iter() {
+        let msg = QueryMsg::DecodeHook {
+            msg: Binary::from(*payload),
+        };
+        let res = contract.query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res: DecodeHookResponse = from_slice(&res).unwrap();
+        assert_eq!(res.kind, None);
+        assert_eq!(res.poll_id, None);
+        assert!(res.error.unwrap().contains("Error parsing"));
+    }
+}
+
+fn owner_of(deps: &MockDeps, contract: &Cw721Contract<Extension, Empty>, token_id: &str) -> String {
+    contract
+        .owner_of(deps.as_ref(), mock_env(), token_id.to_string(), false)
+        .unwrap()
+        .owner
+}
+
+#[test]
+fn operator_transfers_until_revoked_or_expired() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(&mut deps);
+    for token_id in ["1", "2", "3"].iter() {
+        mint(&mut deps, &contract, token_id);
+    }
+    let transfer = |token_id: &str| ExecuteMsg::TransferNft {
+        recipient: BUYER.to_string(),
+        token_id: token_id.to_string(),
+    };
+
+    let err = exec(&mut deps, &contract, OPERATOR, &[], transfer("1")).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let approve_all = ExecuteMsg::ApproveAll {
+        operator: OPERATOR.to_string(),
+        expires: Some(Expiration::AtHeight(mock_env().block.height + 10)),
+    };
+    exec(&mut deps, &contract, HOLDER, &[], approve_all).unwrap();
+    exec(&mut deps, &contract, OPERATOR, &[], transfer("1")).unwrap();
+    assert_eq!(owner_of(&deps, &contract, "1"), BUYER);
+
+    // approval of all holder's tokens ends at its expiration
+    let mut expired = mock_env();
+    expired.block.height += 10;
+    let info = mock_info(OPERATOR, &[]);
+    let err = contract
+        .execute(deps.as_mut(), expired, info, transfer("2"))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let revoke_all = ExecuteMsg::RevokeAll {
+        operator: OPERATOR.to_string(),
+    };
+    exec(&mut deps, &contract, HOLDER, &[], revoke_all).unwrap();
+    let err = exec(&mut deps, &contract, OPERATOR, &[], transfer("3")).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    assert_eq!(owner_of(&deps, &contract, "3"), HOLDER);
+}
+
+fn actor_of(res: &Response) -> &str {
+    &res.attributes
+        .iter()
+        .find(|a| a.key == "actor")
+        .unwrap()
+        .value
+}
+
+fn cast_vote(token_id: &str) -> ExecuteMsg<Extension> {
+    ExecuteMsg::execute_dao_cast_vote(token_id.to_string(), 1, VoteOption::Yes).unwrap()
+}
+
+#[test]
+fn owner_and_approved_accounts_govern() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(&mut deps);
+    mint(&mut deps, &contract, "1");
+
+    let res = exec(&mut deps, &contract, HOLDER, &[], cast_vote("1")).unwrap();
+    assert_eq!(actor_of(&res), "owner");
+    let err = exec(&mut deps, &contract, BUYER, &[], cast_vote("1")).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let approve = ExecuteMsg::Approve {
+        spender: BUYER.to_string(),
+        token_id: "1".to_string(),
+        expires: Some(Expiration::AtHeight(mock_env().block.height + 10)),
+    };
+    exec(&mut deps, &contract, HOLDER, &[], approve).unwrap();
+    let res = exec(&mut deps, &contract, BUYER, &[], cast_vote("1")).unwrap();
+    assert_eq!(actor_of(&res), "operator");
+    // votes stay bound to the holder
+    let hook = Cw721HookMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        conviction: None,
+    };
+    let send = Cw721ReceiveMsg {
+        sender: HOLDER.to_string(),
+        token_id: "1".to_string(),
+        msg: to_binary(&hook).unwrap(),
+    };
+    assert_eq!(res.messages[0].msg, send.into_cosmos_msg(GOV).unwrap());
+
+    let approve_all = ExecuteMsg::ApproveAll {
+        operator: OPERATOR.to_string(),
+        expires: None,
+    };
+    exec(&mut deps, &contract, HOLDER, &[], approve_all).unwrap();
+    let res = exec(&mut deps, &contract, OPERATOR, &[], cast_vote("1")).unwrap();
+    assert_eq!(actor_of(&res), "operator");
+}
+
+#[test]
+fn expired_approval_cannot_govern() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(&mut deps);
+    mint(&mut deps, &contract, "1");
+    let approve = ExecuteMsg::Approve {
+        spender: BUYER.to_string(),
+        token_id: "1".to_string(),
+        expires: Some(Expiration::AtHeight(mock_env().block.height + 10)),
+    };
+    exec(&mut deps, &contract, HOLDER, &[], approve).unwrap();
+
+    let mut expired = mock_env();
+    expired.block.height += 10;
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            expired,
+            mock_info(BUYER, &[]),
+            cast_vote("1"),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn owner_opts_out_of_governance_by_operator() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(&mut deps);
+    mint(&mut deps, &contract, "1");
+    let approve_all = ExecuteMsg::ApproveAll {
+        operator: OPERATOR.to_string(),
+        expires: None,
+    };
+    exec(&mut deps, &contract, HOLDER, &[], approve_all).unwrap();
+    let opt_out = |enabled| ExecuteMsg::SetGovernanceByOperator {
+        token_id: "1".to_string(),
+        enabled,
+    };
+
+    // only the token owner switches it
+    let err = exec(&mut deps, &contract, OPERATOR, &[], opt_out(false)).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    exec(&mut deps, &contract, HOLDER, &[], opt_out(false)).unwrap();
+
+    let err = exec(&mut deps, &contract, OPERATOR, &[], cast_vote("1")).unwrap_err();
+    assert_eq!(err, ContractError::GovernanceByOperatorDisabled {});
+    // an unrelated account is still unauthorized, the owner still governs
+    let err = exec(&mut deps, &contract, BUYER, &[], cast_vote("1")).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    exec(&mut deps, &contract, HOLDER, &[], cast_vote("1")).unwrap();
+
+    exec(&mut deps, &contract, HOLDER, &[], opt_out(true)).unwrap();
+    exec(&mut deps, &contract, OPERATOR, &[], cast_vote("1")).unwrap();
+}
+
+#[test]
+fn execute_dao_helpers_round_trip_through_shared_hooks() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(&mut deps);
+    mint(&mut deps, &contract, "1");
+    // wire format dao-gov decodes (its tests submit the same json)
+    let cases = vec![
+        (
+            ExecuteMsg::execute_dao_cast_vote("1".to_string(), 3, VoteOption::No).unwrap(),
+            r#"{"cast_vote":{"poll_id":3,"vote":"no","conviction":null}}"#,
+            Cw721HookMsg::CastVote {
+                poll_id: 3,
+                vote: VoteOption::No,
+                conviction: None,
+            },
+        ),
+        (
+            ExecuteMsg::execute_dao_cancel_vote("1".to_string(), 3).unwrap(),
+            r#"{"cancel_vote":{"poll_id":3}}"#,
+            Cw721HookMsg::CancelVote { poll_id: 3 },
+        ),
+        (
+            ExecuteMsg::execute_dao_end_poll("1".to_string(), 3).unwrap(),
+            r#"{"end_poll":{"poll_id":3}}"#,
+            Cw721HookMsg::EndPoll { poll_id: 3 },
+        ),
+    ];
+    for (msg, json, hook) in cases {
+        let res = exec(&mut deps, &contract, HOLDER, &[], msg).unwrap();
+        let send = Cw721ReceiveMsg {
+            sender: HOLDER.to_string(),
+            token_id: "1".to_string(),
+            msg: Binary::from(json.as_bytes()),
+        };
+        assert_eq!(res.messages[0].msg, send.into_cosmos_msg(GOV).unwrap());
+        assert_eq!(from_slice::<Cw721HookMsg>(json.as_bytes()).unwrap(), hook);
+    }
+}
+
+#[test]
+fn num_tokens_counts_mints() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(&mut deps);
+    let count = |deps: &MockDeps| {
+        let res = contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {})
+            .unwrap();
+        from_slice::<cw721::NumTokensResponse>(res.as_slice())
+            .unwrap()
+            .count
+    };
+    assert_eq!(count(&deps), 0);
+    for token_id in ["1", "2", "3"].iter() {
+        mint(&mut deps, &contract, token_id);
+    }
+    assert_eq!(count(&deps), 3);
+
+    // a rejected mint of an existing token leaves the count alone
+    let msg = ExecuteMsg::Mint(MintMsg {
+        token_id: "1".to_string(),
+        owner: HOLDER.to_string(),
+        token_uri: None,
+        extension: None,
+    });
+    exec(&mut deps, &contract, OWNER, &[], msg).unwrap_err();
+    assert_eq!(count(&deps), 3);
+}
+
+fn mint_to(
+    deps: &mut MockDeps,
+    contract: &Cw721Contract<Extension, Empty>,
+    token_id: &str,
+    owner: &str,
+) {
+    let msg = ExecuteMsg::Mint(MintMsg {
+        token_id: token_id.to_string(),
+        owner: owner.to_string(),
+        token_uri: None,
+        extension: None,
+    });
+    exec(deps, contract, OWNER, &[], msg).unwrap();
+}
+
+#[test]
+fn tokens_of_owner_are_paginated() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(&mut deps);
+    for token_id in ["1", "2", "4", "5", "6"].iter() {
+        mint_to(&mut deps, &contract, token_id, HOLDER);
+    }
+    mint_to(&mut deps, &contract, "3", BUYER);
+
+    let page = |deps: &MockDeps, start_after: Option<&str>| {
+        contract
+            .tokens(
+                deps.as_ref(),
+                HOLDER.to_string(),
+                start_after.map(String::from),
+                Some(2),
+            )
+            .unwrap()
+            .tokens
+    };
+    assert_eq!(page(&deps, None), vec!["1", "2"]);
+    assert_eq!(page(&deps, Some("2")), vec!["4", "5"]);
+    assert_eq!(page(&deps, Some("5")), vec!["6"]);
+    assert!(page(&deps, Some("6")).is_empty());
+
+    let buyer = contract
+        .tokens(deps.as_ref(), BUYER.to_string(), None, None)
+        .unwrap();
+    assert_eq!(buyer.tokens, vec!["3"]);
+
+    // all_tokens pages over every owner
+    let all = contract.all_tokens(deps.as_ref(), None, Some(4)).unwrap();
+    assert_eq!(all.tokens, vec!["1", "2", "3", "4"]);
+    let rest = contract
+        .all_tokens(deps.as_ref(), Some("4".to_string()), Some(4))
+        .unwrap();
+    assert_eq!(rest.tokens, vec!["5", "6"]);
+
+    // the index follows transfers
+    let msg = ExecuteMsg::TransferNft {
+        recipient: BUYER.to_string(),
+        token_id: "1".to_string(),
+    };
+    exec(&mut deps, &contract, HOLDER, &[], msg).unwrap();
+    assert_eq!(page(&deps, None), vec!["2", "4"]);
+}
+
+#[test]
+fn owner_updates_contract_info() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(&mut deps);
+    let contract_info = |deps: &MockDeps| {
+        let res = contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::ContractInfo {})
+            .unwrap();
+        from_slice::<cw721::ContractInfoResponse>(res.as_slice()).unwrap()
+    };
+    let info = contract_info(&deps);
+    assert_eq!(info.name, "dao-cw721");
+    assert_eq!(info.symbol, "dcc");
+
+    let msg = ExecuteMsg::UpdateContractInfo {
+        name: Some("renamed".to_string()),
+        symbol: None,
+    };
+    let err = exec(&mut deps, &contract, HOLDER, &[], msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    assert_eq!(contract_info(&deps).name, "dao-cw721");
+
+    // left out fields are kept
+    exec(&mut deps, &contract, OWNER, &[], msg).unwrap();
+    let info = contract_info(&deps);
+    assert_eq!(info.name, "renamed");
+    assert_eq!(info.symbol, "dcc");
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, StdError};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -17,4 +17,16 @@ pub enum ContractError {
 
     #[error("Cannot set approval that is already expired")]
     Expired {},
+
+    #[error("Transfer fee requires {required}")]
+    TransferFeeRequired { required: Coin },
+
+    #[error("Unexpected funds in {denom}")]
+    UnexpectedFunds { denom: String },
+
+    #[error("Transfer fee can't exceed {max_bps} bps")]
+    TransferFeeTooHigh { max_bps: u16 },
+
+    #[error("Token owner disabled governance by operators")]
+    GovernanceByOperatorDisabled {},
 }
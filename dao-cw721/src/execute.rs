@@ -2,7 +2,7 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use cosmwasm_std::{
-    to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, WasmMsg,
+    BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
 };
 
 use cw2::set_contract_version;
@@ -10,7 +10,7 @@ use cw721::{ContractInfoResponse, CustomMsg, Cw721Execute, Cw721ReceiveMsg, Expi
 
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg, MintMsg};
-use crate::state::{Approval, Cw721Contract, TokenInfo};
+use crate::state::{Approval, Cw721Contract, TokenInfo, TransferFee, MAX_TRANSFER_FEE_BPS};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cw721-base";
@@ -44,7 +44,7 @@ where
 
     pub fn execute(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
         msg: ExecuteMsg<T>,
@@ -54,14 +54,48 @@ where
             ExecuteMsg::TransferNft {
                 recipient,
                 token_id,
-            } => self.transfer_nft(deps, env, info, recipient, token_id),
+            } => {
+                let fee = self.collect_transfer_fee(deps.as_ref(), &info, &token_id)?;
+                let res = self.transfer_nft(deps, env, info, recipient, token_id)?;
+                Ok(match fee {
+                    Some(msg) => res.add_message(msg),
+                    None => res,
+                })
+            }
+            ExecuteMsg::Approve {
+                spender,
+                token_id,
+                expires,
+            } => self.approve(deps, env, info, spender, token_id, expires),
+            ExecuteMsg::Revoke { spender, token_id } => {
+                self.revoke(deps, env, info, spender, token_id)
+            }
+            ExecuteMsg::ApproveAll { operator, expires } => {
+                self.approve_all(deps, env, info, operator, expires)
+            }
+            ExecuteMsg::RevokeAll { operator } => self.revoke_all(deps, env, info, operator),
             ExecuteMsg::ExecuteDAO { token_id, msg } => {
                 self.execute_dao(deps, env, info, token_id, msg)
             }
+            ExecuteMsg::SetGovernanceByOperator { token_id, enabled } => {
+                self.set_governance_by_operator(deps, info, token_id, enabled)
+            }
             ExecuteMsg::UpdateConfig {
                 owner,
                 gov_contract,
-            } => self.update_config(deps, env, info, owner, gov_contract),
+                transfer_fee,
+            } => {
+                if let Some(transfer_fee) = transfer_fee {
+                    self.set_transfer_fee(deps.branch(), &info, transfer_fee)?;
+                }
+                self.update_config(deps, env, info, owner, gov_contract)
+            }
+            ExecuteMsg::SetSalePrice { token_id, price } => {
+                self.set_sale_price(deps, info, token_id, price)
+            }
+            ExecuteMsg::UpdateContractInfo { name, symbol } => {
+                self.update_contract_info(deps, env, info, name, symbol)
+            }
         }
     }
 }
@@ -89,7 +123,9 @@ where
         // create the token
         let token = TokenInfo {
             owner: deps.api.addr_validate(&msg.owner)?,
+            approvals: vec![],
             token_uri: msg.token_uri,
+            governance_by_operator: true,
             extension: msg.extension,
         };
         self.tokens
@@ -105,6 +141,51 @@ where
             .add_attribute("minter", info.sender)
             .add_attribute("token_id", msg.token_id))
     }
+
+    /// only owner can record sale price, zero price makes the token transfer free
+    pub fn set_sale_price(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token_id: String,
+        price: Uint128,
+    ) -> Result<Response<C>, ContractError> {
+        let owner = self.owner.load(deps.storage)?;
+
+        if info.sender != owner {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        self.tokens.load(deps.storage, &token_id)?;
+        self.sale_prices.save(deps.storage, &token_id, &price)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_sale_price")
+            .add_attribute("token_id", token_id)
+            .add_attribute("price", price))
+    }
+
+    /// only token owner can let approved spenders / operators govern with the token
+    pub fn set_governance_by_operator(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token_id: String,
+        enabled: bool,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        if token.owner != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        token.governance_by_operator = enabled;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_governance_by_operator")
+            .add_attribute("token_id", token_id)
+            .add_attribute("enabled", enabled.to_string()))
+    }
 }
 
 impl<'a, T, C> Cw721Execute<T, C> for Cw721Contract<'a, T, C>
@@ -114,7 +195,7 @@ where
 {
     type Err = ContractError;
 
-    // only owner of the contract can transfer
+    // owner of the contract, owner of the token or approved spender / operator can transfer
     fn transfer_nft(
         &self,
         deps: DepsMut,
@@ -123,15 +204,14 @@ where
         recipient: String,
         token_id: String,
     ) -> Result<Response<C>, ContractError> {
-        let owner_address = self.owner.load(deps.storage)?;
-
-        if info.sender != owner_address {
-            return Err(ContractError::Unauthorized {});
-        }
         let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+
         let old_owner = token.owner;
         // set owner and remove existing approvals
         token.owner = deps.api.addr_validate(&recipient)?;
+        token.approvals = vec![];
+        token.governance_by_operator = true;
         self.tokens.save(deps.storage, &token_id, &token)?;
         Ok(Response::new()
             .add_attribute("action", "transfer_nft")
@@ -140,6 +220,83 @@ where
             .add_attribute("token_id", token_id))
     }
 
+    fn approve(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    ) -> Result<Response<C>, ContractError> {
+        self._update_approvals(deps, &env, &info, &spender, &token_id, true, expires)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "approve")
+            .add_attribute("sender", info.sender)
+            .add_attribute("spender", spender)
+            .add_attribute("token_id", token_id))
+    }
+
+    fn revoke(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        spender: String,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        self._update_approvals(deps, &env, &info, &spender, &token_id, false, None)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke")
+            .add_attribute("sender", info.sender)
+            .add_attribute("spender", spender)
+            .add_attribute("token_id", token_id))
+    }
+
+    fn approve_all(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        operator: String,
+        expires: Option<Expiration>,
+    ) -> Result<Response<C>, ContractError> {
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
+
+        // set the operator for us
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        self.operators
+            .save(deps.storage, (&info.sender, &operator_addr), &expires)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "approve_all")
+            .add_attribute("sender", info.sender)
+            .add_attribute("operator", operator))
+    }
+
+    fn revoke_all(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        operator: String,
+    ) -> Result<Response<C>, ContractError> {
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        self.operators
+            .remove(deps.storage, (&info.sender, &operator_addr));
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke_all")
+            .add_attribute("sender", info.sender)
+            .add_attribute("operator", operator))
+    }
+
     fn execute_dao(
         &self,
         deps: DepsMut,
@@ -150,12 +307,10 @@ where
     ) -> Result<Response<C>, ContractError> {
         let token = self.tokens.load(deps.storage, &token_id)?;
         let gov_contract = self.gov_contract.load(deps.storage)?;
-        let sender = info.sender;
-        if token.owner != sender {
-            return Err(ContractError::Unauthorized {});
-        }
+        let actor = self.check_can_govern(deps.as_ref(), &env, &info, &token)?;
+        // gov_contract binds votes to the holder, so the owner stays the sender
         let send = Cw721ReceiveMsg {
-            sender: sender.to_string(),
+            sender: token.owner.to_string(),
             token_id: token_id.clone(),
             msg,
         };
@@ -163,14 +318,16 @@ where
         Ok(Response::new()
             .add_message(send.into_cosmos_msg(gov_contract.clone())?)
             .add_attribute("action", "execute_dao")
-            .add_attribute("sender", sender)
+            .add_attribute("sender", info.sender)
+            .add_attribute("owner", token.owner)
+            .add_attribute("actor", actor)
             .add_attribute("token_id", token_id))
     }
 
     fn update_config(
         &self,
         deps: DepsMut,
-        env: Env,
+        _env: Env,
         info: MessageInfo,
         owner: Option<String>,
         gov_contract: Option<String>,
@@ -193,4 +350,254 @@ where
 
         Ok(Response::new().add_attribute("action", "update_config"))
     }
+
+    fn update_contract_info(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        name: Option<String>,
+        symbol: Option<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let owner_address = self.owner.load(deps.storage)?;
+
+        if info.sender != owner_address {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let mut contract_info = self.contract_info.load(deps.storage)?;
+        if let Some(name) = name {
+            contract_info.name = name;
+        }
+        if let Some(symbol) = symbol {
+            contract_info.symbol = symbol;
+        }
+        self.contract_info.save(deps.storage, &contract_info)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_contract_info")
+            .add_attribute("name", contract_info.name)
+            .add_attribute("symbol", contract_info.symbol))
+    }
+}
+
+// helpers
+impl<'a, T, C> Cw721Contract<'a, T, C>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+{
+    /// only owner can set the transfer fee (capped at MAX_TRANSFER_FEE_BPS)
+    pub fn set_transfer_fee(
+        &self,
+        deps: DepsMut,
+        info: &MessageInfo,
+        transfer_fee: TransferFee,
+    ) -> Result<(), ContractError> {
+        let owner_address = self.owner.load(deps.storage)?;
+
+        if info.sender != owner_address {
+            return Err(ContractError::Unauthorized {});
+        }
+        if transfer_fee.bps > MAX_TRANSFER_FEE_BPS {
+            return Err(ContractError::TransferFeeTooHigh {
+                max_bps: MAX_TRANSFER_FEE_BPS,
+            });
+        }
+
+        if transfer_fee.bps == 0 {
+            self.transfer_fee.remove(deps.storage);
+        } else {
+            self.transfer_fee.save(deps.storage, &transfer_fee)?;
+        }
+        Ok(())
+    }
+
+    /// checks info.funds cover the fee on the sale price set by the owner,
+    /// funds go to gov_contract (funds in other denoms, or without a fee, are rejected)
+    pub fn collect_transfer_fee(
+        &self,
+        deps: Deps,
+        info: &MessageInfo,
+        token_id: &str,
+    ) -> Result<Option<BankMsg>, ContractError> {
+        let transfer_fee = self.transfer_fee.may_load(deps.storage)?;
+        let price = self
+            .sale_prices
+            .may_load(deps.storage, token_id)?
+            .unwrap_or_default();
+        let transfer_fee = match transfer_fee {
+            Some(transfer_fee) if !transfer_fee.amount(price).is_zero() => Some(transfer_fee),
+            _ => None,
+        };
+        // funds only pay the fee, anything else would stay in this contract
+        if let Some(coin) = info.funds.iter().find(|coin| {
+            transfer_fee
+                .as_ref()
+                .map_or(true, |transfer_fee| coin.denom != transfer_fee.denom)
+        }) {
+            return Err(ContractError::UnexpectedFunds {
+                denom: coin.denom.clone(),
+            });
+        }
+        let transfer_fee = match transfer_fee {
+            Some(transfer_fee) => transfer_fee,
+            None => return Ok(None),
+        };
+        let required = transfer_fee.amount(price);
+
+        let paid = info
+            .funds
+            .iter()
+            .filter(|coin| coin.denom == transfer_fee.denom)
+            .fold(Uint128::zero(), |acc, coin| acc + coin.amount);
+        if paid < required {
+            return Err(ContractError::TransferFeeRequired {
+                required: Coin {
+                    denom: transfer_fee.denom,
+                    amount: required,
+                },
+            });
+        }
+
+        let gov_contract = self.gov_contract.load(deps.storage)?;
+        Ok(Some(BankMsg::Send {
+            to_address: gov_contract.to_string(),
+            amount: vec![Coin {
+                denom: transfer_fee.denom,
+                amount: paid,
+            }],
+        }))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn _update_approvals(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        info: &MessageInfo,
+        spender: &str,
+        token_id: &str,
+        // if add == false, remove. if add == true, remove then set with this expiration
+        add: bool,
+        expires: Option<Expiration>,
+    ) -> Result<TokenInfo<T>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, token_id)?;
+        // ensure we have permissions
+        self.check_can_approve(deps.as_ref(), env, info, &token)?;
+
+        // update the approval list (remove any for the same spender before adding)
+        let spender_addr = deps.api.addr_validate(spender)?;
+        token.approvals.retain(|apr| apr.spender != spender_addr);
+
+        // only difference between approve and revoke
+        if add {
+            // reject expired data as invalid
+            let expires = expires.unwrap_or_default();
+            if expires.is_expired(&env.block) {
+                return Err(ContractError::Expired {});
+            }
+            let approval = Approval {
+                spender: spender_addr,
+                expires,
+            };
+            token.approvals.push(approval);
+        }
+
+        self.tokens.save(deps.storage, token_id, &token)?;
+
+        Ok(token)
+    }
+
+    /// returns true iff the sender can execute approve or reject on the contract
+    pub fn check_can_approve(
+        &self,
+        deps: Deps,
+        env: &Env,
+        info: &MessageInfo,
+        token: &TokenInfo<T>,
+    ) -> Result<(), ContractError> {
+        // owner can approve
+        if token.owner == info.sender {
+            return Ok(());
+        }
+        // operator can approve
+        let op = self
+            .operators
+            .may_load(deps.storage, (&token.owner, &info.sender))?;
+        match op {
+            Some(ex) if !ex.is_expired(&env.block) => Ok(()),
+            _ => Err(ContractError::Unauthorized {}),
+        }
+    }
+
+    /// returns "owner" or "operator" iff the sender can run ExecuteDAO for the token,
+    /// spenders / operators follow the transfer rules unless the owner opted out
+    pub fn check_can_govern(
+        &self,
+        deps: Deps,
+        env: &Env,
+        info: &MessageInfo,
+        token: &TokenInfo<T>,
+    ) -> Result<&'static str, ContractError> {
+        // owner can govern
+        if token.owner == info.sender {
+            return Ok("owner");
+        }
+
+        // any non-expired token approval can govern
+        let approved = token
+            .approvals
+            .iter()
+            .any(|apr| apr.spender == info.sender && !apr.is_expired(&env.block));
+        // operator can govern
+        let operator = !approved
+            && self
+                .operators
+                .may_load(deps.storage, (&token.owner, &info.sender))?
+                .map_or(false, |ex| !ex.is_expired(&env.block));
+        if !approved && !operator {
+            return Err(ContractError::Unauthorized {});
+        }
+        if !token.governance_by_operator {
+            return Err(ContractError::GovernanceByOperatorDisabled {});
+        }
+        Ok("operator")
+    }
+
+    /// returns true iff the sender can transfer ownership of the token
+    pub fn check_can_send(
+        &self,
+        deps: Deps,
+        env: &Env,
+        info: &MessageInfo,
+        token: &TokenInfo<T>,
+    ) -> Result<(), ContractError> {
+        // owner of the contract can always transfer
+        if self.owner.load(deps.storage)? == info.sender {
+            return Ok(());
+        }
+        // owner can send
+        if token.owner == info.sender {
+            return Ok(());
+        }
+
+        // any non-expired token approval can send
+        if token
+            .approvals
+            .iter()
+            .any(|apr| apr.spender == info.sender && !apr.is_expired(&env.block))
+        {
+            return Ok(());
+        }
+
+        // operator can send
+        let op = self
+            .operators
+            .may_load(deps.storage, (&token.owner, &info.sender))?;
+        match op {
+            Some(ex) if !ex.is_expired(&env.block) => Ok(()),
+            _ => Err(ContractError::Unauthorized {}),
+        }
+    }
 }
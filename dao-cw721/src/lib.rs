@@ -1,3 +1,4 @@
+mod contract_tests;
 mod error;
 mod execute;
 pub mod msg;
@@ -5,12 +6,15 @@ mod query;
 pub mod state;
 
 pub use crate::error::ContractError;
-pub use crate::msg::{ExecuteMsg, InstantiateMsg, MintMsg, MinterResponse, QueryMsg};
+pub use crate::msg::{
+    DecodeHookResponse, ExecuteMsg, InstantiateMsg, MintMsg, MinterResponse, QueryMsg,
+};
 pub use crate::state::Cw721Contract;
 use cosmwasm_std::Empty;
+use dao_common::VotingExtension;
 
-// This is a simple type to let us handle empty extensions
-pub type Extension = Option<Empty>;
+// membership NFTs carry the vote multiplier dao-gov reads from NftInfo
+pub type Extension = Option<VotingExtension>;
 
 #[cfg(not(feature = "library"))]
 pub mod entry {
@@ -1,8 +1,13 @@
-use cosmwasm_std::{Binary, Uint128};
+use cosmwasm_std::{to_binary, Binary, StdResult, Uint128};
 use cw721::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::state::TransferFee;
+
+// hook schema of the gov contract, shared so ExecuteDAO payloads match it
+pub use dao_common::{Cw721HookMsg, VoteOption};
+
 /*
 {
     "name": "dao-cw721",
@@ -32,10 +37,34 @@ pub struct InstantiateMsg {
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg<T> {
     /// Transfer is a base message to move a token to another account without triggering actions
+    /// Can be called by the contract owner, the token owner or an approved spender / operator
+    /// With transfer_fee set, info.funds must cover the fee on the sale price set by the owner
     TransferNft {
         recipient: String,
         token_id: String,
     },
+    /// Allows operator to transfer the token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted Approval
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    /// Allows operator to transfer any token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted ApproveAll permission
+    RevokeAll {
+        operator: String,
+    },
     /// Mint a new NFT, can only be called by the contract minter
     /*
     {
@@ -46,10 +75,19 @@ pub enum ExecuteMsg<T> {
     }
      */
     Mint(MintMsg<T>),
+    // Forward a hook to gov_contract on behalf of the token owner
+    // Can be called by the token owner or an approved spender / operator,
+    // unless the owner turned governance_by_operator off
     ExecuteDAO {
         token_id: String,
         msg: Binary,
     },
+    /// Allow or forbid approved spenders / operators to run ExecuteDAO for a token,
+    /// can only be called by the token owner
+    SetGovernanceByOperator {
+        token_id: String,
+        enabled: bool,
+    },
     /*
     {
         "update_config": {
@@ -60,7 +98,61 @@ pub enum ExecuteMsg<T> {
     UpdateConfig {
         owner: Option<String>,
         gov_contract: Option<String>,
+        /// at most MAX_TRANSFER_FEE_BPS, bps 0 disables the fee
+        #[serde(default)]
+        transfer_fee: Option<TransferFee>,
+    },
+    /// Record the last sale price of a token, can only be called by the owner
+    SetSalePrice {
+        token_id: String,
+        price: Uint128,
     },
+    /// Rename the collection, can only be called by the owner
+    /*
+    {
+        "update_contract_info": {
+            "name": "dao-cw721",
+            "symbol": "dcc"
+        }
+    }
+    */
+    UpdateContractInfo {
+        name: Option<String>,
+        symbol: Option<String>,
+    },
+}
+
+impl<T> ExecuteMsg<T> {
+    /// ExecuteDAO forwarding hook to the gov contract
+    pub fn execute_dao(token_id: String, hook: &Cw721HookMsg) -> StdResult<Self> {
+        Ok(ExecuteMsg::ExecuteDAO {
+            token_id,
+            msg: to_binary(hook)?,
+        })
+    }
+
+    pub fn execute_dao_cast_vote(
+        token_id: String,
+        poll_id: u64,
+        vote: VoteOption,
+    ) -> StdResult<Self> {
+        Self::execute_dao(
+            token_id,
+            &Cw721HookMsg::CastVote {
+                poll_id,
+                vote,
+                conviction: None,
+            },
+        )
+    }
+
+    pub fn execute_dao_cancel_vote(token_id: String, poll_id: u64) -> StdResult<Self> {
+        Self::execute_dao(token_id, &Cw721HookMsg::CancelVote { poll_id })
+    }
+
+    pub fn execute_dao_end_poll(token_id: String, poll_id: u64) -> StdResult<Self> {
+        Self::execute_dao(token_id, &Cw721HookMsg::EndPoll { poll_id })
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -135,6 +227,42 @@ pub enum QueryMsg {
 
     // Return the minter
     Minter {},
+
+    /// Decode msg of ExecuteDAO as the gov contract would (for previews before signing)
+    /// Return type: DecodeHookResponse
+    DecodeHook {
+        msg: Binary,
+    },
+}
+
+/// Flat summary of the hook in ExecuteDAO, e.g. {"kind":"cast_vote","poll_id":7,"vote":"yes"}
+/// only the fields of the decoded hook are set, error is set instead if msg is not a valid hook
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema, Debug)]
+pub struct DecodeHookResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vote: Option<VoteOption>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub votes: Option<Vec<(u64, VoteOption)>>, // CastVotes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Uint128>, // Withdraw amount or CastVote conviction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegatee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_blocks: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 /// Shows who can mint these tokens
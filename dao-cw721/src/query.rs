@@ -1,7 +1,9 @@
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use cosmwasm_std::{to_binary, Binary, BlockInfo, Deps, Env, Order, Pair, StdError, StdResult};
+use cosmwasm_std::{
+    from_binary, to_binary, Binary, BlockInfo, Deps, Env, Order, Pair, StdError, StdResult,
+};
 
 use cw0::maybe_addr;
 use cw721::{
@@ -10,7 +12,7 @@ use cw721::{
 };
 use cw_storage_plus::Bound;
 
-use crate::msg::{MinterResponse, QueryMsg};
+use crate::msg::{Cw721HookMsg, DecodeHookResponse, MinterResponse, QueryMsg, VoteOption};
 use crate::state::{Approval, Cw721Contract, TokenInfo};
 
 const DEFAULT_LIMIT: u32 = 10;
@@ -48,6 +50,7 @@ where
         let info = self.tokens.load(deps.storage, &token_id)?;
         Ok(OwnerOfResponse {
             owner: info.owner.to_string(),
+            approvals: humanize_approvals(&env.block, &info, include_expired),
         })
     }
 
@@ -110,8 +113,8 @@ where
         limit: Option<u32>,
     ) -> StdResult<TokensResponse> {
         let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-        let start_addr = maybe_addr(deps.api, start_after)?;
-        let start = start_addr.map(|addr| Bound::exclusive(addr.as_ref()));
+        // start_after is a token_id (not an address)
+        let start = start_after.map(Bound::exclusive);
 
         let tokens: StdResult<Vec<String>> = self
             .tokens
@@ -133,6 +136,7 @@ where
         Ok(AllNftInfoResponse {
             access: OwnerOfResponse {
                 owner: info.owner.to_string(),
+                approvals: humanize_approvals(&env.block, &info, include_expired),
             },
             info: NftInfoResponse {
                 token_uri: info.token_uri,
@@ -196,7 +200,95 @@ where
             QueryMsg::AllTokens { start_after, limit } => {
                 to_binary(&self.all_tokens(deps, start_after, limit)?)
             }
+            QueryMsg::DecodeHook { msg } => to_binary(&decode_hook(&msg)),
+        }
+    }
+}
+
+/// parse error is returned in the response rather than failing the query
+pub fn decode_hook(msg: &Binary) -> DecodeHookResponse {
+    let hook = match from_binary::<Cw721HookMsg>(msg) {
+        Ok(hook) => hook,
+        Err(err) => {
+            return DecodeHookResponse {
+                error: Some(err.to_string()),
+                ..DecodeHookResponse::default()
+            }
         }
+    };
+    let summary = DecodeHookResponse {
+        kind: Some(hook.name().to_string()),
+        ..DecodeHookResponse::default()
+    };
+    match hook {
+        Cw721HookMsg::Exit {}
+        | Cw721HookMsg::Rebind {}
+        | Cw721HookMsg::UnDelegateVote {}
+        | Cw721HookMsg::RegisterVoteKey { .. } => summary,
+        Cw721HookMsg::Withdraw { amount } => DecodeHookResponse {
+            amount: Some(amount),
+            ..summary
+        },
+        Cw721HookMsg::DelegateVote { delegator } => DecodeHookResponse {
+            delegatee: Some(delegator),
+            ..summary
+        },
+        Cw721HookMsg::AddAlias { namespace, address } => DecodeHookResponse {
+            namespace: Some(namespace),
+            address: Some(address),
+            ..summary
+        },
+        Cw721HookMsg::RemoveAlias { namespace } => DecodeHookResponse {
+            namespace: Some(namespace),
+            ..summary
+        },
+        Cw721HookMsg::CreatePoll { title, draft, .. } => DecodeHookResponse {
+            title: Some(title),
+            draft: Some(draft),
+            ..summary
+        },
+        Cw721HookMsg::PublishPoll { poll_id }
+        | Cw721HookMsg::WithdrawPoll { poll_id }
+        | Cw721HookMsg::CancelVote { poll_id }
+        | Cw721HookMsg::EndPoll { poll_id } => DecodeHookResponse {
+            poll_id: Some(poll_id),
+            ..summary
+        },
+        Cw721HookMsg::ExtendPoll {
+            poll_id,
+            additional_blocks,
+        } => DecodeHookResponse {
+            poll_id: Some(poll_id),
+            additional_blocks: Some(additional_blocks),
+            ..summary
+        },
+        Cw721HookMsg::CastVote {
+            poll_id,
+            vote,
+            conviction,
+        } => DecodeHookResponse {
+            poll_id: Some(poll_id),
+            vote: Some(vote),
+            amount: conviction,
+            ..summary
+        },
+        Cw721HookMsg::CastChoiceVote {
+            poll_id,
+            option_index,
+        } => DecodeHookResponse {
+            poll_id: Some(poll_id),
+            vote: Some(VoteOption::Choice(option_index)),
+            ..summary
+        },
+        Cw721HookMsg::CastVotes { votes } => DecodeHookResponse {
+            votes: Some(votes),
+            ..summary
+        },
+        Cw721HookMsg::VoteOverride { poll_id, vote } => DecodeHookResponse {
+            poll_id: Some(poll_id),
+            vote: Some(vote),
+            ..summary
+        },
     }
 }
 
@@ -206,3 +298,22 @@ fn parse_approval(item: StdResult<Pair<Expiration>>) -> StdResult<cw721::Approva
         Ok(cw721::Approval { spender, expires })
     })
 }
+
+fn humanize_approvals<T>(
+    block: &BlockInfo,
+    info: &TokenInfo<T>,
+    include_expired: bool,
+) -> Vec<cw721::Approval> {
+    info.approvals
+        .iter()
+        .filter(|apr| include_expired || !apr.is_expired(block))
+        .map(humanize_approval)
+        .collect()
+}
+
+fn humanize_approval(approval: &Approval) -> cw721::Approval {
+    cw721::Approval {
+        spender: approval.spender.to_string(),
+        expires: approval.expires,
+    }
+}
@@ -3,7 +3,7 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 
-use cosmwasm_std::{Addr, BlockInfo, StdResult, Storage};
+use cosmwasm_std::{Addr, BlockInfo, StdResult, Storage, Uint128};
 
 use cw721::{ContractInfoResponse, CustomMsg, Cw721, Expiration};
 use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
@@ -19,6 +19,10 @@ where
     /// Stored as (granter, operator) giving operator full control over granter's account
     pub operators: Map<'a, (&'a Addr, &'a Addr), Expiration>,
     pub tokens: IndexedMap<'a, &'a str, TokenInfo<T>, TokenIndexes<'a, T>>,
+    /// Fee on transfers routed to gov_contract, unset charges nothing
+    pub transfer_fee: Item<'a, TransferFee>,
+    /// Last sale price per token, the transfer fee is a share of it
+    pub sale_prices: Map<'a, &'a str, Uint128>,
 
     pub(crate) _custom_response: PhantomData<C>,
 }
@@ -44,6 +48,8 @@ where
             "operators",
             "tokens",
             "tokens__owner",
+            "transfer_fee",
+            "sale_prices",
         )
     }
 }
@@ -52,6 +58,7 @@ impl<'a, T, C> Cw721Contract<'a, T, C>
 where
     T: Serialize + DeserializeOwned + Clone,
 {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         contract_key: &'a str,
         minter_key: &'a str,
@@ -60,6 +67,8 @@ where
         operator_key: &'a str,
         tokens_key: &'a str,
         tokens_owner_key: &'a str,
+        transfer_fee_key: &'a str,
+        sale_prices_key: &'a str,
     ) -> Self {
         let indexes = TokenIndexes {
             owner: MultiIndex::new(token_owner_idx, tokens_key, tokens_owner_key),
@@ -71,6 +80,8 @@ where
             token_count: Item::new(token_count_key),
             operators: Map::new(operator_key),
             tokens: IndexedMap::new(tokens_key, indexes),
+            transfer_fee: Item::new(transfer_fee_key),
+            sale_prices: Map::new(sale_prices_key),
             _custom_response: PhantomData,
         }
     }
@@ -90,15 +101,52 @@ where
 pub struct TokenInfo<T> {
     /// The owner of the newly minted NFT
     pub owner: Addr,
+    /// Approvals are stored here, as we clear them all upon transfer and cannot accumulate much
+    #[serde(default)]
+    pub approvals: Vec<Approval>,
     /// Universal resource identifier for this NFT
     /// Should point to a JSON file that conforms to the ERC721
     /// Metadata JSON Schema
     pub token_uri: Option<String>,
+    /// Whether approved spenders / operators can run ExecuteDAO for this token,
+    /// reset on transfer like the approvals
+    #[serde(default = "default_governance_by_operator")]
+    pub governance_by_operator: bool,
 
     /// You can add any custom metadata here when you extend cw721-base
     pub extension: T,
 }
 
+fn default_governance_by_operator() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TransferFee {
+    /// Share of the last sale price in basis points (0 disables the fee)
+    pub bps: u16,
+    pub denom: String,
+}
+
+impl TransferFee {
+    /// Fee on a sale price, rounded up so a priced token never transfers for free
+    pub fn amount(&self, price: Uint128) -> Uint128 {
+        let bps = u128::from(self.bps);
+        let fee = price.multiply_ratio(bps, BPS_DENOMINATOR);
+        if (price.u128() % BPS_DENOMINATOR * bps) % BPS_DENOMINATOR == 0 {
+            fee
+        } else {
+            fee + Uint128::new(1)
+        }
+    }
+}
+
+/// Basis points in a whole
+pub const BPS_DENOMINATOR: u128 = 10_000;
+
+/// Highest transfer fee the owner can set (10%)
+pub const MAX_TRANSFER_FEE_BPS: u16 = 1_000;
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Approval {
     /// Account that can transfer/send the token
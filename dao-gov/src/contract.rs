@@ -1,19 +1,38 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
-    Uint128,
+    to_binary, Binary, CanonicalAddr, Decimal, Deps, DepsMut, Env, MessageInfo, Reply, Response,
+    StdResult, Uint128,
 };
 use cw2::set_contract_version;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{config_store, state_store, Config, State};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SignedBallot};
+use crate::state::{config_store, migrate_legacy_owner, state_store, Config, State};
+use crate::utils::{
+    validate_decay, validate_ibc_channel, validate_max_vote_share, validate_quorum,
+    validate_season_length, validate_text_limits, validate_threshold, validate_voting_period,
+};
 
-use crate::execute::{mint, receive_cw721, transfer_from, update_config};
+use crate::execute::{
+    approve_admin_action, break_delegation, cast_vote_signed, draw_committee, end_poll_by_address,
+    extend_poll, force_end_poll, mint, mint_locked, propose_admin_action, prune_drafts,
+    prune_member, rebuild_poll_index, receive_cw721, reclaim_deposit,
+    recompute_delegated_share_page, recompute_supply, relay_vote, release_vested,
+    resolve_dependency, roll_season, set_blocked, snapshot_poll_parameters_page, suspend_member,
+    sweep_inactive_delegations, transfer_from, unsuspend_member, update_config, validate_admins,
+    IBC_NOTIFY_REPLY_ID,
+};
 
 use crate::query::{
-    query_config, query_poll, query_polls, query_member, query_state, query_voters,
+    query_admin_action, query_admin_actions, query_committee, query_config,
+    query_delegation_status, query_deposit_info, query_eligible_polls, query_free_share,
+    query_ibc_config, query_index_consistency, query_is_blocked, query_member,
+    query_member_by_alias, query_members, query_members_batch, query_owner, query_poll,
+    query_poll_description, query_polls, query_seasons, query_share_to_min_balance, query_state,
+    query_storage_version, query_top_delegates, query_total_supply, query_validate_hook,
+    query_verify_invariants, query_vote_receipt, query_voter_count, query_voters,
+    query_withdrawable,
 };
 
 // version info for migration info
@@ -30,19 +49,103 @@ pub fn instantiate(
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     validate_quorum(msg.quorum)?;
     validate_threshold(msg.threshold)?;
+    validate_voting_period(msg.voting_period)?;
+    if let Some(ibc_channel) = &msg.ibc_channel {
+        validate_ibc_channel(ibc_channel)?;
+    }
+    if let Some(signal_quorum) = msg.signal_quorum {
+        validate_quorum(signal_quorum)?;
+    }
+    if let Some(signal_threshold) = msg.signal_threshold {
+        validate_threshold(signal_threshold)?;
+    }
+    if let Some(bootstrap_quorum) = msg.bootstrap_quorum {
+        validate_quorum(bootstrap_quorum)?;
+    } else if msg.bootstrap_period.map_or(false, |period| period > 0) {
+        return Err(ContractError::BootstrapQuorumRequired {});
+    }
+    let max_vote_share = msg.max_vote_share.filter(|share| !share.is_zero());
+    if let Some(max_vote_share) = max_vote_share {
+        validate_max_vote_share(max_vote_share)?;
+    }
+    if let Some(season_length) = msg.season_length {
+        validate_season_length(season_length)?;
+    }
+    let decay_rate = msg.decay_rate.unwrap_or_default();
+    let decay_floor = msg.decay_floor.unwrap_or_default();
+    validate_decay(decay_rate, decay_floor)?;
+    let text_limits = msg.text_limits.unwrap_or_default();
+    validate_text_limits(&text_limits)?;
+
+    // a lone admin (sender by default) with threshold 1 acts as single owner
+    let admins = match msg.admins {
+        Some(admins) => admins
+            .iter()
+            .map(|admin| deps.api.addr_canonicalize(admin))
+            .collect::<StdResult<Vec<CanonicalAddr>>>()?,
+        None => vec![deps.api.addr_canonicalize(info.sender.as_str())?],
+    };
+    let admin_threshold = msg.admin_threshold.unwrap_or(1);
+    validate_admins(&admins, admin_threshold)?;
 
     let config = Config {
-        owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+        admins,
+        admin_threshold,
         cw721_token: deps.api.addr_canonicalize(&msg.cw721_token)?,
         quorum: msg.quorum,
         threshold: msg.threshold,
         voting_period: msg.voting_period,
+        share_rounding: msg.share_rounding.unwrap_or_default(),
+        voting_model: msg.voting_model.unwrap_or_default(),
+        season_length: msg.season_length,
+        season_blackout: msg.season_blackout.unwrap_or_default(),
+        end_poll_reward: msg.end_poll_reward.unwrap_or_default(),
+        decay_after: msg.decay_after,
+        decay_rate,
+        decay_floor,
+        bind_wallet: msg.bind_wallet.unwrap_or_default(),
+        text_limits,
+        draft_ttl: msg.draft_ttl,
+        threshold_inclusive: msg.threshold_inclusive.unwrap_or_default(),
+        failed_poll_cooldown: msg.failed_poll_cooldown.unwrap_or_default(),
+        signal_quorum: msg.signal_quorum,
+        signal_threshold: msg.signal_threshold,
+        max_extension: msg.max_extension.unwrap_or_default(),
+        ibc_channel: msg.ibc_channel,
+        ibc_notify: msg.ibc_notify.unwrap_or_default(),
+        ibc_receiver: msg.ibc_receiver,
+        ibc_denom: msg.ibc_denom,
+        tie_policy: msg.tie_policy.unwrap_or_default(),
+        auto_finalize: msg.auto_finalize.unwrap_or(false),
+        max_supply: msg.max_supply,
+        text_deposit: msg.text_deposit.unwrap_or_default(),
+        execute_deposit: msg.execute_deposit.unwrap_or_default(),
+        end_poll_permission: msg.end_poll_permission.unwrap_or_default(),
+        adaptive_quorum: msg.adaptive_quorum.unwrap_or(false),
+        vote_freeze_window: msg.vote_freeze_window.unwrap_or(0),
+        freeze_new_votes: msg.freeze_new_votes.unwrap_or(false),
+        voting_time: msg.voting_time.unwrap_or(0),
+        use_token_multiplier: msg.use_token_multiplier.unwrap_or(false),
+        max_multiplier: msg.max_multiplier.unwrap_or_else(Decimal::one),
+        bootstrap_period: msg.bootstrap_period.unwrap_or(0),
+        bootstrap_quorum: msg.bootstrap_quorum.unwrap_or_default(),
+        delegate_inactivity_timeout: msg
+            .delegate_inactivity_timeout
+            .filter(|timeout| *timeout > 0),
+        max_vote_share,
     };
 
     let state = State {
         contract_addr: deps.api.addr_canonicalize(env.contract.address.as_str())?,
         poll_count: 0,
         total_share: Uint128::zero(),
+        total_supply: Uint128::zero(),
+        current_season: 0,
+        season_start_height: env.block.height,
+        admin_action_count: 0,
+        committee_count: 0,
+        recent_turnout: vec![],
+        instantiate_height: env.block.height,
     };
 
     config_store(deps.storage).save(&config)?;
@@ -60,70 +163,309 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::ReceiveNft(msg) => receive_cw721(deps, env, info, msg),
-        ExecuteMsg::Mint { recipient, amount } => mint(deps, info, recipient, amount),
+        ExecuteMsg::Mint { recipient, amount } => mint(deps, env, info, recipient, amount),
+        ExecuteMsg::MintLocked {
+            recipient,
+            amount,
+            until_height,
+        } => mint_locked(deps, env, info, recipient, amount, until_height),
+        ExecuteMsg::ReleaseVested { member_id } => release_vested(deps, env, member_id),
         ExecuteMsg::TransferFrom {
             owner,
             recipient,
             amount,
-        } => transfer_from(deps, info, owner, recipient, amount),
+        } => transfer_from(deps, env, info, owner, recipient, amount),
         ExecuteMsg::UpdateConfig {
-            owner,
+            admins,
+            admin_threshold,
+            quorum,
+            threshold,
+            voting_period,
+            season_length,
+            season_blackout,
+            end_poll_reward,
+            decay_after,
+            decay_rate,
+            decay_floor,
+            bind_wallet,
+            text_limits,
+            draft_ttl,
+            threshold_inclusive,
+            failed_poll_cooldown,
+            signal_quorum,
+            signal_threshold,
+            max_extension,
+            ibc_channel,
+            ibc_notify,
+            ibc_receiver,
+            ibc_denom,
+            tie_policy,
+            auto_finalize,
+            max_supply,
+            text_deposit,
+            execute_deposit,
+            adaptive_quorum,
+            vote_freeze_window,
+            freeze_new_votes,
+            voting_time,
+            use_token_multiplier,
+            max_multiplier,
+            bootstrap_period,
+            bootstrap_quorum,
+            delegate_inactivity_timeout,
+            max_vote_share,
+            dry_run,
+        } => update_config(
+            deps,
+            env,
+            info,
+            admins,
+            admin_threshold,
             quorum,
             threshold,
             voting_period,
-        } => update_config(deps, info, owner, quorum, threshold, voting_period),
+            season_length,
+            season_blackout,
+            end_poll_reward,
+            decay_after,
+            decay_rate,
+            decay_floor,
+            bind_wallet,
+            text_limits,
+            draft_ttl,
+            threshold_inclusive,
+            failed_poll_cooldown,
+            signal_quorum,
+            signal_threshold,
+            max_extension,
+            ibc_channel,
+            ibc_notify,
+            ibc_receiver,
+            ibc_denom,
+            tie_policy,
+            auto_finalize,
+            max_supply,
+            text_deposit,
+            execute_deposit,
+            adaptive_quorum,
+            vote_freeze_window,
+            freeze_new_votes,
+            voting_time,
+            use_token_multiplier,
+            max_multiplier,
+            bootstrap_period,
+            bootstrap_quorum,
+            delegate_inactivity_timeout,
+            max_vote_share,
+            dry_run,
+        ),
+        ExecuteMsg::ReclaimDeposit { poll_id } => reclaim_deposit(deps, env, poll_id),
+        ExecuteMsg::RollSeason {} => roll_season(deps, env),
+        ExecuteMsg::PruneDrafts {} => prune_drafts(deps, env),
+        ExecuteMsg::SetBlocked { member_id, blocked } => {
+            set_blocked(deps, info, member_id, blocked)
+        }
+        ExecuteMsg::ResolveDependency { poll_id } => resolve_dependency(deps, env, poll_id),
+        ExecuteMsg::DrawCommittee { size, seed_poll_id } => {
+            draw_committee(deps, env, info, size, seed_poll_id)
+        }
+        ExecuteMsg::PruneMember { member_id } => prune_member(deps, env, member_id),
+        ExecuteMsg::SweepInactiveDelegations { delegate_id, limit } => {
+            sweep_inactive_delegations(deps, env, delegate_id, limit)
+        }
+        ExecuteMsg::RecomputeSupply {} => recompute_supply(deps, info),
+        ExecuteMsg::RebuildPollIndex { start_after, limit } => {
+            rebuild_poll_index(deps, info, start_after, limit)
+        }
+        ExecuteMsg::SnapshotPollParameters { start_after, limit } => {
+            snapshot_poll_parameters_page(deps, start_after, limit)
+        }
+        ExecuteMsg::RecomputeDelegatedShare { start_after, limit } => {
+            recompute_delegated_share_page(deps, start_after, limit)
+        }
+        ExecuteMsg::EndPoll { poll_id } => end_poll_by_address(deps, env, info, poll_id),
+        ExecuteMsg::ForceEndPoll { poll_id, outcome } => {
+            force_end_poll(deps, env, info, poll_id, outcome)
+        }
+        ExecuteMsg::ExtendPoll {
+            poll_id,
+            additional_blocks,
+        } => extend_poll(deps, env, info, poll_id, additional_blocks),
+        ExecuteMsg::BreakDelegation { member_id } => break_delegation(deps, env, info, member_id),
+        ExecuteMsg::SuspendMember {
+            member_id,
+            until_height,
+        } => suspend_member(deps, env, info, member_id, until_height),
+        ExecuteMsg::UnsuspendMember { member_id } => unsuspend_member(deps, info, member_id),
+        ExecuteMsg::ProposeAdminAction { action, expires } => {
+            propose_admin_action(deps, env, info, action, expires)
+        }
+        ExecuteMsg::ApproveAdminAction { id } => approve_admin_action(deps, env, info, id),
+        ExecuteMsg::RelayVote { ballots } => relay_vote(deps, env, info, ballots),
+        ExecuteMsg::CastVoteSigned {
+            poll_id,
+            voter,
+            vote,
+            nonce,
+            signature,
+        } => cast_vote_signed(
+            deps,
+            env,
+            info,
+            SignedBallot {
+                token_id: voter,
+                poll_id,
+                vote,
+                nonce,
+                signature,
+            },
+        ),
     }
 }
 
-// quorum: 정족수
-// 0~1
-fn validate_quorum(quorum: Decimal) -> StdResult<()> {
-    if quorum > Decimal::one() {
-        Err(StdError::generic_err("quorum must be 0 to 1"))
-    } else {
-        Ok(())
+/// failed IBC notification is recorded, not propagated, so end_poll still finalizes
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        IBC_NOTIFY_REPLY_ID => {
+            let error = msg.result.unwrap_err();
+            Ok(Response::new()
+                .add_attributes(vec![("action", "ibc_notify_failed"), ("error", &error)]))
+        }
+        _ => Err(ContractError::InvalidReplyId {}),
     }
 }
 
-// 0~1
-fn validate_threshold(threshold: Decimal) -> StdResult<()> {
-    if threshold > Decimal::one() {
-        Err(StdError::generic_err("threshold must be 0 to 1"))
-    } else {
-        Ok(())
-    }
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let owner_migrated = migrate_legacy_owner(deps.storage)?;
+    // polls and members are migrated in pages afterwards, see SnapshotPollParameters
+    // and RecomputeDelegatedShare
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("owner_migrated", &owner_migrated.to_string()),
+    ]))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Config {} => Ok(to_binary(&query_config(deps)?)?),
-        QueryMsg::State {} => Ok(to_binary(&query_state(deps)?)?),
-        QueryMsg::Member { member_id } => Ok(to_binary(&query_member(deps, member_id)?)?),
-        QueryMsg::Poll { poll_id } => Ok(to_binary(&query_poll(deps, poll_id)?)?),
+        QueryMsg::Owner {} => Ok(to_binary(&query_owner(deps)?)?),
+        QueryMsg::State {} => Ok(to_binary(&query_state(deps, env)?)?),
+        QueryMsg::StorageVersion {} => Ok(to_binary(&query_storage_version(deps)?)?),
+        QueryMsg::VerifyInvariants {
+            start_after,
+            limit,
+            carried,
+        } => Ok(to_binary(&query_verify_invariants(
+            deps,
+            start_after,
+            limit,
+            carried,
+        )?)?),
+        QueryMsg::TotalSupply {} => Ok(to_binary(&query_total_supply(deps)?)?),
+        QueryMsg::Seasons { start_after, limit } => {
+            Ok(to_binary(&query_seasons(deps, start_after, limit)?)?)
+        }
+        QueryMsg::Member { member_id } => Ok(to_binary(&query_member(deps, env, member_id)?)?),
+        QueryMsg::Members { start_after, limit } => {
+            Ok(to_binary(&query_members(deps, start_after, limit)?)?)
+        }
+        QueryMsg::MembersBatch { member_ids } => {
+            Ok(to_binary(&query_members_batch(deps, env, member_ids)?)?)
+        }
+        QueryMsg::FreeShare { member_id } => Ok(to_binary(&query_free_share(deps, member_id)?)?),
+        QueryMsg::Withdrawable {
+            member_id,
+            at_height,
+        } => Ok(to_binary(&query_withdrawable(
+            deps, env, member_id, at_height,
+        )?)?),
+        QueryMsg::ShareToMinBalance { share } => {
+            Ok(to_binary(&query_share_to_min_balance(deps, share)?)?)
+        }
+        QueryMsg::DelegationStatus { member_id } => {
+            Ok(to_binary(&query_delegation_status(deps, member_id)?)?)
+        }
+        QueryMsg::MemberByAlias { namespace, address } => Ok(to_binary(&query_member_by_alias(
+            deps, namespace, address,
+        )?)?),
+        QueryMsg::Poll { poll_id, member_id } => {
+            Ok(to_binary(&query_poll(deps, env, poll_id, member_id)?)?)
+        }
+        QueryMsg::DepositInfo { poll_id } => Ok(to_binary(&query_deposit_info(deps, poll_id)?)?),
+        QueryMsg::PollDescription { poll_id, member_id } => Ok(to_binary(
+            &query_poll_description(deps, poll_id, member_id)?,
+        )?),
+        QueryMsg::EligiblePolls {
+            member_id,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_eligible_polls(
+            deps,
+            env,
+            member_id,
+            start_after,
+            limit,
+        )?)?),
         QueryMsg::Polls {
             filter,
             start_after,
             limit,
             order_by,
+            include_drafts,
+            member_id,
+            poll_type,
+            viewer,
         } => Ok(to_binary(&query_polls(
             deps,
+            env,
             filter,
             start_after,
             limit,
             order_by,
+            include_drafts,
+            member_id,
+            poll_type,
+            viewer,
         )?)?),
         QueryMsg::Voters {
             poll_id,
             start_after,
             limit,
             order_by,
+            vote_filter,
         } => Ok(to_binary(&query_voters(
             deps,
             poll_id,
             start_after,
             limit,
             order_by,
+            vote_filter,
+        )?)?),
+        QueryMsg::IsBlocked { member_id } => Ok(to_binary(&query_is_blocked(deps, member_id)?)?),
+        QueryMsg::ValidateHook { member_id, hook } => Ok(to_binary(&query_validate_hook(
+            deps, env, member_id, hook,
+        )?)?),
+        QueryMsg::TopDelegates { limit } => Ok(to_binary(&query_top_delegates(deps, limit)?)?),
+        QueryMsg::IndexConsistency { start_after, limit } => Ok(to_binary(
+            &query_index_consistency(deps, start_after, limit)?,
+        )?),
+        QueryMsg::Committee { id } => Ok(to_binary(&query_committee(deps, id)?)?),
+        QueryMsg::IbcConfig {} => Ok(to_binary(&query_ibc_config(deps)?)?),
+        QueryMsg::VoterCount { poll_id } => Ok(to_binary(&query_voter_count(deps, poll_id)?)?),
+        QueryMsg::VoteReceipt { poll_id, member_id } => {
+            Ok(to_binary(&query_vote_receipt(deps, poll_id, member_id)?)?)
+        }
+        QueryMsg::AdminAction { id } => Ok(to_binary(&query_admin_action(deps, env, id)?)?),
+        QueryMsg::AdminActions { start_after, limit } => Ok(to_binary(&query_admin_actions(
+            deps,
+            env,
+            start_after,
+            limit,
         )?)?),
     }
 }
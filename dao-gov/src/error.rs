@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError};
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -51,6 +51,9 @@ pub enum ContractError {
     #[error("Poll is not in progress")]
     PollNotInProgress {},
 
+    #[error("Voting period of the poll has ended")]
+    PollEnded {},
+
     #[error("Poll is not in passed status")]
     PollNotPassed {},
 
@@ -66,9 +69,39 @@ pub enum ContractError {
     #[error("User has already delegated")]
     AlreadyDelegated {},
 
+    #[error("User has already delegated to {delegatee}, undelegate first")]
+    AlreadyDelegatedTo { delegatee: String },
+
     #[error("User has not yet delegated")]
     NotYetDelegated {},
 
+    #[error("Must undelegate before exit")]
+    UndelegateBeforeExit {},
+
+    #[error("Delegatee is not a member")]
+    DelegateeNotMember {},
+
+    #[error("Cannot both delegate out and receive delegations")]
+    ConflictingDelegation {},
+
+    #[error("Delegate is still active (last voted at {last_vote_height})")]
+    DelegateActive { last_vote_height: u64 },
+
+    #[error("Member already holds votes in {max} in-progress polls")]
+    TooManyLockedPolls { max: usize },
+
+    #[error("Alias is already claimed")]
+    AliasAlreadyClaimed {},
+
+    #[error("Alias is already set for this namespace")]
+    AliasAlreadySet {},
+
+    #[error("Alias not found")]
+    AliasNotFound {},
+
+    #[error("Cannot have more than {0} aliases")]
+    TooManyAliases(usize),
+
     #[error("Share is locked in in progress polls")]
     ShareLocked {},
 
@@ -78,6 +111,188 @@ pub enum ContractError {
     #[error("Voting period has not expired")]
     PollVotingPeriod {},
 
+    #[error("Reclaim grace period has not expired")]
+    ReclaimGracePeriod {},
+
+    #[error("Cannot create poll in season blackout (season ends at {season_ends_at})")]
+    SeasonBlackout { season_ends_at: u64 },
+
+    #[error("Season has not ended")]
+    SeasonNotEnded {},
+
+    #[error("Not a member")]
+    NotAMember {},
+
+    #[error("Member is suspended until height {until}")]
+    MemberSuspended { until: u64 },
+
+    #[error("Admin threshold must be between 1 and the number of admins")]
+    InvalidAdminThreshold {},
+
+    #[error("Duplicate admin")]
+    DuplicateAdmin {},
+
+    #[error("A non-zero bootstrap_period requires bootstrap_quorum")]
+    BootstrapQuorumRequired {},
+
+    #[error("Bootstrap period has ended and can't be extended")]
+    BootstrapEnded {},
+
+    #[error("Action requires approval of {0} admins (use ProposeAdminAction)")]
+    AdminApprovalRequired(u32),
+
+    #[error("Admin action does not exist")]
+    AdminActionNotFound {},
+
+    #[error("Admin action has expired")]
+    AdminActionExpired {},
+
+    #[error("Admin has already approved")]
+    AlreadyApproved {},
+
+    #[error("NFT holder changed, rebind before governance actions")]
+    WalletMismatch {},
+
+    #[error("{field} too long (max {max}, got {got})")]
+    TextTooLong { field: String, max: u32, got: usize },
+
+    #[error("{field} must be between {min} and {ceiling} (got {got})")]
+    InvalidTextLimit {
+        field: String,
+        min: u32,
+        ceiling: u32,
+        got: u32,
+    },
+
+    #[error("Cannot relay more than {0} ballots")]
+    TooManyBallots(usize),
+
+    #[error("Vote key is not registered")]
+    VoteKeyNotRegistered {},
+
+    #[error("Invalid ballot signature")]
+    InvalidBallotSignature {},
+
+    #[error("Ballot nonce must equal member's vote_nonce")]
+    InvalidNonce {},
+
+    #[error("Poll is a draft")]
+    PollIsDraft {},
+
+    #[error("Poll is not a draft")]
+    PollNotDraft {},
+
+    #[error("Draft expired at height {expired_at} and can only be pruned")]
+    DraftExpired { expired_at: u64 },
+
+    #[error("Vote option does not match poll")]
+    InvalidVoteOption {},
+
+    #[error("Multiple choice poll needs 2 to {0} distinct non-empty options")]
+    InvalidPollOptions(usize),
+
+    #[error("Last poll failed quorum, cannot create poll until height {until_height}")]
+    CooldownActive { until_height: u64 },
+
+    #[error("Cannot cast more than {0} votes at once")]
+    TooManyVotes(usize),
+
+    #[error("Vote on poll {poll_id} failed: {reason}")]
+    BatchVoteFailed { poll_id: u64, reason: String },
+
+    #[error("Conviction must be between 1 and balance")]
+    InvalidConviction {},
+
+    #[error("Poll can be extended by at most {0} blocks in total")]
+    ExtensionExceeded(u64),
+
+    #[error("Poll must be extended by at least one block")]
+    ZeroExtension {},
+
+    #[error("Poll has reached quorum, only admin can extend it")]
+    QuorumAlreadyReached {},
+
+    #[error("Cannot query more than {0} members at once")]
+    TooManyMembers(usize),
+
+    #[error("Delegation would create a cycle: {}", .path.join(" -> "))]
+    DelegationCycle { path: Vec<String> },
+
+    #[error("Delegation chain is longer than {0}")]
+    DelegationChainTooLong(usize),
+
+    #[error("Contract balance of {denom} can't cover IBC notification")]
+    InsufficientIbcFee { denom: String },
+
+    #[error("IBC notification channel must be unordered")]
+    IbcChannelOrder {},
+
+    #[error("IBC notification channel version must be {expected}, got {version}")]
+    IbcChannelVersion { expected: String, version: String },
+
+    #[error("IBC notification channel only sends packets")]
+    IbcReceiveUnsupported {},
+
+    #[error("Member still has balance or in-progress votes")]
+    MemberNotEmpty {},
+
+    #[error("Member is blocked from governance")]
+    MemberBlocked {},
+
+    #[error("Poll is not pending")]
+    PollNotPending {},
+
+    #[error("Dependency poll didn't pass")]
+    DependencyFailed {},
+
+    #[error("Poll already has votes")]
+    PollHasVotes {},
+
+    #[error("Poll has not ended")]
+    PollNotEnded {},
+
+    #[error("Committee not found")]
+    CommitteeNotFound {},
+
+    #[error("Committee size must be between 1 and number of candidates ({0})")]
+    InvalidCommitteeSize(usize),
+
     #[error("Invalid Reply Id")]
     InvalidReplyId {},
+
+    #[error("Hook can't be validated without executing it")]
+    HookNotValidatable {},
+
+    #[error("Owner and recipient of transfer are the same member")]
+    TransferToSelf {},
+
+    #[error("Max supply can't be raised")]
+    CannotRaiseMaxSupply {},
+
+    #[error("Mint would bring total supply to {attempted_total}, above max supply {max}")]
+    MaxSupplyExceeded {
+        max: Uint128,
+        attempted_total: Uint128,
+    },
+
+    #[error("Another hook of member {member_id} is in flight")]
+    MemberInFlight { member_id: String },
+
+    #[error("Member {member_id} is inconsistent: {reason}")]
+    InconsistentMember { member_id: String, reason: String },
+
+    #[error("Poll can only be force ended as passed or rejected")]
+    InvalidForceEndOutcome {},
+
+    #[error("Votes are frozen for the last {until_end} blocks of the poll")]
+    VoteFrozen { until_end: u64 },
+
+    #[error("Vested balance {amount} is locked until {until:?}")]
+    VestedLocked { amount: Uint128, until: Option<u64> },
+
+    #[error("Vesting release height {until_height} must be in the future")]
+    VestingInPast { until_height: u64 },
+
+    #[error("No vested balance to release")]
+    NothingVested {},
 }
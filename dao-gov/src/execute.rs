@@ -1,19 +1,73 @@
 use cosmwasm_std::{
-    from_binary, Decimal, DepsMut, Env, Isqrt, MessageInfo, Response, Storage, Uint128,
+    from_binary, to_binary, Attribute, Binary, BlockInfo, CanonicalAddr, Decimal, Deps, DepsMut,
+    Env, IbcMsg, MessageInfo, Order, Response, StdResult, Storage, SubMsg, Uint128,
 };
 
 use crate::error::ContractError;
-use crate::msg::Cw721HookMsg;
+use crate::msg::{AdminAction, Cw721HookMsg, IbcNotifyPacket, SignedBallot};
 use crate::state::{
-    bank_read, bank_store, config_read, config_store, poll_indexer_store, poll_read, poll_store,
-    poll_voter_read, poll_voter_store, state_read, state_store, Config, Poll, State, TokenManager,
+    adjust_delegated_in_share, admin_proposal_read, admin_proposal_store, alias_read, alias_store,
+    blocklist_read, blocklist_store, committee_store, config_read, config_store, delete_member,
+    in_flight_read, in_flight_store, load_member, load_member_or_new, load_poll, may_load_member,
+    may_load_poll, move_poll_indexer, poll_id_from_key, poll_index_drift, poll_indexer_store,
+    poll_voter_read, poll_voter_store, range_members, read_committee_draw, read_poll_page,
+    read_tmp_supply, recompute_delegated_in_share, remove_committee_draw, remove_poll,
+    remove_tmp_supply, save_member, save_poll, save_poll_description, season_store,
+    set_delegated_in_share, snapshot_poll_parameters, state_read, state_store,
+    store_committee_draw, store_tmp_supply, AdminProposal, Committee, CommitteeDraw, Config,
+    DrawnMember, EndedPoll, Poll, SeasonSnapshot, State, TokenManager, TURNOUT_WINDOW,
 };
 use crate::utils::{
-    validate_description, validate_link, validate_title, PollStatus, VoteInfo, VoteOption,
+    ballot_message, conviction_weight, effective_share, keep_drawn, max_conviction_weight,
+    query_token_multiplier, sample_key, share_cap, summary_of, threshold_reached, validate_alias,
+    validate_decay, validate_description, validate_ibc_channel, validate_link, validate_links,
+    validate_max_vote_share, validate_poll_options, validate_quorum, validate_season_length,
+    validate_summary, validate_text_limits, validate_threshold, validate_title, validate_vote_key,
+    validate_voting_period, EndPollPermission, PollKind, PollLink, PollOutcome, PollStatus,
+    PollType, StateTransition, TextLimits, TiePolicy, VoteInfo, VoteOption,
+    STATE_TRANSITION_VERSION,
 };
 use cw721::Cw721ReceiveMsg;
+use sha2::{Digest, Sha256};
+
+// reply id of the fire-and-forget IBC notification of poll results
+pub const IBC_NOTIFY_REPLY_ID: u64 = 1;
+
+// ibc_denom the contract must hold to send a notification (reserve for relayer fees)
+const IBC_NOTIFY_AMOUNT: u128 = 1;
+
+// timeout of the IBC notification packet
+const IBC_TIMEOUT_SECONDS: u64 = 600;
+
+// characters of poll title in poll_title attribute of receive_cw721
+const HOOK_TITLE_MAX: usize = 64;
+// maximum number of delegated_from_<id> attributes in cast_vote response
+const MAX_DELEGATED_ATTRIBUTES: usize = 20;
+// maximum number of polls a member's votes are locked in (bounds loads per lock scan)
+const MAX_LOCKED_POLLS: usize = 50;
+// maximum number of delegators released per SweepInactiveDelegations call
+const MAX_SWEEP_LIMIT: u32 = 100;
+// number of members scanned per RecomputeSupply call
+const RECOMPUTE_SUPPLY_PAGE: usize = 30;
+// number of oldest admin proposals checked for expiry per propose/approve
+const ADMIN_PROPOSAL_PRUNE_SCAN: usize = 10;
+// maximum number of aliases per member
+const MAX_ALIASES: usize = 5;
+// blocks after end_height before an abandoned poll can be reclaimed
+const RECLAIM_GRACE_PERIOD: u64 = 100_000;
+// maximum number of ballots per RelayVote
+const MAX_RELAY_BALLOTS: usize = 30;
+// maximum hops of the delegate_to chain a delegation may end up in
+const MAX_DELEGATION_DEPTH: usize = 8;
+// maximum number of votes per CastVotes
+const MAX_BATCH_VOTES: usize = 10;
+// bank entries DrawCommittee scans per call
+const COMMITTEE_DRAW_PAGE: usize = 100;
+// maximum number of drafts scanned per PruneDrafts
+const MAX_PRUNE_DRAFTS: usize = 30;
+
 pub fn receive_cw721(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     cw721_msg: Cw721ReceiveMsg,
@@ -23,42 +77,715 @@ pub fn receive_cw721(
     if config.cw721_token != deps.api.addr_canonicalize(info.sender.as_str())? {
         return Err(ContractError::Unauthorized {});
     }
-    match from_binary(&cw721_msg.msg) {
-        Ok(Cw721HookMsg::CastVote { poll_id, vote }) => {
-            cast_vote(deps, env, cw721_msg.token_id, poll_id, vote)
+    let hook_msg: Cw721HookMsg = match from_binary(&cw721_msg.msg) {
+        Ok(hook_msg) => hook_msg,
+        Err(_) => return Err(ContractError::DataShouldBeGiven {}),
+    };
+
+    // wallet is cw721_msg.sender (holder of the NFT, set by cw721 execute_dao)
+    let wallet = cw721_msg.sender;
+    if !matches!(hook_msg, Cw721HookMsg::Rebind {}) {
+        check_wallet(deps.storage, &config, &cw721_msg.token_id, &wallet)?;
+    }
+
+    // one hook per member at a time, only matters once hooks are split into submessages
+    let member_id = cw721_msg.token_id;
+    if in_flight_read(deps.storage)
+        .may_load(member_id.as_bytes())?
+        .is_some()
+    {
+        return Err(ContractError::MemberInFlight { member_id });
+    }
+
+    // delegation to an inactive delegate is dropped before acting (UnDelegateVote drops it itself)
+    let mut voided = vec![];
+    if !matches!(hook_msg, Cw721HookMsg::UnDelegateVote {}) {
+        voided = void_inactive_delegation(deps.storage, &env.block, &member_id)?;
+    }
+
+    // block explorers show poll title next to the vote
+    let poll_title = match &hook_msg {
+        Cw721HookMsg::CastVote { poll_id, .. } | Cw721HookMsg::CancelVote { poll_id } => {
+            may_load_poll(deps.storage, *poll_id)?.map(|a_poll| {
+                a_poll
+                    .title
+                    .chars()
+                    .take(HOOK_TITLE_MAX)
+                    .collect::<String>()
+            })
+        }
+        _ => None,
+    };
+
+    let hook_kind = hook_msg.name();
+    in_flight_store(deps.storage).save(member_id.as_bytes(), &1u8)?;
+    let dispatched = dispatch_hook(deps.branch(), env, member_id.clone(), wallet, hook_msg);
+    // cleared on failure too, the tx reverting it is not something to rely on here
+    in_flight_store(deps.storage).remove(member_id.as_bytes());
+    let mut response = dispatched?;
+    if let Some(poll_title) = poll_title {
+        response = response.add_attribute("poll_title", poll_title);
+    }
+
+    #[cfg(any(test, feature = "strict-invariants"))]
+    assert_member_consistent(deps.storage, &member_id)?;
+    Ok(response
+        // hook is the key indexers already read, hook_kind the one previews document
+        .add_attribute("hook", hook_kind)
+        .add_attribute("hook_kind", hook_kind)
+        .add_attributes(voided))
+}
+
+/// invariants of a member's bank entry (checked after hooks in tests and with strict-invariants)
+/// share matches balance under the voting model, locks reference existing polls
+pub fn assert_member_consistent(
+    storage: &dyn Storage,
+    member_id: &str,
+) -> Result<(), ContractError> {
+    let token_manager = match may_load_member(storage, member_id.as_bytes())? {
+        Some(token_manager) => token_manager,
+        None => return Ok(()),
+    };
+    let config: Config = config_read(storage).load()?;
+    if token_manager.share != config.share(token_manager.balance) {
+        return Err(ContractError::InconsistentMember {
+            member_id: member_id.to_string(),
+            reason: "share doesn't match balance".to_string(),
+        });
+    }
+    for (poll_id, _) in token_manager.locked_share.iter() {
+        if may_load_poll(storage, *poll_id)?.is_none() {
+            return Err(ContractError::InconsistentMember {
+                member_id: member_id.to_string(),
+                reason: format!("lock on missing poll {}", poll_id),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn dispatch_hook(
+    deps: DepsMut,
+    env: Env,
+    member_id: String,
+    wallet: String,
+    hook_msg: Cw721HookMsg,
+) -> Result<Response, ContractError> {
+    match hook_msg {
+        Cw721HookMsg::CastVote {
+            poll_id,
+            vote,
+            conviction,
+        } => cast_vote(deps, env, member_id, wallet, poll_id, vote, conviction),
+        Cw721HookMsg::CastChoiceVote {
+            poll_id,
+            option_index,
+        } => cast_vote(
+            deps,
+            env,
+            member_id,
+            wallet,
+            poll_id,
+            VoteOption::Choice(option_index),
+            None,
+        ),
+        Cw721HookMsg::CastVotes { votes } => cast_votes(deps, env, member_id, wallet, votes),
+        Cw721HookMsg::CancelVote { poll_id } => cancel_vote(deps, env, member_id, poll_id),
+        Cw721HookMsg::VoteOverride { poll_id, vote } => {
+            vote_override(deps, env, member_id, wallet, poll_id, vote)
+        }
+        Cw721HookMsg::CreatePoll {
+            title,
+            description,
+            summary,
+            link,
+            links,
+            draft,
+            options,
+            poll_type,
+            depends_on,
+        } => create_poll(
+            deps,
+            env,
+            member_id,
+            wallet,
+            title,
+            description,
+            summary,
+            link,
+            links,
+            draft,
+            options,
+            poll_type,
+            depends_on,
+        ),
+        Cw721HookMsg::PublishPoll { poll_id } => publish_poll(deps, env, member_id, poll_id),
+        Cw721HookMsg::WithdrawPoll { poll_id } => withdraw_poll(deps, env, member_id, poll_id),
+        Cw721HookMsg::ExtendPoll {
+            poll_id,
+            additional_blocks,
+        } => creator_extend_poll(deps, env, member_id, poll_id, additional_blocks),
+        Cw721HookMsg::EndPoll { poll_id } => {
+            end_poll_by_member(deps, env, member_id, wallet, poll_id)
+        }
+        Cw721HookMsg::DelegateVote { delegator } => delegate_vote(deps, env, member_id, delegator),
+        Cw721HookMsg::UnDelegateVote {} => undelegate_vote(deps, env, member_id),
+        Cw721HookMsg::AddAlias { namespace, address } => {
+            add_alias(deps, member_id, namespace, address)
+        }
+        Cw721HookMsg::RemoveAlias { namespace } => remove_alias(deps, member_id, namespace),
+        Cw721HookMsg::Exit {} => exit(deps, &env.block, member_id),
+        Cw721HookMsg::Withdraw { amount } => withdraw(deps, &env.block, member_id, amount),
+        Cw721HookMsg::RegisterVoteKey { pubkey } => register_vote_key(deps, member_id, pubkey),
+        Cw721HookMsg::Rebind {} => rebind(deps, env, member_id, wallet),
+    }
+}
+
+/// preconditions of hook sent by member, checked without executing it
+/// wallet binding is not checked (holder of the NFT is not known to a query)
+pub fn validate_hook(
+    deps: Deps,
+    env: &Env,
+    member_id: &str,
+    hook: Cw721HookMsg,
+) -> Result<(), ContractError> {
+    let height = env.block.height;
+    match hook {
+        Cw721HookMsg::CastVote {
+            poll_id,
+            vote,
+            conviction,
+        } => validate_cast_vote(deps, &env.block, member_id, poll_id, &vote, conviction),
+        Cw721HookMsg::CastChoiceVote {
+            poll_id,
+            option_index,
+        } => validate_cast_vote(
+            deps,
+            &env.block,
+            member_id,
+            poll_id,
+            &VoteOption::Choice(option_index),
+            None,
+        ),
+        Cw721HookMsg::CancelVote { poll_id } => {
+            let a_poll = load_existing_poll(deps.storage, poll_id)?;
+            check_cancel_vote(deps.storage, &env.block, member_id, &a_poll)?;
+            Ok(())
+        }
+        Cw721HookMsg::DelegateVote { delegator } => {
+            check_delegate_vote(deps.storage, height, member_id, &delegator)
         }
-        Ok(Cw721HookMsg::CancelVote { poll_id }) => {
-            cancel_vote(deps, env, cw721_msg.token_id, poll_id)
+        Cw721HookMsg::UnDelegateVote {} => {
+            check_undelegate_vote(deps.storage, member_id)?;
+            Ok(())
         }
-        Ok(Cw721HookMsg::CreatePoll {
+        Cw721HookMsg::CreatePoll {
             title,
             description,
+            summary,
             link,
-        }) => create_poll(deps, env, cw721_msg.token_id, title, description, link),
-        Ok(Cw721HookMsg::EndPoll { poll_id }) => end_poll(deps, env, poll_id),
-        Ok(Cw721HookMsg::DelegateVote { delegator }) => {
-            delegate_vote(deps, cw721_msg.token_id, delegator)
+            links,
+            draft,
+            options,
+            poll_type,
+            depends_on,
+        } => {
+            let config: Config = config_read(deps.storage).load()?;
+            let links = poll_links(link.clone(), links);
+            let summary = summary.unwrap_or_else(|| summary_of(&description));
+            check_create_poll(
+                deps.storage,
+                &config,
+                height,
+                member_id,
+                &title,
+                &description,
+                &summary,
+                &link,
+                &links,
+                &options,
+                draft,
+                &poll_type,
+                depends_on,
+            )?;
+            Ok(())
+        }
+        Cw721HookMsg::Exit {} => {
+            check_exit(deps.storage, height, member_id)?;
+            Ok(())
+        }
+        Cw721HookMsg::Withdraw { amount } => {
+            check_withdraw(deps.storage, height, member_id, amount)?;
+            Ok(())
+        }
+        _ => Err(ContractError::HookNotValidatable {}),
+    }
+}
+
+/// pending poll is resolved in memory as cast_vote would do
+fn validate_cast_vote(
+    deps: Deps,
+    block: &BlockInfo,
+    voter_id: &str,
+    poll_id: u64,
+    vote: &VoteOption,
+    conviction: Option<Uint128>,
+) -> Result<(), ContractError> {
+    let mut a_poll = load_existing_poll(deps.storage, poll_id)?;
+    resolve_pending(deps.storage, block, &mut a_poll)?;
+    check_cast_vote(deps.storage, block, voter_id, &a_poll, vote, conviction)?;
+    Ok(())
+}
+
+/// record wallet holding member's NFT
+/// if bind_wallet, a wallet other than the recorded one must rebind first
+fn check_wallet(
+    storage: &mut dyn Storage,
+    config: &Config,
+    member_id: &str,
+    wallet: &str,
+) -> Result<(), ContractError> {
+    let mut token_manager = match may_load_member(storage, member_id.as_bytes())? {
+        Some(token_manager) => token_manager,
+        None => return Ok(()),
+    };
+    match token_manager.wallet.as_deref() {
+        Some(recorded) if recorded == wallet => return Ok(()),
+        Some(_) if config.bind_wallet => return Err(ContractError::WalletMismatch {}),
+        _ => {}
+    }
+    token_manager.wallet = Some(wallet.to_string());
+    save_member(storage, member_id.as_bytes(), &token_manager)?;
+    Ok(())
+}
+
+/// register public key verifying my relayed ballots
+fn register_vote_key(
+    deps: DepsMut,
+    member_id: String,
+    pubkey: Binary,
+) -> Result<Response, ContractError> {
+    validate_vote_key(&pubkey)?;
+
+    let member_key = member_id.as_bytes();
+    let mut token_manager = match may_load_member(deps.storage, member_key)? {
+        Some(token_manager) => token_manager,
+        None => return Err(ContractError::NotAMember {}),
+    };
+    token_manager.vote_key = Some(pubkey.clone());
+    save_member(deps.storage, member_key, &token_manager)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "register_vote_key"),
+        ("member", member_id.as_str()),
+        ("pubkey", pubkey.to_base64().as_str()),
+    ]))
+}
+
+/// apply ballots signed by members' vote keys
+/// invalid ballot is skipped (skipped_<index>: reason), others are applied
+/// ballots are checked before anything is written, a failure after that fails the whole relay
+pub fn relay_vote(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ballots: Vec<SignedBallot>,
+) -> Result<Response, ContractError> {
+    if ballots.len() > MAX_RELAY_BALLOTS {
+        return Err(ContractError::TooManyBallots(MAX_RELAY_BALLOTS));
+    }
+    let config: Config = config_read(deps.storage).load()?;
+
+    let mut applied = 0;
+    let mut skipped: Vec<(String, String)> = vec![];
+    let mut quorum_reached_polls: Vec<String> = vec![];
+    let mut finalized_polls: Vec<String> = vec![];
+    let mut messages: Vec<SubMsg> = vec![];
+    let mut transitions: Vec<Attribute> = vec![];
+    for (index, ballot) in ballots.iter().enumerate() {
+        let checked = match check_ballot(deps.as_ref(), &env, &config, ballot) {
+            Ok(checked) => checked,
+            Err(err) => {
+                skipped.push((format!("skipped_{}", index), err.to_string()));
+                continue;
+            }
+        };
+        let (quorum_reached, finalized, transition) =
+            apply_ballot(deps.branch(), &env, &config, ballot, checked)?;
+        applied += 1;
+        transitions.push(transition);
+        if quorum_reached {
+            quorum_reached_polls.push(ballot.poll_id.to_string());
+        }
+        if let Some(finalized) = finalized {
+            finalized_polls.push(ballot.poll_id.to_string());
+            messages.extend(finalized.messages);
+        }
+    }
+
+    let mut response = Response::new()
+        .add_attributes(vec![
+            ("action", "relay_vote"),
+            ("relayer", info.sender.as_str()),
+            ("applied", &applied.to_string()),
+        ])
+        .add_attributes(skipped)
+        .add_attributes(transitions)
+        .add_submessages(messages);
+    if !quorum_reached_polls.is_empty() {
+        response = response.add_attribute("quorum_reached_polls", quorum_reached_polls.join(","));
+    }
+    if !finalized_polls.is_empty() {
+        response = response.add_attribute("auto_finalized_polls", finalized_polls.join(","));
+    }
+    Ok(response)
+}
+
+/// apply one ballot signed by member's vote key, crediting the member's share
+pub fn cast_vote_signed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ballot: SignedBallot,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    let checked = check_ballot(deps.as_ref(), &env, &config, &ballot)?;
+    let (quorum_reached, finalized, transition) =
+        apply_ballot(deps, &env, &config, &ballot, checked)?;
+
+    let mut response = Response::new()
+        .add_attributes(vec![
+            ("action", "cast_vote_signed"),
+            ("relayer", info.sender.as_str()),
+            ("poll_id", &ballot.poll_id.to_string()),
+            ("voter", ballot.token_id.as_str()),
+            ("vote_option", &ballot.vote.to_string()),
+            ("quorum_reached", &quorum_reached.to_string()),
+        ])
+        .add_attributes(vec![transition]);
+    if let Some(finalized) = finalized {
+        response = response
+            .add_attribute("auto_finalized", "true")
+            .add_submessages(finalized.messages);
+    }
+    Ok(response)
+}
+
+/// poll, wallet and multiplier of a ballot check_ballot accepted
+struct CheckedBallot {
+    a_poll: Poll,
+    wallet: String,
+    multiplier: Option<Decimal>,
+}
+
+/// everything that can refuse a ballot, without writing anything, so a ballot
+/// skipped by relay_vote leaves no state behind and can be resubmitted
+fn check_ballot(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    ballot: &SignedBallot,
+) -> Result<CheckedBallot, ContractError> {
+    let poll_id = ballot.poll_id;
+    let voter_key = ballot.token_id.as_bytes();
+    let a_poll = load_existing_poll(deps.storage, poll_id)?;
+    check_poll_open(&a_poll, &env.block)?;
+    if !a_poll.accepts(&ballot.vote) {
+        return Err(ContractError::InvalidVoteOption {});
+    }
+    assert_not_frozen(config, &a_poll, env.block.height, true)?;
+
+    let token_manager = match may_load_member(deps.storage, voter_key)? {
+        Some(token_manager) => token_manager,
+        None => return Err(ContractError::NotAMember {}),
+    };
+    let pubkey = match &token_manager.vote_key {
+        Some(pubkey) => pubkey,
+        None => return Err(ContractError::VoteKeyNotRegistered {}),
+    };
+
+    // replay protection: each ballot uses up the member's current nonce
+    if ballot.nonce != token_manager.vote_nonce {
+        return Err(ContractError::InvalidNonce {});
+    }
+
+    let message = ballot_message(env.contract.address.as_str(), ballot);
+    let message_hash = Sha256::digest(message.as_bytes());
+    let verified = deps
+        .api
+        .secp256k1_verify(&message_hash, &ballot.signature, pubkey)
+        .unwrap_or(false);
+    if !verified {
+        return Err(ContractError::InvalidBallotSignature {});
+    }
+
+    if poll_voter_read(deps.storage, poll_id)
+        .may_load(voter_key)?
+        .is_some()
+    {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    // delegated shares are only counted by delegatee's CastVote
+    if let Some(delegatee) = &token_manager.delegate_to {
+        return Err(ContractError::AlreadyDelegatedTo {
+            delegatee: delegatee.clone(),
+        });
+    }
+    assert_not_suspended(&token_manager, env.block.height)?;
+    assert_not_blocked(deps.storage, ballot.token_id.as_str())?;
+    assert_lock_available(deps.storage, &token_manager)?;
+
+    let multiplier = token_multiplier(deps, config, &ballot.token_id)?;
+    Ok(CheckedBallot {
+        a_poll,
+        wallet: token_manager.wallet.unwrap_or_default(),
+        multiplier,
+    })
+}
+
+/// cast the ballot check_ballot accepted, using up the member's nonce
+/// returns whether the ballot made the poll reach quorum, response of auto_finalize
+/// and state_transition attribute of the poll
+fn apply_ballot(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    ballot: &SignedBallot,
+    checked: CheckedBallot,
+) -> Result<(bool, Option<Response>, Attribute), ContractError> {
+    let voter_key = ballot.token_id.as_bytes();
+    let CheckedBallot {
+        mut a_poll,
+        wallet,
+        multiplier,
+    } = checked;
+    let quorum_reached_before = a_poll.quorum_reached_height.is_some();
+    cast_single_vote(
+        deps.storage,
+        config,
+        env.block.height,
+        voter_key,
+        &mut a_poll,
+        ballot.vote.clone(),
+        &wallet,
+        None,
+        Uint128::zero(),
+        multiplier,
+    )?;
+    save_poll(deps.storage, &a_poll)?;
+
+    // replay protection: reloaded since cast_single_vote saved the lock
+    let mut token_manager = load_member(deps.storage, voter_key)?;
+    token_manager.vote_nonce += 1;
+    save_member(deps.storage, voter_key, &token_manager)?;
+
+    let quorum_reached = !quorum_reached_before && a_poll.quorum_reached_height.is_some();
+    let finalized = auto_finalize(deps, env, config, &mut a_poll, &ballot.token_id)?;
+    let transition = state_transition(
+        Some(PollStatus::InProgress),
+        &a_poll,
+        false,
+        env.block.height,
+    )?;
+    Ok((quorum_reached, finalized, transition))
+}
+
+/// bind membership to current holder of the NFT
+/// votes in polls still in voting period are withdrawn (previous holder's decisions)
+fn rebind(
+    deps: DepsMut,
+    env: Env,
+    member_id: String,
+    wallet: String,
+) -> Result<Response, ContractError> {
+    let member_key = member_id.as_bytes();
+    let mut token_manager = match may_load_member(deps.storage, member_key)? {
+        Some(token_manager) => token_manager,
+        None => return Err(ContractError::NotAMember {}),
+    };
+
+    // votes in polls within vote_freeze_window stay until the poll ends
+    let config: Config = config_read(deps.storage).load()?;
+    let mut withdrawn_polls: Vec<String> = vec![];
+    let mut transitions: Vec<Attribute> = vec![];
+    let mut locked_share: Vec<(u64, VoteInfo)> = vec![];
+    for (poll_id, vote_info) in token_manager.locked_share.into_iter() {
+        if let Some(mut a_poll) = may_load_poll(deps.storage, poll_id)? {
+            if a_poll.status == PollStatus::InProgress
+                && a_poll.voting_open(&env.block)
+                && assert_not_frozen(&config, &a_poll, env.block.height, false).is_ok()
+            {
+                a_poll.remove_vote(&vote_info.vote, vote_info.weight());
+                save_poll(deps.storage, &a_poll)?;
+                poll_voter_store(deps.storage, poll_id).remove(member_key);
+                withdrawn_polls.push(poll_id.to_string());
+                transitions.push(state_transition(
+                    Some(PollStatus::InProgress),
+                    &a_poll,
+                    false,
+                    env.block.height,
+                )?);
+                continue;
+            }
+        }
+        locked_share.push((poll_id, vote_info));
+    }
+    token_manager.locked_share = locked_share;
+    token_manager.wallet = Some(wallet.clone());
+    // vote key was registered by previous holder
+    token_manager.vote_key = None;
+    save_member(deps.storage, member_key, &token_manager)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "rebind"),
+            ("member", member_id.as_str()),
+            ("wallet", wallet.as_str()),
+            ("withdrawn_polls", &withdrawn_polls.join(",")),
+        ])
+        .add_attributes(transitions))
+}
+
+/// legacy link goes first
+fn poll_links(link: Option<String>, links: Option<Vec<PollLink>>) -> Vec<PollLink> {
+    let mut links = links.unwrap_or_default();
+    if let Some(link) = link {
+        links.insert(0, PollLink::from_legacy(link));
+    }
+    links
+}
+
+/// preconditions of create_poll, returns status of the new poll
+#[allow(clippy::too_many_arguments)]
+fn check_create_poll(
+    storage: &dyn Storage,
+    config: &Config,
+    height: u64,
+    sender_id: &str,
+    title: &str,
+    description: &str,
+    summary: &str,
+    link: &Option<String>,
+    links: &[PollLink],
+    options: &Option<Vec<String>>,
+    draft: bool,
+    poll_type: &PollType,
+    depends_on: Option<u64>,
+) -> Result<PollStatus, ContractError> {
+    validate_title(title, &config.text_limits)?;
+    validate_description(description, &config.text_limits)?;
+    validate_summary(summary)?;
+    validate_link(link, &config.text_limits)?;
+    if let Some(options) = options {
+        validate_poll_options(options)?;
+    }
+    validate_links(links)?;
+
+    let creator = load_member_or_new(storage, sender_id.as_bytes())?;
+    assert_not_suspended(&creator, height)?;
+    assert_not_blocked(storage, sender_id)?;
+    assert_no_cooldown(config, &creator, height)?;
+
+    // deposit comes out of the creator's withdrawable balance
+    let deposit = config.deposit_for(poll_type);
+    if !deposit.is_zero() {
+        check_withdrawable(storage, height, &creator, deposit).map_err(|err| match err {
+            ContractError::InvalidWithdrawAmount {} => {
+                ContractError::InsufficientProposalDeposit(deposit.u128())
+            }
+            err => err,
+        })?;
+    }
+
+    let dependency_passed = match depends_on {
+        Some(dependency_id) => {
+            if may_load_poll(storage, dependency_id)?.is_none() {
+                return Err(ContractError::PollNotFound {});
+            }
+            dependency_passed(storage, dependency_id)?
         }
-        Ok(Cw721HookMsg::UnDelegateVote {}) => undelegate_vote(deps, cw721_msg.token_id),
-        Ok(Cw721HookMsg::Exit {}) => exit(deps, cw721_msg.token_id),
-        _ => Err(ContractError::DataShouldBeGiven {}),
+        None => true,
+    };
+
+    // voting clock of draft starts when published, of pending poll when dependency passes
+    if draft {
+        return Ok(PollStatus::Draft);
+    }
+    if !dependency_passed {
+        return Ok(PollStatus::Pending);
     }
+    let state: State = state_read(storage).load()?;
+    assert_not_season_blackout(config, &state, height)?;
+    Ok(PollStatus::InProgress)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_poll(
     deps: DepsMut,
     env: Env,
     sender_id: String,
+    wallet: String,
     title: String,
     description: String,
+    summary: Option<String>,
     link: Option<String>,
+    links: Option<Vec<PollLink>>,
+    draft: bool,
+    options: Option<Vec<String>>,
+    poll_type: PollType,
+    depends_on: Option<u64>,
 ) -> Result<Response, ContractError> {
-    validate_title(&title)?;
-    validate_description(&description)?;
-    validate_link(&link)?;
     let config: Config = config_store(deps.storage).load()?;
+    let links = poll_links(link.clone(), links);
+    // description alone (older clients) also makes the summary
+    let summary = summary.unwrap_or_else(|| summary_of(&description));
+    let status = check_create_poll(
+        deps.storage,
+        &config,
+        env.block.height,
+        &sender_id,
+        &title,
+        &description,
+        &summary,
+        &link,
+        &links,
+        &options,
+        draft,
+        &poll_type,
+        depends_on,
+    )?;
+
+    // take deposit before the share snapshot, refunded when the poll is closed
+    let deposit = config.deposit_for(&poll_type);
+    let orphaned_polls = if deposit.is_zero() {
+        vec![]
+    } else {
+        _burn(
+            deps.storage,
+            env.block.height,
+            sender_id.as_bytes(),
+            deposit,
+        )?
+    };
+
+    let (kind, votes_per_option) = match options {
+        Some(options) => {
+            let votes_per_option = vec![Uint128::zero(); options.len()];
+            (PollKind::MultipleChoice { options }, votes_per_option)
+        }
+        None => (PollKind::Binary, vec![]),
+    };
 
     let mut state: State = state_store(deps.storage).load()?;
+
+    let (end_height, end_time) = match status {
+        PollStatus::InProgress => (
+            env.block.height + config.voting_period,
+            config.end_time(env.block.time),
+        ),
+        _ => (0, None),
+    };
+
     let poll_id = state.poll_count + 1;
 
     // Increase poll count & total deposit amount
@@ -67,522 +794,3670 @@ fn create_poll(
     let new_poll = Poll {
         id: poll_id,
         creator: sender_id.clone(),
-        status: PollStatus::InProgress,
+        status: status.clone(),
         yes_votes: Uint128::zero(),
         no_votes: Uint128::zero(),
-        end_height: env.block.height + config.voting_period,
+        end_height,
         title,
-        description,
-        link,
+        description: String::new(),
+        summary,
+        link: None,
+        links,
         total_share_at_start_poll: state.total_share,
         total_share_at_end_poll: None,
+        deposit,
+        deposit_claimed: false,
+        deposit_slashed: false,
+        ended_by: None,
+        ended_at_height: 0,
+        quorum: Some(config.quorum_for(&poll_type)),
+        threshold: Some(config.threshold_for(&poll_type)),
+        threshold_inclusive: Some(config.threshold_inclusive),
+        creator_wallet: wallet,
+        quorum_reached_height: None,
+        created_at_height: env.block.height,
+        extended_blocks: 0,
+        tie_extended: false,
+        poll_type,
+        kind,
+        votes_per_option,
+        winning_option: None,
+        depends_on,
+        force_ended: false,
+        raw_quorum: None,
+        quorum_used: None,
+        participation_anomaly: false,
+        end_time,
     };
 
-    poll_store(deps.storage).save(&poll_id.to_be_bytes(), &new_poll)?;
-    poll_indexer_store(deps.storage, &PollStatus::InProgress)
-        .save(&poll_id.to_be_bytes(), &true)?;
+    save_poll(deps.storage, &new_poll)?;
+    save_poll_description(deps.storage, poll_id, &description)?;
+    poll_indexer_store(deps.storage, &status).save(&poll_id.to_be_bytes(), &true)?;
 
     state_store(deps.storage).save(&state)?;
 
-    Ok(Response::new().add_attributes(vec![
-        ("action", "create_poll"),
-        ("creator", sender_id.as_str()),
-        ("poll_id", &poll_id.to_string()),
-        ("end_height", new_poll.end_height.to_string().as_str()),
-    ]))
+    let transition = state_transition(None, &new_poll, false, env.block.height)?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "create_poll"),
+            ("creator", sender_id.as_str()),
+            ("poll_id", &poll_id.to_string()),
+            ("draft", &draft.to_string()),
+            ("poll_type", &format!("{:?}", new_poll.poll_type)),
+            ("status", &new_poll.status.to_string()),
+            ("end_height", new_poll.end_height.to_string().as_str()),
+            ("deposit", &deposit.to_string()),
+        ])
+        .add_attributes(vec![transition])
+        .add_attributes(orphaned_polls_attribute(&orphaned_polls)))
 }
 
-/// cast vote (can't vote if delegated)
-fn cast_vote(
+/// open draft poll for voting (only creator)
+/// voting period and share snapshot start from publish height
+fn publish_poll(
     deps: DepsMut,
     env: Env,
-    voter_id: String,
+    sender_id: String,
     poll_id: u64,
-    vote: VoteOption,
 ) -> Result<Response, ContractError> {
-    let voter_key = voter_id.as_bytes();
-    let state = state_read(deps.storage).load()?;
+    let config: Config = config_read(deps.storage).load()?;
+    let state: State = state_read(deps.storage).load()?;
 
-    // check if valid poll id
-    if poll_id == 0 || state.poll_count < poll_id {
-        return Err(ContractError::PollNotFound {});
+    let mut a_poll: Poll = match may_load_poll(deps.storage, poll_id)? {
+        Some(poll) => poll,
+        None => return Err(ContractError::PollNotFound {}),
+    };
+    if a_poll.status != PollStatus::Draft {
+        return Err(ContractError::PollNotDraft {});
+    }
+    if a_poll.creator != sender_id {
+        return Err(ContractError::Unauthorized {});
+    }
+    // same expiry as prune_drafts, so a draft due for pruning can't go live
+    if let Some(draft_ttl) = config.draft_ttl {
+        let expired_at = a_poll.created_at_height + draft_ttl;
+        if expired_at <= env.block.height {
+            return Err(ContractError::DraftExpired { expired_at });
+        }
     }
 
-    // check if poll is in progress and not ended
-    let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
-    if a_poll.status != PollStatus::InProgress || env.block.height > a_poll.end_height {
-        return Err(ContractError::PollNotInProgress {});
+    let creator = load_member_or_new(deps.storage, sender_id.as_bytes())?;
+    assert_not_suspended(&creator, env.block.height)?;
+    assert_not_blocked(deps.storage, &sender_id)?;
+
+    // wait for dependency, voting clock starts when it passes
+    if let Some(dependency_id) = a_poll.depends_on {
+        if !dependency_passed(deps.storage, dependency_id)? {
+            a_poll.status = PollStatus::Pending;
+            save_poll(deps.storage, &a_poll)?;
+            move_poll_indexer(
+                deps.storage,
+                poll_id,
+                &PollStatus::Draft,
+                &PollStatus::Pending,
+            )?;
+            let transition =
+                state_transition(Some(PollStatus::Draft), &a_poll, false, env.block.height)?;
+            return Ok(Response::new()
+                .add_attributes(vec![
+                    ("action", "publish_poll"),
+                    ("creator", sender_id.as_str()),
+                    ("poll_id", &poll_id.to_string()),
+                    ("status", &a_poll.status.to_string()),
+                ])
+                .add_attributes(vec![transition]));
+        }
     }
 
-    // check if already voted
+    assert_not_season_blackout(&config, &state, env.block.height)?;
+
+    a_poll.status = PollStatus::InProgress;
+    a_poll.end_height = env.block.height + config.voting_period;
+    a_poll.end_time = config.end_time(env.block.time);
+    a_poll.total_share_at_start_poll = state.total_share;
+    a_poll.quorum = Some(config.quorum_for(&a_poll.poll_type));
+    a_poll.threshold = Some(config.threshold_for(&a_poll.poll_type));
+    a_poll.threshold_inclusive = Some(config.threshold_inclusive);
+    save_poll(deps.storage, &a_poll)?;
+
+    move_poll_indexer(
+        deps.storage,
+        poll_id,
+        &PollStatus::Draft,
+        &PollStatus::InProgress,
+    )?;
+
+    let transition = state_transition(Some(PollStatus::Draft), &a_poll, false, env.block.height)?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "publish_poll"),
+            ("creator", sender_id.as_str()),
+            ("poll_id", &poll_id.to_string()),
+            ("end_height", &a_poll.end_height.to_string()),
+        ])
+        .add_attributes(vec![transition]))
+}
+
+/// cancel in-progress poll nobody voted on yet (only creator), deposit is refunded
+fn withdraw_poll(
+    deps: DepsMut,
+    env: Env,
+    sender_id: String,
+    poll_id: u64,
+) -> Result<Response, ContractError> {
+    let mut a_poll: Poll = match may_load_poll(deps.storage, poll_id)? {
+        Some(poll) => poll,
+        None => return Err(ContractError::PollNotFound {}),
+    };
+    if a_poll.creator != sender_id {
+        return Err(ContractError::Unauthorized {});
+    }
+    if a_poll.status != PollStatus::InProgress {
+        return Err(ContractError::PollNotInProgress {});
+    }
+    // zero weight votes count as well
     if poll_voter_read(deps.storage, poll_id)
-        .load(voter_key)
-        .is_ok()
+        .range(None, None, Order::Ascending)
+        .next()
+        .is_some()
     {
-        return Err(ContractError::AlreadyVoted {});
+        return Err(ContractError::PollHasVotes {});
     }
 
-    let token_manager = bank_read(deps.storage)
-        .may_load(voter_key)?
-        .unwrap_or_default();
-
-    // delegated user can't cast vote (must undelegate first)
-    if token_manager.delegate_to.is_some() {
-        return Err(ContractError::AlreadyDelegated {});
-    }
+    let deposit = refund_deposit(deps.storage, &mut a_poll, env.block.height)?;
 
-    // cast my vote
-    let mut total_amount = cast_single_vote(deps.storage, voter_key, &mut a_poll, vote.clone())?;
+    move_poll_indexer(
+        deps.storage,
+        poll_id,
+        &PollStatus::InProgress,
+        &PollStatus::Cancelled,
+    )?;
 
-    // cast delegated votes
-    for id in token_manager.delegated_from.iter() {
-        let amount = cast_single_vote(deps.storage, id.as_bytes(), &mut a_poll, vote.clone())?;
-        total_amount += amount;
-    }
+    a_poll.status = PollStatus::Cancelled;
+    a_poll.ended_at_height = env.block.height;
+    save_poll(deps.storage, &a_poll)?;
 
-    poll_store(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
+    let transition = state_transition(
+        Some(PollStatus::InProgress),
+        &a_poll,
+        false,
+        env.block.height,
+    )?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "withdraw_poll"),
+            ("creator", sender_id.as_str()),
+            ("poll_id", &poll_id.to_string()),
+            ("refunded_deposit", &deposit.to_string()),
+        ])
+        .add_attributes(vec![transition]))
+}
 
-    Ok(Response::new().add_attributes(vec![
-        ("action", "cast_vote"),
-        ("poll_id", poll_id.to_string().as_str()),
-        ("my_share", token_manager.share.to_string().as_str()),
-        ("total_amount", total_amount.to_string().as_str()),
-        ("voter", voter_id.as_str()),
-        ("vote_option", vote.to_string().as_str()),
-    ]))
+/// whether dependency poll passed (false while it can still pass)
+/// a dependency removed since (pruned draft) can't pass anymore
+fn dependency_passed(storage: &dyn Storage, dependency_id: u64) -> Result<bool, ContractError> {
+    let dependency = match may_load_poll(storage, dependency_id)? {
+        Some(poll) => poll,
+        None => return Err(ContractError::DependencyFailed {}),
+    };
+    match dependency.status {
+        PollStatus::Passed | PollStatus::Executed => Ok(true),
+        PollStatus::Rejected | PollStatus::Failed | PollStatus::Cancelled => {
+            Err(ContractError::DependencyFailed {})
+        }
+        _ => Ok(false),
+    }
 }
 
-/// cast single vote used in cast vote
-/// not check already voted (not voted -> delegated, delegated -> not voted)
-/// delegated from member can't be voted
-fn cast_single_vote(
+/// open pending poll once its dependency passed (voting period starts now)
+/// or reject it once the dependency ended otherwise
+/// returns whether status of poll changed (poll is saved then)
+fn apply_dependency(
     storage: &mut dyn Storage,
-    voter_key: &[u8],
+    block: &BlockInfo,
     a_poll: &mut Poll,
-    vote: VoteOption,
-) -> Result<u128, ContractError> {
-    let poll_id = a_poll.id;
-    let mut token_manager = bank_read(storage).may_load(voter_key)?.unwrap_or_default();
+) -> Result<bool, ContractError> {
+    if !resolve_pending(storage, block, a_poll)? {
+        return Ok(false);
+    }
+    move_poll_indexer(storage, a_poll.id, &PollStatus::Pending, &a_poll.status)?;
+    // rejected poll refunds deposit to creator
+    if a_poll.status == PollStatus::Rejected {
+        refund_deposit(storage, a_poll, block.height)?;
+    }
+    save_poll(storage, a_poll)?;
+    Ok(true)
+}
 
-    let amount = token_manager.share;
-    // if amount.is_zero() {
-    //     return Ok(0);
-    // }
+/// apply_dependency without saving (poll is only updated in memory)
+fn resolve_pending(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    a_poll: &mut Poll,
+) -> Result<bool, ContractError> {
+    if a_poll.status != PollStatus::Pending {
+        return Ok(false);
+    }
+    let status = match a_poll.depends_on.map(|id| dependency_passed(storage, id)) {
+        Some(Ok(true)) => PollStatus::InProgress,
+        Some(Err(ContractError::DependencyFailed {})) => PollStatus::Rejected,
+        Some(Err(err)) => return Err(err),
+        _ => return Ok(false),
+    };
 
-    // increment yes/no votes
-    if vote == VoteOption::Yes {
-        a_poll.yes_votes += amount;
+    if status == PollStatus::InProgress {
+        let config: Config = config_read(storage).load()?;
+        let state: State = state_read(storage).load()?;
+        a_poll.end_height = block.height + config.voting_period;
+        a_poll.end_time = config.end_time(block.time);
+        a_poll.total_share_at_start_poll = state.total_share;
+        a_poll.quorum = Some(config.quorum_for(&a_poll.poll_type));
+        a_poll.threshold = Some(config.threshold_for(&a_poll.poll_type));
+        a_poll.threshold_inclusive = Some(config.threshold_inclusive);
     } else {
-        a_poll.no_votes += amount;
+        a_poll.ended_at_height = block.height;
     }
+    a_poll.status = status;
+    Ok(true)
+}
 
-    // save vote info to voter's token manager
-    let vote_info = VoteInfo {
-        vote,
-        balance: amount,
+/// resolve pending poll (anyone can call)
+pub fn resolve_dependency(
+    deps: DepsMut,
+    env: Env,
+    poll_id: u64,
+) -> Result<Response, ContractError> {
+    let mut a_poll: Poll = match may_load_poll(deps.storage, poll_id)? {
+        Some(poll) => poll,
+        None => return Err(ContractError::PollNotFound {}),
     };
-    token_manager
-        .locked_share
-        .push((poll_id, vote_info.clone()));
-    bank_store(storage).save(voter_key, &token_manager)?;
+    if a_poll.status != PollStatus::Pending {
+        return Err(ContractError::PollNotPending {});
+    }
 
-    // store poll voter, update poll data
-    poll_voter_store(storage, poll_id).save(voter_key, &vote_info)?;
+    let resolved = apply_dependency(deps.storage, &env.block, &mut a_poll)?;
+    let rejected_reason = if a_poll.status == PollStatus::Rejected {
+        "dependency failed"
+    } else {
+        ""
+    };
+
+    let response = Response::new().add_attributes(vec![
+        ("action", "resolve_dependency"),
+        ("poll_id", &poll_id.to_string()),
+        ("resolved", &resolved.to_string()),
+        ("status", &a_poll.status.to_string()),
+        ("rejected_reason", rejected_reason),
+        ("end_height", &a_poll.end_height.to_string()),
+    ]);
+    if !resolved {
+        return Ok(response);
+    }
+    let transition = state_transition(Some(PollStatus::Pending), &a_poll, false, env.block.height)?;
+    Ok(response.add_attributes(vec![transition]))
+}
 
-    Ok(amount.u128())
+/// poll by id (ids never issued are not found)
+fn load_existing_poll(storage: &dyn Storage, poll_id: u64) -> Result<Poll, ContractError> {
+    let state = state_read(storage).load()?;
+    if poll_id == 0 || state.poll_count < poll_id {
+        return Err(ContractError::PollNotFound {});
+    }
+    Ok(load_poll(storage, poll_id)?)
 }
 
-/// ends poll
-fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
-    let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
+/// check if poll is in progress and not ended
+fn check_poll_open(a_poll: &Poll, block: &BlockInfo) -> Result<(), ContractError> {
+    if a_poll.status == PollStatus::Draft {
+        return Err(ContractError::PollIsDraft {});
+    }
+    if a_poll.status != PollStatus::InProgress {
+        return Err(ContractError::PollNotInProgress {});
+    }
+    if !a_poll.voting_open(block) {
+        return Err(ContractError::PollEnded {});
+    }
+    Ok(())
+}
+
+/// within vote_freeze_window votes can't change, fresh votes are let through
+/// unless freeze_new_votes
+fn assert_not_frozen(
+    config: &Config,
+    a_poll: &Poll,
+    height: u64,
+    fresh_vote: bool,
+) -> Result<(), ContractError> {
+    if fresh_vote && !config.freeze_new_votes {
+        return Ok(());
+    }
+    match config.freeze_starts_at(a_poll) {
+        Some(starts_at) if height >= starts_at => Err(ContractError::VoteFrozen {
+            until_end: a_poll.end_height.saturating_sub(height),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// preconditions of cast_vote (dependency of poll is already resolved)
+/// returns token manager of voter
+fn check_cast_vote(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    voter_id: &str,
+    a_poll: &Poll,
+    vote: &VoteOption,
+    conviction: Option<Uint128>,
+) -> Result<TokenManager, ContractError> {
+    let voter_key = voter_id.as_bytes();
+    let height = block.height;
+    check_poll_open(a_poll, block)?;
+    if !a_poll.accepts(vote) {
+        return Err(ContractError::InvalidVoteOption {});
+    }
+    let config: Config = config_read(storage).load()?;
+    assert_not_frozen(&config, a_poll, height, true)?;
+
+    // check if already voted (including votes cast on my behalf by delegatee)
+    if poll_voter_read(storage, a_poll.id).load(voter_key).is_ok() {
+        return Err(ContractError::AlreadyVoted {});
+    }
+
+    let token_manager = load_member_or_new(storage, voter_key)?;
+
+    // delegated user can't cast vote (must undelegate first)
+    if let Some(delegatee) = &token_manager.delegate_to {
+        return Err(ContractError::AlreadyDelegatedTo {
+            delegatee: delegatee.clone(),
+        });
+    }
+
+    assert_not_suspended(&token_manager, height)?;
+    assert_not_blocked(storage, voter_id)?;
+
+    if let Some(conviction) = conviction {
+        if conviction.is_zero() || conviction > token_manager.balance {
+            return Err(ContractError::InvalidConviction {});
+        }
+        check_withdrawable(storage, height, &token_manager, conviction)?;
+    }
+    Ok(token_manager)
+}
+
+/// cast vote (can't vote if delegated)
+fn cast_vote(
+    deps: DepsMut,
+    env: Env,
+    voter_id: String,
+    wallet: String,
+    poll_id: u64,
+    vote: VoteOption,
+    conviction: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let voter_key = voter_id.as_bytes();
+    let mut a_poll = load_existing_poll(deps.storage, poll_id)?;
+    let from = a_poll.status.clone();
+    apply_dependency(deps.storage, &env.block, &mut a_poll)?;
+    let token_manager = check_cast_vote(
+        deps.storage,
+        &env.block,
+        &voter_id,
+        &a_poll,
+        &vote,
+        conviction,
+    )?;
+
+    // burn conviction before voting (only amplifies my own vote, share is after burn)
+    let mut orphaned_polls: Vec<u64> = vec![];
+    let my_conviction_weight = if let Some(conviction) = conviction {
+        orphaned_polls = _burn(deps.storage, env.block.height, voter_key, conviction)?;
+        conviction_weight(conviction)
+    } else {
+        Uint128::zero()
+    };
+
+    // cast my vote
+    let config: Config = config_read(deps.storage).load()?;
+    let height = env.block.height;
+    let multiplier = token_multiplier(deps.as_ref(), &config, &voter_id)?;
+    let quorum_reached_before = a_poll.quorum_reached_height.is_some();
+    let my_vote = cast_single_vote(
+        deps.storage,
+        &config,
+        height,
+        voter_key,
+        &mut a_poll,
+        vote.clone(),
+        &wallet,
+        None,
+        my_conviction_weight,
+        multiplier,
+    )?;
+    let my_share = my_vote.counted_balance().u128();
+    let mut total_amount = my_share + my_conviction_weight.u128();
+
+    // cast delegated votes (void while I was inactive, counted again from my next vote)
+    let delegations_void = config.delegate_inactive(&token_manager, height);
+    let mut delegated_votes: Vec<(String, u128)> = vec![];
+    let mut skipped_suspended: Vec<String> = vec![];
+    let mut skipped_blocked: Vec<String> = vec![];
+    let mut skipped_lock_cap: Vec<String> = vec![];
+    let mut capped_delegators: Vec<String> = vec![];
+    let delegated_from: &[String] = if delegations_void {
+        &[]
+    } else {
+        &token_manager.delegated_from
+    };
+    for id in delegated_from.iter() {
+        // delegator who voted personally (VoteOverride) is not counted again
+        if poll_voter_read(deps.storage, poll_id)
+            .may_load(id.as_bytes())?
+            .is_some()
+        {
+            continue;
+        }
+        // share of suspended delegator is not counted
+        let delegator = load_member_or_new(deps.storage, id.as_bytes())?;
+        if delegator.is_suspended(env.block.height) {
+            skipped_suspended.push(id.clone());
+            continue;
+        }
+        // share of blocked delegator is not counted
+        if blocklist_read(deps.storage)
+            .may_load(id.as_bytes())?
+            .is_some()
+        {
+            skipped_blocked.push(id.clone());
+            continue;
+        }
+        // delegator at MAX_LOCKED_POLLS doesn't block the delegate's vote
+        // each delegator is capped by max_vote_share on its own
+        let vote_info = match cast_single_vote(
+            deps.storage,
+            &config,
+            height,
+            id.as_bytes(),
+            &mut a_poll,
+            vote.clone(),
+            &wallet,
+            Some(voter_id.clone()),
+            Uint128::zero(),
+            None,
+        ) {
+            Err(ContractError::TooManyLockedPolls { .. }) => {
+                skipped_lock_cap.push(id.clone());
+                continue;
+            }
+            vote_info => vote_info?,
+        };
+        if vote_info.capped_balance.is_some() {
+            capped_delegators.push(id.clone());
+        }
+        let amount = vote_info.counted_balance().u128();
+        total_amount += amount;
+        delegated_votes.push((id.clone(), amount));
+    }
+
+    save_poll(deps.storage, &a_poll)?;
+
+    let mut response = Response::new().add_attributes(vec![
+        ("action", "cast_vote"),
+        ("poll_id", poll_id.to_string().as_str()),
+        ("my_share", my_share.to_string().as_str()),
+        ("total_amount", total_amount.to_string().as_str()),
+        ("voter", voter_id.as_str()),
+        ("vote_option", vote.to_string().as_str()),
+    ]);
+    if !my_conviction_weight.is_zero() {
+        response = response.add_attributes(vec![
+            (
+                "conviction_burned",
+                conviction.unwrap_or_default().to_string(),
+            ),
+            ("conviction_weight", my_conviction_weight.to_string()),
+        ]);
+        response = response.add_attributes(orphaned_polls_attribute(&orphaned_polls));
+    }
+
+    // one attribute per delegated vote (full breakdown is available by VoteReceipt query)
+    for (id, amount) in delegated_votes.iter().take(MAX_DELEGATED_ATTRIBUTES) {
+        response = response.add_attribute(format!("delegated_from_{}", id), amount.to_string());
+    }
+    if delegated_votes.len() > MAX_DELEGATED_ATTRIBUTES {
+        response = response.add_attribute("delegated_truncated", "true");
+    }
+    if !skipped_suspended.is_empty() {
+        response = response.add_attribute("skipped_suspended", skipped_suspended.join(","));
+    }
+    if !skipped_blocked.is_empty() {
+        response = response.add_attribute("skipped_blocked", skipped_blocked.join(","));
+    }
+    if my_vote.capped_balance.is_some() {
+        response = response.add_attribute("capped", "true");
+    }
+    if !capped_delegators.is_empty() {
+        response = response.add_attribute("capped_delegators", capped_delegators.join(","));
+    }
+    if !skipped_lock_cap.is_empty() {
+        response = response.add_attribute("skipped_lock_cap", skipped_lock_cap.join(","));
+    }
+    if delegations_void && !token_manager.delegated_from.is_empty() {
+        response = response.add_attribute("delegations_void", "true");
+    }
+    if !quorum_reached_before && a_poll.quorum_reached_height.is_some() {
+        response = response.add_attribute("quorum_reached", "true");
+    }
+    if let Some(finalized) = auto_finalize(deps, &env, &config, &mut a_poll, &voter_id)? {
+        response = response
+            .add_submessages(finalized.messages)
+            .add_attributes(finalized.attributes);
+    }
+
+    let transition = state_transition(Some(from), &a_poll, false, env.block.height)?;
+    Ok(response.add_attributes(vec![transition]))
+}
+
+/// cast vote on each poll in order (atomic: error names the failing poll)
+fn cast_votes(
+    mut deps: DepsMut,
+    env: Env,
+    voter_id: String,
+    wallet: String,
+    votes: Vec<(u64, VoteOption)>,
+) -> Result<Response, ContractError> {
+    if votes.is_empty() {
+        return Err(ContractError::DataShouldBeGiven {});
+    }
+    if votes.len() > MAX_BATCH_VOTES {
+        return Err(ContractError::TooManyVotes(MAX_BATCH_VOTES));
+    }
+
+    let mut response = Response::new()
+        .add_attributes(vec![("action", "cast_votes"), ("voter", voter_id.as_str())]);
+    let mut quorum_reached_polls: Vec<String> = vec![];
+    let mut finalized_polls: Vec<String> = vec![];
+    for (poll_id, vote) in votes {
+        let voted = cast_vote(
+            deps.branch(),
+            env.clone(),
+            voter_id.clone(),
+            wallet.clone(),
+            poll_id,
+            vote.clone(),
+            None,
+        )
+        .map_err(|err| ContractError::BatchVoteFailed {
+            poll_id,
+            reason: err.to_string(),
+        })?;
+
+        // "<vote option>:<total amount>" per poll
+        let attribute = |key: &str| {
+            voted
+                .attributes
+                .iter()
+                .find(|attr| attr.key == key)
+                .map(|attr| attr.value.clone())
+        };
+        response = response.add_attribute(
+            format!("poll_{}", poll_id),
+            format!("{}:{}", vote, attribute("total_amount").unwrap_or_default()),
+        );
+        if attribute("quorum_reached").is_some() {
+            quorum_reached_polls.push(poll_id.to_string());
+        }
+        if attribute("auto_finalized").is_some() {
+            finalized_polls.push(poll_id.to_string());
+        }
+        response = response.add_submessages(voted.messages).add_attributes(
+            voted
+                .attributes
+                .into_iter()
+                .filter(|attr| attr.key == "state_transition"),
+        );
+    }
+    if !quorum_reached_polls.is_empty() {
+        response = response.add_attribute("quorum_reached_polls", quorum_reached_polls.join(","));
+    }
+    if !finalized_polls.is_empty() {
+        response = response.add_attribute("auto_finalized_polls", finalized_polls.join(","));
+    }
+
+    Ok(response)
+}
+
+/// delegated member casts own vote on a single poll
+/// vote already cast on my behalf by delegatee is removed from the tally first
+fn vote_override(
+    deps: DepsMut,
+    env: Env,
+    voter_id: String,
+    wallet: String,
+    poll_id: u64,
+    vote: VoteOption,
+) -> Result<Response, ContractError> {
+    let voter_key = voter_id.as_bytes();
+    let mut a_poll = load_existing_poll(deps.storage, poll_id)?;
+    let from = a_poll.status.clone();
+    apply_dependency(deps.storage, &env.block, &mut a_poll)?;
+    check_poll_open(&a_poll, &env.block)?;
+    if !a_poll.accepts(&vote) {
+        return Err(ContractError::InvalidVoteOption {});
+    }
+
+    let mut token_manager = load_member_or_new(deps.storage, voter_key)?;
+
+    // not delegated member should cast vote
+    if token_manager.delegate_to.is_none() {
+        return Err(ContractError::NotYetDelegated {});
+    }
+
+    assert_not_suspended(&token_manager, env.block.height)?;
+    assert_not_blocked(deps.storage, voter_id.as_str())?;
+
+    // replacing the vote delegatee cast is a change, frozen within vote_freeze_window
+    let config: Config = config_read(deps.storage).load()?;
+    let cast_by_delegatee = poll_voter_read(deps.storage, poll_id).may_load(voter_key)?;
+    assert_not_frozen(
+        &config,
+        &a_poll,
+        env.block.height,
+        cast_by_delegatee.is_none(),
+    )?;
+
+    // remove vote cast by delegatee (own vote can't be overridden again)
+    let mut replaced = Uint128::zero();
+    if let Some(vote_info) = cast_by_delegatee {
+        if vote_info.via_delegate.is_none() {
+            return Err(ContractError::AlreadyVoted {});
+        }
+        a_poll.remove_vote(&vote_info.vote, vote_info.weight());
+        replaced = vote_info.balance;
+        token_manager.locked_share.retain(|(id, _)| *id != poll_id);
+        save_member(deps.storage, voter_key, &token_manager)?;
+    }
+
+    let multiplier = token_multiplier(deps.as_ref(), &config, &voter_id)?;
+    let quorum_reached_before = a_poll.quorum_reached_height.is_some();
+    let vote_info = cast_single_vote(
+        deps.storage,
+        &config,
+        env.block.height,
+        voter_key,
+        &mut a_poll,
+        vote.clone(),
+        &wallet,
+        None,
+        Uint128::zero(),
+        multiplier,
+    )?;
+    let amount = vote_info.counted_balance();
+    save_poll(deps.storage, &a_poll)?;
+
+    let mut response = Response::new().add_attributes(vec![
+        ("action", "vote_override"),
+        ("poll_id", poll_id.to_string().as_str()),
+        ("voter", voter_id.as_str()),
+        ("amount", amount.to_string().as_str()),
+        ("vote_option", vote.to_string().as_str()),
+        ("replaced_amount", replaced.to_string().as_str()),
+    ]);
+    if vote_info.capped_balance.is_some() {
+        response = response.add_attribute("capped", "true");
+    }
+    if !quorum_reached_before && a_poll.quorum_reached_height.is_some() {
+        response = response.add_attribute("quorum_reached", "true");
+    }
+    if let Some(finalized) = auto_finalize(deps, &env, &config, &mut a_poll, &voter_id)? {
+        response = response
+            .add_submessages(finalized.messages)
+            .add_attributes(finalized.attributes);
+    }
+    let transition = state_transition(Some(from), &a_poll, false, env.block.height)?;
+    Ok(response.add_attributes(vec![transition]))
+}
+
+/// cast single vote used in cast vote
+/// not check already voted (not voted -> delegated, delegated -> not voted)
+/// delegated from member can't be voted
+#[allow(clippy::too_many_arguments)]
+fn cast_single_vote(
+    storage: &mut dyn Storage,
+    config: &Config,
+    height: u64,
+    voter_key: &[u8],
+    a_poll: &mut Poll,
+    vote: VoteOption,
+    wallet: &str,
+    via_delegate: Option<String>,
+    conviction_weight: Uint128,
+    multiplier: Option<Decimal>,
+) -> Result<VoteInfo, ContractError> {
+    let poll_id = a_poll.id;
+    let mut token_manager = load_member_or_new(storage, voter_key)?;
+    if token_manager.locked_share.len() >= MAX_LOCKED_POLLS {
+        // locks of ended polls are only released lazily, drop them before refusing
+        release_locks(storage, &mut token_manager, voter_key, None)?;
+        save_member(storage, voter_key, &token_manager)?;
+        if token_manager.locked_share.len() >= MAX_LOCKED_POLLS {
+            return Err(ContractError::TooManyLockedPolls {
+                max: MAX_LOCKED_POLLS,
+            });
+        }
+    }
+
+    // share decayed for inactivity (delegated votes count as activity)
+    let amount = effective_share(config, &token_manager, height);
+    token_manager.last_vote_height = Some(height);
+    // if amount.is_zero() {
+    //     return Ok(0);
+    // }
+
+    let mut vote_info = VoteInfo {
+        vote,
+        balance: amount,
+        via_delegate,
+        wallet: wallet.to_string(),
+        conviction_weight,
+        multiplier,
+        capped_balance: None,
+    };
+    // cap is per member of the poll's share snapshot (conviction is paid for, not capped)
+    if let Some(max_vote_share) = config.max_vote_share {
+        let cap = share_cap(a_poll.total_share_at_start_poll, max_vote_share);
+        if vote_info.weighted_balance() > cap {
+            vote_info.capped_balance = Some(cap);
+        }
+    }
+
+    // increment tally of the option
+    a_poll.add_vote(&vote_info.vote, vote_info.weight());
+
+    // record when quorum is first reached
+    if a_poll.quorum_reached_height.is_none() && !a_poll.tallied().is_zero() {
+        let state: State = state_read(storage).load()?;
+        let quorum_required = a_poll
+            .quorum
+            .unwrap_or_else(|| config.quorum_for(&a_poll.poll_type));
+        if poll_quorum(a_poll, state.total_share) >= quorum_required {
+            a_poll.quorum_reached_height = Some(height);
+        }
+    }
+
+    // save vote info to voter's token manager
+    token_manager
+        .locked_share
+        .push((poll_id, vote_info.clone()));
+    save_member(storage, voter_key, &token_manager)?;
+
+    // store poll voter, update poll data
+    poll_voter_store(storage, poll_id).save(voter_key, &vote_info)?;
+
+    Ok(vote_info)
+}
+
+/// multiplier of the voter's own NFT with use_token_multiplier (delegated votes are unweighted)
+fn token_multiplier(deps: Deps, config: &Config, token_id: &str) -> StdResult<Option<Decimal>> {
+    if !config.use_token_multiplier {
+        return Ok(None);
+    }
+    let cw721_addr = deps.api.addr_humanize(&config.cw721_token)?;
+    Ok(Some(query_token_multiplier(
+        &deps.querier,
+        cw721_addr.as_str(),
+        token_id,
+        config.max_multiplier,
+    )))
+}
+
+/// ratio of tallied votes to staked share, at most one
+fn poll_quorum(a_poll: &Poll, total_share: Uint128) -> Decimal {
+    std::cmp::min(raw_poll_quorum(a_poll, total_share), Decimal::one())
+}
+
+/// ratio of tallied votes to staked share, above one if conviction weight was tallied
+/// while both total_share snapshots are small (voters can't exit while locked, but
+/// members who joined after the poll started can)
+fn raw_poll_quorum(a_poll: &Poll, total_share: Uint128) -> Decimal {
+    // if total_share is 0
+    if total_share.is_zero() {
+        Decimal::zero()
+    } else {
+        let staked_amount = std::cmp::max(a_poll.total_share_at_start_poll, total_share);
+        Decimal::from_ratio(a_poll.tallied(), staked_amount)
+    }
+}
+
+/// end poll through the EndPoll hook, ender member with share gets end_poll_reward
+fn end_poll_by_member(
+    deps: DepsMut,
+    env: Env,
+    ender_id: String,
+    wallet: String,
+    poll_id: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    // tokens without share (never minted or emptied) can end polls but not farm the reward
+    let has_share = may_load_member(deps.storage, ender_id.as_bytes())?
+        .map_or(false, |ender| !ender.share.is_zero());
+    match config.end_poll_permission {
+        EndPollPermission::Anyone => {}
+        EndPollPermission::OwnerOnly => assert_privileged(&deps, &config, &wallet)?,
+        EndPollPermission::TokenHolders => {
+            if !has_share {
+                return Err(ContractError::NotAMember {});
+            }
+        }
+    }
+    end_poll(deps, env, ender_id, has_share, poll_id)
+}
+
+/// end poll by sender address (ExecuteMsg::EndPoll), no end_poll_reward
+pub fn end_poll_by_address(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    match config.end_poll_permission {
+        EndPollPermission::Anyone => {}
+        EndPollPermission::OwnerOnly => assert_privileged(&deps, &config, info.sender.as_str())?,
+        // token holders end polls through the EndPoll hook
+        EndPollPermission::TokenHolders => return Err(ContractError::Unauthorized {}),
+    }
+    end_poll(deps, env, info.sender.to_string(), false, poll_id)
+}
+
+/// end poll without quorum / threshold, locks on it are released lazily once it
+/// is no longer in progress (no end_poll_reward, no ibc notification)
+pub fn force_end_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+    outcome: Option<PollStatus>,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
+
+    apply_force_end_poll(deps, env, info.sender.as_str(), poll_id, outcome)
+}
+
+fn apply_force_end_poll(
+    deps: DepsMut,
+    env: Env,
+    sender: &str,
+    poll_id: u64,
+    outcome: Option<PollStatus>,
+) -> Result<Response, ContractError> {
+    let poll_status = outcome.unwrap_or(PollStatus::Rejected);
+    if poll_status != PollStatus::Passed && poll_status != PollStatus::Rejected {
+        return Err(ContractError::InvalidForceEndOutcome {});
+    }
+
+    let mut a_poll: Poll = load_poll(deps.storage, poll_id)?;
+    let from = a_poll.status.clone();
+    if from != PollStatus::InProgress && from != PollStatus::Pending {
+        return Err(ContractError::PollNotInProgress {});
+    }
+
+    let total_share = state_read(deps.storage).load()?.total_share;
+    a_poll.force_ended = true;
+    close_poll(
+        deps.storage,
+        env.block.height,
+        &mut a_poll,
+        poll_status,
+        PollOutcome::ForceEnded,
+        sender,
+        total_share,
+    )?;
+
+    let transition = state_transition(Some(from), &a_poll, false, env.block.height)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "force_end_poll"),
+            ("poll_id", &poll_id.to_string()),
+            ("status", &a_poll.status.to_string()),
+            ("ended_by", sender),
+            ("ended_at_height", &env.block.height.to_string()),
+        ])
+        .add_attributes(vec![transition]))
+}
+
+/// ended_by is member_id (rewarded) or address of the sender
+fn end_poll(
+    deps: DepsMut,
+    env: Env,
+    ender_id: String,
+    rewarded: bool,
+    poll_id: u64,
+) -> Result<Response, ContractError> {
+    let mut a_poll: Poll = load_poll(deps.storage, poll_id)?;
+    let from = a_poll.status.clone();
+
+    // pending poll whose dependency didn't pass is ended as rejected
+    if apply_dependency(deps.storage, &env.block, &mut a_poll)?
+        && a_poll.status == PollStatus::Rejected
+    {
+        let transition = state_transition(Some(from), &a_poll, false, env.block.height)?;
+        return Ok(Response::new()
+            .add_attributes(vec![
+                ("action", "end_poll"),
+                ("poll_id", &poll_id.to_string()),
+                ("rejected_reason", "dependency failed"),
+                ("passed", "false"),
+                ("ended_by", &ender_id),
+                ("ended_at_height", &env.block.height.to_string()),
+            ])
+            .add_attributes(vec![transition]));
+    }
+
+    if a_poll.status != PollStatus::InProgress {
+        return Err(ContractError::PollNotInProgress {});
+    }
+
+    if !a_poll.voting_ended(&env.block) {
+        return Err(ContractError::PollVotingPeriod {});
+    }
+
+    let tallied_weight = a_poll.tallied();
+
+    let mut poll_status = PollStatus::Rejected;
+    let mut outcome = PollOutcome::QuorumNotReached;
+    let mut rejected_reason = "";
+    let mut passed = false;
+    let mut quorum_met = false;
+    let mut threshold_met = false;
+
+    let config: Config = config_read(deps.storage).load()?;
+    let state: State = state_read(deps.storage).load()?;
+    let total_share = state.total_share;
+
+    // quorum is against undecayed total_share: decay depends on each member's
+    // last vote so it can't be kept in aggregate, and inactive share should
+    // still count against quorum rather than lower it
+    let quorum = poll_quorum(&a_poll, total_share);
+
+    // prefer parameters snapshotted at poll creation
+    let quorum_required = config.end_quorum(
+        &state,
+        a_poll
+            .quorum
+            .unwrap_or_else(|| config.quorum_for(&a_poll.poll_type)),
+        env.block.height,
+    );
+    let threshold_required = a_poll
+        .threshold
+        .unwrap_or_else(|| config.threshold_for(&a_poll.poll_type));
+    let threshold_inclusive = a_poll
+        .threshold_inclusive
+        .unwrap_or(config.threshold_inclusive);
+
+    if tallied_weight.is_zero() || quorum < quorum_required {
+        rejected_reason = "Quorum not reached";
+    } else {
+        quorum_met = true;
+        match a_poll.kind {
+            PollKind::Binary => {
+                let tied = a_poll.yes_votes == a_poll.no_votes;
+                if tied && config.tie_policy == TiePolicy::ExtendOnce && !a_poll.tie_extended {
+                    // poll stays in progress for one more voting period
+                    a_poll.end_height += config.voting_period;
+                    a_poll.extend_end_time(config.extension_seconds(config.voting_period));
+                    a_poll.tie_extended = true;
+                    save_poll(deps.storage, &a_poll)?;
+                    let transition =
+                        state_transition(Some(from), &a_poll, false, env.block.height)?;
+                    return Ok(Response::new()
+                        .add_attributes(vec![
+                            ("action", "end_poll"),
+                            ("poll_id", &poll_id.to_string()),
+                            ("tie_extended", "true"),
+                            ("end_height", &a_poll.end_height.to_string()),
+                        ])
+                        .add_attributes(vec![transition]));
+                }
+
+                // poll passed (Reject and spent ExtendOnce fall back to threshold)
+                if (tied && config.tie_policy == TiePolicy::Pass)
+                    || threshold_reached(
+                        a_poll.yes_votes,
+                        tallied_weight,
+                        threshold_required,
+                        threshold_inclusive,
+                    )
+                {
+                    threshold_met = true;
+                    poll_status = PollStatus::Passed;
+                    outcome = PollOutcome::Passed;
+                    passed = true;
+                } else {
+                    outcome = PollOutcome::ThresholdNotReached;
+                    rejected_reason = "Threshold not reached";
+                }
+            }
+            // option with most votes wins (no threshold), tie is rejected
+            PollKind::MultipleChoice { .. } => match plurality_winner(&a_poll.votes_per_option) {
+                Some(index) => {
+                    threshold_met = true;
+                    poll_status = PollStatus::Passed;
+                    outcome = PollOutcome::Passed;
+                    passed = true;
+                    a_poll.winning_option = Some(index);
+                }
+                None => {
+                    outcome = PollOutcome::Tied;
+                    rejected_reason = "Tie between options";
+                }
+            },
+        }
+    }
+
+    let refunded_deposit = close_poll(
+        deps.storage,
+        env.block.height,
+        &mut a_poll,
+        poll_status,
+        outcome,
+        &ender_id,
+        total_share,
+    )?;
+
+    // incentive for timely finalization
+    let end_poll_reward = if rewarded {
+        config.end_poll_reward
+    } else {
+        Uint128::zero()
+    };
+    if !end_poll_reward.is_zero() {
+        _mint(
+            deps.storage,
+            ender_id.as_bytes(),
+            end_poll_reward,
+            env.block.height,
+        )?;
+    }
+
+    let response = notify_ibc(deps.as_ref(), &env, &config, &a_poll, passed)?;
+    let transition = state_transition(Some(from), &a_poll, false, env.block.height)?;
+
+    Ok(response
+        .add_attributes(vec![
+            ("action", "end_poll"),
+            ("poll_id", &poll_id.to_string()),
+            ("rejected_reason", rejected_reason),
+            ("passed", &passed.to_string()),
+            ("quorum_met", &quorum_met.to_string()),
+            ("threshold_met", &threshold_met.to_string()),
+            (
+                "winning_option",
+                &a_poll
+                    .winning_option
+                    .map(|index| index.to_string())
+                    .unwrap_or_default(),
+            ),
+            ("ended_by", &ender_id),
+            ("ended_at_height", &env.block.height.to_string()),
+            ("end_poll_reward", &end_poll_reward.to_string()),
+            ("refunded_deposit", &refunded_deposit.to_string()),
+            ("deposit_slashed", &a_poll.deposit_slashed.to_string()),
+        ])
+        .add_attributes(vec![transition]))
+}
+
+/// state_transition attribute of poll whose status was from before the execute
+/// (None: created), removed poll has no status after it
+fn state_transition(
+    from: Option<PollStatus>,
+    a_poll: &Poll,
+    removed: bool,
+    height: u64,
+) -> StdResult<Attribute> {
+    StateTransition {
+        v: STATE_TRANSITION_VERSION,
+        poll_id: a_poll.id,
+        from,
+        to: if removed {
+            None
+        } else {
+            Some(a_poll.status.clone())
+        },
+        yes: a_poll.yes_votes,
+        no: a_poll.no_votes,
+        height,
+    }
+    .attribute()
+}
+
+/// move in progress poll to its final status, returns the deposit refunded to the creator
+/// deposit of a poll that ended without quorum is slashed (it stays burned) instead
+fn close_poll(
+    storage: &mut dyn Storage,
+    height: u64,
+    a_poll: &mut Poll,
+    poll_status: PollStatus,
+    outcome: PollOutcome,
+    ended_by: &str,
+    total_share: Uint128,
+) -> Result<Uint128, ContractError> {
+    // Update poll indexer, remove from current (in progress) and add to new poll status indexer
+    move_poll_indexer(storage, a_poll.id, &a_poll.status.clone(), &poll_status)?;
+
+    let deposit = if outcome == PollOutcome::QuorumNotReached {
+        a_poll.deposit_slashed = !a_poll.deposit_claimed && !a_poll.deposit.is_zero();
+        Uint128::zero()
+    } else {
+        refund_deposit(storage, a_poll, height)?
+    };
+
+    // Update poll status
+    a_poll.status = poll_status;
+    a_poll.total_share_at_end_poll = Some(total_share);
+    let raw_quorum = raw_poll_quorum(a_poll, total_share);
+    a_poll.raw_quorum = Some(raw_quorum);
+    a_poll.quorum_used = Some(poll_quorum(a_poll, total_share));
+    a_poll.participation_anomaly = raw_quorum > Decimal::one();
+    a_poll.ended_by = Some(ended_by.to_string());
+    a_poll.ended_at_height = height;
+    save_poll(storage, a_poll)?;
+
+    // turnout history of adaptive quorum (kept even while it is off)
+    // force ended and unvoted polls say nothing about turnout and would drag the quorum down
+    if !a_poll.force_ended && !a_poll.tallied().is_zero() {
+        let mut state: State = state_store(storage).load()?;
+        state.recent_turnout.push(poll_quorum(a_poll, total_share));
+        if state.recent_turnout.len() > TURNOUT_WINDOW {
+            state.recent_turnout.remove(0);
+        }
+        state_store(storage).save(&state)?;
+    }
+
+    // outcome of creator's last poll decides CreatePoll cooldown (creator may have exited)
+    if let Some(mut creator) = may_load_member(storage, a_poll.creator.as_bytes())? {
+        creator.last_ended_poll = Some(EndedPoll {
+            poll_id: a_poll.id,
+            outcome,
+            ended_at_height: height,
+        });
+        save_member(storage, a_poll.creator.as_bytes(), &creator)?;
+    }
+    Ok(deposit)
+}
+
+/// binary poll passes right away if share not tallied yet can't change the outcome
+/// (config.auto_finalize), saved poll is ended by the voter without end_poll_reward
+/// returns response of the pass (ibc notification) or None if poll stays in progress
+fn auto_finalize(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    a_poll: &mut Poll,
+    voter_id: &str,
+) -> Result<Option<Response>, ContractError> {
+    if !config.auto_finalize || a_poll.kind != PollKind::Binary {
+        return Ok(None);
+    }
+    let state: State = state_read(deps.storage).load()?;
+    if !pass_decided(config, a_poll, &state, env.block.height) {
+        return Ok(None);
+    }
+
+    close_poll(
+        deps.storage,
+        env.block.height,
+        a_poll,
+        PollStatus::Passed,
+        PollOutcome::Passed,
+        voter_id,
+        state.total_share,
+    )?;
+    let response = notify_ibc(deps.as_ref(), env, config, a_poll, true)?;
+    Ok(Some(response.add_attribute("auto_finalized", "true")))
+}
+
+/// binary poll passes in end_poll whatever the share not tallied yet votes
+/// all staked share is assumed to be still able to vote no (share already tallied
+/// is counted twice, which only makes this stricter) as well as the conviction weight
+/// all members could still burn for; cancelled votes aren't share left to vote
+fn pass_decided(config: &Config, a_poll: &Poll, state: &State, height: u64) -> bool {
+    let total_share = state.total_share;
+    let tallied_weight = a_poll.tallied();
+    let quorum_required = config.end_quorum(
+        state,
+        a_poll
+            .quorum
+            .unwrap_or_else(|| config.quorum_for(&a_poll.poll_type)),
+        height,
+    );
+    // more votes only raise quorum
+    if tallied_weight.is_zero() || poll_quorum(a_poll, total_share) < quorum_required {
+        return false;
+    }
+
+    let mut remaining = std::cmp::max(a_poll.total_share_at_start_poll, total_share);
+    // share yet to vote may carry the largest token multiplier
+    if config.use_token_multiplier {
+        remaining = config.max_multiplier.max(Decimal::one()) * remaining;
+    }
+    // conviction weight isn't multiplied
+    remaining += max_conviction_weight(&config.voting_model, total_share, state.total_supply);
+    // a tie that would extend the poll must be out of reach as well
+    if config.tie_policy == TiePolicy::ExtendOnce
+        && !a_poll.tie_extended
+        && a_poll.yes_votes <= a_poll.no_votes + remaining
+    {
+        return false;
+    }
+    let threshold_required = a_poll
+        .threshold
+        .unwrap_or_else(|| config.threshold_for(&a_poll.poll_type));
+    let threshold_inclusive = a_poll
+        .threshold_inclusive
+        .unwrap_or(config.threshold_inclusive);
+    threshold_reached(
+        a_poll.yes_votes,
+        tallied_weight + remaining,
+        threshold_required,
+        threshold_inclusive,
+    )
+}
+
+/// packet carrying the poll result for ibc_receiver over ibc_channel (opened by this contract,
+/// see ibc.rs), failure doesn't block end_poll
+fn notify_ibc(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    a_poll: &Poll,
+    passed: bool,
+) -> Result<Response, ContractError> {
+    let response = Response::new();
+    let (channel_id, to_address, denom) =
+        match (&config.ibc_channel, &config.ibc_receiver, &config.ibc_denom) {
+            (Some(channel_id), Some(to_address), Some(denom)) if config.ibc_notify => {
+                (channel_id, to_address, denom)
+            }
+            _ => return Ok(response),
+        };
+
+    // a failing balance query must not block finalization either
+    let funded = match deps
+        .querier
+        .query_balance(env.contract.address.clone(), denom)
+    {
+        Ok(balance) => balance.amount >= Uint128::from(IBC_NOTIFY_AMOUNT),
+        Err(err) => return Ok(response.add_attribute("ibc_notify_error", err.to_string())),
+    };
+    if !funded {
+        return Ok(response.add_attribute(
+            "ibc_notify_error",
+            ContractError::InsufficientIbcFee {
+                denom: denom.clone(),
+            }
+            .to_string(),
+        ));
+    }
+
+    // transfers of cosmwasm-std 0.16 carry no memo, the result goes as packet data instead
+    let packet = IbcNotifyPacket {
+        receiver: to_address.clone(),
+        poll_id: a_poll.id,
+        passed,
+        yes: a_poll.yes_votes,
+        no: a_poll.no_votes,
+        height: env.block.height,
+    };
+    Ok(response.add_submessage(SubMsg::reply_on_error(
+        IbcMsg::SendPacket {
+            channel_id: channel_id.clone(),
+            data: to_binary(&packet)?,
+            timeout: env.block.time.plus_seconds(IBC_TIMEOUT_SECONDS).into(),
+        },
+        IBC_NOTIFY_REPLY_ID,
+    )))
+}
+
+/// index of the option with most votes, None if tied
+fn plurality_winner(votes_per_option: &[Uint128]) -> Option<u32> {
+    let max = votes_per_option.iter().max()?;
+    let mut winners = votes_per_option
+        .iter()
+        .enumerate()
+        .filter(|(_, votes)| *votes == max);
+    match (winners.next(), winners.next()) {
+        (Some((index, _)), None) => Some(index as u32),
+        _ => None,
+    }
+}
+
+/// finalize poll nobody ended as rejected (after grace period past end_height and end_time)
+/// and refund deposit to creator
+pub fn reclaim_deposit(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    let mut a_poll: Poll = match may_load_poll(deps.storage, poll_id)? {
+        Some(poll) => poll,
+        None => return Err(ContractError::PollNotFound {}),
+    };
+
+    if a_poll.status != PollStatus::InProgress {
+        return Err(ContractError::PollNotInProgress {});
+    }
+
+    // grace runs from whichever of end_height and end_time comes later
+    let grace_seconds = config.extension_seconds(RECLAIM_GRACE_PERIOD);
+    let in_grace = a_poll.end_height + RECLAIM_GRACE_PERIOD > env.block.height
+        || a_poll.end_time.map_or(false, |end_time| {
+            end_time.plus_seconds(grace_seconds) > env.block.time
+        });
+    if in_grace {
+        return Err(ContractError::ReclaimGracePeriod {});
+    }
+
+    let state: State = state_read(deps.storage).load()?;
+
+    let deposit = close_poll(
+        deps.storage,
+        env.block.height,
+        &mut a_poll,
+        PollStatus::Rejected,
+        PollOutcome::Abandoned,
+        "reclaim",
+        state.total_share,
+    )?;
+
+    let transition = state_transition(
+        Some(PollStatus::InProgress),
+        &a_poll,
+        false,
+        env.block.height,
+    )?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "reclaim_deposit"),
+            ("poll_id", &poll_id.to_string()),
+            ("rejected_reason", "Inactivity"),
+            ("creator", a_poll.creator.as_str()),
+            ("deposit", &deposit.to_string()),
+        ])
+        .add_attributes(vec![transition]))
+}
+
+/// remove drafts not published within draft_ttl
+/// scans the oldest drafts first, at most MAX_PRUNE_DRAFTS per call
+pub fn prune_drafts(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    let draft_ttl = match config.draft_ttl {
+        Some(draft_ttl) => draft_ttl,
+        None => return Ok(Response::new().add_attribute("action", "prune_drafts")),
+    };
+
+    let draft_keys = poll_indexer_store(deps.storage, &PollStatus::Draft)
+        .range(None, None, Order::Ascending)
+        .take(MAX_PRUNE_DRAFTS)
+        .map(|item| item.map(|(k, _)| k))
+        .collect::<StdResult<Vec<Vec<u8>>>>()?;
+
+    let mut pruned: Vec<String> = vec![];
+    let mut transitions: Vec<Attribute> = vec![];
+    for key in draft_keys {
+        let mut a_poll: Poll = load_poll(deps.storage, poll_id_from_key(&key)?)?;
+        if a_poll.created_at_height + draft_ttl <= env.block.height {
+            refund_deposit(deps.storage, &mut a_poll, env.block.height)?;
+            remove_poll(deps.storage, a_poll.id);
+            poll_indexer_store(deps.storage, &PollStatus::Draft).remove(&key);
+            pruned.push(a_poll.id.to_string());
+            transitions.push(state_transition(
+                Some(PollStatus::Draft),
+                &a_poll,
+                true,
+                env.block.height,
+            )?);
+        }
+    }
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "prune_drafts"),
+            ("pruned_polls", &pruned.join(",")),
+        ])
+        .add_attributes(transitions))
+}
+
+/// start next season, snapshot of ended season is recorded
+pub fn roll_season(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    let mut state: State = state_store(deps.storage).load()?;
+
+    let season_length = match config.season_length {
+        Some(season_length) => season_length,
+        None => return Err(ContractError::SeasonNotEnded {}),
+    };
+    if state.season_start_height + season_length > env.block.height {
+        return Err(ContractError::SeasonNotEnded {});
+    }
+
+    let snapshot = SeasonSnapshot {
+        season: state.current_season,
+        total_share: state.total_share,
+        poll_count: state.poll_count,
+        height: env.block.height,
+    };
+    season_store(deps.storage).save(&snapshot.season.to_be_bytes(), &snapshot)?;
+
+    state.current_season += 1;
+    state.season_start_height = env.block.height;
+    state_store(deps.storage).save(&state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "roll_season"),
+        ("ended_season", &snapshot.season.to_string()),
+        ("season", &state.current_season.to_string()),
+        ("total_share", &snapshot.total_share.to_string()),
+        ("poll_count", &snapshot.poll_count.to_string()),
+    ]))
+}
+
+/// admins must be unique and threshold between 1 and number of admins
+pub fn validate_admins(
+    admins: &[CanonicalAddr],
+    admin_threshold: u32,
+) -> Result<(), ContractError> {
+    if admin_threshold == 0 || admin_threshold as usize > admins.len() {
+        return Err(ContractError::InvalidAdminThreshold {});
+    }
+    for (i, admin) in admins.iter().enumerate() {
+        if admins[..i].contains(admin) {
+            return Err(ContractError::DuplicateAdmin {});
+        }
+    }
+    Ok(())
+}
+
+/// sender should be an admin
+fn assert_admin(deps: &DepsMut, config: &Config, sender: &str) -> Result<(), ContractError> {
+    if !config.is_admin(&deps.api.addr_canonicalize(sender)?) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+/// privileged action can be executed directly only if one approval is enough
+fn assert_privileged(deps: &DepsMut, config: &Config, sender: &str) -> Result<(), ContractError> {
+    assert_admin(deps, config, sender)?;
+    if config.admin_threshold > 1 {
+        return Err(ContractError::AdminApprovalRequired(config.admin_threshold));
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    admins: Option<Vec<String>>,
+    admin_threshold: Option<u32>,
+    quorum: Option<Decimal>,
+    threshold: Option<Decimal>,
+    voting_period: Option<u64>,
+    season_length: Option<Option<u64>>,
+    season_blackout: Option<u64>,
+    end_poll_reward: Option<Uint128>,
+    decay_after: Option<Option<u64>>,
+    decay_rate: Option<Decimal>,
+    decay_floor: Option<Decimal>,
+    bind_wallet: Option<bool>,
+    text_limits: Option<TextLimits>,
+    draft_ttl: Option<Option<u64>>,
+    threshold_inclusive: Option<bool>,
+    failed_poll_cooldown: Option<u64>,
+    signal_quorum: Option<Decimal>,
+    signal_threshold: Option<Decimal>,
+    max_extension: Option<u64>,
+    ibc_channel: Option<Option<String>>,
+    ibc_notify: Option<bool>,
+    ibc_receiver: Option<Option<String>>,
+    ibc_denom: Option<Option<String>>,
+    tie_policy: Option<TiePolicy>,
+    auto_finalize: Option<bool>,
+    max_supply: Option<Uint128>,
+    text_deposit: Option<Uint128>,
+    execute_deposit: Option<Uint128>,
+    adaptive_quorum: Option<bool>,
+    vote_freeze_window: Option<u64>,
+    freeze_new_votes: Option<bool>,
+    voting_time: Option<u64>,
+    use_token_multiplier: Option<bool>,
+    max_multiplier: Option<Decimal>,
+    bootstrap_period: Option<u64>,
+    bootstrap_quorum: Option<Decimal>,
+    delegate_inactivity_timeout: Option<u64>,
+    max_vote_share: Option<Decimal>,
+    dry_run: bool,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
+
+    apply_update_config(
+        deps,
+        env.block.height,
+        admins,
+        admin_threshold,
+        quorum,
+        threshold,
+        voting_period,
+        season_length,
+        season_blackout,
+        end_poll_reward,
+        decay_after,
+        decay_rate,
+        decay_floor,
+        bind_wallet,
+        text_limits,
+        draft_ttl,
+        threshold_inclusive,
+        failed_poll_cooldown,
+        signal_quorum,
+        signal_threshold,
+        max_extension,
+        ibc_channel,
+        ibc_notify,
+        ibc_receiver,
+        ibc_denom,
+        tie_policy,
+        auto_finalize,
+        max_supply,
+        text_deposit,
+        execute_deposit,
+        adaptive_quorum,
+        vote_freeze_window,
+        freeze_new_votes,
+        voting_time,
+        use_token_multiplier,
+        max_multiplier,
+        bootstrap_period,
+        bootstrap_quorum,
+        delegate_inactivity_timeout,
+        max_vote_share,
+        dry_run,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn apply_update_config(
+    deps: DepsMut,
+    height: u64,
+    admins: Option<Vec<String>>,
+    admin_threshold: Option<u32>,
+    quorum: Option<Decimal>,
+    threshold: Option<Decimal>,
+    voting_period: Option<u64>,
+    season_length: Option<Option<u64>>,
+    season_blackout: Option<u64>,
+    end_poll_reward: Option<Uint128>,
+    decay_after: Option<Option<u64>>,
+    decay_rate: Option<Decimal>,
+    decay_floor: Option<Decimal>,
+    bind_wallet: Option<bool>,
+    text_limits: Option<TextLimits>,
+    draft_ttl: Option<Option<u64>>,
+    threshold_inclusive: Option<bool>,
+    failed_poll_cooldown: Option<u64>,
+    signal_quorum: Option<Decimal>,
+    signal_threshold: Option<Decimal>,
+    max_extension: Option<u64>,
+    ibc_channel: Option<Option<String>>,
+    ibc_notify: Option<bool>,
+    ibc_receiver: Option<Option<String>>,
+    ibc_denom: Option<Option<String>>,
+    tie_policy: Option<TiePolicy>,
+    auto_finalize: Option<bool>,
+    max_supply: Option<Uint128>,
+    text_deposit: Option<Uint128>,
+    execute_deposit: Option<Uint128>,
+    adaptive_quorum: Option<bool>,
+    vote_freeze_window: Option<u64>,
+    freeze_new_votes: Option<bool>,
+    voting_time: Option<u64>,
+    use_token_multiplier: Option<bool>,
+    max_multiplier: Option<Decimal>,
+    bootstrap_period: Option<u64>,
+    bootstrap_quorum: Option<Decimal>,
+    delegate_inactivity_timeout: Option<u64>,
+    max_vote_share: Option<Decimal>,
+    dry_run: bool,
+) -> Result<Response, ContractError> {
+    let api = deps.api;
+    let mut config: Config = config_read(deps.storage).load()?;
+    if let Some(admins) = admins {
+        config.admins = admins
+            .iter()
+            .map(|admin| api.addr_canonicalize(admin))
+            .collect::<StdResult<Vec<CanonicalAddr>>>()?;
+    }
+    if let Some(admin_threshold) = admin_threshold {
+        config.admin_threshold = admin_threshold;
+    }
+    validate_admins(&config.admins, config.admin_threshold)?;
+    if let Some(quorum) = quorum {
+        validate_quorum(quorum)?;
+        config.quorum = quorum;
+    }
+    if let Some(threshold) = threshold {
+        validate_threshold(threshold)?;
+        config.threshold = threshold;
+    }
+    if let Some(voting_period) = voting_period {
+        validate_voting_period(voting_period)?;
+        config.voting_period = voting_period;
+    }
+    if let Some(season_length) = season_length {
+        if let Some(season_length) = season_length {
+            validate_season_length(season_length)?;
+        }
+        config.season_length = season_length;
+    }
+    if let Some(season_blackout) = season_blackout {
+        config.season_blackout = season_blackout;
+    }
+    if let Some(end_poll_reward) = end_poll_reward {
+        config.end_poll_reward = end_poll_reward;
+    }
+    if let Some(decay_after) = decay_after {
+        config.decay_after = decay_after;
+    }
+    if let Some(decay_rate) = decay_rate {
+        config.decay_rate = decay_rate;
+    }
+    if let Some(decay_floor) = decay_floor {
+        config.decay_floor = decay_floor;
+    }
+    validate_decay(config.decay_rate, config.decay_floor)?;
+    if let Some(bind_wallet) = bind_wallet {
+        config.bind_wallet = bind_wallet;
+    }
+    if let Some(text_limits) = text_limits {
+        validate_text_limits(&text_limits)?;
+        config.text_limits = text_limits;
+    }
+    if let Some(draft_ttl) = draft_ttl {
+        config.draft_ttl = draft_ttl;
+    }
+    if let Some(threshold_inclusive) = threshold_inclusive {
+        config.threshold_inclusive = threshold_inclusive;
+    }
+    if let Some(failed_poll_cooldown) = failed_poll_cooldown {
+        config.failed_poll_cooldown = failed_poll_cooldown;
+    }
+    if let Some(signal_quorum) = signal_quorum {
+        validate_quorum(signal_quorum)?;
+        config.signal_quorum = Some(signal_quorum);
+    }
+    if let Some(signal_threshold) = signal_threshold {
+        validate_threshold(signal_threshold)?;
+        config.signal_threshold = Some(signal_threshold);
+    }
+    if let Some(max_extension) = max_extension {
+        config.max_extension = max_extension;
+    }
+    if let Some(ibc_channel) = ibc_channel {
+        if let Some(ibc_channel) = &ibc_channel {
+            validate_ibc_channel(ibc_channel)?;
+        }
+        config.ibc_channel = ibc_channel;
+    }
+    if let Some(ibc_notify) = ibc_notify {
+        config.ibc_notify = ibc_notify;
+    }
+    if let Some(ibc_receiver) = ibc_receiver {
+        config.ibc_receiver = ibc_receiver;
+    }
+    if let Some(ibc_denom) = ibc_denom {
+        config.ibc_denom = ibc_denom;
+    }
+    if let Some(tie_policy) = tie_policy {
+        config.tie_policy = tie_policy;
+    }
+    if let Some(auto_finalize) = auto_finalize {
+        config.auto_finalize = auto_finalize;
+    }
+    if let Some(max_supply) = max_supply {
+        // no governance path to raise the cap, so it can only go down
+        if config
+            .max_supply
+            .map_or(false, |current| max_supply > current)
+        {
+            return Err(ContractError::CannotRaiseMaxSupply {});
+        }
+        let state: State = state_read(deps.storage).load()?;
+        if max_supply < state.total_supply {
+            return Err(ContractError::MaxSupplyExceeded {
+                max: max_supply,
+                attempted_total: state.total_supply,
+            });
+        }
+        config.max_supply = Some(max_supply);
+    }
+    if let Some(text_deposit) = text_deposit {
+        config.text_deposit = text_deposit;
+    }
+    if let Some(execute_deposit) = execute_deposit {
+        config.execute_deposit = execute_deposit;
+    }
+    if let Some(adaptive_quorum) = adaptive_quorum {
+        config.adaptive_quorum = adaptive_quorum;
+    }
+    if let Some(vote_freeze_window) = vote_freeze_window {
+        config.vote_freeze_window = vote_freeze_window;
+    }
+    if let Some(freeze_new_votes) = freeze_new_votes {
+        config.freeze_new_votes = freeze_new_votes;
+    }
+    if let Some(voting_time) = voting_time {
+        config.voting_time = voting_time;
+    }
+    if let Some(use_token_multiplier) = use_token_multiplier {
+        config.use_token_multiplier = use_token_multiplier;
+    }
+    if let Some(max_multiplier) = max_multiplier {
+        config.max_multiplier = max_multiplier;
+    }
+    if let Some(bootstrap_period) = bootstrap_period {
+        if bootstrap_period > 0 && bootstrap_quorum.is_none() {
+            return Err(ContractError::BootstrapQuorumRequired {});
+        }
+        // a closed bootstrap window stays closed
+        if bootstrap_period > config.bootstrap_period {
+            let state: State = state_read(deps.storage).load()?;
+            if !config.in_bootstrap(&state, height) {
+                return Err(ContractError::BootstrapEnded {});
+            }
+        }
+        config.bootstrap_period = bootstrap_period;
+    }
+    if let Some(bootstrap_quorum) = bootstrap_quorum {
+        validate_quorum(bootstrap_quorum)?;
+        config.bootstrap_quorum = bootstrap_quorum;
+    }
+    // 0 turns the timeout off
+    if let Some(delegate_inactivity_timeout) = delegate_inactivity_timeout {
+        config.delegate_inactivity_timeout =
+            Some(delegate_inactivity_timeout).filter(|timeout| *timeout > 0);
+    }
+    // 0 removes the cap
+    if let Some(max_vote_share) = max_vote_share {
+        config.max_vote_share = Some(max_vote_share).filter(|share| !share.is_zero());
+        if let Some(max_vote_share) = config.max_vote_share {
+            validate_max_vote_share(max_vote_share)?;
+        }
+    }
+
+    // dry run: only validate and show the would-be config
+    if !dry_run {
+        config_store(deps.storage).save(&config)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_config"),
+        ("dry_run", &dry_run.to_string()),
+        (
+            "admins",
+            &config
+                .admins
+                .iter()
+                .map(|admin| api.addr_humanize(admin).map(|addr| addr.to_string()))
+                .collect::<StdResult<Vec<String>>>()?
+                .join(","),
+        ),
+        ("admin_threshold", &config.admin_threshold.to_string()),
+        ("quorum", &config.quorum.to_string()),
+        ("threshold", &config.threshold.to_string()),
+        ("voting_period", &config.voting_period.to_string()),
+        (
+            "season_length",
+            &config
+                .season_length
+                .map(|season_length| season_length.to_string())
+                .unwrap_or_default(),
+        ),
+        ("season_blackout", &config.season_blackout.to_string()),
+        ("end_poll_reward", &config.end_poll_reward.to_string()),
+        (
+            "decay_after",
+            &config
+                .decay_after
+                .map(|decay_after| decay_after.to_string())
+                .unwrap_or_default(),
+        ),
+        ("decay_rate", &config.decay_rate.to_string()),
+        ("decay_floor", &config.decay_floor.to_string()),
+        ("bind_wallet", &config.bind_wallet.to_string()),
+        ("title_max", &config.text_limits.title_max.to_string()),
+        ("desc_max", &config.text_limits.desc_max.to_string()),
+        ("link_max", &config.text_limits.link_max.to_string()),
+        (
+            "draft_ttl",
+            &config
+                .draft_ttl
+                .map(|draft_ttl| draft_ttl.to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            "threshold_inclusive",
+            &config.threshold_inclusive.to_string(),
+        ),
+        (
+            "failed_poll_cooldown",
+            &config.failed_poll_cooldown.to_string(),
+        ),
+        (
+            "signal_quorum",
+            &config.quorum_for(&PollType::Signal).to_string(),
+        ),
+        (
+            "signal_threshold",
+            &config.threshold_for(&PollType::Signal).to_string(),
+        ),
+        ("max_extension", &config.max_extension.to_string()),
+        (
+            "ibc_channel",
+            &config.ibc_channel.clone().unwrap_or_default(),
+        ),
+        ("ibc_notify", &config.ibc_notify.to_string()),
+        (
+            "ibc_receiver",
+            &config.ibc_receiver.clone().unwrap_or_default(),
+        ),
+        ("ibc_denom", &config.ibc_denom.clone().unwrap_or_default()),
+        ("tie_policy", &format!("{:?}", config.tie_policy)),
+        ("auto_finalize", &config.auto_finalize.to_string()),
+        (
+            "max_supply",
+            &config
+                .max_supply
+                .map(|max_supply| max_supply.to_string())
+                .unwrap_or_default(),
+        ),
+        ("text_deposit", &config.text_deposit.to_string()),
+        ("execute_deposit", &config.execute_deposit.to_string()),
+        ("adaptive_quorum", &config.adaptive_quorum.to_string()),
+        ("vote_freeze_window", &config.vote_freeze_window.to_string()),
+        ("freeze_new_votes", &config.freeze_new_votes.to_string()),
+        ("voting_time", &config.voting_time.to_string()),
+        (
+            "use_token_multiplier",
+            &config.use_token_multiplier.to_string(),
+        ),
+        ("max_multiplier", &config.max_multiplier.to_string()),
+        ("bootstrap_period", &config.bootstrap_period.to_string()),
+        ("bootstrap_quorum", &config.bootstrap_quorum.to_string()),
+        (
+            "delegate_inactivity_timeout",
+            &config
+                .delegate_inactivity_timeout
+                .map(|timeout| timeout.to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            "max_vote_share",
+            &config
+                .max_vote_share
+                .map(|max_vote_share| max_vote_share.to_string())
+                .unwrap_or_default(),
+        ),
+    ]))
+}
+
+/// preconditions of delegate_vote
+fn check_delegate_vote(
+    storage: &dyn Storage,
+    height: u64,
+    voter_id: &str,
+    delegator_id: &str,
+) -> Result<(), ContractError> {
+    let token_manager = load_member_or_new(storage, voter_id.as_bytes())?;
+
+    // if voted in in progress polls
+    for (poll_id, _) in token_manager.locked_share.iter() {
+        if lock_is_active(storage, *poll_id)? {
+            return Err(ContractError::AlreadyVoted {});
+        }
+    }
+
+    // if already delegated to other
+    if token_manager.delegate_to.is_some() {
+        return Err(ContractError::AlreadyDelegated {});
+    }
+
+    assert_not_suspended(&token_manager, height)?;
+    assert_not_blocked(storage, voter_id)?;
+
+    // member receiving delegations can't delegate out
+    if !token_manager.delegated_from.is_empty() {
+        return Err(ContractError::ConflictingDelegation {});
+    }
+
+    // delegatee should be a current token holder
+    let delegatee = match may_load_member(storage, delegator_id.as_bytes())? {
+        Some(delegatee) if !delegatee.balance.is_zero() => delegatee,
+        _ => return Err(ContractError::DelegateeNotMember {}),
+    };
+
+    // walk delegate_to chain from delegatee, it must not reach back to voter nor get
+    // longer than MAX_DELEGATION_DEPTH hops (single hop rules below already forbid chains,
+    // this also catches self delegation and chains left by older versions)
+    let mut path = vec![voter_id.to_string(), delegator_id.to_string()];
+    if delegator_id == voter_id {
+        return Err(ContractError::DelegationCycle { path });
+    }
+    let mut next = delegatee.delegate_to.clone();
+    while let Some(current) = next {
+        path.push(current.clone());
+        if current == voter_id {
+            return Err(ContractError::DelegationCycle { path });
+        }
+        if path.len() - 1 > MAX_DELEGATION_DEPTH {
+            return Err(ContractError::DelegationChainTooLong(MAX_DELEGATION_DEPTH));
+        }
+        next = may_load_member(storage, current.as_bytes())?
+            .and_then(|token_manager| token_manager.delegate_to);
+    }
+
+    // delegatee delegating out can't receive delegations
+    if delegatee.delegate_to.is_some() {
+        return Err(ContractError::ConflictingDelegation {});
+    }
+
+    // stale entry would count voter's share twice in cast_vote
+    if delegatee.delegated_from.iter().any(|id| id == voter_id) {
+        return Err(ContractError::AlreadyDelegated {});
+    }
+    Ok(())
+}
+
+/// delegate my share
+/// should not be currently voted in in progress polls
+fn delegate_vote(
+    deps: DepsMut,
+    env: Env,
+    voter_id: String,
+    delegator_id: String,
+) -> Result<Response, ContractError> {
+    check_delegate_vote(deps.storage, env.block.height, &voter_id, &delegator_id)?;
+
+    // save in delegate to
+    let voter_key = voter_id.as_bytes();
+    let mut token_manager = load_member_or_new(deps.storage, voter_key)?;
+
+    // only leave in progress polls
+    let orphaned_polls = release_locks(deps.storage, &mut token_manager, voter_key, None)?;
+
+    token_manager.delegate_to = Some(delegator_id.clone());
+    save_member(deps.storage, voter_key, &token_manager)?;
+    let voter_share = token_manager.share;
+
+    // save in delegate from
+    let delegator_key = delegator_id.as_bytes();
+    let mut token_manager = load_member_or_new(deps.storage, delegator_key)?;
+    token_manager.delegated_from.push(voter_id.clone());
+    save_member(deps.storage, delegator_key, &token_manager)?;
+    adjust_delegated_in_share(deps.storage, &delegator_id, voter_share, Uint128::zero())?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "delegate"),
+            ("from", voter_id.as_str()),
+            ("to", delegator_id.as_str()),
+        ])
+        .add_attributes(orphaned_polls_attribute(&orphaned_polls)))
+}
+
+/// creator whose last poll failed quorum waits failed_poll_cooldown blocks
+fn assert_no_cooldown(
+    config: &Config,
+    creator: &TokenManager,
+    height: u64,
+) -> Result<(), ContractError> {
+    if let Some(last) = &creator.last_ended_poll {
+        let until_height = last.ended_at_height + config.failed_poll_cooldown;
+        if last.outcome == PollOutcome::QuorumNotReached && height < until_height {
+            return Err(ContractError::CooldownActive { until_height });
+        }
+    }
+    Ok(())
+}
+
+/// no new polls in the final blocks of a season
+fn assert_not_season_blackout(
+    config: &Config,
+    state: &State,
+    height: u64,
+) -> Result<(), ContractError> {
+    if let Some(season_length) = config.season_length {
+        let season_ends_at = state.season_start_height + season_length;
+        if height + config.season_blackout >= season_ends_at {
+            return Err(ContractError::SeasonBlackout { season_ends_at });
+        }
+    }
+    Ok(())
+}
+
+/// suspended member can't create poll, vote or delegate
+fn assert_not_suspended(token_manager: &TokenManager, height: u64) -> Result<(), ContractError> {
+    match token_manager.suspended_until {
+        Some(until) if token_manager.is_suspended(height) => {
+            Err(ContractError::MemberSuspended { until })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// blocked member can't create polls or vote
+fn assert_not_blocked(storage: &dyn Storage, member_id: &str) -> Result<(), ContractError> {
+    if blocklist_read(storage)
+        .may_load(member_id.as_bytes())?
+        .is_some()
+    {
+        return Err(ContractError::MemberBlocked {});
+    }
+    Ok(())
+}
+
+/// undelegate my share
+/// votes cast on my behalf by delegatee are withdrawn from polls still in voting period
+fn undelegate_vote(deps: DepsMut, env: Env, voter_id: String) -> Result<Response, ContractError> {
+    let (delegator, withdrawn_polls, transitions) =
+        release_delegation(deps.storage, &env.block, &voter_id)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "undelegate"),
+            ("from", voter_id.as_str()),
+            ("to", delegator.as_str()),
+            ("withdrawn_polls", &withdrawn_polls.join(",")),
+        ])
+        .add_attributes(transitions))
+}
+
+/// preconditions of undelegate_vote, returns delegatee
+fn check_undelegate_vote(storage: &dyn Storage, voter_id: &str) -> Result<String, ContractError> {
+    let token_manager = load_member_or_new(storage, voter_id.as_bytes())?;
+
+    // if not delegated to other
+    match token_manager.delegate_to {
+        Some(delegator) => Ok(delegator),
+        None => Err(ContractError::NotYetDelegated {}),
+    }
+}
+
+/// clear delegate_to of voter and remove voter from delegated_from of delegatee
+/// returns delegatee, polls whose delegated votes were withdrawn and their state_transition
+fn release_delegation(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    voter_id: &str,
+) -> Result<(String, Vec<String>, Vec<Attribute>), ContractError> {
+    let voter_key = voter_id.as_bytes();
+    let delegator = check_undelegate_vote(storage, voter_id)?;
+
+    // delete delegate to
+    let mut token_manager = load_member_or_new(storage, voter_key)?;
+
+    // withdraw delegated votes (otherwise my share is counted again if I vote directly)
+    // votes in polls within vote_freeze_window stay until the poll ends
+    let config: Config = config_read(storage).load()?;
+    let mut withdrawn_polls: Vec<String> = vec![];
+    let mut transitions: Vec<Attribute> = vec![];
+    let mut locked_share: Vec<(u64, VoteInfo)> = vec![];
+    for (poll_id, vote_info) in token_manager.locked_share.into_iter() {
+        if vote_info.via_delegate.as_deref() == Some(delegator.as_str()) {
+            if let Some(mut a_poll) = may_load_poll(storage, poll_id)? {
+                if a_poll.status == PollStatus::InProgress
+                    && a_poll.voting_open(block)
+                    && assert_not_frozen(&config, &a_poll, block.height, false).is_ok()
+                {
+                    a_poll.remove_vote(&vote_info.vote, vote_info.weight());
+                    save_poll(storage, &a_poll)?;
+                    poll_voter_store(storage, poll_id).remove(voter_key);
+                    withdrawn_polls.push(poll_id.to_string());
+                    transitions.push(state_transition(
+                        Some(PollStatus::InProgress),
+                        &a_poll,
+                        false,
+                        block.height,
+                    )?);
+                    continue;
+                }
+            }
+        }
+        locked_share.push((poll_id, vote_info));
+    }
+    token_manager.locked_share = locked_share;
+
+    token_manager.delegate_to = None;
+    save_member(storage, voter_key, &token_manager)?;
+    let voter_share = token_manager.share;
+
+    // delete in delegate from
+    let delegator_key = delegator.as_bytes();
+    let mut token_manager = load_member_or_new(storage, delegator_key)?;
+    // missing entry is tolerated so BreakDelegation can repair inconsistent state
+    if let Some(index) = token_manager
+        .delegated_from
+        .iter()
+        .position(|x| x == voter_id)
+    {
+        token_manager.delegated_from.swap_remove(index);
+    }
+
+    save_member(storage, delegator_key, &token_manager)?;
+    adjust_delegated_in_share(storage, &delegator, Uint128::zero(), voter_share)?;
+
+    Ok((delegator, withdrawn_polls, transitions))
+}
+
+/// release delegation of member whose delegatee is inactive (Config::delegate_inactive)
+/// returns attribute trail of the voided delegation (empty if it is kept)
+fn void_inactive_delegation(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    member_id: &str,
+) -> Result<Vec<Attribute>, ContractError> {
+    let config: Config = config_read(storage).load()?;
+    let delegatee_id = match may_load_member(storage, member_id.as_bytes())? {
+        Some(TokenManager {
+            delegate_to: Some(delegatee_id),
+            ..
+        }) if config.delegate_inactivity_timeout.is_some() => delegatee_id,
+        _ => return Ok(vec![]),
+    };
+    let delegatee = load_member_or_new(storage, delegatee_id.as_bytes())?;
+    if !config.delegate_inactive(&delegatee, block.height) {
+        return Ok(vec![]);
+    }
+
+    let (_, withdrawn_polls, transitions) = release_delegation(storage, block, member_id)?;
+    let mut attributes = vec![
+        Attribute::new("voided_delegation_to", delegatee_id),
+        Attribute::new("voided_withdrawn_polls", withdrawn_polls.join(",")),
+    ];
+    attributes.extend(transitions);
+    Ok(attributes)
+}
+
+/// undelegate up to limit members delegating to delegate_id once it is inactive
+/// delegated_from shrinks as delegators are released, so each call continues where
+/// the last one stopped ("done" once none are left)
+/// anyone can call
+pub fn sweep_inactive_delegations(
+    deps: DepsMut,
+    env: Env,
+    delegate_id: String,
+    limit: u32,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    let delegate = match may_load_member(deps.storage, delegate_id.as_bytes())? {
+        Some(delegate) => delegate,
+        None => return Err(ContractError::NotAMember {}),
+    };
+    if !config.delegate_inactive(&delegate, env.block.height) {
+        return Err(ContractError::DelegateActive {
+            last_vote_height: delegate.last_vote_height.unwrap_or_default(),
+        });
+    }
+
+    let limit = limit.min(MAX_SWEEP_LIMIT) as usize;
+    let mut released: Vec<String> = vec![];
+    let mut withdrawn: Vec<String> = vec![];
+    let mut transitions: Vec<Attribute> = vec![];
+    for delegator_id in delegate.delegated_from.iter().take(limit) {
+        let (_, withdrawn_polls, poll_transitions) =
+            release_delegation(deps.storage, &env.block, delegator_id)?;
+        released.push(delegator_id.clone());
+        withdrawn.extend(withdrawn_polls);
+        transitions.extend(poll_transitions);
+    }
+    let remaining = delegate.delegated_from.len() - released.len();
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "sweep_inactive_delegations"),
+            ("delegate_id", delegate_id.as_str()),
+            ("released_delegators", &released.join(",")),
+            ("withdrawn_polls", &withdrawn.join(",")),
+            ("remaining", &remaining.to_string()),
+            ("done", &(remaining == 0).to_string()),
+        ])
+        .add_attributes(transitions))
+}
+
+/// cast_single_vote would refuse the member with MAX_LOCKED_POLLS active locks
+/// (checked up front where the refusal must not release ended locks first)
+fn assert_lock_available(
+    storage: &dyn Storage,
+    token_manager: &TokenManager,
+) -> Result<(), ContractError> {
+    if token_manager.locked_share.len() < MAX_LOCKED_POLLS {
+        return Ok(());
+    }
+    let mut active = 0;
+    for (poll_id, _) in token_manager.locked_share.iter() {
+        if lock_is_active(storage, *poll_id)? {
+            active += 1;
+        }
+    }
+    if active >= MAX_LOCKED_POLLS {
+        return Err(ContractError::TooManyLockedPolls {
+            max: MAX_LOCKED_POLLS,
+        });
+    }
+    Ok(())
+}
+
+/// lock is kept by release_locks (poll exists and is in progress)
+fn lock_is_active(storage: &dyn Storage, poll_id: u64) -> StdResult<bool> {
+    Ok(matches!(
+        may_load_poll(storage, poll_id)?,
+        Some(poll) if poll.status == PollStatus::InProgress
+    ))
+}
+
+/// only leave locks of in progress polls (except release_poll_id)
+/// voter is removed from released polls
+/// lock of missing poll is released as well and its poll_id is returned
+/// (a poll that fails to load is an error, its lock is not dropped)
+fn release_locks(
+    storage: &mut dyn Storage,
+    token_manager: &mut TokenManager,
+    voter_key: &[u8],
+    release_poll_id: Option<u64>,
+) -> StdResult<Vec<u64>> {
+    let mut orphaned_polls: Vec<u64> = vec![];
+    // nothing to load (e.g. delegate_vote of a member who never voted)
+    if token_manager.locked_share.is_empty() {
+        return Ok(orphaned_polls);
+    }
+    let mut locked_share: Vec<(u64, VoteInfo)> = vec![];
+    for (poll_id, vote_info) in token_manager.locked_share.drain(..) {
+        match may_load_poll(storage, poll_id)? {
+            Some(poll) => {
+                if poll.status == PollStatus::InProgress && Some(poll_id) != release_poll_id {
+                    locked_share.push((poll_id, vote_info));
+                } else {
+                    // remove voter if lock is released
+                    poll_voter_store(storage, poll_id).remove(voter_key);
+                }
+            }
+            None => orphaned_polls.push(poll_id),
+        }
+    }
+    token_manager.locked_share = locked_share;
+    Ok(orphaned_polls)
+}
+
+/// attribute noting locks released because their poll does not exist
+fn orphaned_polls_attribute(orphaned_polls: &[u64]) -> Vec<(&'static str, String)> {
+    if orphaned_polls.is_empty() {
+        return vec![];
+    }
+    let ids: Vec<String> = orphaned_polls.iter().map(|id| id.to_string()).collect();
+    vec![("orphaned_polls", ids.join(","))]
+}
+
+/// attach external address to my membership
+/// a (namespace, address) can be claimed by only one member
+fn add_alias(
+    deps: DepsMut,
+    member_id: String,
+    namespace: String,
+    address: String,
+) -> Result<Response, ContractError> {
+    validate_alias(&namespace, &address)?;
+
+    let member_key = member_id.as_bytes();
+    let mut token_manager = load_member_or_new(deps.storage, member_key)?;
+
+    if token_manager.aliases.iter().any(|(ns, _)| *ns == namespace) {
+        return Err(ContractError::AliasAlreadySet {});
+    }
+    if token_manager.aliases.len() >= MAX_ALIASES {
+        return Err(ContractError::TooManyAliases(MAX_ALIASES));
+    }
+    if alias_read(deps.storage, &namespace)
+        .may_load(address.as_bytes())?
+        .is_some()
+    {
+        return Err(ContractError::AliasAlreadyClaimed {});
+    }
+
+    alias_store(deps.storage, &namespace).save(address.as_bytes(), &member_id)?;
+    token_manager
+        .aliases
+        .push((namespace.clone(), address.clone()));
+    save_member(deps.storage, member_key, &token_manager)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "add_alias"),
+        ("member", member_id.as_str()),
+        ("namespace", namespace.as_str()),
+        ("address", address.as_str()),
+    ]))
+}
+
+/// detach external address of namespace from my membership
+fn remove_alias(
+    deps: DepsMut,
+    member_id: String,
+    namespace: String,
+) -> Result<Response, ContractError> {
+    let member_key = member_id.as_bytes();
+    let mut token_manager = load_member_or_new(deps.storage, member_key)?;
+
+    let index = match token_manager
+        .aliases
+        .iter()
+        .position(|(ns, _)| *ns == namespace)
+    {
+        Some(index) => index,
+        None => return Err(ContractError::AliasNotFound {}),
+    };
+    let (_, address) = token_manager.aliases.remove(index);
+
+    alias_store(deps.storage, &namespace).remove(address.as_bytes());
+    save_member(deps.storage, member_key, &token_manager)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "remove_alias"),
+        ("member", member_id.as_str()),
+        ("namespace", namespace.as_str()),
+        ("address", address.as_str()),
+    ]))
+}
+
+/// return maximum balance between in progress polls
+/// (and poll ids of released orphaned locks)
+/// amount can be burned without touching balance locked in in progress polls
+fn check_withdrawable(
+    storage: &dyn Storage,
+    height: u64,
+    token_manager: &TokenManager,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    // vested balance stays until its release height
+    // (without any, an amount above balance is an invalid amount, not a vesting lock)
+    let vested = token_manager.vested_at(height);
+    if !vested.is_zero() && vested + amount > token_manager.balance {
+        return Err(ContractError::VestedLocked {
+            amount: vested,
+            until: token_manager.vested_until,
+        });
+    }
+
+    let config: Config = config_read(storage).load()?;
+    let mut locked_balance = 0u128;
+    for (poll_id, vote_info) in token_manager.locked_share.iter() {
+        if lock_is_active(storage, *poll_id)? {
+            locked_balance = locked_balance.max(vote_info.balance.u128());
+        }
+    }
+    if config.min_balance(locked_balance) + amount.u128() > token_manager.balance.u128() {
+        return Err(ContractError::InvalidWithdrawAmount {});
+    }
+    Ok(())
+}
+
+/// preconditions of cancel_vote, returns vote to cancel
+fn check_cancel_vote(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    voter_id: &str,
+    a_poll: &Poll,
+) -> Result<VoteInfo, ContractError> {
+    check_poll_open(a_poll, block)?;
+    let config: Config = config_read(storage).load()?;
+    assert_not_frozen(&config, a_poll, block.height, false)?;
+
+    // check if sender_address has voted
+    match poll_voter_read(storage, a_poll.id).may_load(voter_id.as_bytes())? {
+        Some(vote_info) => Ok(vote_info),
+        None => Err(ContractError::NotYetVoted {}),
+    }
+}
+
+fn cancel_vote(
+    deps: DepsMut,
+    env: Env,
+    voter_id: String,
+    poll_id: u64,
+) -> Result<Response, ContractError> {
+    let voter_key = voter_id.as_bytes();
+    let mut a_poll = load_existing_poll(deps.storage, poll_id)?;
+    let vote_info = check_cancel_vote(deps.storage, &env.block, &voter_id, &a_poll)?;
+
+    let mut token_manager = load_member_or_new(deps.storage, voter_key)?;
+
+    // decrement tally of the option
+    a_poll.remove_vote(&vote_info.vote, vote_info.weight());
+
+    // remove voter if poll is not in progress or poll is the same vote to cancel
+    let mut orphaned_polls =
+        release_locks(deps.storage, &mut token_manager, voter_key, Some(poll_id))?;
+
+    save_member(deps.storage, voter_key, &token_manager)?;
+
+    // votes I cast on behalf of my delegators are cancelled with mine
+    let mut withdrawn_delegators: Vec<String> = vec![];
+    for id in token_manager.delegated_from.iter() {
+        let delegator_key = id.as_bytes();
+        let delegated = match poll_voter_read(deps.storage, poll_id).may_load(delegator_key)? {
+            Some(delegated) if delegated.via_delegate.as_ref() == Some(&voter_id) => delegated,
+            _ => continue,
+        };
+        a_poll.remove_vote(&delegated.vote, delegated.weight());
+        let mut delegator = load_member_or_new(deps.storage, delegator_key)?;
+        orphaned_polls.extend(release_locks(
+            deps.storage,
+            &mut delegator,
+            delegator_key,
+            Some(poll_id),
+        )?);
+        save_member(deps.storage, delegator_key, &delegator)?;
+        withdrawn_delegators.push(id.clone());
+    }
+
+    save_poll(deps.storage, &a_poll)?;
+
+    let transition = state_transition(
+        Some(PollStatus::InProgress),
+        &a_poll,
+        false,
+        env.block.height,
+    )?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "cancel_vote"),
+            ("poll_id", poll_id.to_string().as_str()),
+            ("amount", vote_info.balance.to_string().as_str()),
+            ("voter", voter_id.as_str()),
+            ("vote_option", vote_info.vote.to_string().as_str()),
+            (
+                "withdrawn_delegators",
+                withdrawn_delegators.join(",").as_str(),
+            ),
+        ])
+        .add_attributes(vec![transition])
+        .add_attributes(orphaned_polls_attribute(&orphaned_polls)))
+}
+
+/// mint warrant tokens
+/// only admin can mint
+pub fn mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient_id: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
+
+    apply_mint(deps, env.block.height, recipient_id, amount)
+}
+
+fn apply_mint(
+    deps: DepsMut,
+    height: u64,
+    recipient_id: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    capped_mint(deps.storage, height, &recipient_id, amount)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "mint"),
+        ("to", &recipient_id),
+        ("amount", amount.to_string().as_str()),
+    ]))
+}
+
+/// _mint within max_supply
+fn capped_mint(
+    storage: &mut dyn Storage,
+    height: u64,
+    recipient_id: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InsufficientFunds {});
+    }
+
+    let config: Config = config_read(storage).load()?;
+    if let Some(max) = config.max_supply {
+        let state: State = state_read(storage).load()?;
+        let attempted_total = state.total_supply + amount;
+        if attempted_total > max {
+            return Err(ContractError::MaxSupplyExceeded {
+                max,
+                attempted_total,
+            });
+        }
+    }
+
+    _mint(storage, recipient_id.as_bytes(), amount, height)
+}
+
+/// mint vested balance (counts for share, locked until until_height)
+/// only admin can mint
+pub fn mint_locked(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient_id: String,
+    amount: Uint128,
+    until_height: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
+
+    apply_mint_locked(deps, env.block.height, recipient_id, amount, until_height)
+}
+
+fn apply_mint_locked(
+    deps: DepsMut,
+    height: u64,
+    recipient_id: String,
+    amount: Uint128,
+    until_height: Option<u64>,
+) -> Result<Response, ContractError> {
+    if let Some(until_height) = until_height.filter(|until| *until <= height) {
+        return Err(ContractError::VestingInPast { until_height });
+    }
+    capped_mint(deps.storage, height, &recipient_id, amount)?;
+
+    // vested balance is released at the latest release height (permanent wins)
+    let key = recipient_id.as_bytes();
+    let mut token_manager = load_member(deps.storage, key)?;
+    token_manager.vested_until = if token_manager.vested_at(height).is_zero() {
+        until_height
+    } else {
+        token_manager
+            .vested_until
+            .zip(until_height)
+            .map(|(a, b)| a.max(b))
+    };
+    token_manager.vested_locked = token_manager.vested_at(height) + amount;
+    save_member(deps.storage, key, &token_manager)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "mint_locked"),
+        ("to", recipient_id.as_str()),
+        ("amount", &amount.to_string()),
+        ("vested_locked", &token_manager.vested_locked.to_string()),
+        (
+            "vested_until",
+            &token_manager
+                .vested_until
+                .map(|until| until.to_string())
+                .unwrap_or_default(),
+        ),
+    ]))
+}
+
+/// vested balance past its release height becomes normal balance (anyone can call)
+pub fn release_vested(
+    deps: DepsMut,
+    env: Env,
+    member_id: String,
+) -> Result<Response, ContractError> {
+    let key = member_id.as_bytes();
+    let mut token_manager = match may_load_member(deps.storage, key)? {
+        Some(token_manager) => token_manager,
+        None => return Err(ContractError::NothingStaked {}),
+    };
+    if token_manager.vested_locked.is_zero() {
+        return Err(ContractError::NothingVested {});
+    }
+    if !token_manager.vested_at(env.block.height).is_zero() {
+        return Err(ContractError::VestedLocked {
+            amount: token_manager.vested_locked,
+            until: token_manager.vested_until,
+        });
+    }
+
+    let released = token_manager.vested_locked;
+    token_manager.vested_locked = Uint128::zero();
+    token_manager.vested_until = None;
+    save_member(deps.storage, key, &token_manager)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "release_vested"),
+        ("member_id", member_id.as_str()),
+        ("released", &released.to_string()),
+    ]))
+}
+
+/// preconditions of exit, returns token manager of member
+fn check_exit(
+    storage: &dyn Storage,
+    height: u64,
+    member_id: &str,
+) -> Result<TokenManager, ContractError> {
+    let token_manager = match may_load_member(storage, member_id.as_bytes())? {
+        Some(token_manager) => token_manager,
+        None => return Err(ContractError::NothingStaked {}),
+    };
+
+    // must undelegate first (otherwise delegatee keeps a stale delegated_from)
+    if token_manager.delegate_to.is_some() {
+        return Err(ContractError::UndelegateBeforeExit {});
+    }
+
+    check_withdrawable(storage, height, &token_manager, token_manager.balance)?;
+    Ok(token_manager)
+}
+
+/// member can burn token all
+/// bank entry is removed once no in-progress locks remain, delegators are undelegated
+fn exit(deps: DepsMut, block: &BlockInfo, sender_id: String) -> Result<Response, ContractError> {
+    let key = sender_id.as_bytes();
+    let token_manager = check_exit(deps.storage, block.height, &sender_id)?;
+
+    let amount = token_manager.balance;
+    let orphaned_polls = _burn(deps.storage, block.height, key, amount)?;
+
+    // remove empty bank entry
+    let token_manager = load_member(deps.storage, key)?;
+    let removed = token_manager.balance.is_zero() && token_manager.locked_share.is_empty();
+    let (released_delegators, transitions) = if removed {
+        remove_member(deps.storage, block, &sender_id, token_manager)?
+    } else {
+        (vec![], vec![])
+    };
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "exit"),
+            ("from", sender_id.as_str()),
+            ("amount", &amount.to_string()),
+            ("removed", &removed.to_string()),
+            ("released_delegators", &released_delegators.join(",")),
+        ])
+        .add_attributes(transitions)
+        .add_attributes(orphaned_polls_attribute(&orphaned_polls)))
+}
+
+/// preconditions of withdraw (all of balance is left to exit)
+fn check_withdraw(
+    storage: &dyn Storage,
+    height: u64,
+    member_id: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let token_manager = match may_load_member(storage, member_id.as_bytes())? {
+        Some(token_manager) => token_manager,
+        None => return Err(ContractError::NothingStaked {}),
+    };
+    if amount.is_zero() || amount >= token_manager.balance {
+        return Err(ContractError::InvalidWithdrawAmount {});
+    }
+    check_withdrawable(storage, height, &token_manager, amount)
+}
+
+/// burn part of balance, share (and weight of later votes) follows the new balance
+/// votes in in-progress polls keep their weight and lock
+fn withdraw(
+    deps: DepsMut,
+    block: &BlockInfo,
+    sender_id: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let key = sender_id.as_bytes();
+    check_withdraw(deps.storage, block.height, &sender_id, amount)?;
+    let orphaned_polls = _burn(deps.storage, block.height, key, amount)?;
+    let token_manager = load_member(deps.storage, key)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "withdraw"),
+            ("from", sender_id.as_str()),
+            ("amount", &amount.to_string()),
+            ("balance", &token_manager.balance.to_string()),
+            ("share", &token_manager.share.to_string()),
+        ])
+        .add_attributes(orphaned_polls_attribute(&orphaned_polls)))
+}
+
+/// remove zero balance bank entry left behind (e.g. exited before entries were removed)
+/// anyone can call
+pub fn prune_member(deps: DepsMut, env: Env, member_id: String) -> Result<Response, ContractError> {
+    let key = member_id.as_bytes();
+    let token_manager = match may_load_member(deps.storage, key)? {
+        Some(token_manager) => token_manager,
+        None => return Err(ContractError::NothingStaked {}),
+    };
+    if !token_manager.balance.is_zero() {
+        return Err(ContractError::MemberNotEmpty {});
+    }
+
+    // undelegate out (zero share has nothing to withdraw from in-progress polls)
+    let delegatee = match token_manager.delegate_to {
+        Some(_) => Some(release_delegation(deps.storage, &env.block, &member_id)?.0),
+        None => None,
+    };
+
+    let mut token_manager = load_member(deps.storage, key)?;
+    let orphaned_polls = release_locks(deps.storage, &mut token_manager, key, None)?;
+    if !token_manager.locked_share.is_empty() {
+        return Err(ContractError::MemberNotEmpty {});
+    }
+
+    let (released_delegators, transitions) =
+        remove_member(deps.storage, &env.block, &member_id, token_manager)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "prune_member"),
+            ("member_id", member_id.as_str()),
+            ("undelegated_from", &delegatee.unwrap_or_default()),
+            ("released_delegators", &released_delegators.join(",")),
+        ])
+        .add_attributes(transitions)
+        .add_attributes(orphaned_polls_attribute(&orphaned_polls)))
+}
+
+/// undelegate members delegating to member, release its aliases and remove its bank entry
+/// (its suspension and vote nonce are kept in a tombstone)
+/// returns released delegators and state_transition of polls their votes were withdrawn from
+fn remove_member(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    member_id: &str,
+    token_manager: TokenManager,
+) -> Result<(Vec<String>, Vec<Attribute>), ContractError> {
+    let mut transitions: Vec<Attribute> = vec![];
+    for delegator_id in token_manager.delegated_from.iter() {
+        transitions.extend(release_delegation(storage, block, delegator_id)?.2);
+    }
+    // drop index entry left by drift
+    set_delegated_in_share(storage, member_id, Uint128::zero())?;
+    for (namespace, address) in token_manager.aliases.iter() {
+        alias_store(storage, namespace).remove(address.as_bytes());
+    }
+    delete_member(storage, member_id.as_bytes(), &token_manager)?;
+
+    Ok((token_manager.delegated_from, transitions))
+}
+
+/// transfer from owner to recipient
+/// only callable by admin
+/// amount: None (transfer all)
+pub fn transfer_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner_id: String,
+    recipient_id: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
+
+    apply_transfer_from(
+        deps,
+        env.block.height,
+        info.sender.as_str(),
+        owner_id,
+        recipient_id,
+        amount,
+    )
+}
+
+fn apply_transfer_from(
+    deps: DepsMut,
+    height: u64,
+    sender: &str,
+    owner_id: String,
+    recipient_id: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let recipient_key = recipient_id.as_bytes();
+    let owner_key = owner_id.as_bytes();
+
+    if amount.is_zero() {
+        return Err(ContractError::InsufficientFunds {});
+    }
+    // burn then mint to the same member would leave balance and share as they are
+    // (share is recomputed from balance) but still release locks and touch supply records
+    if owner_id == recipient_id {
+        return Err(ContractError::TransferToSelf {});
+    }
+
+    let orphaned_polls = _burn(deps.storage, height, owner_key, amount)?;
+    _mint(deps.storage, recipient_key, amount, height)?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "transfer_from"),
+            ("from", &owner_id),
+            ("to", &recipient_id),
+            ("by", sender),
+            ("amount", amount.to_string().as_str()),
+        ])
+        .add_attributes(orphaned_polls_attribute(&orphaned_polls)))
+}
+
+/// propose privileged action, proposer approves it as well
+/// executed right away if admin_threshold is met
+pub fn propose_admin_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: AdminAction,
+    expires: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    assert_admin(&deps, &config, info.sender.as_str())?;
+
+    if expires <= env.block.height {
+        return Err(ContractError::AdminActionExpired {});
+    }
+    let pruned = prune_expired_admin_proposals(deps.storage, env.block.height)?;
+
+    let mut state: State = state_read(deps.storage).load()?;
+    state.admin_action_count += 1;
+    state_store(deps.storage).save(&state)?;
+
+    let proposer = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let proposal = AdminProposal {
+        id: state.admin_action_count,
+        action,
+        proposer: proposer.clone(),
+        approvals: vec![proposer],
+        expires,
+    };
+
+    let response = Response::new().add_attributes(vec![
+        ("action", "propose_admin_action"),
+        ("id", &proposal.id.to_string()),
+        ("proposer", info.sender.as_str()),
+        ("expires", &expires.to_string()),
+        ("pruned", &pruned.to_string()),
+    ]);
+    settle_admin_proposal(deps, env, &config, info.sender.as_str(), proposal, response)
+}
+
+/// approve privileged action, executed once admin_threshold is met
+pub fn approve_admin_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    assert_admin(&deps, &config, info.sender.as_str())?;
+
+    let mut proposal = match admin_proposal_read(deps.storage).may_load(&id.to_be_bytes())? {
+        Some(proposal) => proposal,
+        None => return Err(ContractError::AdminActionNotFound {}),
+    };
+
+    if proposal.expires <= env.block.height {
+        return Err(ContractError::AdminActionExpired {});
+    }
+    let pruned = prune_expired_admin_proposals(deps.storage, env.block.height)?;
 
-    if a_poll.status != PollStatus::InProgress {
-        return Err(ContractError::PollNotInProgress {});
+    let approver = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if proposal.approvals.contains(&approver) {
+        return Err(ContractError::AlreadyApproved {});
     }
+    proposal.approvals.push(approver);
 
-    if a_poll.end_height > env.block.height {
-        return Err(ContractError::PollVotingPeriod {});
+    let response = Response::new().add_attributes(vec![
+        ("action", "approve_admin_action"),
+        ("id", &id.to_string()),
+        ("approver", info.sender.as_str()),
+        ("pruned", &pruned.to_string()),
+    ]);
+    settle_admin_proposal(deps, env, &config, info.sender.as_str(), proposal, response)
+}
+
+/// execute and remove proposal if enough admins approved, otherwise save it
+/// approvals of addresses no longer admin are not counted
+fn settle_admin_proposal(
+    deps: DepsMut,
+    env: Env,
+    config: &Config,
+    sender: &str,
+    proposal: AdminProposal,
+    response: Response,
+) -> Result<Response, ContractError> {
+    let approvals = proposal
+        .approvals
+        .iter()
+        .filter(|approver| config.is_admin(approver))
+        .count();
+    let key = proposal.id.to_be_bytes();
+
+    if approvals < config.admin_threshold as usize {
+        admin_proposal_store(deps.storage).save(&key, &proposal)?;
+        return Ok(response.add_attributes(vec![
+            ("approvals", approvals.to_string()),
+            ("executed", false.to_string()),
+        ]));
     }
 
-    let no = a_poll.no_votes.u128();
-    let yes = a_poll.yes_votes.u128();
+    admin_proposal_store(deps.storage).remove(&key);
+    let height = env.block.height;
+    let executed = match proposal.action {
+        AdminAction::Mint { recipient, amount } => apply_mint(deps, height, recipient, amount)?,
+        AdminAction::MintLocked {
+            recipient,
+            amount,
+            until_height,
+        } => apply_mint_locked(deps, height, recipient, amount, until_height)?,
+        AdminAction::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => apply_transfer_from(deps, height, sender, owner, recipient, amount)?,
+        AdminAction::UpdateConfig {
+            admins,
+            admin_threshold,
+            quorum,
+            threshold,
+            voting_period,
+            season_length,
+            season_blackout,
+            end_poll_reward,
+            decay_after,
+            decay_rate,
+            decay_floor,
+            bind_wallet,
+            text_limits,
+            draft_ttl,
+            threshold_inclusive,
+            failed_poll_cooldown,
+            signal_quorum,
+            signal_threshold,
+            max_extension,
+            ibc_channel,
+            ibc_notify,
+            ibc_receiver,
+            ibc_denom,
+            tie_policy,
+            auto_finalize,
+            max_supply,
+            text_deposit,
+            execute_deposit,
+            adaptive_quorum,
+            vote_freeze_window,
+            freeze_new_votes,
+            voting_time,
+            use_token_multiplier,
+            max_multiplier,
+            bootstrap_period,
+            bootstrap_quorum,
+            delegate_inactivity_timeout,
+            max_vote_share,
+        } => apply_update_config(
+            deps,
+            height,
+            admins,
+            admin_threshold,
+            quorum,
+            threshold,
+            voting_period,
+            season_length,
+            season_blackout,
+            end_poll_reward,
+            decay_after,
+            decay_rate,
+            decay_floor,
+            bind_wallet,
+            text_limits,
+            draft_ttl,
+            threshold_inclusive,
+            failed_poll_cooldown,
+            signal_quorum,
+            signal_threshold,
+            max_extension,
+            ibc_channel,
+            ibc_notify,
+            ibc_receiver,
+            ibc_denom,
+            tie_policy,
+            auto_finalize,
+            max_supply,
+            text_deposit,
+            execute_deposit,
+            adaptive_quorum,
+            vote_freeze_window,
+            freeze_new_votes,
+            voting_time,
+            use_token_multiplier,
+            max_multiplier,
+            bootstrap_period,
+            bootstrap_quorum,
+            delegate_inactivity_timeout,
+            max_vote_share,
+            false,
+        )?,
+        AdminAction::ExtendPoll {
+            poll_id,
+            additional_blocks,
+        } => apply_admin_extend_poll(deps, env, config, sender, poll_id, additional_blocks)?,
+        AdminAction::SuspendMember {
+            member_id,
+            until_height,
+        } => apply_suspend_member(deps, height, member_id, until_height)?,
+        AdminAction::UnsuspendMember { member_id } => apply_unsuspend_member(deps, member_id)?,
+        AdminAction::BreakDelegation { member_id } => {
+            apply_break_delegation(deps, &env.block, member_id)?
+        }
+        AdminAction::RecomputeSupply {} => apply_recompute_supply(deps)?,
+        AdminAction::ForceEndPoll { poll_id, outcome } => {
+            apply_force_end_poll(deps, env, sender, poll_id, outcome)?
+        }
+    };
 
-    let tallied_weight = yes + no;
+    // attributes of executed action follow
+    Ok(response
+        .add_attributes(vec![
+            ("approvals", approvals.to_string()),
+            ("executed", true.to_string()),
+        ])
+        .add_attributes(executed.attributes))
+}
 
-    let mut poll_status = PollStatus::Rejected;
-    let mut rejected_reason = "";
-    let mut passed = false;
+/// remove expired admin proposals among the oldest ADMIN_PROPOSAL_PRUNE_SCAN
+/// returns number of removed proposals
+fn prune_expired_admin_proposals(
+    storage: &mut dyn Storage,
+    height: u64,
+) -> Result<usize, ContractError> {
+    let expired: Vec<Vec<u8>> = admin_proposal_read(storage)
+        .range(None, None, Order::Ascending)
+        .take(ADMIN_PROPOSAL_PRUNE_SCAN)
+        .filter_map(|item| match item {
+            Ok((key, proposal)) if proposal.expires <= height => Some(Ok(key)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<StdResult<_>>()?;
+    for key in expired.iter() {
+        admin_proposal_store(storage).remove(key);
+    }
+    Ok(expired.len())
+}
 
+/// extend voting period of in-progress poll
+/// only admin can extend
+pub fn extend_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+    additional_blocks: u64,
+) -> Result<Response, ContractError> {
     let config: Config = config_read(deps.storage).load()?;
-    let state: State = state_read(deps.storage).load()?;
-    let total_share = state.total_share;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
 
-    // if total_share is 0
-    let quorum = if state.total_share.u128() == 0 {
-        Decimal::zero()
-    } else {
-        let staked_amount = std::cmp::max(a_poll.total_share_at_start_poll, total_share);
-        Decimal::from_ratio(tallied_weight, staked_amount)
+    apply_admin_extend_poll(
+        deps,
+        env,
+        &config,
+        info.sender.as_str(),
+        poll_id,
+        additional_blocks,
+    )
+}
+
+fn apply_admin_extend_poll(
+    deps: DepsMut,
+    env: Env,
+    config: &Config,
+    sender: &str,
+    poll_id: u64,
+    additional_blocks: u64,
+) -> Result<Response, ContractError> {
+    let mut a_poll: Poll = match may_load_poll(deps.storage, poll_id)? {
+        Some(poll) => poll,
+        None => return Err(ContractError::PollNotFound {}),
     };
+    apply_extend_poll(deps, env, config, &mut a_poll, additional_blocks, sender)
+}
 
-    if tallied_weight == 0 || quorum < config.quorum {
-        rejected_reason = "Quorum not reached";
-    } else {
-        // poll passed
-        if Decimal::from_ratio(yes, tallied_weight) > config.threshold {
-            poll_status = PollStatus::Passed;
-            passed = true;
-        } else {
-            rejected_reason = "Threshold not reached";
-        }
+/// creator extends own poll (only before it reaches quorum)
+fn creator_extend_poll(
+    deps: DepsMut,
+    env: Env,
+    sender_id: String,
+    poll_id: u64,
+    additional_blocks: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    let mut a_poll: Poll = match may_load_poll(deps.storage, poll_id)? {
+        Some(poll) => poll,
+        None => return Err(ContractError::PollNotFound {}),
+    };
+    if a_poll.creator != sender_id {
+        return Err(ContractError::Unauthorized {});
+    }
+    if a_poll.quorum_reached_height.is_some() {
+        return Err(ContractError::QuorumAlreadyReached {});
     }
+    apply_extend_poll(
+        deps,
+        env,
+        &config,
+        &mut a_poll,
+        additional_blocks,
+        &sender_id,
+    )
+}
 
-    // Update poll indexer, remove from in progress and add to new poll status indexer
-    poll_indexer_store(deps.storage, &PollStatus::InProgress).remove(&a_poll.id.to_be_bytes());
-    poll_indexer_store(deps.storage, &poll_status).save(&a_poll.id.to_be_bytes(), &true)?;
+fn apply_extend_poll(
+    deps: DepsMut,
+    env: Env,
+    config: &Config,
+    a_poll: &mut Poll,
+    additional_blocks: u64,
+    extended_by: &str,
+) -> Result<Response, ContractError> {
+    if a_poll.status != PollStatus::InProgress {
+        return Err(ContractError::PollNotInProgress {});
+    }
+    if !a_poll.voting_open(&env.block) {
+        return Err(ContractError::PollEnded {});
+    }
+    if additional_blocks == 0 {
+        return Err(ContractError::ZeroExtension {});
+    }
+    let extended_blocks = a_poll.extended_blocks + additional_blocks;
+    if extended_blocks > config.max_extension {
+        return Err(ContractError::ExtensionExceeded(config.max_extension));
+    }
 
-    // Update poll status
-    a_poll.status = poll_status;
-    a_poll.total_share_at_end_poll = Some(total_share);
-    poll_store(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
+    a_poll.end_height += additional_blocks;
+    a_poll.extend_end_time(config.extension_seconds(additional_blocks));
+    a_poll.extended_blocks = extended_blocks;
+    save_poll(deps.storage, a_poll)?;
 
-    Ok(Response::new().add_attributes(vec![
-        ("action", "end_poll"),
-        ("poll_id", &poll_id.to_string()),
-        ("rejected_reason", rejected_reason),
-        ("passed", &passed.to_string()),
-    ]))
+    let transition = state_transition(
+        Some(PollStatus::InProgress),
+        a_poll,
+        false,
+        env.block.height,
+    )?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "extend_poll"),
+            ("poll_id", &a_poll.id.to_string()),
+            ("extended_by", extended_by),
+            ("additional_blocks", &additional_blocks.to_string()),
+            ("end_height", &a_poll.end_height.to_string()),
+        ])
+        .add_attributes(vec![transition]))
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn update_config(
+/// suspend member from governance actions until until_height
+/// only admin can suspend
+pub fn suspend_member(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    owner: Option<String>,
-    quorum: Option<Decimal>,
-    threshold: Option<Decimal>,
-    voting_period: Option<u64>,
+    member_id: String,
+    until_height: u64,
 ) -> Result<Response, ContractError> {
-    let api = deps.api;
-    config_store(deps.storage).update(|mut config| {
-        if config.owner != api.addr_canonicalize(info.sender.as_str())? {
-            return Err(ContractError::Unauthorized {});
-        }
-        if let Some(owner) = owner {
-            config.owner = api.addr_canonicalize(&owner)?;
-        }
-        if let Some(quorum) = quorum {
-            config.quorum = quorum;
-        }
-        if let Some(threshold) = threshold {
-            config.threshold = threshold;
-        }
-        if let Some(voting_period) = voting_period {
-            config.voting_period = voting_period;
-        }
-        Ok(config)
-    })?;
+    let config: Config = config_read(deps.storage).load()?;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
 
-    Ok(Response::new().add_attributes(vec![("action", "update_config")]))
+    apply_suspend_member(deps, env.block.height, member_id, until_height)
 }
 
-/// delegate my share (
-/// should not be currently voted in in progress polls
-fn delegate_vote(
+fn apply_suspend_member(
     deps: DepsMut,
-    voter_id: String,
-    delegator_id: String,
+    height: u64,
+    member_id: String,
+    until_height: u64,
 ) -> Result<Response, ContractError> {
-    // save in delegate to
-    let voter_key = voter_id.as_bytes();
-    let mut token_manager = bank_read(deps.storage)
-        .may_load(voter_key)?
-        .unwrap_or_default();
-
-    // only leave in progress polls
-    token_manager.locked_share.retain(|(poll_id, _)| {
-        let poll: Poll = poll_read(deps.storage)
-            .load(&poll_id.to_be_bytes())
-            .unwrap();
-
-        // remove voter if poll is not in progress
-        if poll.status != PollStatus::InProgress {
-            poll_voter_store(deps.storage, *poll_id).remove(voter_key);
-        }
+    let member_key = member_id.as_bytes();
+    let mut token_manager = match may_load_member(deps.storage, member_key)? {
+        Some(token_manager) => token_manager,
+        None => return Err(ContractError::NotAMember {}),
+    };
+    token_manager.suspended_until = Some(until_height);
+    save_member(deps.storage, member_key, &token_manager)?;
 
-        poll.status == PollStatus::InProgress
-    });
+    Ok(Response::new().add_attributes(vec![
+        ("action", "suspend_member"),
+        ("member", member_id.as_str()),
+        ("until_height", &until_height.to_string()),
+        ("suspended", &token_manager.is_suspended(height).to_string()),
+    ]))
+}
 
-    // if voted in in progress polls
-    if token_manager.locked_share.len() != 0 {
-        return Err(ContractError::AlreadyVoted {});
-    }
+/// add member to (or remove from) blocklist
+/// only admin can set blocked, balance and share of blocked member are kept
+pub fn set_blocked(
+    deps: DepsMut,
+    info: MessageInfo,
+    member_id: String,
+    blocked: bool,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
 
-    // if already delegated to other
-    if token_manager.delegate_to.is_some() {
-        return Err(ContractError::AlreadyDelegated {});
+    if blocked {
+        blocklist_store(deps.storage).save(member_id.as_bytes(), &true)?;
+    } else {
+        blocklist_store(deps.storage).remove(member_id.as_bytes());
     }
-    token_manager.delegate_to = Some(delegator_id.clone());
-    bank_store(deps.storage).save(voter_key, &token_manager)?;
-
-    // save in delegate from
-    let delegator_key = delegator_id.as_bytes();
-    let mut token_manager = bank_read(deps.storage)
-        .may_load(delegator_key)?
-        .unwrap_or_default();
-    token_manager.delegated_from.push(voter_id.clone());
-    bank_store(deps.storage).save(delegator_key, &token_manager)?;
 
     Ok(Response::new().add_attributes(vec![
-        ("action", "delegate"),
-        ("from", voter_id.as_str()),
-        ("to", delegator_id.as_str()),
+        ("action", "set_blocked"),
+        ("member", member_id.as_str()),
+        ("blocked", &blocked.to_string()),
     ]))
 }
 
-/// undelegate my share
-fn undelegate_vote(deps: DepsMut, voter_id: String) -> Result<Response, ContractError> {
-    let voter_key = voter_id.as_bytes();
-    // delete delegate to
-    let mut token_manager = bank_read(deps.storage)
-        .may_load(voter_key)?
-        .unwrap_or_default();
+/// fix poll indexers of a page of polls against their stored status (only admin)
+/// next_cursor attribute is start_after of the next call (empty after the last page)
+pub fn rebuild_poll_index(
+    deps: DepsMut,
+    info: MessageInfo,
+    start_after: Option<u64>,
+    limit: u32,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
 
-    // if not delegated to other
-    if token_manager.delegate_to.is_none() {
-        return Err(ContractError::NotYetDelegated {});
+    let (polls, next_cursor) = read_poll_page(deps.storage, start_after, limit)?;
+    let mut fixed_polls: Vec<String> = vec![];
+    for poll in polls.iter() {
+        let (stale, missing) = poll_index_drift(deps.storage, poll)?;
+        if stale.is_empty() && !missing {
+            continue;
+        }
+        for status in stale.iter() {
+            poll_indexer_store(deps.storage, status).remove(&poll.id.to_be_bytes());
+        }
+        if missing {
+            poll_indexer_store(deps.storage, &poll.status).save(&poll.id.to_be_bytes(), &true)?;
+        }
+        fixed_polls.push(poll.id.to_string());
     }
-    let delegator = token_manager.delegate_to.unwrap();
-
-    token_manager.delegate_to = None;
-    bank_store(deps.storage).save(voter_key, &token_manager)?;
-
-    // delete in delegate from
-    let delegator_key = delegator.as_bytes();
-    let mut token_manager = bank_read(deps.storage)
-        .may_load(delegator_key)?
-        .unwrap_or_default();
-    let delegated_from = token_manager.delegated_from.clone();
-    let index = delegated_from
-        .into_iter()
-        .position(|x| x == voter_id)
-        .unwrap();
-    token_manager.delegated_from.swap_remove(index);
-
-    bank_store(deps.storage).save(delegator_key, &token_manager)?;
 
     Ok(Response::new().add_attributes(vec![
-        ("action", "undelegate"),
-        ("from", voter_id.as_str()),
-        ("to", delegator.as_str()),
+        ("action", "rebuild_poll_index"),
+        ("scanned", &polls.len().to_string()),
+        ("fixed_polls", &fixed_polls.join(",")),
+        (
+            "next_cursor",
+            &next_cursor.map(|id| id.to_string()).unwrap_or_default(),
+        ),
     ]))
 }
 
-/// return maximum balance between in progress polls
-fn compute_locked_balance(
-    storage: &mut dyn Storage,
-    token_manager: &mut TokenManager,
-    voter_key: &[u8],
-) -> u128 {
-    // only leave in progress polls
-    token_manager.locked_share.retain(|(poll_id, _)| {
-        let poll: Poll = poll_read(storage).load(&poll_id.to_be_bytes()).unwrap();
+/// migration step, see snapshot_poll_parameters
+pub fn snapshot_poll_parameters_page(
+    deps: DepsMut,
+    start_after: Option<u64>,
+    limit: u32,
+) -> Result<Response, ContractError> {
+    let (snapshotted, next_cursor) = snapshot_poll_parameters(deps.storage, start_after, limit)?;
 
-        // remove voter if poll is not in progress
-        if poll.status != PollStatus::InProgress {
-            poll_voter_store(storage, *poll_id).remove(voter_key);
-        }
+    Ok(Response::new().add_attributes(vec![
+        ("action", "snapshot_poll_parameters"),
+        ("snapshotted_polls", &snapshotted.to_string()),
+        (
+            "next_cursor",
+            &next_cursor.map(|id| id.to_string()).unwrap_or_default(),
+        ),
+    ]))
+}
 
-        poll.status == PollStatus::InProgress
-    });
+/// migration step, see recompute_delegated_in_share
+pub fn recompute_delegated_share_page(
+    deps: DepsMut,
+    start_after: Option<String>,
+    limit: u32,
+) -> Result<Response, ContractError> {
+    let (recomputed, next_cursor) = recompute_delegated_in_share(deps.storage, start_after, limit)?;
 
-    token_manager
-        .locked_share
-        .iter()
-        .map(|(_, v)| v.balance.u128())
-        .max()
-        .unwrap_or_default()
+    Ok(Response::new().add_attributes(vec![
+        ("action", "recompute_delegated_share"),
+        ("recomputed_members", &recomputed.to_string()),
+        ("next_cursor", &next_cursor.unwrap_or_default()),
+    ]))
 }
 
-fn cancel_vote(
+/// draw committee weighted by share (only admin), one page of the bank per call,
+/// a member is weighed by its share when its page is scanned
+/// seed is sha256 of the ended seed poll's tallies and the height and time of the first call,
+/// so the admin can't pick members without also picking the block it lands in
+pub fn draw_committee(
     deps: DepsMut,
     env: Env,
-    voter_id: String,
-    poll_id: u64,
+    info: MessageInfo,
+    size: u8,
+    seed_poll_id: u64,
 ) -> Result<Response, ContractError> {
-    let voter_key = voter_id.as_bytes();
-    let state = state_read(deps.storage).load()?;
+    let config: Config = config_read(deps.storage).load()?;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
 
-    // check if valid poll id
-    if poll_id == 0 || state.poll_count < poll_id {
-        return Err(ContractError::PollNotFound {});
+    if size == 0 {
+        return Err(ContractError::InvalidCommitteeSize(0));
     }
 
-    // check if poll is in progress and not ended
-    let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
-    if a_poll.status != PollStatus::InProgress || env.block.height > a_poll.end_height {
-        return Err(ContractError::PollNotInProgress {});
+    // a call with other parameters than the draw in progress starts over
+    let mut draw = match read_committee_draw(deps.storage)? {
+        Some(draw) if draw.size == size && draw.seed_poll_id == seed_poll_id => draw,
+        _ => CommitteeDraw {
+            size,
+            seed_poll_id,
+            seed: committee_seed(deps.storage, &env, seed_poll_id)?,
+            next_key: vec![],
+            drawn: vec![],
+        },
+    };
+
+    // blocked members and members without share are not drawn
+    let members: Vec<(Vec<u8>, TokenManager)> = range_members(
+        deps.storage,
+        Some(draw.next_key.as_slice()),
+        None,
+        Order::Ascending,
+    )
+    .take(COMMITTEE_DRAW_PAGE)
+    .collect::<StdResult<_>>()?;
+    for (key, token_manager) in members.iter() {
+        if token_manager.share.is_zero() || blocklist_read(deps.storage).may_load(key)?.is_some() {
+            continue;
+        }
+        let member_id = String::from_utf8_lossy(key).to_string();
+        let candidate = DrawnMember {
+            key: sample_key(&draw.seed, &member_id),
+            member_id,
+            share: token_manager.share,
+        };
+        keep_drawn(&mut draw.drawn, candidate, size as usize);
     }
 
-    // check if sender_address has voted
-    if !poll_voter_read(deps.storage, poll_id)
-        .load(voter_key)
-        .is_ok()
-    {
-        return Err(ContractError::NotYetVoted {});
+    if members.len() == COMMITTEE_DRAW_PAGE {
+        let mut next_key = members.last().unwrap().0.clone();
+        next_key.push(1);
+        draw.next_key = next_key;
+        store_committee_draw(deps.storage, &draw)?;
+        return Ok(Response::new().add_attributes(vec![
+            ("action", "draw_committee"),
+            ("done", "false"),
+            ("seed_poll_id", &seed_poll_id.to_string()),
+        ]));
     }
 
-    let mut token_manager = bank_read(deps.storage)
-        .may_load(voter_key)?
-        .unwrap_or_default();
+    // fewer candidates than size: the draw stays until called with other parameters
+    if draw.drawn.len() < size as usize {
+        return Err(ContractError::InvalidCommitteeSize(draw.drawn.len()));
+    }
+    remove_committee_draw(deps.storage);
 
-    let vote_info = poll_voter_read(deps.storage, poll_id).load(voter_key)?;
+    let mut state: State = state_store(deps.storage).load()?;
+    state.committee_count += 1;
+    let committee = Committee {
+        id: state.committee_count,
+        seed_poll_id,
+        members: draw
+            .drawn
+            .into_iter()
+            .map(|entry| entry.member_id)
+            .collect(),
+        drawn_at_height: env.block.height,
+    };
+    committee_store(deps.storage).save(&committee.id.to_be_bytes(), &committee)?;
+    state_store(deps.storage).save(&state)?;
 
-    // increment yes/no votes
-    if vote_info.vote == VoteOption::Yes {
-        a_poll.yes_votes -= vote_info.balance;
-    } else {
-        a_poll.no_votes -= vote_info.balance;
-    }
+    Ok(Response::new().add_attributes(vec![
+        ("action", "draw_committee"),
+        ("done", "true"),
+        ("committee_id", &committee.id.to_string()),
+        ("seed_poll_id", &seed_poll_id.to_string()),
+        ("members", &committee.members.join(",")),
+    ]))
+}
 
-    token_manager.locked_share.retain(|(id, _)| {
-        let poll: Poll = poll_read(deps.storage).load(&id.to_be_bytes()).unwrap();
+/// seed of a committee draw: tallies of the ended seed poll and height/time the draw starts
+fn committee_seed(
+    storage: &dyn Storage,
+    env: &Env,
+    seed_poll_id: u64,
+) -> Result<Binary, ContractError> {
+    let seed_poll = match may_load_poll(storage, seed_poll_id)? {
+        Some(poll) => poll,
+        None => return Err(ContractError::PollNotFound {}),
+    };
+    if !matches!(
+        seed_poll.status,
+        PollStatus::Passed | PollStatus::Rejected | PollStatus::Executed | PollStatus::Failed
+    ) {
+        return Err(ContractError::PollNotEnded {});
+    }
 
-        // remove voter if poll is not in progress or poll is the same vote to cancel
-        if poll.status != PollStatus::InProgress || *id == poll_id {
-            poll_voter_store(deps.storage, *id).remove(voter_key);
-        }
+    let mut hasher = Sha256::new();
+    hasher.update(seed_poll.id.to_be_bytes());
+    hasher.update(seed_poll.yes_votes.u128().to_be_bytes());
+    hasher.update(seed_poll.no_votes.u128().to_be_bytes());
+    for votes in seed_poll.votes_per_option.iter() {
+        hasher.update(votes.u128().to_be_bytes());
+    }
+    hasher.update(seed_poll.ended_at_height.to_be_bytes());
+    hasher.update(env.block.height.to_be_bytes());
+    hasher.update(env.block.time.nanos().to_be_bytes());
+    Ok(Binary::from(hasher.finalize().as_slice()))
+}
 
-        poll.status == PollStatus::InProgress && *id != poll_id
-    });
+/// undelegate member on its behalf (repair of delegation cycles already on chain)
+/// only admin can break delegation
+pub fn break_delegation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    member_id: String,
+) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
 
-    bank_store(deps.storage).save(voter_key, &token_manager)?;
+    apply_break_delegation(deps, &env.block, member_id)
+}
 
-    poll_store(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
+fn apply_break_delegation(
+    deps: DepsMut,
+    block: &BlockInfo,
+    member_id: String,
+) -> Result<Response, ContractError> {
+    let (delegatee, withdrawn_polls, transitions) =
+        release_delegation(deps.storage, block, &member_id)?;
 
-    Ok(Response::new().add_attributes(vec![
-        ("action", "cancel_vote"),
-        ("poll_id", poll_id.to_string().as_str()),
-        ("amount", vote_info.balance.to_string().as_str()),
-        ("voter", voter_id.as_str()),
-        ("vote_option", vote_info.vote.to_string().as_str()),
-    ]))
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("action", "break_delegation"),
+            ("from", member_id.as_str()),
+            ("to", delegatee.as_str()),
+            ("withdrawn_polls", &withdrawn_polls.join(",")),
+        ])
+        .add_attributes(transitions))
 }
 
-/// mint warrant tokens
-/// only owner can mint
-pub fn mint(
+/// lift suspension before it expires
+/// only admin can unsuspend
+pub fn unsuspend_member(
     deps: DepsMut,
     info: MessageInfo,
-    recipient_id: String,
-    amount: Uint128,
+    member_id: String,
 ) -> Result<Response, ContractError> {
-    let config: Config = config_store(deps.storage).load()?;
-    let sender = info.sender;
-    if config.owner != deps.api.addr_canonicalize(sender.as_str())? {
-        return Err(ContractError::Unauthorized {});
-    }
+    let config: Config = config_read(deps.storage).load()?;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
 
-    if amount.is_zero() {
-        return Err(ContractError::InsufficientFunds {});
-    }
+    apply_unsuspend_member(deps, member_id)
+}
 
-    _mint(deps.storage, recipient_id.as_bytes(), amount)?;
+fn apply_unsuspend_member(deps: DepsMut, member_id: String) -> Result<Response, ContractError> {
+    let member_key = member_id.as_bytes();
+    let mut token_manager = match may_load_member(deps.storage, member_key)? {
+        Some(token_manager) => token_manager,
+        None => return Err(ContractError::NotAMember {}),
+    };
+    token_manager.suspended_until = None;
+    save_member(deps.storage, member_key, &token_manager)?;
 
     Ok(Response::new().add_attributes(vec![
-        ("action", "mint"),
-        ("to", &recipient_id),
-        ("amount", amount.to_string().as_str()),
+        ("action", "unsuspend_member"),
+        ("member", member_id.as_str()),
     ]))
 }
 
-/// member can burn token all
-fn exit(deps: DepsMut, sender_id: String) -> Result<Response, ContractError> {
-    let key = sender_id.as_bytes();
-    let token_manager = bank_read(deps.storage).may_load(key)?.unwrap_or_default();
-    let amount = token_manager.balance;
-    _burn(deps.storage, key, amount)?;
-    Ok(Response::new().add_attributes(vec![
-        ("action", "exit"),
-        ("from", sender_id.as_str()),
-        ("amount", &amount.to_string()),
-    ]))
+/// recompute total_supply from bank balances
+/// only admin can recompute
+pub fn recompute_supply(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    assert_privileged(&deps, &config, info.sender.as_str())?;
+
+    apply_recompute_supply(deps)
 }
 
-/// transfer from owner to recipient
-/// only callable by owner address
-/// amount: None (transfer all)
-pub fn transfer_from(
-    deps: DepsMut,
-    info: MessageInfo,
-    owner_id: String,
-    recipient_id: String,
-    amount: Uint128,
-) -> Result<Response, ContractError> {
-    let config: Config = config_store(deps.storage).load()?;
-    let sender = info.sender.as_str();
-    if config.owner != deps.api.addr_canonicalize(sender)? {
-        return Err(ContractError::Unauthorized {});
+fn apply_recompute_supply(deps: DepsMut) -> Result<Response, ContractError> {
+    let mut progress = read_tmp_supply(deps.storage)?.unwrap_or_default();
+    let members: Vec<(Vec<u8>, TokenManager)> = range_members(
+        deps.storage,
+        Some(progress.next_key.as_slice()),
+        None,
+        Order::Ascending,
+    )
+    .take(RECOMPUTE_SUPPLY_PAGE)
+    .collect::<StdResult<_>>()?;
+
+    for (_, token_manager) in members.iter() {
+        progress.total_supply += token_manager.balance;
     }
-    let recipient_key = recipient_id.as_bytes();
-    let owner_key = owner_id.as_bytes();
 
-    if amount.is_zero() {
-        return Err(ContractError::InsufficientFunds {});
+    let done = members.len() < RECOMPUTE_SUPPLY_PAGE;
+    if done {
+        let mut state: State = state_store(deps.storage).load()?;
+        state.total_supply = progress.total_supply;
+        state_store(deps.storage).save(&state)?;
+        remove_tmp_supply(deps.storage);
+    } else {
+        // this will set the first key after the last scanned key, by appending a 1 byte
+        let mut next_key = members.last().unwrap().0.clone();
+        next_key.push(1);
+        progress.next_key = next_key;
+        store_tmp_supply(deps.storage, &progress)?;
     }
 
-    _burn(deps.storage, owner_key, amount)?;
-    _mint(deps.storage, recipient_key, amount)?;
     Ok(Response::new().add_attributes(vec![
-        ("action", "transfer_from"),
-        ("from", &owner_id),
-        ("to", &recipient_id),
-        ("by", sender),
-        ("amount", amount.to_string().as_str()),
+        ("action", "recompute_supply"),
+        ("done", &done.to_string()),
+        ("total_supply", &progress.total_supply.to_string()),
     ]))
 }
 
+/// keep in progress supply recomputation correct for already scanned members
+fn update_tmp_supply(
+    storage: &mut dyn Storage,
+    key: &[u8],
+    old_balance: Uint128,
+    new_balance: Uint128,
+) -> StdResult<()> {
+    if let Some(mut progress) = read_tmp_supply(storage)? {
+        if key < progress.next_key.as_slice() {
+            progress.total_supply = progress.total_supply + new_balance - old_balance;
+            store_tmp_supply(storage, &progress)?;
+        }
+    }
+    Ok(())
+}
+
+/// refund deposit of poll to its creator once, returns refunded amount
+/// deposit keeps the amount for DepositInfo (caller saves the poll)
+fn refund_deposit(
+    storage: &mut dyn Storage,
+    a_poll: &mut Poll,
+    height: u64,
+) -> Result<Uint128, ContractError> {
+    if a_poll.deposit_claimed || a_poll.deposit.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    _mint(storage, a_poll.creator.as_bytes(), a_poll.deposit, height)?;
+    a_poll.deposit_claimed = true;
+    Ok(a_poll.deposit)
+}
+
 /// mint warrant tokens
-fn _mint(storage: &mut dyn Storage, key: &[u8], amount: Uint128) -> Result<(), ContractError> {
-    let mut token_manager = bank_read(storage).may_load(key)?.unwrap_or_default();
+fn _mint(
+    storage: &mut dyn Storage,
+    key: &[u8],
+    amount: Uint128,
+    height: u64,
+) -> Result<(), ContractError> {
+    let config: Config = config_read(storage).load()?;
+    let mut token_manager = load_member_or_new(storage, key)?;
+    // inactivity of new member is counted from joining
+    if token_manager.last_vote_height.is_none() {
+        token_manager.last_vote_height = Some(height);
+    }
     let mut state: State = state_store(storage).load()?;
     let old_share = token_manager.share;
+    let old_balance = token_manager.balance;
     state.total_share -= old_share;
     token_manager.balance += amount;
-    token_manager.share = token_manager.balance.isqrt();
+    token_manager.share = config.share(token_manager.balance);
     let new_share = token_manager.share;
     state.total_share += new_share;
+    state.total_supply += amount;
 
     state_store(storage).save(&state)?;
-    bank_store(storage).save(key, &token_manager)?;
+    save_member(storage, key, &token_manager)?;
+    update_tmp_supply(storage, key, old_balance, token_manager.balance)?;
+    if let Some(delegatee) = &token_manager.delegate_to {
+        adjust_delegated_in_share(storage, delegatee, new_share, old_share)?;
+    }
 
     Ok(())
 }
 
 /// burn tokens (used in instant_burn, transfer(burn --> mint))
 /// can burn only non locked shares
-fn _burn(storage: &mut dyn Storage, key: &[u8], amount: Uint128) -> Result<(), ContractError> {
-    if let Some(mut token_manager) = bank_read(storage).may_load(key)? {
+/// returns poll ids of released orphaned locks
+fn _burn(
+    storage: &mut dyn Storage,
+    height: u64,
+    key: &[u8],
+    amount: Uint128,
+) -> Result<Vec<u64>, ContractError> {
+    if let Some(mut token_manager) = may_load_member(storage, key)? {
+        check_withdrawable(storage, height, &token_manager, amount)?;
+        let config: Config = config_read(storage).load()?;
         let mut state: State = state_store(storage).load()?;
-        // Load total share & total balance except proposal deposit amount
-        let locked_share = compute_locked_balance(storage, &mut token_manager, key);
+        // only leave in progress polls
+        let orphaned_polls = release_locks(storage, &mut token_manager, key, None)?;
 
         let balance = token_manager.balance.u128();
-        let locked_amount = locked_share.pow(2);
         let withdraw_amount = amount.u128();
-        if locked_amount + withdraw_amount > balance {
-            Err(ContractError::InvalidWithdrawAmount {})
-        } else {
-            let old_share = token_manager.share;
-            state.total_share -= old_share;
-            token_manager.balance = Uint128::from(balance - withdraw_amount);
-            token_manager.share = token_manager.balance.isqrt();
-            let new_share = token_manager.share;
-            state.total_share += new_share;
-            bank_store(storage).save(key, &token_manager)?;
-            state_store(storage).save(&state)?;
-            Ok(())
+        let old_share = token_manager.share;
+        state.total_share -= old_share;
+        token_manager.balance = Uint128::from(balance - withdraw_amount);
+        token_manager.share = config.share(token_manager.balance);
+        let new_share = token_manager.share;
+        state.total_share += new_share;
+        state.total_supply = state.total_supply.saturating_sub(amount);
+        save_member(storage, key, &token_manager)?;
+        state_store(storage).save(&state)?;
+        update_tmp_supply(storage, key, Uint128::from(balance), token_manager.balance)?;
+        if let Some(delegatee) = &token_manager.delegate_to {
+            adjust_delegated_in_share(storage, delegatee, new_share, old_share)?;
         }
+        Ok(orphaned_polls)
     } else {
         Err(ContractError::NothingStaked {})
     }
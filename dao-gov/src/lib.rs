@@ -3,7 +3,11 @@ mod error;
 pub mod msg;
 pub mod state;
 pub mod execute;
+pub mod ibc;
 pub mod query;
 pub mod utils;
 
 pub use crate::error::ContractError;
+
+#[cfg(test)]
+mod testing;
@@ -1,8 +1,13 @@
-use crate::utils::{OrderBy, PollStatus, VoteOption};
+use crate::utils::{
+    EndPollPermission, OrderBy, PollStatus, PollType, RoundingMode, TextLimits, TiePolicy,
+    VoteOption, VotingModel,
+};
 use cosmwasm_std::{Binary, Decimal, Uint128};
 use cw721::Cw721ReceiveMsg;
+// shared with dao-cw721, re-exported for compatibility
+pub use dao_common::Cw721HookMsg;
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 /**
 {
@@ -18,6 +23,44 @@ pub struct InstantiateMsg {
     pub quorum: Decimal,
     pub threshold: Decimal,
     pub voting_period: u64,
+    pub share_rounding: Option<RoundingMode>, // default: floor
+    pub voting_model: Option<VotingModel>,    // default: quadratic
+    pub season_length: Option<u64>,           // default: no seasons
+    pub season_blackout: Option<u64>,         // default: 0
+    pub end_poll_reward: Option<Uint128>,     // default: 0
+    pub admins: Option<Vec<String>>,          // default: [sender]
+    pub admin_threshold: Option<u32>,         // default: 1
+    pub decay_after: Option<u64>,             // default: no decay
+    pub decay_rate: Option<Decimal>,          // default: 0
+    pub decay_floor: Option<Decimal>,         // default: 0
+    pub bind_wallet: Option<bool>,            // default: false
+    pub text_limits: Option<TextLimits>,      // default: title 64, description 1024, link 128
+    pub draft_ttl: Option<u64>,               // default: drafts are never pruned
+    pub threshold_inclusive: Option<bool>,    // default: false (yes ratio must exceed threshold)
+    pub failed_poll_cooldown: Option<u64>,    // default: 0 (no cooldown)
+    pub signal_quorum: Option<Decimal>,       // default: quorum
+    pub signal_threshold: Option<Decimal>,    // default: threshold
+    pub max_extension: Option<u64>,           // default: 0 (polls can't be extended)
+    pub ibc_channel: Option<String>,          // default: none
+    pub ibc_notify: Option<bool>,             // default: false
+    pub ibc_receiver: Option<String>,         // default: none
+    pub ibc_denom: Option<String>,            // default: none
+    pub tie_policy: Option<TiePolicy>,        // default: reject
+    pub auto_finalize: Option<bool>,          // default: false
+    pub max_supply: Option<Uint128>,          // default: none (no cap)
+    pub text_deposit: Option<Uint128>,        // default: 0 (signal polls are free)
+    pub execute_deposit: Option<Uint128>,     // default: 0 (binding polls are free)
+    pub end_poll_permission: Option<EndPollPermission>, // default: anyone
+    pub adaptive_quorum: Option<bool>,        // default: false (static quorum)
+    pub vote_freeze_window: Option<u64>,      // default: 0 (no freeze)
+    pub freeze_new_votes: Option<bool>,       // default: false (new votes allowed while frozen)
+    pub voting_time: Option<u64>,             // default: 0 (polls end by height only)
+    pub use_token_multiplier: Option<bool>,   // default: false (votes are not weighted by token)
+    pub max_multiplier: Option<Decimal>,      // default: 1
+    pub bootstrap_period: Option<u64>, // blocks after instantiate bootstrap_quorum applies (default 0: none)
+    pub bootstrap_quorum: Option<Decimal>, // reduced quorum during bootstrap_period (required with it)
+    pub delegate_inactivity_timeout: Option<u64>, // default (or 0): delegations never expire
+    pub max_vote_share: Option<Decimal>,   // default (or 0): no cap
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -36,68 +79,459 @@ pub enum ExecuteMsg {
         recipient: String,
         amount: Uint128,
     },
+    /// mint balance that votes but can't be burned, exited or transferred
+    /// until until_height (forever if none)
+    MintLocked {
+        recipient: String,
+        amount: Uint128,
+        until_height: Option<u64>,
+    },
     TransferFrom {
         owner: String,
         recipient: String,
         amount: Uint128,
     },
+    /// fields left out are kept, null clears season_length, decay_after, draft_ttl
+    /// and the ibc settings
     UpdateConfig {
-        owner: Option<String>,
+        admins: Option<Vec<String>>,
+        admin_threshold: Option<u32>,
         quorum: Option<Decimal>,
         threshold: Option<Decimal>,
         voting_period: Option<u64>,
+        #[serde(
+            default,
+            deserialize_with = "double_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        season_length: Option<Option<u64>>,
+        season_blackout: Option<u64>,
+        end_poll_reward: Option<Uint128>,
+        #[serde(
+            default,
+            deserialize_with = "double_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        decay_after: Option<Option<u64>>,
+        decay_rate: Option<Decimal>,
+        decay_floor: Option<Decimal>,
+        bind_wallet: Option<bool>,
+        text_limits: Option<TextLimits>,
+        #[serde(
+            default,
+            deserialize_with = "double_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        draft_ttl: Option<Option<u64>>,
+        threshold_inclusive: Option<bool>,
+        failed_poll_cooldown: Option<u64>,
+        signal_quorum: Option<Decimal>,
+        signal_threshold: Option<Decimal>,
+        max_extension: Option<u64>,
+        #[serde(
+            default,
+            deserialize_with = "double_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        ibc_channel: Option<Option<String>>,
+        ibc_notify: Option<bool>,
+        #[serde(
+            default,
+            deserialize_with = "double_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        ibc_receiver: Option<Option<String>>,
+        #[serde(
+            default,
+            deserialize_with = "double_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        ibc_denom: Option<Option<String>>,
+        tie_policy: Option<TiePolicy>,
+        auto_finalize: Option<bool>,
+        /// can only be lowered once set (not below total supply)
+        max_supply: Option<Uint128>,
+        /// deposit create_poll takes from creator balance for signal polls
+        text_deposit: Option<Uint128>,
+        /// deposit create_poll takes from creator balance for binding polls
+        execute_deposit: Option<Uint128>,
+        /// scale quorum to turnout of recently ended polls
+        adaptive_quorum: Option<bool>,
+        /// blocks before end_height in which votes can't be cancelled or overridden
+        vote_freeze_window: Option<u64>,
+        /// reject fresh votes within vote_freeze_window as well
+        freeze_new_votes: Option<bool>,
+        /// seconds polls also stay open for (0: end by height only)
+        voting_time: Option<u64>,
+        /// weight own votes by the multiplier in the voting NFT's extension
+        use_token_multiplier: Option<bool>,
+        /// cap of token multipliers
+        max_multiplier: Option<Decimal>,
+        /// blocks after instantiate bootstrap_quorum applies in end_poll
+        /// (non-zero needs bootstrap_quorum, can't be extended once over)
+        bootstrap_period: Option<u64>,
+        /// reduced quorum during bootstrap_period
+        bootstrap_quorum: Option<Decimal>,
+        /// blocks without voting after which delegations to a delegate are void (0: never)
+        delegate_inactivity_timeout: Option<u64>,
+        /// cap of a member's counted vote as a fraction of the poll's total_share_at_start_poll
+        /// (0: no cap)
+        max_vote_share: Option<Decimal>,
+        /// validate and return the new config as attributes without saving
+        #[serde(default)]
+        dry_run: bool,
+    },
+    /// finalize poll abandoned past end_height + grace period as rejected
+    /// and refund deposit to creator
+    ReclaimDeposit {
+        poll_id: u64,
+    },
+    /// start next season once season_length has elapsed (anyone can call)
+    RollSeason {},
+    /// recompute total_supply from bank balances (for deployments before total_supply)
+    /// scans members in pages, call repeatedly until "done" attribute is true
+    RecomputeSupply {},
+    /// extend voting period of in-progress poll (only admin, bounded by max_extension)
+    ExtendPoll {
+        poll_id: u64,
+        additional_blocks: u64,
+    },
+    /// undelegate member on its behalf (repairs delegation cycles)
+    BreakDelegation {
+        member_id: String,
+    },
+    /// suspend member from creating polls, voting and delegating until until_height
+    /// (balance is kept and member can still exit, cancel vote or undelegate)
+    SuspendMember {
+        member_id: String,
+        until_height: u64,
+    },
+    UnsuspendMember {
+        member_id: String,
+    },
+    /// propose privileged action (counted as proposer's approval)
+    /// executed once admin_threshold admins approved before expires (block height)
+    ProposeAdminAction {
+        action: AdminAction,
+        expires: u64,
+    },
+    ApproveAdminAction {
+        id: u64,
+    },
+    /// apply ballots signed off-chain by members' registered vote keys (anyone can relay)
+    /// invalid ballots are skipped and reported in attributes
+    RelayVote {
+        ballots: Vec<SignedBallot>,
+    },
+    /// apply a single ballot signed off-chain by voter's vote key (anyone can relay)
+    /// fails instead of skipping if the ballot is invalid
+    CastVoteSigned {
+        poll_id: u64,
+        voter: String, // member id (token_id)
+        vote: VoteOption,
+        nonce: u64,
+        signature: Binary,
+    },
+    /// remove drafts older than draft_ttl (anyone can call, scans drafts in pages)
+    PruneDrafts {},
+    /// block member from creating polls and voting (only admin)
+    SetBlocked {
+        member_id: String,
+        blocked: bool,
+    },
+    /// open pending poll whose dependency passed or reject it if dependency didn't pass
+    /// (anyone can call, also applied on the next vote or end_poll)
+    ResolveDependency {
+        poll_id: u64,
+    },
+    /// draw size members weighted by share (only admin)
+    /// seeded by tallies of ended seed poll and height/time of the first call
+    /// scans members in pages, call repeatedly until "done" attribute is true
+    /// (a call with other size or seed_poll_id starts the draw over)
+    DrawCommittee {
+        size: u8,
+        seed_poll_id: u64,
+    },
+    /// remove bank entry of member with zero balance and no in-progress locks (anyone can call)
+    PruneMember {
+        member_id: String,
     },
+    /// undelegate members delegating to delegate_id inactive past delegate_inactivity_timeout
+    /// (at most limit, capped at 100, per call; anyone can call until "done" is true)
+    SweepInactiveDelegations {
+        delegate_id: String,
+        limit: u32,
+    },
+    /// re-index a page of polls (at most 100) under their stored status (only admin)
+    /// keep calling with start_after = next_cursor attribute until it is empty
+    RebuildPollIndex {
+        start_after: Option<u64>,
+        limit: u32,
+    },
+    /// after migrate: give a page of polls (at most 100) created before snapshotting
+    /// the current quorum / threshold of their poll type (anyone can call)
+    /// keep calling with start_after = next_cursor attribute until it is empty
+    SnapshotPollParameters {
+        start_after: Option<u64>,
+        limit: u32,
+    },
+    /// after migrate: recompute delegated_in_share of a page of members (at most 30)
+    /// from their delegators (anyone can call)
+    /// keep calling with start_after = next_cursor attribute until it is empty
+    RecomputeDelegatedShare {
+        start_after: Option<String>,
+        limit: u32,
+    },
+    /// end poll past its voting period without a token (no end_poll_reward)
+    /// allowed by end_poll_permission Anyone, or OwnerOnly for an admin
+    EndPoll {
+        poll_id: u64,
+    },
+    /// turn vested balance past its release height into normal balance (anyone can call)
+    ReleaseVested {
+        member_id: String,
+    },
+    /// end in progress or pending poll without tallying it (privileged)
+    /// outcome is Passed or Rejected (default)
+    ForceEndPoll {
+        poll_id: u64,
+        outcome: Option<PollStatus>,
+    },
+}
+
+/// secp256k1 signature is over sha256 of utils::ballot_message
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedBallot {
+    pub token_id: String,
+    pub poll_id: u64,
+    pub vote: VoteOption,
+    pub nonce: u64, // member's current vote_nonce (see Staker query)
+    pub signature: Binary,
 }
 
+/// packet data end_poll sends over ibc_channel (channel version ibc::IBC_VERSION)
+/// yes/no are balances, receiver is the configured ibc_receiver
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IbcNotifyPacket {
+    pub receiver: String,
+    pub poll_id: u64,
+    pub passed: bool,
+    pub yes: Uint128,
+    pub no: Uint128,
+    pub height: u64,
+}
+
+/// privileged actions requiring admin_threshold approvals
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum Cw721HookMsg {
-    Exit {},
-    DelegateVote {
-        delegator: String,
+pub enum AdminAction {
+    Mint {
+        recipient: String,
+        amount: Uint128,
     },
-    UnDelegateVote {},
-    CreatePoll {
-        title: String,
-        description: String,
-        link: Option<String>,
+    /// mint balance that votes but can't be burned, exited or transferred
+    /// until until_height (forever if none)
+    MintLocked {
+        recipient: String,
+        amount: Uint128,
+        until_height: Option<u64>,
     },
-    /*
-    {"cast_vote": {
-        "poll_id": 2,
-        "vote": "yes",
-        "amount": "100000"
-        }
-    }
-    */
-    CastVote {
-        poll_id: u64,
-        vote: VoteOption,
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// fields left out are kept, null clears season_length, decay_after, draft_ttl
+    /// and the ibc settings
+    UpdateConfig {
+        admins: Option<Vec<String>>,
+        admin_threshold: Option<u32>,
+        quorum: Option<Decimal>,
+        threshold: Option<Decimal>,
+        voting_period: Option<u64>,
+        #[serde(
+            default,
+            deserialize_with = "double_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        season_length: Option<Option<u64>>,
+        season_blackout: Option<u64>,
+        end_poll_reward: Option<Uint128>,
+        #[serde(
+            default,
+            deserialize_with = "double_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        decay_after: Option<Option<u64>>,
+        decay_rate: Option<Decimal>,
+        decay_floor: Option<Decimal>,
+        bind_wallet: Option<bool>,
+        text_limits: Option<TextLimits>,
+        #[serde(
+            default,
+            deserialize_with = "double_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        draft_ttl: Option<Option<u64>>,
+        threshold_inclusive: Option<bool>,
+        failed_poll_cooldown: Option<u64>,
+        signal_quorum: Option<Decimal>,
+        signal_threshold: Option<Decimal>,
+        max_extension: Option<u64>,
+        #[serde(
+            default,
+            deserialize_with = "double_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        ibc_channel: Option<Option<String>>,
+        ibc_notify: Option<bool>,
+        #[serde(
+            default,
+            deserialize_with = "double_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        ibc_receiver: Option<Option<String>>,
+        #[serde(
+            default,
+            deserialize_with = "double_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        ibc_denom: Option<Option<String>>,
+        tie_policy: Option<TiePolicy>,
+        auto_finalize: Option<bool>,
+        /// can only be lowered once set (not below total supply)
+        max_supply: Option<Uint128>,
+        /// deposit create_poll takes from creator balance for signal polls
+        text_deposit: Option<Uint128>,
+        /// deposit create_poll takes from creator balance for binding polls
+        execute_deposit: Option<Uint128>,
+        /// scale quorum to turnout of recently ended polls
+        adaptive_quorum: Option<bool>,
+        /// blocks before end_height in which votes can't be cancelled or overridden
+        vote_freeze_window: Option<u64>,
+        /// reject fresh votes within vote_freeze_window as well
+        freeze_new_votes: Option<bool>,
+        /// seconds polls also stay open for (0: end by height only)
+        voting_time: Option<u64>,
+        /// weight own votes by the multiplier in the voting NFT's extension
+        use_token_multiplier: Option<bool>,
+        /// cap of token multipliers
+        max_multiplier: Option<Decimal>,
+        /// blocks after instantiate bootstrap_quorum applies in end_poll
+        /// (non-zero needs bootstrap_quorum, can't be extended once over)
+        bootstrap_period: Option<u64>,
+        /// reduced quorum during bootstrap_period
+        bootstrap_quorum: Option<Decimal>,
+        /// blocks without voting after which delegations to a delegate are void (0: never)
+        delegate_inactivity_timeout: Option<u64>,
+        /// cap of a member's counted vote as a fraction of the poll's total_share_at_start_poll
+        /// (0: no cap)
+        max_vote_share: Option<Decimal>,
     },
-    CancelVote {
+    ExtendPoll {
         poll_id: u64,
+        additional_blocks: u64,
     },
-    /*
-    {"end_poll": {
-        "poll_id": 2
-        }
-    }
-    */
-    EndPoll {
+    SuspendMember {
+        member_id: String,
+        until_height: u64,
+    },
+    UnsuspendMember {
+        member_id: String,
+    },
+    /// undelegate member on its behalf
+    BreakDelegation {
+        member_id: String,
+    },
+    /// scan one page of bank balances towards total_supply
+    RecomputeSupply {},
+    /// end in progress or pending poll without tallying it, outcome Passed or Rejected (default)
+    ForceEndPoll {
         poll_id: u64,
+        outcome: Option<PollStatus>,
     },
 }
 
+/// snapshots current quorum/threshold into polls created before snapshotting,
+/// recomputes delegated_in_share of all members and makes legacy owner the only admin
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    /// first admin (instantiate sender by default) and the whole admin set
+    Owner {},
     State {},
+    TotalSupply {},
+    /// cw2 version and counts of key structures (sanity check after migration)
+    StorageVersion {},
+    /// compare sum of member shares with total_share, a page of members per query
+    /// pass next_cursor as start_after and computed as carried until next_cursor is None
+    VerifyInvariants {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        carried: Option<Uint128>,
+    },
+    Seasons {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
     Member {
         member_id: String,
     },
+    /// (member_id, balance, share) of all members ordered by member_id
+    Members {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// at most 30 members, None for unknown member
+    MembersBatch {
+        member_ids: Vec<String>,
+    },
+    /// share not locked by votes in in-progress polls
+    FreeShare {
+        member_id: String,
+    },
+    /// balance member can withdraw now (or at at_height) and when locked balance frees
+    Withdrawable {
+        member_id: String,
+        at_height: Option<u64>,
+    },
+    /// minimum balance which has given share under the voting model
+    ShareToMinBalance {
+        share: Uint128,
+    },
+    DelegationStatus {
+        member_id: String,
+    },
+    MemberByAlias {
+        namespace: String,
+        address: String,
+    },
+    /// draft poll is returned only if member_id is its creator
     Poll {
         poll_id: u64,
+        member_id: Option<String>,
+    },
+    /// full description of poll (drafts only for member_id of their creator)
+    PollDescription {
+        poll_id: u64,
+        member_id: Option<String>,
+    },
+    /// deposit taken from the poll creator and whether it was refunded
+    DepositInfo {
+        poll_id: u64,
+    },
+    /// in progress polls member_id can still vote on ("awaiting your vote")
+    /// empty while member_id is delegating or suspended
+    /// examines at most 300 polls per call, keep paging while next_cursor is set
+    EligiblePolls {
+        member_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
     },
     /*
     {"polls": {
@@ -105,17 +539,74 @@ pub enum QueryMsg {
         }
     }
     */
+    /// examines at most 300 polls per call, keep paging while next_cursor is set
+    /// (a page can be short or empty when most examined polls are filtered out)
     Polls {
         filter: Option<PollStatus>,
         start_after: Option<u64>,
         limit: Option<u32>,
         order_by: Option<OrderBy>,
+        /// list drafts created by member_id as well
+        /// (queries are not authenticated, so drafts are only softly private)
+        include_drafts: Option<bool>,
+        member_id: Option<String>,
+        poll_type: Option<PollType>,
+        /// add viewer's vote and creator's share to each poll
+        viewer: Option<String>,
     },
     Voters {
         poll_id: u64,
         start_after: Option<String>,
         limit: Option<u32>,
         order_by: Option<OrderBy>,
+        /// only voters who voted this option (the page still scans voters of other options)
+        vote_filter: Option<VoteOption>,
+    },
+    IsBlocked {
+        member_id: String,
+    },
+    /// dry run of CastVote, CastChoiceVote, CancelVote, DelegateVote, UnDelegateVote,
+    /// CreatePoll or Exit sent by member (error is the one the hook would fail with)
+    ValidateHook {
+        member_id: String,
+        hook: Cw721HookMsg,
+    },
+    Committee {
+        id: u64,
+    },
+    /// members with largest delegated_in_share (at most 100)
+    TopDelegates {
+        limit: Option<u32>,
+    },
+    /// polls whose indexers disagree with their stored status, a page (at most 100) at a time
+    IndexConsistency {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// IBC notification settings of poll results
+    IbcConfig {},
+    /// number of voters of poll (including delegated votes), counted up to a cap
+    VoterCount {
+        poll_id: u64,
+    },
+    /*
+    {"vote_receipt": {
+        "poll_id": 2,
+        "member_id": "1"
+        }
+    }
+    */
+    VoteReceipt {
+        poll_id: u64,
+        member_id: String,
+    },
+    AdminAction {
+        id: u64,
+    },
+    /// admin actions waiting for approvals (including expired ones)
+    AdminActions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
     },
 }
 
@@ -132,3 +623,12 @@ pub struct PollExecuteMsg {
     pub contract: String,
     pub msg: Binary,
 }
+
+/// Some(None) for an explicit null (a missing field stays None through serde(default))
+fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
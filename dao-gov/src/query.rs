@@ -1,89 +1,555 @@
-use cosmwasm_std::{Deps, StdResult};
+use cosmwasm_std::{CanonicalAddr, Deps, Env, Order, StdError, StdResult, Uint128};
+use cw2::get_contract_version;
 
 use crate::error::ContractError;
+use crate::execute::validate_hook;
+use crate::msg::Cw721HookMsg;
 use crate::state::{
-    bank_read, config_read, poll_read, read_poll_voters, read_polls, state_read, Config, Poll,
-    State,
+    admin_proposal_read, alias_read, blocklist_read, committee_read, config_read,
+    load_member_or_new, may_load_member, may_load_poll, poll_index_drift, poll_voter_read,
+    range_members, range_polls, read_admin_proposals, read_eligible_polls, read_members,
+    read_poll_description, read_poll_page, read_poll_voters, read_polls, read_seasons,
+    read_top_delegates, state_read, AdminProposal, Committee, Config, Poll, State, TokenManager,
 };
 use crate::utils::{
-    ConfigResponse, OrderBy, PollResponse, PollStatus, PollsResponse, StakerResponse,
-    StateResponse, VotersResponse, VotersResponseItem,
+    effective_share, summary_of, unlock_schedule, AdminActionResponse, AdminActionsResponse,
+    ConfigResponse, DelegationStatusResponse, DepositInfoResponse, DepositStatus,
+    FreeShareResponse, IbcConfigResponse, IndexConsistencyResponse, IsBlockedResponse,
+    MemberByAliasResponse, MembersBatchResponse, MembersResponse, OrderBy, OwnerResponse,
+    PollDescriptionResponse, PollLink, PollResponse, PollStatus, PollType, PollsResponse,
+    ResponseMeta, SeasonsResponse, ShareToMinBalanceResponse, StakerResponse, StateResponse,
+    StorageVersionResponse, TopDelegatesResponse, TotalSupplyResponse, ValidateHookResponse,
+    VerifyInvariantsResponse, VoteInfo, VoteOption, VoteReceiptResponse, VoterCountResponse,
+    VotersResponse, VotersResponseItem, WithdrawableResponse,
 };
 
+// maximum number of members per MembersBatch
+const MAX_BATCH_MEMBERS: usize = 30;
+// maximum number of in-progress locks listed per member in MembersBatch
+const MAX_BATCH_LOCKS: usize = 20;
+// maximum number of voters counted by VoterCount
+const MAX_VOTER_COUNT: usize = 10_000;
+// maximum (and default) number of delegates listed by TopDelegates
+const MAX_TOP_DELEGATES: usize = 100;
+const DEFAULT_TOP_DELEGATES: usize = 10;
+// members summed per VerifyInvariants page (read_members returns at most 30)
+const INVARIANTS_PAGE: u32 = 30;
+// default number of polls checked per IndexConsistency page
+const DEFAULT_INDEX_PAGE: u32 = 30;
+
 /// query configurations
 pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
     let config: Config = config_read(deps.storage).load()?;
     Ok(ConfigResponse {
-        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        admins: config
+            .admins
+            .iter()
+            .map(|admin| deps.api.addr_humanize(admin).map(|addr| addr.to_string()))
+            .collect::<StdResult<Vec<String>>>()?,
+        admin_threshold: config.admin_threshold,
         quorum: config.quorum,
         threshold: config.threshold,
         voting_period: config.voting_period,
+        share_rounding: config.share_rounding,
+        voting_model: config.voting_model,
+        season_length: config.season_length,
+        season_blackout: config.season_blackout,
+        end_poll_reward: config.end_poll_reward,
+        decay_after: config.decay_after,
+        decay_rate: config.decay_rate,
+        decay_floor: config.decay_floor,
+        bind_wallet: config.bind_wallet,
+        text_limits: config.text_limits,
+        draft_ttl: config.draft_ttl,
+        threshold_inclusive: config.threshold_inclusive,
+        failed_poll_cooldown: config.failed_poll_cooldown,
+        signal_quorum: config.signal_quorum,
+        signal_threshold: config.signal_threshold,
+        max_extension: config.max_extension,
+        ibc_notify: config.ibc_notify,
+        tie_policy: config.tie_policy,
+        auto_finalize: config.auto_finalize,
+        text_deposit: config.text_deposit,
+        execute_deposit: config.execute_deposit,
+        end_poll_permission: config.end_poll_permission,
+        adaptive_quorum: config.adaptive_quorum,
+        vote_freeze_window: config.vote_freeze_window,
+        freeze_new_votes: config.freeze_new_votes,
+        voting_time: config.voting_time,
+        use_token_multiplier: config.use_token_multiplier,
+        max_multiplier: config.max_multiplier,
+        bootstrap_period: config.bootstrap_period,
+        bootstrap_quorum: config.bootstrap_quorum,
+        delegate_inactivity_timeout: config.delegate_inactivity_timeout,
+        max_vote_share: config.max_vote_share,
+    })
+}
+
+/// query admins (owner is the first admin)
+pub fn query_owner(deps: Deps) -> Result<OwnerResponse, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    let admins = config
+        .admins
+        .iter()
+        .map(|admin| deps.api.addr_humanize(admin).map(|addr| addr.to_string()))
+        .collect::<StdResult<Vec<String>>>()?;
+    Ok(OwnerResponse {
+        owner: admins.first().cloned().unwrap_or_default(),
+        admins,
+        admin_threshold: config.admin_threshold,
     })
 }
 
 /// query current state
-pub fn query_state(deps: Deps) -> Result<StateResponse, ContractError> {
+pub fn query_state(deps: Deps, env: Env) -> Result<StateResponse, ContractError> {
     let state: State = state_read(deps.storage).load()?;
+    let config: Config = config_read(deps.storage).load()?;
     Ok(StateResponse {
         poll_count: state.poll_count,
         total_share: state.total_share,
+        total_supply: state.total_supply,
+        current_season: state.current_season,
+        season_start_height: state.season_start_height,
+        max_supply: config.max_supply,
+        supply_headroom: config
+            .max_supply
+            .map(|max_supply| max_supply.saturating_sub(state.total_supply)),
+        instantiate_height: state.instantiate_height,
+        bootstrap_ends_at: state
+            .instantiate_height
+            .saturating_add(config.bootstrap_period),
+        meta: ResponseMeta::new(&env),
     })
 }
 
-/// query single poll
-pub fn query_poll(deps: Deps, poll_id: u64) -> Result<PollResponse, ContractError> {
-    let poll = match poll_read(deps.storage).may_load(&poll_id.to_be_bytes())? {
-        Some(poll) => Some(poll),
-        None => return Err(ContractError::PollNotFound {}),
+/// total_share must equal the sum of member shares kept up by _mint / _burn
+/// summed over pages, the caller carries the sum of the previous pages
+pub fn query_verify_invariants(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    carried: Option<Uint128>,
+) -> Result<VerifyInvariantsResponse, ContractError> {
+    let state: State = state_read(deps.storage).load()?;
+    let limit = limit.unwrap_or(INVARIANTS_PAGE).clamp(1, INVARIANTS_PAGE);
+
+    let members = read_members(deps.storage, start_after, Some(limit))?;
+    let mut computed = carried.unwrap_or_default();
+    for (_, token_manager) in members.iter() {
+        computed += token_manager.share;
     }
-    .unwrap();
+    // a short page is the last one
+    let next_cursor = if members.len() < limit as usize {
+        None
+    } else {
+        members.last().map(|(member_id, _)| member_id.clone())
+    };
+
+    Ok(VerifyInvariantsResponse {
+        matches: if next_cursor.is_none() {
+            Some(computed == state.total_share)
+        } else {
+            None
+        },
+        computed,
+        stored: state.total_share,
+        next_cursor,
+    })
+}
+
+/// query snapshots of ended seasons
+pub fn query_seasons(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<SeasonsResponse, ContractError> {
+    Ok(SeasonsResponse {
+        seasons: read_seasons(deps.storage, start_after, limit)?,
+    })
+}
+
+/// query total balance of warrant tokens
+pub fn query_total_supply(deps: Deps) -> Result<TotalSupplyResponse, ContractError> {
+    let state: State = state_read(deps.storage).load()?;
+    Ok(TotalSupplyResponse {
+        total_supply: state.total_supply,
+    })
+}
+
+/// scans every poll and member, meant for operators rather than frontends
+pub fn query_storage_version(deps: Deps) -> Result<StorageVersionResponse, ContractError> {
+    let version = get_contract_version(deps.storage)?;
+    let state: State = state_read(deps.storage).load()?;
+
+    let stored_poll_count = range_polls(deps.storage, None, None, Order::Ascending).count() as u64;
+    let mut member_count = 0u64;
+    let mut member_share_sum = Uint128::zero();
+    for item in range_members(deps.storage, None, None, Order::Ascending) {
+        let (_, token_manager) = item?;
+        member_count += 1;
+        member_share_sum += token_manager.share;
+    }
+
+    Ok(StorageVersionResponse {
+        contract: version.contract,
+        version: version.version,
+        poll_count: state.poll_count,
+        stored_poll_count,
+        member_count,
+        total_share: state.total_share,
+        member_share_sum,
+    })
+}
+
+// state is loaded once by the caller, not per poll of a list
+fn to_poll_response(
+    deps: Deps,
+    config: &Config,
+    state: &State,
+    poll: Poll,
+    env: &Env,
+) -> PollResponse {
+    // same fallback as end_poll for polls not snapshotted yet
+    let base_quorum = poll
+        .quorum
+        .unwrap_or_else(|| config.quorum_for(&poll.poll_type));
+    let applied_quorum = config.end_quorum(state, base_quorum, env.block.height);
+    let applied_threshold = poll
+        .threshold
+        .unwrap_or_else(|| config.threshold_for(&poll.poll_type));
+    let dependency_status = poll.depends_on.and_then(|dependency_id| {
+        may_load_poll(deps.storage, dependency_id)
+            .ok()
+            .flatten()
+            .map(|dependency| dependency.status)
+    });
 
-    Ok(PollResponse {
+    let in_progress = poll.status == PollStatus::InProgress;
+    let blocks_remaining = if in_progress {
+        Some(poll.end_height.saturating_sub(env.block.height))
+    } else {
+        None
+    };
+
+    let is_expired = in_progress && !poll.voting_open(&env.block);
+    let freeze_starts_at = if in_progress {
+        config.freeze_starts_at(&poll)
+    } else {
+        None
+    };
+
+    // legacy polls only have the full description
+    let summary = if poll.summary.is_empty() {
+        summary_of(&poll.description)
+    } else {
+        poll.summary.clone()
+    };
+
+    // old polls only have the legacy link
+    let links = match poll.link {
+        Some(link) if poll.links.is_empty() => vec![PollLink::from_legacy(link)],
+        _ => poll.links,
+    };
+
+    PollResponse {
         id: poll.id,
         creator: poll.creator,
         status: poll.status,
         end_height: poll.end_height,
+        end_time: poll.end_time,
         title: poll.title,
-        description: poll.description,
-        link: poll.link,
+        summary,
+        description: None,
+        link: links.first().map(|link| link.url.clone()),
+        links,
         yes_votes: poll.yes_votes,
         no_votes: poll.no_votes,
         total_share_at_start_poll: poll.total_share_at_start_poll,
         total_share_at_end_poll: poll.total_share_at_end_poll,
+        deposit: poll.deposit,
+        deposit_claimed: poll.deposit_claimed,
+        deposit_slashed: poll.deposit_slashed,
+        ended_by: poll.ended_by,
+        ended_at_height: poll.ended_at_height,
+        creator_wallet: poll.creator_wallet,
+        quorum_reached_height: poll.quorum_reached_height,
+        created_at_height: poll.created_at_height,
+        extended_blocks: poll.extended_blocks,
+        tie_extended: poll.tie_extended,
+        poll_type: poll.poll_type,
+        kind: poll.kind,
+        votes_per_option: poll.votes_per_option,
+        winning_option: poll.winning_option,
+        depends_on: poll.depends_on,
+        dependency_status,
+        force_ended: poll.force_ended,
+        raw_quorum: poll.raw_quorum,
+        quorum_used: poll.quorum_used,
+        participation_anomaly: poll.participation_anomaly,
+        applied_quorum,
+        applied_threshold,
+        blocks_remaining,
+        is_expired,
+        freeze_starts_at,
+        viewer_vote: None,
+        viewer_locked: None,
+        creator_share: None,
+        meta: ResponseMeta::new(env),
+    }
+}
+
+/// decorate poll of a list with the viewer's vote and the creator's share
+fn add_viewer(deps: Deps, viewer: &str, poll: &mut PollResponse) -> StdResult<()> {
+    if let Some(vote_info) = poll_voter_read(deps.storage, poll.id).may_load(viewer.as_bytes())? {
+        poll.viewer_vote = Some(vote_info.vote);
+        poll.viewer_locked = Some(vote_info.balance);
+    }
+    // creator may have exited
+    poll.creator_share = Some(
+        may_load_member(deps.storage, poll.creator.as_bytes())?
+            .map(|creator| creator.share)
+            .unwrap_or_default(),
+    );
+    Ok(())
+}
+
+/// draft poll is hidden (PollNotFound) unless member_id is its creator
+fn load_visible_poll(
+    deps: Deps,
+    poll_id: u64,
+    member_id: Option<String>,
+) -> Result<Poll, ContractError> {
+    let poll = match may_load_poll(deps.storage, poll_id)? {
+        Some(poll) => poll,
+        None => return Err(ContractError::PollNotFound {}),
+    };
+    if poll.status == PollStatus::Draft && member_id.as_ref() != Some(&poll.creator) {
+        return Err(ContractError::PollNotFound {});
+    }
+    Ok(poll)
+}
+
+/// query single poll (with full description, unlike poll lists)
+/// draft poll is hidden (PollNotFound) unless member_id is its creator
+pub fn query_poll(
+    deps: Deps,
+    env: Env,
+    poll_id: u64,
+    member_id: Option<String>,
+) -> Result<PollResponse, ContractError> {
+    let poll = load_visible_poll(deps, poll_id, member_id)?;
+    let description = read_poll_description(deps.storage, &poll)?;
+
+    let config: Config = config_read(deps.storage).load()?;
+    let state: State = state_read(deps.storage).load()?;
+    let mut response = to_poll_response(deps, &config, &state, poll, &env);
+    response.description = Some(description);
+    Ok(response)
+}
+
+/// deposit locked by poll (drafts included, they hold a deposit as well)
+pub fn query_deposit_info(deps: Deps, poll_id: u64) -> Result<DepositInfoResponse, ContractError> {
+    let poll = match may_load_poll(deps.storage, poll_id)? {
+        Some(poll) => poll,
+        None => return Err(ContractError::PollNotFound {}),
+    };
+    let status = if poll.deposit.is_zero() {
+        DepositStatus::None
+    } else if poll.deposit_claimed {
+        DepositStatus::Refunded
+    } else if poll.deposit_slashed {
+        DepositStatus::Slashed
+    } else {
+        DepositStatus::Locked
+    };
+    Ok(DepositInfoResponse {
+        poll_id,
+        creator: poll.creator,
+        amount: poll.deposit,
+        status,
+    })
+}
+
+/// full description of poll
+pub fn query_poll_description(
+    deps: Deps,
+    poll_id: u64,
+    member_id: Option<String>,
+) -> Result<PollDescriptionResponse, ContractError> {
+    let poll = load_visible_poll(deps, poll_id, member_id)?;
+    Ok(PollDescriptionResponse {
+        poll_id,
+        description: read_poll_description(deps.storage, &poll)?,
+    })
+}
+
+/// polls awaiting member's vote
+pub fn query_eligible_polls(
+    deps: Deps,
+    env: Env,
+    member_id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<PollsResponse, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    let state: State = state_read(deps.storage).load()?;
+    let (polls, next_cursor) =
+        read_eligible_polls(deps.storage, &env.block, &member_id, start_after, limit)?;
+
+    Ok(PollsResponse {
+        polls: polls
+            .into_iter()
+            .map(|poll| to_poll_response(deps, &config, &state, poll, &env))
+            .collect(),
+        next_cursor,
+        meta: ResponseMeta::new(&env),
     })
 }
 
 /// query multiple polls between time period
+#[allow(clippy::too_many_arguments)]
 pub fn query_polls(
     deps: Deps,
+    env: Env,
     filter: Option<PollStatus>,
     start_after: Option<u64>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
+    include_drafts: Option<bool>,
+    member_id: Option<String>,
+    poll_type: Option<PollType>,
+    viewer: Option<String>,
 ) -> Result<PollsResponse, ContractError> {
-    let polls = read_polls(deps.storage, filter, start_after, limit, order_by)?;
+    let draft_viewer = match include_drafts {
+        Some(true) => member_id,
+        _ => None,
+    };
+    let config: Config = config_read(deps.storage).load()?;
+    let state: State = state_read(deps.storage).load()?;
+    let (polls, next_cursor) = read_polls(
+        deps.storage,
+        filter,
+        start_after,
+        limit,
+        order_by,
+        draft_viewer.as_deref(),
+        poll_type,
+    )?;
 
-    let poll_responses: StdResult<Vec<PollResponse>> = polls
-        .iter()
-        .map(|poll| {
-            Ok(PollResponse {
-                id: poll.id,
-                creator: poll.creator.clone(),
-                status: poll.status.clone(),
-                end_height: poll.end_height,
-                title: poll.title.to_string(),
-                description: poll.description.to_string(),
-                link: poll.link.clone(),
-                yes_votes: poll.yes_votes,
-                no_votes: poll.no_votes,
-                total_share_at_start_poll: poll.total_share_at_start_poll,
-                total_share_at_end_poll: poll.total_share_at_end_poll,
-            })
-        })
+    // page is bounded by the limit clamp of read_polls
+    let mut polls: Vec<PollResponse> = polls
+        .into_iter()
+        .map(|poll| to_poll_response(deps, &config, &state, poll, &env))
         .collect();
+    if let Some(viewer) = viewer {
+        for poll in polls.iter_mut() {
+            add_viewer(deps, &viewer, poll)?;
+        }
+    }
 
     Ok(PollsResponse {
-        polls: poll_responses?,
+        polls,
+        next_cursor,
+        meta: ResponseMeta::new(&env),
+    })
+}
+
+pub fn query_is_blocked(deps: Deps, member_id: String) -> StdResult<IsBlockedResponse> {
+    let blocked = blocklist_read(deps.storage)
+        .may_load(member_id.as_bytes())?
+        .is_some();
+    Ok(IsBlockedResponse { member_id, blocked })
+}
+
+pub fn query_validate_hook(
+    deps: Deps,
+    env: Env,
+    member_id: String,
+    hook: Cw721HookMsg,
+) -> StdResult<ValidateHookResponse> {
+    let error = validate_hook(deps, &env, &member_id, hook)
+        .err()
+        .map(|err| err.to_string());
+    Ok(ValidateHookResponse {
+        valid: error.is_none(),
+        error,
+    })
+}
+
+pub fn query_index_consistency(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<IndexConsistencyResponse> {
+    let (polls, next_cursor) = read_poll_page(
+        deps.storage,
+        start_after,
+        limit.unwrap_or(DEFAULT_INDEX_PAGE),
+    )?;
+    let mut mismatched: Vec<u64> = vec![];
+    for poll in polls.iter() {
+        let (stale, missing) = poll_index_drift(deps.storage, poll)?;
+        if !stale.is_empty() || missing {
+            mismatched.push(poll.id);
+        }
+    }
+    Ok(IndexConsistencyResponse {
+        mismatched,
+        next_cursor,
+    })
+}
+
+pub fn query_top_delegates(deps: Deps, limit: Option<u32>) -> StdResult<TopDelegatesResponse> {
+    let limit = limit
+        .map(|limit| limit as usize)
+        .unwrap_or(DEFAULT_TOP_DELEGATES)
+        .min(MAX_TOP_DELEGATES);
+
+    let mut delegates: Vec<(String, Uint128, Uint128)> = vec![];
+    for member_id in read_top_delegates(deps.storage, limit)? {
+        let token_manager =
+            may_load_member(deps.storage, member_id.as_bytes())?.unwrap_or_default();
+        delegates.push((
+            member_id,
+            token_manager.delegated_in_share,
+            token_manager.share,
+        ));
+    }
+    Ok(TopDelegatesResponse { delegates })
+}
+
+pub fn query_committee(deps: Deps, id: u64) -> Result<Committee, ContractError> {
+    match committee_read(deps.storage).may_load(&id.to_be_bytes())? {
+        Some(committee) => Ok(committee),
+        None => Err(ContractError::CommitteeNotFound {}),
+    }
+}
+
+pub fn query_ibc_config(deps: Deps) -> Result<IbcConfigResponse, ContractError> {
+    let config: Config = config_read(deps.storage).load()?;
+    Ok(IbcConfigResponse {
+        ibc_channel: config.ibc_channel,
+        ibc_notify: config.ibc_notify,
+        ibc_receiver: config.ibc_receiver,
+        ibc_denom: config.ibc_denom,
+    })
+}
+
+/// voter map of poll is scanned up to MAX_VOTER_COUNT entries
+pub fn query_voter_count(deps: Deps, poll_id: u64) -> Result<VoterCountResponse, ContractError> {
+    if may_load_poll(deps.storage, poll_id)?.is_none() {
+        return Err(ContractError::PollNotFound {});
+    }
+
+    let scanned = poll_voter_read(deps.storage, poll_id)
+        .range(None, None, Order::Ascending)
+        .take(MAX_VOTER_COUNT + 1)
+        .count();
+
+    Ok(VoterCountResponse {
+        poll_id,
+        voter_count: scanned.min(MAX_VOTER_COUNT) as u64,
+        capped: scanned > MAX_VOTER_COUNT,
     })
 }
 
@@ -93,8 +559,9 @@ pub fn query_voters(
     start_after: Option<String>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
+    vote_filter: Option<VoteOption>,
 ) -> Result<VotersResponse, ContractError> {
-    let poll: Poll = match poll_read(deps.storage).may_load(&poll_id.to_be_bytes())? {
+    let poll: Poll = match may_load_poll(deps.storage, poll_id)? {
         Some(poll) => Some(poll),
         None => return Err(ContractError::PollNotFound {}),
     }
@@ -106,50 +573,345 @@ pub fn query_voters(
         read_poll_voters(
             deps.storage,
             poll_id,
-            Some(deps.api.addr_canonicalize(&start_after)?),
+            Some(CanonicalAddr::from(start_after.as_bytes())),
             limit,
             order_by,
+            vote_filter,
         )?
     } else {
-        read_poll_voters(deps.storage, poll_id, None, limit, order_by)?
+        read_poll_voters(deps.storage, poll_id, None, limit, order_by, vote_filter)?
     };
 
-    let voters_response: StdResult<Vec<VotersResponseItem>> = voters
+    // voters are keyed by member id (token id), not by address
+    let voters_response: Vec<VotersResponseItem> = voters
         .iter()
-        .map(|voter_info| {
-            Ok(VotersResponseItem {
-                voter: deps.api.addr_humanize(&voter_info.0)?.to_string(),
-                vote: voter_info.1.vote.clone(),
-                balance: voter_info.1.balance,
-            })
+        .map(|voter_info| VotersResponseItem {
+            voter: String::from_utf8_lossy(voter_info.0.as_slice()).to_string(),
+            vote: voter_info.1.vote.clone(),
+            balance: voter_info.1.balance,
+            capped: voter_info.1.capped_balance.is_some(),
         })
         .collect();
 
     Ok(VotersResponse {
-        voters: voters_response?,
+        voters: voters_response,
     })
 }
 
-pub fn query_member(deps: Deps, member_id: String) -> StdResult<StakerResponse> {
-    let member_key = member_id.as_bytes();
-    let mut token_manager = bank_read(deps.storage)
-        .may_load(member_key)?
-        .unwrap_or_default();
+pub fn query_members(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<MembersResponse> {
+    let members = read_members(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(member_id, token_manager)| (member_id, token_manager.balance, token_manager.share))
+        .collect();
+    Ok(MembersResponse { members })
+}
 
-    // leave only in-progress polls
-    token_manager.locked_share.retain(|(poll_id, _)| {
-        let poll: Poll = poll_read(deps.storage)
-            .load(&poll_id.to_be_bytes())
-            .unwrap();
+pub fn query_member(deps: Deps, env: Env, member_id: String) -> StdResult<StakerResponse> {
+    let config: Config = config_read(deps.storage).load()?;
+    let token_manager = load_member_or_new(deps.storage, member_id.as_bytes())?;
 
-        poll.status == PollStatus::InProgress
-    });
+    to_staker_response(deps, &env, &config, token_manager, usize::MAX)
+}
+
+/// members in input order, None for unknown member
+pub fn query_members_batch(
+    deps: Deps,
+    env: Env,
+    member_ids: Vec<String>,
+) -> Result<MembersBatchResponse, ContractError> {
+    if member_ids.len() > MAX_BATCH_MEMBERS {
+        return Err(ContractError::TooManyMembers(MAX_BATCH_MEMBERS));
+    }
+    let config: Config = config_read(deps.storage).load()?;
+
+    let members = member_ids
+        .into_iter()
+        .map(|member_id| {
+            let member = match may_load_member(deps.storage, member_id.as_bytes())? {
+                Some(token_manager) => Some(to_staker_response(
+                    deps,
+                    &env,
+                    &config,
+                    token_manager,
+                    MAX_BATCH_LOCKS,
+                )?),
+                None => None,
+            };
+            Ok((member_id, member))
+        })
+        .collect::<StdResult<Vec<(String, Option<StakerResponse>)>>>()?;
+
+    Ok(MembersBatchResponse { members })
+}
+
+/// in-progress locks are listed up to max_locked (locked_truncated if more)
+fn to_staker_response(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    token_manager: TokenManager,
+    max_locked: usize,
+) -> StdResult<StakerResponse> {
+    let mut locked_balance: Vec<(u64, VoteInfo)> = vec![];
+    let mut locked_truncated = false;
+    for (poll_id, vote_info) in token_manager.locked_share.iter() {
+        if !is_active_poll(deps, *poll_id)? {
+            continue;
+        }
+        if locked_balance.len() >= max_locked {
+            locked_truncated = true;
+            break;
+        }
+        locked_balance.push((*poll_id, vote_info.clone()));
+    }
 
     Ok(StakerResponse {
+        effective_share: effective_share(config, &token_manager, env.block.height),
+        vested_locked: token_manager.vested_at(env.block.height),
         balance: token_manager.balance,
         share: token_manager.share,
-        locked_balance: token_manager.locked_share,
+        locked_balance,
+        locked_truncated,
         delegated_to: token_manager.delegate_to.unwrap_or_default(),
         delegated_from: token_manager.delegated_from,
+        aliases: token_manager.aliases,
+        suspended_until: token_manager.suspended_until,
+        last_vote_height: token_manager.last_vote_height,
+        wallet: token_manager.wallet,
+        vote_key: token_manager.vote_key,
+        vote_nonce: token_manager.vote_nonce,
+        last_ended_poll: token_manager.last_ended_poll,
+        vested_until: token_manager.vested_until,
+        meta: ResponseMeta::new(env),
+    })
+}
+
+/// missing poll is treated as not in progress
+fn is_active_poll(deps: Deps, poll_id: u64) -> StdResult<bool> {
+    Ok(matches!(
+        may_load_poll(deps.storage, poll_id)?,
+        Some(poll) if poll.status == PollStatus::InProgress
+    ))
+}
+
+/// leave only in-progress polls
+fn retain_active_locks(deps: Deps, token_manager: &mut TokenManager) -> StdResult<()> {
+    let mut locked_share: Vec<(u64, VoteInfo)> = vec![];
+    for (poll_id, vote_info) in token_manager.locked_share.drain(..) {
+        if is_active_poll(deps, poll_id)? {
+            locked_share.push((poll_id, vote_info));
+        }
+    }
+    token_manager.locked_share = locked_share;
+    Ok(())
+}
+
+/// share not committed to any in-progress vote (in share units, not balance)
+pub fn query_free_share(deps: Deps, member_id: String) -> StdResult<FreeShareResponse> {
+    let mut token_manager =
+        may_load_member(deps.storage, member_id.as_bytes())?.unwrap_or_default();
+    retain_active_locks(deps, &mut token_manager)?;
+
+    let locked_share = token_manager
+        .locked_share
+        .iter()
+        .map(|(_, vote_info)| vote_info.balance)
+        .max()
+        .unwrap_or_default();
+
+    Ok(FreeShareResponse {
+        share: token_manager.share,
+        locked_share,
+        free_share: token_manager.share.saturating_sub(locked_share),
+    })
+}
+
+/// balance member can withdraw and when locks of in-progress polls free the rest
+/// (by end_height, locks stay until the poll is ended), locks ending by at_height count as freed
+pub fn query_withdrawable(
+    deps: Deps,
+    env: Env,
+    member_id: String,
+    at_height: Option<u64>,
+) -> StdResult<WithdrawableResponse> {
+    let config: Config = config_read(deps.storage).load()?;
+    let mut token_manager =
+        may_load_member(deps.storage, member_id.as_bytes())?.unwrap_or_default();
+    retain_active_locks(deps, &mut token_manager)?;
+
+    let mut locks: Vec<(u64, Uint128)> = vec![];
+    for (poll_id, vote_info) in token_manager.locked_share.iter() {
+        let end_height = match may_load_poll(deps.storage, *poll_id)? {
+            Some(poll) => poll.end_height,
+            None => continue,
+        };
+        if at_height.map_or(false, |at_height| end_height <= at_height) {
+            continue;
+        }
+        let locked = Uint128::from(config.min_balance(vote_info.balance.u128()));
+        locks.push((end_height, locked.min(token_manager.balance)));
+    }
+
+    // vested balance frees at vested_until, permanent one never does
+    let vested = token_manager.vested_at(at_height.unwrap_or(env.block.height));
+    let permanent = match token_manager.vested_until {
+        Some(until) if !vested.is_zero() => {
+            locks.push((until, vested.min(token_manager.balance)));
+            Uint128::zero()
+        }
+        _ => vested.min(token_manager.balance),
+    };
+
+    let locked = locks
+        .iter()
+        .map(|(_, locked)| *locked)
+        .max()
+        .unwrap_or_default()
+        .max(permanent);
+    Ok(WithdrawableResponse {
+        withdrawable_now: token_manager.balance - locked,
+        locked,
+        unlock_schedule: unlock_schedule(&locks),
+    })
+}
+
+/// balance needed for share (share^2 under Quadratic with Floor rounding)
+pub fn query_share_to_min_balance(
+    deps: Deps,
+    share: Uint128,
+) -> StdResult<ShareToMinBalanceResponse> {
+    let config: Config = config_read(deps.storage).load()?;
+    if share > config.share(Uint128::MAX) {
+        return Err(StdError::generic_err("No balance has this share"));
+    }
+    Ok(ShareToMinBalanceResponse {
+        share,
+        min_balance: Uint128::from(config.min_balance(share.u128())),
+    })
+}
+
+/// query delegation of member (delegating out and delegated in)
+pub fn query_delegation_status(
+    deps: Deps,
+    member_id: String,
+) -> Result<DelegationStatusResponse, ContractError> {
+    let token_manager = may_load_member(deps.storage, member_id.as_bytes())?.unwrap_or_default();
+
+    let mut incoming_share = Uint128::zero();
+    for id in token_manager.delegated_from.iter() {
+        if let Some(delegator) = may_load_member(deps.storage, id.as_bytes())? {
+            incoming_share += delegator.share;
+        }
+    }
+
+    Ok(DelegationStatusResponse {
+        is_delegating: token_manager.delegate_to.is_some(),
+        delegate_to: token_manager.delegate_to,
+        delegated_from: token_manager.delegated_from,
+        incoming_share,
+    })
+}
+
+/// query member who claimed the alias
+pub fn query_member_by_alias(
+    deps: Deps,
+    namespace: String,
+    address: String,
+) -> Result<MemberByAliasResponse, ContractError> {
+    match alias_read(deps.storage, &namespace).may_load(address.as_bytes())? {
+        Some(member_id) => Ok(MemberByAliasResponse { member_id }),
+        None => Err(ContractError::AliasNotFound {}),
+    }
+}
+
+fn to_admin_action_response(
+    deps: Deps,
+    env: &Env,
+    proposal: AdminProposal,
+) -> Result<AdminActionResponse, ContractError> {
+    Ok(AdminActionResponse {
+        id: proposal.id,
+        action: proposal.action,
+        proposer: deps.api.addr_humanize(&proposal.proposer)?.to_string(),
+        approvals: proposal
+            .approvals
+            .iter()
+            .map(|approver| {
+                deps.api
+                    .addr_humanize(approver)
+                    .map(|addr| addr.to_string())
+            })
+            .collect::<StdResult<Vec<String>>>()?,
+        expires: proposal.expires,
+        expired: proposal.expires <= env.block.height,
+    })
+}
+
+/// query admin action waiting for approvals
+pub fn query_admin_action(
+    deps: Deps,
+    env: Env,
+    id: u64,
+) -> Result<AdminActionResponse, ContractError> {
+    match admin_proposal_read(deps.storage).may_load(&id.to_be_bytes())? {
+        Some(proposal) => to_admin_action_response(deps, &env, proposal),
+        None => Err(ContractError::AdminActionNotFound {}),
+    }
+}
+
+/// query admin actions waiting for approvals (by id ascending)
+pub fn query_admin_actions(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<AdminActionsResponse, ContractError> {
+    let admin_actions = read_admin_proposals(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|proposal| to_admin_action_response(deps, &env, proposal))
+        .collect::<Result<Vec<AdminActionResponse>, ContractError>>()?;
+    Ok(AdminActionsResponse { admin_actions })
+}
+
+/// reconstruct what a member's vote did in a poll
+/// (own vote + votes cast on behalf of delegators at vote time)
+pub fn query_vote_receipt(
+    deps: Deps,
+    poll_id: u64,
+    member_id: String,
+) -> Result<VoteReceiptResponse, ContractError> {
+    let vote_info = match poll_voter_read(deps.storage, poll_id).may_load(member_id.as_bytes())? {
+        Some(vote_info) => vote_info,
+        None => return Err(ContractError::NotYetVoted {}),
+    };
+
+    let delegated_from: StdResult<Vec<(String, Uint128)>> = poll_voter_read(deps.storage, poll_id)
+        .range(None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, v)) => v.via_delegate.as_deref() == Some(member_id.as_str()),
+            Err(_) => true,
+        })
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((String::from_utf8_lossy(&k).to_string(), v.balance))
+        })
+        .collect();
+    let delegated_from = delegated_from?;
+
+    let total_amount = delegated_from
+        .iter()
+        .fold(vote_info.weight(), |acc, (_, amount)| acc + *amount);
+
+    Ok(VoteReceiptResponse {
+        poll_id,
+        voter: member_id,
+        vote: vote_info.vote,
+        my_share: vote_info.balance,
+        delegated_from,
+        total_amount,
     })
 }
@@ -1,32 +1,127 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Binary, CanonicalAddr, Decimal, StdResult, Storage, Uint128};
+use cosmwasm_std::{
+    from_slice, to_vec, Binary, BlockInfo, CanonicalAddr, Decimal, Order, StdError, StdResult,
+    Storage, Timestamp, Uint128,
+};
 use cosmwasm_storage::{
-    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
-    Singleton,
+    bucket, bucket_read, singleton, singleton_read, to_length_prefixed, Bucket, PrefixedStorage,
+    ReadonlyBucket, ReadonlyPrefixedStorage, ReadonlySingleton, Singleton,
 };
 
-use crate::utils::{OrderBy, PollStatus, VoteInfo};
+use crate::msg::AdminAction;
+use crate::utils::{
+    EndPollPermission, OrderBy, PollKind, PollLink, PollOutcome, PollStatus, PollType,
+    RoundingMode, TextLimits, TiePolicy, VoteInfo, VoteOption, VotingModel,
+};
+use std::any::type_name;
 use std::cmp::Ordering;
+use std::convert::TryInto;
 
 static KEY_CONFIG: &[u8] = b"config";
 static KEY_STATE: &[u8] = b"state";
 static KEY_TMP_POLL_ID: &[u8] = b"tmp_poll_id";
+static KEY_TMP_SUPPLY: &[u8] = b"tmp_supply";
+static KEY_COMMITTEE_DRAW: &[u8] = b"committee_draw";
 
 static PREFIX_POLL_INDEXER: &[u8] = b"poll_indexer";
 static PREFIX_POLL_VOTER: &[u8] = b"poll_voter";
 static PREFIX_POLL: &[u8] = b"poll";
+static PREFIX_POLL_DESCRIPTION: &[u8] = b"poll_description";
 static PREFIX_BANK: &[u8] = b"bank";
+static PREFIX_ALIAS: &[u8] = b"alias";
+static PREFIX_SEASON: &[u8] = b"season";
+static PREFIX_ADMIN_ACTION: &[u8] = b"admin_action";
+static PREFIX_BLOCKLIST: &[u8] = b"blocklist";
+static PREFIX_COMMITTEE: &[u8] = b"committee";
+static PREFIX_DELEGATE_INDEX: &[u8] = b"delegate_index";
+static PREFIX_IN_FLIGHT: &[u8] = b"in_flight";
+static PREFIX_MEMBER_TOMBSTONE: &[u8] = b"member_tombstone";
 
 /// default information & parameters for the contract
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
-    pub owner: CanonicalAddr, // owner of the contract
+    #[serde(default)]
+    pub admins: Vec<CanonicalAddr>, // admins of the contract (empty only in legacy config)
+    #[serde(default)]
+    pub admin_threshold: u32, // approvals required for privileged actions
     pub cw721_token: CanonicalAddr,
     pub quorum: Decimal,    // quorum
     pub threshold: Decimal, // threshold for ratio of yes votes
     pub voting_period: u64,
+    #[serde(default)]
+    pub share_rounding: RoundingMode, // rounding of sqrt(balance)
+    #[serde(default)]
+    pub voting_model: VotingModel, // curve of share over balance
+    #[serde(default)]
+    pub season_length: Option<u64>, // blocks per governance season (None: no seasons)
+    #[serde(default)]
+    pub season_blackout: u64, // no new polls in the final blocks of a season
+    #[serde(default)]
+    pub end_poll_reward: Uint128, // warrant tokens minted to member who ends a poll
+    #[serde(default)]
+    pub decay_after: Option<u64>, // blocks without voting before share decays (None: no decay)
+    #[serde(default)]
+    pub decay_rate: Decimal, // fraction of share lost per voting_period beyond decay_after
+    #[serde(default)]
+    pub decay_floor: Decimal, // share never decays below this fraction
+    #[serde(default)]
+    pub bind_wallet: bool, // reject hooks from a wallet other than the member's bound wallet
+    #[serde(default)]
+    pub text_limits: TextLimits, // max lengths of poll text
+    #[serde(default)]
+    pub draft_ttl: Option<u64>, // blocks after which unpublished drafts can be pruned (and no longer published)
+    #[serde(default)]
+    pub threshold_inclusive: bool, // poll passes when yes ratio equals threshold
+    #[serde(default)]
+    pub failed_poll_cooldown: u64, // blocks creator waits after own poll failed quorum
+    #[serde(default)]
+    pub signal_quorum: Option<Decimal>, // quorum of signal polls (None: quorum)
+    #[serde(default)]
+    pub signal_threshold: Option<Decimal>, // threshold of signal polls (None: threshold)
+    #[serde(default)]
+    pub max_extension: u64, // total blocks a poll's voting period can be extended by
+    #[serde(default)]
+    pub ibc_channel: Option<String>, // channel notified of poll results (channel-N)
+    #[serde(default)]
+    pub ibc_notify: bool,
+    #[serde(default)]
+    pub ibc_receiver: Option<String>, // address on the remote chain receiving notifications
+    #[serde(default)]
+    pub ibc_denom: Option<String>, // denom of the minimal transfer carrying notification
+    #[serde(default)]
+    pub tie_policy: TiePolicy, // action when yes and no votes tie at quorum
+    #[serde(default)]
+    pub auto_finalize: bool, // pass poll on the vote after which remaining share can't change it
+    #[serde(default)]
+    pub max_supply: Option<Uint128>, // cap on total supply of warrant tokens minted by admins
+    #[serde(default)]
+    pub text_deposit: Uint128, // deposit taken by create_poll for signal polls
+    #[serde(default)]
+    pub execute_deposit: Uint128, // deposit taken by create_poll for binding polls
+    #[serde(default)]
+    pub end_poll_permission: EndPollPermission, // who can end polls (set at instantiate)
+    #[serde(default)]
+    pub adaptive_quorum: bool, // quorum follows average turnout of recently ended polls
+    #[serde(default)]
+    pub vote_freeze_window: u64, // final blocks of voting period in which votes are frozen
+    #[serde(default)]
+    pub freeze_new_votes: bool, // fresh votes are frozen as well
+    #[serde(default)]
+    pub voting_time: u64, // seconds of voting period alongside voting_period (0: height only)
+    #[serde(default)]
+    pub use_token_multiplier: bool, // votes weighted by multiplier of the voting NFT
+    #[serde(default)]
+    pub max_multiplier: Decimal, // cap of token multipliers (at least one)
+    #[serde(default)]
+    pub bootstrap_period: u64, // blocks after instantiate bootstrap_quorum applies in end_poll
+    #[serde(default)]
+    pub bootstrap_quorum: Decimal, // quorum cap of polls ended during bootstrap_period
+    #[serde(default)]
+    pub delegate_inactivity_timeout: Option<u64>, // blocks delegate can go without voting (None: no limit)
+    #[serde(default)]
+    pub max_vote_share: Option<Decimal>, // counted vote cap per member, of total_share_at_start_poll
 }
 
 // state for the contract
@@ -35,6 +130,178 @@ pub struct State {
     pub contract_addr: CanonicalAddr, // address of this contract
     pub poll_count: u64,              // total polls proposed
     pub total_share: Uint128,         // total shares
+    #[serde(default)]
+    pub total_supply: Uint128, // total balance of warrant tokens
+    #[serde(default)]
+    pub current_season: u64,
+    #[serde(default)]
+    pub season_start_height: u64,
+    #[serde(default)]
+    pub admin_action_count: u64, // total admin actions proposed
+    #[serde(default)]
+    pub committee_count: u64, // total committees drawn
+    #[serde(default)]
+    pub recent_turnout: Vec<Decimal>, // turnout of the last ended polls, oldest first
+    #[serde(default)]
+    pub instantiate_height: u64, // start of bootstrap_period
+}
+
+// number of ended polls averaged by adaptive quorum
+pub const TURNOUT_WINDOW: usize = 10;
+
+impl Config {
+    pub fn is_admin(&self, addr: &CanonicalAddr) -> bool {
+        self.admins.contains(addr)
+    }
+
+    pub fn share(&self, balance: Uint128) -> Uint128 {
+        self.voting_model.share(&self.share_rounding, balance)
+    }
+
+    pub fn min_balance(&self, share: u128) -> u128 {
+        self.voting_model.min_balance(&self.share_rounding, share)
+    }
+
+    /// quorum end_poll compares against, base is the quorum snapshotted by the poll
+    /// with adaptive_quorum it is the average recent turnout, kept between half and
+    /// double of base (base until a poll ended)
+    pub fn effective_quorum(&self, state: &State, base: Decimal) -> Decimal {
+        if !self.adaptive_quorum || state.recent_turnout.is_empty() {
+            return base;
+        }
+        let sum = state
+            .recent_turnout
+            .iter()
+            .fold(Decimal::zero(), |sum, turnout| sum + *turnout);
+        let average = sum / Uint128::from(state.recent_turnout.len() as u128);
+        let floor = base / Uint128::from(2u128);
+        let ceiling = std::cmp::min(base + base, Decimal::one());
+        average.max(floor).min(ceiling)
+    }
+
+    /// quorum required to end a poll at height: effective_quorum, lowered to
+    /// bootstrap_quorum within bootstrap_period blocks of instantiate
+    pub fn end_quorum(&self, state: &State, base: Decimal, height: u64) -> Decimal {
+        let quorum = self.effective_quorum(state, base);
+        if self.in_bootstrap(state, height) {
+            quorum.min(self.bootstrap_quorum)
+        } else {
+            quorum
+        }
+    }
+
+    /// delegations to delegate are void once it hasn't voted for delegate_inactivity_timeout
+    /// blocks (joining height counts as a vote, members without one never expire)
+    pub fn delegate_inactive(&self, delegate: &TokenManager, height: u64) -> bool {
+        match (self.delegate_inactivity_timeout, delegate.last_vote_height) {
+            (Some(timeout), Some(last_vote_height)) => {
+                height.saturating_sub(last_vote_height) > timeout
+            }
+            _ => false,
+        }
+    }
+
+    /// height is within bootstrap_period blocks of instantiate
+    pub fn in_bootstrap(&self, state: &State, height: u64) -> bool {
+        height
+            < state
+                .instantiate_height
+                .saturating_add(self.bootstrap_period)
+    }
+
+    /// end_time of poll whose voting period starts at time (None without voting_time)
+    pub fn end_time(&self, time: Timestamp) -> Option<Timestamp> {
+        if self.voting_time == 0 {
+            return None;
+        }
+        Some(time.plus_seconds(self.voting_time))
+    }
+
+    /// seconds end_time is pushed by when end_height is extended by blocks
+    pub fn extension_seconds(&self, blocks: u64) -> u64 {
+        blocks.saturating_mul(self.voting_time) / self.voting_period.max(1)
+    }
+
+    /// first height of the poll's vote_freeze_window (None without a window)
+    pub fn freeze_starts_at(&self, a_poll: &Poll) -> Option<u64> {
+        if self.vote_freeze_window == 0 {
+            return None;
+        }
+        Some(a_poll.end_height.saturating_sub(self.vote_freeze_window) + 1)
+    }
+
+    pub fn quorum_for(&self, poll_type: &PollType) -> Decimal {
+        match poll_type {
+            PollType::Signal => self.signal_quorum.unwrap_or(self.quorum),
+            PollType::Binding => self.quorum,
+        }
+    }
+
+    pub fn deposit_for(&self, poll_type: &PollType) -> Uint128 {
+        match poll_type {
+            PollType::Signal => self.text_deposit,
+            PollType::Binding => self.execute_deposit,
+        }
+    }
+
+    pub fn threshold_for(&self, poll_type: &PollType) -> Decimal {
+        match poll_type {
+            PollType::Signal => self.signal_threshold.unwrap_or(self.threshold),
+            PollType::Binding => self.threshold,
+        }
+    }
+}
+
+/// privileged action waiting for admin approvals (removed once executed)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdminProposal {
+    pub id: u64,
+    pub action: AdminAction,
+    pub proposer: CanonicalAddr,
+    pub approvals: Vec<CanonicalAddr>,
+    pub expires: u64, // can't be approved after this height
+}
+
+/// snapshot taken when a season ends
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SeasonSnapshot {
+    pub season: u64,
+    pub total_share: Uint128,
+    pub poll_count: u64,
+    pub height: u64, // height at rollover
+}
+
+/// members drawn by DrawCommittee (in draw order)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Committee {
+    pub id: u64,
+    pub seed_poll_id: u64,
+    pub members: Vec<String>,
+    pub drawn_at_height: u64,
+}
+
+/// committee draw in progress (scans bank in pages)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CommitteeDraw {
+    pub size: u8,
+    pub seed_poll_id: u64,
+    pub seed: Binary,
+    pub next_key: Vec<u8>, // bank key to resume from (empty: from the beginning)
+    pub drawn: Vec<DrawnMember>, // members with the smallest keys so far, in draw order
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DrawnMember {
+    pub member_id: String,
+    pub key: Uint128, // utils::sample_key
+    pub share: Uint128,
+}
+
+/// progress of total_supply recomputation (scans bank in pages)
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SupplyRecompute {
+    pub next_key: Vec<u8>, // bank key to resume from (empty: from the beginning)
+    pub total_supply: Uint128, // sum of balances of bank keys before next_key
 }
 
 /// token manager maps to each address
@@ -46,6 +313,38 @@ pub struct TokenManager {
     pub locked_share: Vec<(u64, VoteInfo)>, // maps poll_id to weight voted
     pub delegate_to: Option<String>,
     pub delegated_from: Vec<String>,
+    pub aliases: Vec<(String, String)>, // (namespace, address) of external addresses
+    pub suspended_until: Option<u64>,   // suspended from governance until this height
+    pub last_vote_height: Option<u64>,  // joining height until first vote (None: no decay)
+    pub wallet: Option<String>,         // wallet holding the NFT at last governance action
+    pub vote_key: Option<Binary>,       // secp256k1 public key for relayed ballots
+    pub last_ended_poll: Option<EndedPoll>, // most recently ended poll created by member
+    pub delegated_in_share: Uint128,    // sum of shares of members in delegated_from
+    pub vote_nonce: u64, // nonce the next relayed ballot must carry (incremented on use)
+    pub vested_locked: Uint128, // part of balance minted by MintLocked (votes, can't leave)
+    pub vested_until: Option<u64>, // release height of vested_locked (None: permanent)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EndedPoll {
+    pub poll_id: u64,
+    pub outcome: PollOutcome,
+    pub ended_at_height: u64,
+}
+
+impl TokenManager {
+    /// suspension is over once block height reaches suspended_until
+    pub fn is_suspended(&self, height: u64) -> bool {
+        matches!(self.suspended_until, Some(until) if height < until)
+    }
+
+    /// vested balance still locked at height (matured once height reaches vested_until)
+    pub fn vested_at(&self, height: u64) -> Uint128 {
+        if self.vested_until.map_or(false, |until| height >= until) {
+            return Uint128::zero();
+        }
+        self.vested_locked
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -57,10 +356,92 @@ pub struct Poll {
     pub no_votes: Uint128,
     pub end_height: u64,
     pub title: String,
-    pub description: String,
-    pub link: Option<String>,
+    pub description: String, // legacy (polls created before poll_description chunks)
+    pub summary: String,     // short text returned by poll lists (empty for legacy polls)
+    pub link: Option<String>, // legacy (polls created before links)
+    pub links: Vec<PollLink>,
     pub total_share_at_end_poll: Option<Uint128>,
     pub total_share_at_start_poll: Uint128,
+    pub deposit: Uint128, // warrant tokens of creator held by poll (refunded to creator)
+    pub deposit_claimed: bool, // deposit was refunded (polls before this flag zeroed deposit instead)
+    pub ended_by: Option<String>, // member who ended the poll
+    pub ended_at_height: u64,
+    pub quorum: Option<Decimal>, // config.quorum at creation (None: polls created before)
+    pub threshold: Option<Decimal>, // config.threshold at creation
+    pub threshold_inclusive: Option<bool>, // config.threshold_inclusive at creation
+    pub creator_wallet: String,  // wallet holding creator's NFT at creation
+    pub quorum_reached_height: Option<u64>, // height when votes first reached quorum
+    pub created_at_height: u64,
+    pub extended_blocks: u64, // total blocks end_height was extended by
+    pub tie_extended: bool,   // voting period was already extended once to break a tie
+    pub poll_type: PollType,
+    pub kind: PollKind,
+    pub votes_per_option: Vec<Uint128>, // tally of multiple choice poll (yes/no votes unused)
+    pub winning_option: Option<u32>,
+    pub depends_on: Option<u64>, // poll that must pass before voting opens
+    pub force_ended: bool,       // ended by owner through ForceEndPoll
+    pub raw_quorum: Option<Decimal>, // tallied / staked share at end (may exceed one)
+    pub quorum_used: Option<Decimal>, // raw_quorum clamped to one, compared against quorum
+    pub participation_anomaly: bool, // raw_quorum was clamped
+    pub end_time: Option<Timestamp>, // set with voting_time (voting also ends by time)
+    #[serde(default)]
+    pub deposit_slashed: bool, // deposit was burned, poll ended without quorum
+}
+
+impl Poll {
+    /// votes are taken up to end_height and, if set, end_time
+    pub fn voting_open(&self, block: &BlockInfo) -> bool {
+        block.height <= self.end_height
+            && self
+                .end_time
+                .map_or(true, |end_time| block.time <= end_time)
+    }
+
+    pub fn extend_end_time(&mut self, seconds: u64) {
+        self.end_time = self.end_time.map(|end_time| end_time.plus_seconds(seconds));
+    }
+
+    /// end_poll can finalize the poll once end_height or end_time is reached
+    pub fn voting_ended(&self, block: &BlockInfo) -> bool {
+        self.end_height <= block.height
+            || self
+                .end_time
+                .map_or(false, |end_time| end_time <= block.time)
+    }
+
+    /// vote option must match poll kind
+    pub fn accepts(&self, vote: &VoteOption) -> bool {
+        match (&self.kind, vote) {
+            (PollKind::Binary, VoteOption::Yes) | (PollKind::Binary, VoteOption::No) => true,
+            (PollKind::MultipleChoice { options }, VoteOption::Choice(index)) => {
+                (*index as usize) < options.len()
+            }
+            _ => false,
+        }
+    }
+
+    pub fn add_vote(&mut self, vote: &VoteOption, amount: Uint128) {
+        match vote {
+            VoteOption::Yes => self.yes_votes += amount,
+            VoteOption::No => self.no_votes += amount,
+            VoteOption::Choice(index) => self.votes_per_option[*index as usize] += amount,
+        }
+    }
+
+    pub fn remove_vote(&mut self, vote: &VoteOption, amount: Uint128) {
+        match vote {
+            VoteOption::Yes => self.yes_votes -= amount,
+            VoteOption::No => self.no_votes -= amount,
+            VoteOption::Choice(index) => self.votes_per_option[*index as usize] -= amount,
+        }
+    }
+
+    /// sum of all votes cast
+    pub fn tallied(&self) -> Uint128 {
+        self.votes_per_option
+            .iter()
+            .fold(self.yes_votes + self.no_votes, |acc, votes| acc + *votes)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -90,19 +471,38 @@ impl PartialEq for ExecuteData {
     }
 }
 
-pub fn config_store(storage: &mut dyn Storage) -> Singleton<Config> {
+pub fn config_store(storage: &mut dyn Storage) -> Singleton<'_, Config> {
     singleton(storage, KEY_CONFIG)
 }
 
-pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<Config> {
+pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<'_, Config> {
     singleton_read(storage, KEY_CONFIG)
 }
 
-pub fn state_store(storage: &mut dyn Storage) -> Singleton<State> {
+/// config stored before owner was replaced by admins (only owner is read)
+#[derive(Serialize, Deserialize)]
+struct LegacyConfig {
+    owner: CanonicalAddr,
+}
+
+/// makes owner of a legacy config its only admin, returns whether config was converted
+pub fn migrate_legacy_owner(storage: &mut dyn Storage) -> StdResult<bool> {
+    let mut config: Config = config_read(storage).load()?;
+    if !config.admins.is_empty() {
+        return Ok(false);
+    }
+    let legacy: LegacyConfig = singleton_read(storage, KEY_CONFIG).load()?;
+    config.admins = vec![legacy.owner];
+    config.admin_threshold = 1;
+    config_store(storage).save(&config)?;
+    Ok(true)
+}
+
+pub fn state_store(storage: &mut dyn Storage) -> Singleton<'_, State> {
     singleton(storage, KEY_STATE)
 }
 
-pub fn state_read(storage: &dyn Storage) -> ReadonlySingleton<State> {
+pub fn state_read(storage: &dyn Storage) -> ReadonlySingleton<'_, State> {
     singleton_read(storage, KEY_STATE)
 }
 
@@ -114,12 +514,322 @@ pub fn read_tmp_poll_id(storage: &dyn Storage) -> StdResult<u64> {
     singleton_read(storage, KEY_TMP_POLL_ID).load()
 }
 
-pub fn poll_store(storage: &mut dyn Storage) -> Bucket<Poll> {
-    bucket(storage, PREFIX_POLL)
+pub fn store_tmp_supply(storage: &mut dyn Storage, progress: &SupplyRecompute) -> StdResult<()> {
+    singleton(storage, KEY_TMP_SUPPLY).save(progress)
 }
 
-pub fn poll_read(storage: &dyn Storage) -> ReadonlyBucket<Poll> {
-    bucket_read(storage, PREFIX_POLL)
+pub fn read_tmp_supply(storage: &dyn Storage) -> StdResult<Option<SupplyRecompute>> {
+    singleton_read(storage, KEY_TMP_SUPPLY).may_load()
+}
+
+pub fn remove_tmp_supply(storage: &mut dyn Storage) {
+    singleton::<SupplyRecompute>(storage, KEY_TMP_SUPPLY).remove()
+}
+
+pub fn store_committee_draw(storage: &mut dyn Storage, draw: &CommitteeDraw) -> StdResult<()> {
+    singleton(storage, KEY_COMMITTEE_DRAW).save(draw)
+}
+
+pub fn read_committee_draw(storage: &dyn Storage) -> StdResult<Option<CommitteeDraw>> {
+    singleton_read(storage, KEY_COMMITTEE_DRAW).may_load()
+}
+
+pub fn remove_committee_draw(storage: &mut dyn Storage) {
+    singleton::<CommitteeDraw>(storage, KEY_COMMITTEE_DRAW).remove()
+}
+
+/// versioned layout of a stored Poll
+/// the latest variant holds Poll itself; adding a field freezes the current layout as
+/// PollV2 and adds V3, so fields never need #[serde(default)] to load older records
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StoredPoll {
+    V1(PollV1),
+    V2(Poll),
+}
+
+impl From<StoredPoll> for Poll {
+    fn from(stored: StoredPoll) -> Self {
+        match stored {
+            StoredPoll::V1(poll) => poll.into(),
+            StoredPoll::V2(poll) => poll,
+        }
+    }
+}
+
+/// Poll as stored before summary, deposit_claimed, force_ended, raw_quorum, quorum_used,
+/// participation_anomaly and end_time (also the layout of untagged pre-versioning records)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollV1 {
+    pub id: u64,
+    pub creator: String,
+    pub status: PollStatus,
+    pub yes_votes: Uint128,
+    pub no_votes: Uint128,
+    pub end_height: u64,
+    pub title: String,
+    pub description: String,
+    pub link: Option<String>,
+    #[serde(default)]
+    pub links: Vec<PollLink>,
+    pub total_share_at_end_poll: Option<Uint128>,
+    pub total_share_at_start_poll: Uint128,
+    #[serde(default)]
+    pub deposit: Uint128,
+    #[serde(default)]
+    pub ended_by: Option<String>,
+    #[serde(default)]
+    pub ended_at_height: u64,
+    #[serde(default)]
+    pub quorum: Option<Decimal>,
+    #[serde(default)]
+    pub threshold: Option<Decimal>,
+    #[serde(default)]
+    pub threshold_inclusive: Option<bool>,
+    #[serde(default)]
+    pub creator_wallet: String,
+    #[serde(default)]
+    pub quorum_reached_height: Option<u64>,
+    #[serde(default)]
+    pub created_at_height: u64,
+    #[serde(default)]
+    pub extended_blocks: u64,
+    #[serde(default)]
+    pub tie_extended: bool,
+    #[serde(default)]
+    pub poll_type: PollType,
+    #[serde(default)]
+    pub kind: PollKind,
+    #[serde(default)]
+    pub votes_per_option: Vec<Uint128>,
+    #[serde(default)]
+    pub winning_option: Option<u32>,
+    #[serde(default)]
+    pub depends_on: Option<u64>,
+}
+
+impl From<PollV1> for Poll {
+    fn from(poll: PollV1) -> Self {
+        Poll {
+            id: poll.id,
+            creator: poll.creator,
+            status: poll.status,
+            yes_votes: poll.yes_votes,
+            no_votes: poll.no_votes,
+            end_height: poll.end_height,
+            title: poll.title,
+            description: poll.description,
+            summary: String::new(),
+            link: poll.link,
+            links: poll.links,
+            total_share_at_end_poll: poll.total_share_at_end_poll,
+            total_share_at_start_poll: poll.total_share_at_start_poll,
+            deposit: poll.deposit,
+            deposit_claimed: false,
+            deposit_slashed: false,
+            ended_by: poll.ended_by,
+            ended_at_height: poll.ended_at_height,
+            quorum: poll.quorum,
+            threshold: poll.threshold,
+            threshold_inclusive: poll.threshold_inclusive,
+            creator_wallet: poll.creator_wallet,
+            quorum_reached_height: poll.quorum_reached_height,
+            created_at_height: poll.created_at_height,
+            extended_blocks: poll.extended_blocks,
+            tie_extended: poll.tie_extended,
+            poll_type: poll.poll_type,
+            kind: poll.kind,
+            votes_per_option: poll.votes_per_option,
+            winning_option: poll.winning_option,
+            depends_on: poll.depends_on,
+            force_ended: false,
+            raw_quorum: None,
+            quorum_used: None,
+            participation_anomaly: false,
+            end_time: None,
+        }
+    }
+}
+
+// serializes like the latest StoredPoll variant without cloning the poll
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum StoredPollRef<'a> {
+    V2(&'a Poll),
+}
+
+// tagged records start with their variant, untagged ones with the first struct field
+fn is_tagged(data: &[u8]) -> bool {
+    data.starts_with(br#"{"v"#)
+}
+
+// polls saved before versioning are untagged V1; they are retagged on next save
+fn decode_poll(data: &[u8]) -> StdResult<Poll> {
+    if is_tagged(data) {
+        from_slice::<StoredPoll>(data).map(Poll::from)
+    } else {
+        from_slice::<PollV1>(data).map(Poll::from)
+    }
+}
+
+pub fn may_load_poll(storage: &dyn Storage, poll_id: u64) -> StdResult<Option<Poll>> {
+    ReadonlyPrefixedStorage::new(storage, PREFIX_POLL)
+        .get(&poll_id.to_be_bytes())
+        .map(|data| decode_poll(&data))
+        .transpose()
+}
+
+pub fn load_poll(storage: &dyn Storage, poll_id: u64) -> StdResult<Poll> {
+    may_load_poll(storage, poll_id)?.ok_or_else(|| StdError::not_found(type_name::<Poll>()))
+}
+
+pub fn save_poll(storage: &mut dyn Storage, poll: &Poll) -> StdResult<()> {
+    PrefixedStorage::new(storage, PREFIX_POLL)
+        .set(&poll.id.to_be_bytes(), &to_vec(&StoredPollRef::V2(poll))?);
+    Ok(())
+}
+
+pub fn remove_poll(storage: &mut dyn Storage, poll_id: u64) {
+    PrefixedStorage::new(storage, PREFIX_POLL).remove(&poll_id.to_be_bytes());
+    remove_poll_description(storage, poll_id);
+}
+
+// bytes per stored chunk of poll description
+pub const DESCRIPTION_CHUNK_SIZE: usize = 2048;
+// chunks per poll description
+pub const MAX_DESCRIPTION_CHUNKS: usize = 8;
+
+/// full description of a poll, kept apart so loading a Poll doesn't read it
+/// stored as raw utf-8 bytes split into chunks of DESCRIPTION_CHUNK_SIZE (key: poll_id + index)
+pub fn save_poll_description(
+    storage: &mut dyn Storage,
+    poll_id: u64,
+    description: &str,
+) -> StdResult<()> {
+    let chunks: Vec<&[u8]> = description
+        .as_bytes()
+        .chunks(DESCRIPTION_CHUNK_SIZE)
+        .collect();
+    if chunks.len() > MAX_DESCRIPTION_CHUNKS {
+        return Err(StdError::generic_err("description has too many chunks"));
+    }
+    remove_poll_description(storage, poll_id);
+    let mut store =
+        PrefixedStorage::multilevel(storage, &[PREFIX_POLL_DESCRIPTION, &poll_id.to_be_bytes()]);
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        store.set(&[index as u8], chunk);
+    }
+    Ok(())
+}
+
+/// description reassembled from its chunks, None for polls without one (legacy polls)
+pub fn may_load_poll_description(storage: &dyn Storage, poll_id: u64) -> StdResult<Option<String>> {
+    let store = ReadonlyPrefixedStorage::multilevel(
+        storage,
+        &[PREFIX_POLL_DESCRIPTION, &poll_id.to_be_bytes()],
+    );
+    let mut bytes: Vec<u8> = vec![];
+    let mut found = false;
+    for (_, chunk) in store.range(None, None, Order::Ascending) {
+        bytes.extend(chunk);
+        found = true;
+    }
+    if !found {
+        return Ok(None);
+    }
+    String::from_utf8(bytes)
+        .map(Some)
+        .map_err(|_| StdError::invalid_utf8("poll description"))
+}
+
+/// full description of poll (legacy polls keep it in the Poll)
+pub fn read_poll_description(storage: &dyn Storage, poll: &Poll) -> StdResult<String> {
+    Ok(may_load_poll_description(storage, poll.id)?.unwrap_or_else(|| poll.description.clone()))
+}
+
+pub fn remove_poll_description(storage: &mut dyn Storage, poll_id: u64) {
+    let mut store =
+        PrefixedStorage::multilevel(storage, &[PREFIX_POLL_DESCRIPTION, &poll_id.to_be_bytes()]);
+    for index in 0..MAX_DESCRIPTION_CHUNKS {
+        store.remove(&[index as u8]);
+    }
+}
+
+/// poll id of a poll (or poll indexer) key
+pub fn poll_id_from_key(key: &[u8]) -> StdResult<u64> {
+    let bytes: [u8; 8] = key
+        .try_into()
+        .map_err(|_| StdError::generic_err("invalid poll key"))?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// polls with ids in [start, end)
+pub fn range_polls<'a>(
+    storage: &'a dyn Storage,
+    start: Option<&[u8]>,
+    end: Option<&[u8]>,
+    order: Order,
+) -> Box<dyn Iterator<Item = StdResult<Poll>> + 'a> {
+    Box::new(range_prefixed(storage, PREFIX_POLL, start, end, order).map(|(_, v)| decode_poll(&v)))
+}
+
+// raw range over keys under namespace, keys are returned without the namespace
+fn range_prefixed<'a>(
+    storage: &'a dyn Storage,
+    namespace: &[u8],
+    start: Option<&[u8]>,
+    end: Option<&[u8]>,
+    order: Order,
+) -> impl Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a {
+    let prefix = to_length_prefixed(namespace);
+    let start = [prefix.as_slice(), start.unwrap_or_default()].concat();
+    let end = match end {
+        Some(end) => [prefix.as_slice(), end].concat(),
+        None => namespace_upper_bound(&prefix),
+    };
+    let prefix_len = prefix.len();
+    storage
+        .range(Some(&start), Some(&end), order)
+        .map(move |(k, v)| (k[prefix_len..].to_vec(), v))
+}
+
+// smallest key greater than all keys starting with prefix
+fn namespace_upper_bound(prefix: &[u8]) -> Vec<u8> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < u8::MAX {
+            end.push(last + 1);
+            break;
+        }
+    }
+    end
+}
+
+/// polls created before snapshotting get current quorum/threshold of their poll type
+/// one page of read_poll_page per call, returns number of updated polls and the next cursor
+pub fn snapshot_poll_parameters(
+    storage: &mut dyn Storage,
+    start_after: Option<u64>,
+    limit: u32,
+) -> StdResult<(u64, Option<u64>)> {
+    let config: Config = config_read(storage).load()?;
+    let (polls, next_cursor) = read_poll_page(storage, start_after, limit)?;
+
+    let mut snapshotted = 0;
+    for mut poll in polls {
+        let snapshot = (poll.quorum, poll.threshold, poll.threshold_inclusive);
+        if let (Some(_), Some(_), Some(_)) = snapshot {
+            continue;
+        }
+        let quorum = snapshot.0.unwrap_or(config.quorum_for(&poll.poll_type));
+        let threshold = snapshot.1.unwrap_or(config.threshold_for(&poll.poll_type));
+        poll.quorum = Some(quorum);
+        poll.threshold = Some(threshold);
+        poll.threshold_inclusive = Some(snapshot.2.unwrap_or(config.threshold_inclusive));
+        save_poll(storage, &poll)?;
+        snapshotted += 1;
+    }
+    Ok((snapshotted, next_cursor))
 }
 
 pub fn poll_indexer_store<'a>(
@@ -132,23 +842,507 @@ pub fn poll_indexer_store<'a>(
     )
 }
 
-pub fn poll_voter_store(storage: &mut dyn Storage, poll_id: u64) -> Bucket<VoteInfo> {
+pub fn poll_indexer_read<'a>(
+    storage: &'a dyn Storage,
+    status: &PollStatus,
+) -> ReadonlyBucket<'a, bool> {
+    ReadonlyBucket::multilevel(
+        storage,
+        &[PREFIX_POLL_INDEXER, status.to_string().as_bytes()],
+    )
+}
+
+/// statuses poll is wrongly indexed under and whether indexer of its own status misses it
+pub fn poll_index_drift(storage: &dyn Storage, poll: &Poll) -> StdResult<(Vec<PollStatus>, bool)> {
+    let key = poll.id.to_be_bytes();
+    let mut stale: Vec<PollStatus> = vec![];
+    let mut missing = true;
+    for status in PollStatus::ALL.iter() {
+        if poll_indexer_read(storage, status).may_load(&key)?.is_none() {
+            continue;
+        }
+        if *status == poll.status {
+            missing = false;
+        } else {
+            stale.push(status.clone());
+        }
+    }
+    Ok((stale, missing))
+}
+
+/// polls after start_after in id order (at most MAX_INDEX_PAGE)
+/// and start_after of the next page (None if last page)
+pub fn read_poll_page(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: u32,
+) -> StdResult<(Vec<Poll>, Option<u64>)> {
+    let limit = limit.min(MAX_INDEX_PAGE) as usize;
+    let start = calc_range_start(start_after);
+    let polls = range_polls(storage, start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<Poll>>>()?;
+    let next_cursor = match polls.last() {
+        Some(poll) if polls.len() == limit => Some(poll.id),
+        _ => None,
+    };
+    Ok((polls, next_cursor))
+}
+
+/// in progress polls still open at block which member can vote on: not voted yet
+/// (by itself or by its delegatee) and not delegating, ordered by poll_id ascending
+/// paged like read_polls (at most MAX_SCAN in progress polls are examined)
+pub fn read_eligible_polls(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    member_id: &str,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<(Vec<Poll>, Option<u64>)> {
+    let limit = page_limit(limit);
+    let token_manager = may_load_member(storage, member_id.as_bytes())?.unwrap_or_default();
+    if token_manager.delegate_to.is_some() || token_manager.is_suspended(block.height) {
+        return Ok((vec![], None));
+    }
+
+    let start = calc_range_start(start_after);
+    let mut polls: Vec<Poll> = vec![];
+    let mut scanned = 0;
+    let mut last_scanned: Option<u64> = None;
+    for item in poll_indexer_read(storage, &PollStatus::InProgress)
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(MAX_SCAN)
+    {
+        let (k, _) = item?;
+        let poll_id = poll_id_from_key(&k)?;
+        scanned += 1;
+        last_scanned = Some(poll_id);
+        let poll = match may_load_poll(storage, poll_id)? {
+            Some(poll) => poll,
+            None => continue,
+        };
+        if poll.voting_open(block)
+            && poll_voter_read(storage, poll_id)
+                .may_load(member_id.as_bytes())?
+                .is_none()
+        {
+            polls.push(poll);
+            if polls.len() > limit {
+                break;
+            }
+        }
+    }
+
+    let next_cursor = if polls.len() > limit {
+        polls.truncate(limit);
+        polls.last().map(|poll| poll.id)
+    } else if scanned == MAX_SCAN {
+        last_scanned
+    } else {
+        None
+    };
+
+    Ok((polls, next_cursor))
+}
+
+/// move poll from one status indexer to another
+/// parts already done are skipped, so repeating it leaves indexers consistent
+pub fn move_poll_indexer(
+    storage: &mut dyn Storage,
+    poll_id: u64,
+    from: &PollStatus,
+    to: &PollStatus,
+) -> StdResult<()> {
+    let key = poll_id.to_be_bytes();
+    if from != to && poll_indexer_store(storage, from).may_load(&key)?.is_some() {
+        poll_indexer_store(storage, from).remove(&key);
+    }
+    if poll_indexer_store(storage, to).may_load(&key)?.is_none() {
+        poll_indexer_store(storage, to).save(&key, &true)?;
+    }
+    Ok(())
+}
+
+pub fn poll_voter_store(storage: &mut dyn Storage, poll_id: u64) -> Bucket<'_, VoteInfo> {
     Bucket::multilevel(storage, &[PREFIX_POLL_VOTER, &poll_id.to_be_bytes()])
 }
 
-pub fn poll_voter_read(storage: &dyn Storage, poll_id: u64) -> ReadonlyBucket<VoteInfo> {
+pub fn poll_voter_read(storage: &dyn Storage, poll_id: u64) -> ReadonlyBucket<'_, VoteInfo> {
     ReadonlyBucket::multilevel(storage, &[PREFIX_POLL_VOTER, &poll_id.to_be_bytes()])
 }
 
 const MAX_LIMIT: u32 = 30;
 const DEFAULT_LIMIT: u32 = 10;
 
-pub fn bank_store(storage: &mut dyn Storage) -> Bucket<TokenManager> {
-    bucket(storage, PREFIX_BANK)
+// limit 0 is read as 1, an empty page would end paging before the last item
+fn page_limit(limit: Option<u32>) -> usize {
+    limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize
+}
+// maximum number of polls examined per read_polls call
+const MAX_SCAN: usize = 300;
+// maximum number of polls per index check or rebuild page
+const MAX_INDEX_PAGE: u32 = 100;
+
+/// versioned layout of a stored TokenManager, see StoredPoll
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StoredMember {
+    V1(TokenManagerV1),
+    V2(TokenManager),
+}
+
+impl From<StoredMember> for TokenManager {
+    fn from(stored: StoredMember) -> Self {
+        match stored {
+            StoredMember::V1(token_manager) => token_manager.into(),
+            StoredMember::V2(token_manager) => token_manager,
+        }
+    }
+}
+
+/// TokenManager as stored before vote_nonce, vested_locked and vested_until
+/// (also the layout of untagged pre-versioning records)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenManagerV1 {
+    pub share: Uint128,
+    pub balance: Uint128,
+    pub locked_share: Vec<(u64, VoteInfo)>,
+    pub delegate_to: Option<String>,
+    pub delegated_from: Vec<String>,
+    #[serde(default)]
+    pub aliases: Vec<(String, String)>,
+    #[serde(default)]
+    pub suspended_until: Option<u64>,
+    #[serde(default)]
+    pub last_vote_height: Option<u64>,
+    #[serde(default)]
+    pub wallet: Option<String>,
+    #[serde(default)]
+    pub vote_key: Option<Binary>,
+    #[serde(default)]
+    pub last_ended_poll: Option<EndedPoll>,
+    #[serde(default)]
+    pub delegated_in_share: Uint128,
+}
+
+impl From<TokenManagerV1> for TokenManager {
+    fn from(token_manager: TokenManagerV1) -> Self {
+        TokenManager {
+            share: token_manager.share,
+            balance: token_manager.balance,
+            locked_share: token_manager.locked_share,
+            delegate_to: token_manager.delegate_to,
+            delegated_from: token_manager.delegated_from,
+            aliases: token_manager.aliases,
+            suspended_until: token_manager.suspended_until,
+            last_vote_height: token_manager.last_vote_height,
+            wallet: token_manager.wallet,
+            vote_key: token_manager.vote_key,
+            last_ended_poll: token_manager.last_ended_poll,
+            delegated_in_share: token_manager.delegated_in_share,
+            vote_nonce: 0,
+            vested_locked: Uint128::zero(),
+            vested_until: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum StoredMemberRef<'a> {
+    V2(&'a TokenManager),
+}
+
+// members saved before versioning are untagged V1; they are retagged on next save
+fn decode_member(data: &[u8]) -> StdResult<TokenManager> {
+    if is_tagged(data) {
+        from_slice::<StoredMember>(data).map(TokenManager::from)
+    } else {
+        from_slice::<TokenManagerV1>(data).map(TokenManager::from)
+    }
+}
+
+pub fn may_load_member(storage: &dyn Storage, key: &[u8]) -> StdResult<Option<TokenManager>> {
+    ReadonlyPrefixedStorage::new(storage, PREFIX_BANK)
+        .get(key)
+        .map(|data| decode_member(&data))
+        .transpose()
+}
+
+pub fn load_member(storage: &dyn Storage, key: &[u8]) -> StdResult<TokenManager> {
+    may_load_member(storage, key)?.ok_or_else(|| StdError::not_found(type_name::<TokenManager>()))
+}
+
+pub fn save_member(
+    storage: &mut dyn Storage,
+    key: &[u8],
+    token_manager: &TokenManager,
+) -> StdResult<()> {
+    PrefixedStorage::new(storage, PREFIX_BANK)
+        .set(key, &to_vec(&StoredMemberRef::V2(token_manager))?);
+    Ok(())
+}
+
+/// what outlives a removed bank entry, carried over if the member comes back
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MemberTombstone {
+    pub suspended_until: Option<u64>,
+    pub vote_nonce: u64, // ballots signed before the removal stay used up
+}
+
+/// remove bank entry, suspension and vote nonce are kept in a tombstone
+pub fn delete_member(
+    storage: &mut dyn Storage,
+    key: &[u8],
+    token_manager: &TokenManager,
+) -> StdResult<()> {
+    PrefixedStorage::new(storage, PREFIX_BANK).remove(key);
+    let mut tombstones = PrefixedStorage::new(storage, PREFIX_MEMBER_TOMBSTONE);
+    // vote_nonce never goes back, so a zero nonce has no older tombstone to keep
+    if token_manager.suspended_until.is_none() && token_manager.vote_nonce == 0 {
+        tombstones.remove(key);
+        return Ok(());
+    }
+    let tombstone = MemberTombstone {
+        suspended_until: token_manager.suspended_until,
+        vote_nonce: token_manager.vote_nonce,
+    };
+    tombstones.set(key, &to_vec(&tombstone)?);
+    Ok(())
+}
+
+/// bank entry of member, or a new one carrying over the tombstone of a removed entry
+pub fn load_member_or_new(storage: &dyn Storage, key: &[u8]) -> StdResult<TokenManager> {
+    if let Some(token_manager) = may_load_member(storage, key)? {
+        return Ok(token_manager);
+    }
+    let mut token_manager = TokenManager::default();
+    if let Some(data) = ReadonlyPrefixedStorage::new(storage, PREFIX_MEMBER_TOMBSTONE).get(key) {
+        let tombstone: MemberTombstone = from_slice(&data)?;
+        token_manager.suspended_until = tombstone.suspended_until;
+        token_manager.vote_nonce = tombstone.vote_nonce;
+    }
+    Ok(token_manager)
+}
+
+/// (bank key, token manager) with keys in [start, end)
+pub fn range_members<'a>(
+    storage: &'a dyn Storage,
+    start: Option<&[u8]>,
+    end: Option<&[u8]>,
+    order: Order,
+) -> Box<dyn Iterator<Item = StdResult<(Vec<u8>, TokenManager)>> + 'a> {
+    Box::new(
+        range_prefixed(storage, PREFIX_BANK, start, end, order)
+            .map(|(k, v)| decode_member(&v).map(|token_manager| (k, token_manager))),
+    )
+}
+
+pub fn read_members(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, TokenManager)>> {
+    let limit = page_limit(limit);
+    let start = calc_range_start_str(start_after);
+
+    range_members(storage, start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((String::from_utf8_lossy(&k).to_string(), v))
+        })
+        .collect()
+}
+
+pub fn season_store(storage: &mut dyn Storage) -> Bucket<'_, SeasonSnapshot> {
+    bucket(storage, PREFIX_SEASON)
+}
+
+pub fn read_seasons(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<SeasonSnapshot>> {
+    let limit = page_limit(limit);
+    let start = calc_range_start(start_after);
+
+    let seasons: ReadonlyBucket<'_, SeasonSnapshot> = bucket_read(storage, PREFIX_SEASON);
+    seasons
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, v) = item?;
+            Ok(v)
+        })
+        .collect()
+}
+
+pub fn admin_proposal_store(storage: &mut dyn Storage) -> Bucket<'_, AdminProposal> {
+    bucket(storage, PREFIX_ADMIN_ACTION)
+}
+
+pub fn admin_proposal_read(storage: &dyn Storage) -> ReadonlyBucket<'_, AdminProposal> {
+    bucket_read(storage, PREFIX_ADMIN_ACTION)
+}
+
+pub fn read_admin_proposals(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<AdminProposal>> {
+    let limit = page_limit(limit);
+    let start = calc_range_start(start_after);
+
+    admin_proposal_read(storage)
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, v) = item?;
+            Ok(v)
+        })
+        .collect()
+}
+
+pub fn committee_store(storage: &mut dyn Storage) -> Bucket<'_, Committee> {
+    bucket(storage, PREFIX_COMMITTEE)
+}
+
+pub fn committee_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Committee> {
+    bucket_read(storage, PREFIX_COMMITTEE)
+}
+
+/// delegates ordered by delegated_in_share (only nonzero)
+/// delegated_in_share (big endian) + member_id: member_id
+pub fn delegate_index_store(storage: &mut dyn Storage) -> Bucket<'_, String> {
+    bucket(storage, PREFIX_DELEGATE_INDEX)
+}
+
+pub fn delegate_index_read(storage: &dyn Storage) -> ReadonlyBucket<'_, String> {
+    bucket_read(storage, PREFIX_DELEGATE_INDEX)
+}
+
+fn delegate_index_key(delegated_in_share: Uint128, member_id: &str) -> Vec<u8> {
+    let mut key = delegated_in_share.u128().to_be_bytes().to_vec();
+    key.extend_from_slice(member_id.as_bytes());
+    key
+}
+
+/// set delegated_in_share of delegatee (missing delegatee is skipped), index follows
+pub fn set_delegated_in_share(
+    storage: &mut dyn Storage,
+    delegatee_id: &str,
+    delegated_in_share: Uint128,
+) -> StdResult<()> {
+    let key = delegatee_id.as_bytes();
+    let mut delegatee = match may_load_member(storage, key)? {
+        Some(delegatee) => delegatee,
+        None => return Ok(()),
+    };
+    let old = delegatee.delegated_in_share;
+    if old == delegated_in_share {
+        return Ok(());
+    }
+    delegatee.delegated_in_share = delegated_in_share;
+    save_member(storage, key, &delegatee)?;
+
+    if !old.is_zero() {
+        delegate_index_store(storage).remove(&delegate_index_key(old, delegatee_id));
+    }
+    if !delegated_in_share.is_zero() {
+        delegate_index_store(storage).save(
+            &delegate_index_key(delegated_in_share, delegatee_id),
+            &delegatee_id.to_string(),
+        )?;
+    }
+    Ok(())
+}
+
+/// delegator's share changed from removed to added
+pub fn adjust_delegated_in_share(
+    storage: &mut dyn Storage,
+    delegatee_id: &str,
+    added: Uint128,
+    removed: Uint128,
+) -> StdResult<()> {
+    let delegated_in_share = match may_load_member(storage, delegatee_id.as_bytes())? {
+        Some(delegatee) => (delegatee.delegated_in_share + added).saturating_sub(removed),
+        None => return Ok(()),
+    };
+    set_delegated_in_share(storage, delegatee_id, delegated_in_share)
+}
+
+/// member_ids with largest delegated_in_share first
+pub fn read_top_delegates(storage: &dyn Storage, limit: usize) -> StdResult<Vec<String>> {
+    delegate_index_read(storage)
+        .range(None, None, Order::Descending)
+        .take(limit)
+        .map(|item| item.map(|(_, member_id)| member_id))
+        .collect()
+}
+
+/// recompute delegated_in_share of a page of members (after start_after, at most MAX_LIMIT)
+/// from the shares of their delegated_from members still delegating to them
+/// (for delegations made before delegated_in_share was maintained)
+/// returns number of updated members and the next cursor
+pub fn recompute_delegated_in_share(
+    storage: &mut dyn Storage,
+    start_after: Option<String>,
+    limit: u32,
+) -> StdResult<(u64, Option<String>)> {
+    let limit = page_limit(Some(limit));
+    let members = read_members(storage, start_after, Some(limit as u32))?;
+    let next_cursor = match members.last() {
+        Some((member_id, _)) if members.len() == limit => Some(member_id.clone()),
+        _ => None,
+    };
+
+    let mut recomputed = 0;
+    for (member_id, token_manager) in members {
+        let mut delegated_in_share = Uint128::zero();
+        for delegator_id in token_manager.delegated_from.iter() {
+            if let Some(delegator) = may_load_member(storage, delegator_id.as_bytes())? {
+                if delegator.delegate_to.as_deref() == Some(member_id.as_str()) {
+                    delegated_in_share += delegator.share;
+                }
+            }
+        }
+        if delegated_in_share != token_manager.delegated_in_share {
+            set_delegated_in_share(storage, &member_id, delegated_in_share)?;
+            recomputed += 1;
+        }
+    }
+    Ok((recomputed, next_cursor))
+}
+
+/// member ids blocked from governance
+/// member_id: true
+pub fn blocklist_store(storage: &mut dyn Storage) -> Bucket<'_, bool> {
+    bucket(storage, PREFIX_BLOCKLIST)
+}
+
+pub fn blocklist_read(storage: &dyn Storage) -> ReadonlyBucket<'_, bool> {
+    bucket_read(storage, PREFIX_BLOCKLIST)
+}
+
+/// members whose hook is being executed (cleared before receive_cw721 returns)
+/// member_id: 1
+pub fn in_flight_store(storage: &mut dyn Storage) -> Bucket<'_, u8> {
+    bucket(storage, PREFIX_IN_FLIGHT)
 }
 
-pub fn bank_read(storage: &dyn Storage) -> ReadonlyBucket<TokenManager> {
-    bucket_read(storage, PREFIX_BANK)
+pub fn in_flight_read(storage: &dyn Storage) -> ReadonlyBucket<'_, u8> {
+    bucket_read(storage, PREFIX_IN_FLIGHT)
+}
+
+/// reverse index of aliases
+/// (namespace, address): member_id
+pub fn alias_store<'a>(storage: &'a mut dyn Storage, namespace: &str) -> Bucket<'a, String> {
+    Bucket::multilevel(storage, &[PREFIX_ALIAS, namespace.as_bytes()])
+}
+
+pub fn alias_read<'a>(storage: &'a dyn Storage, namespace: &str) -> ReadonlyBucket<'a, String> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_ALIAS, namespace.as_bytes()])
 }
 
 // this will set the first key after the provided key, by appending a 1 byte
@@ -160,7 +1354,7 @@ fn calc_range_start(start_after: Option<u64>) -> Option<Vec<u8>> {
     })
 }
 
-// this will set the first key after the provided key, by appending a 1 byte
+// range end is exclusive, so the provided key itself is not included
 fn calc_range_end(start_after: Option<u64>) -> Option<Vec<u8>> {
     start_after.map(|id| id.to_be_bytes().to_vec())
 }
@@ -175,18 +1369,30 @@ fn calc_range_start_addr(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>>
 }
 
 // this will set the first key after the provided key, by appending a 1 byte
+fn calc_range_start_str(start_after: Option<String>) -> Option<Vec<u8>> {
+    start_after.map(|id| {
+        let mut v = id.into_bytes();
+        v.push(1);
+        v
+    })
+}
+
+// range end is exclusive, so the provided key itself is not included
 fn calc_range_end_addr(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
     start_after.map(|addr| addr.as_slice().to_vec())
 }
 
+/// with vote_filter only matching voters are returned, others are still iterated over
+/// (votes are stored per voter, there is no index by option)
 pub fn read_poll_voters<'a>(
     storage: &'a dyn Storage,
     poll_id: u64,
     start_after: Option<CanonicalAddr>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
+    vote_filter: Option<VoteOption>,
 ) -> StdResult<Vec<(CanonicalAddr, VoteInfo)>> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let limit = page_limit(limit);
     let (start, end, order_by) = match order_by {
         Some(OrderBy::Asc) => (calc_range_start_addr(start_after), None, OrderBy::Asc),
         _ => (None, calc_range_end_addr(start_after), OrderBy::Desc),
@@ -196,6 +1402,10 @@ pub fn read_poll_voters<'a>(
         ReadonlyBucket::multilevel(storage, &[PREFIX_POLL_VOTER, &poll_id.to_be_bytes()]);
     voters
         .range(start.as_deref(), end.as_deref(), order_by.into())
+        .filter(|item| match (item, &vote_filter) {
+            (Ok((_, vote_info)), Some(vote)) => vote_info.vote == *vote,
+            _ => true,
+        })
         .take(limit)
         .map(|item| {
             let (k, v) = item?;
@@ -204,42 +1414,77 @@ pub fn read_poll_voters<'a>(
         .collect()
 }
 
+/// returns polls ordered strictly by poll_id in both directions, with or without filter
+/// start_after is exclusive in both directions
+/// also returns the cursor to pass as start_after for the next page (None if last page)
 pub fn read_polls<'a>(
     storage: &'a dyn Storage,
     filter: Option<PollStatus>,
     start_after: Option<u64>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
-) -> StdResult<Vec<Poll>> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    draft_viewer: Option<&str>,
+    poll_type: Option<PollType>,
+) -> StdResult<(Vec<Poll>, Option<u64>)> {
+    let limit = page_limit(limit);
     let (start, end, order_by) = match order_by {
         Some(OrderBy::Asc) => (calc_range_start(start_after), None, OrderBy::Asc),
         _ => (None, calc_range_end(start_after), OrderBy::Desc),
     };
 
-    if let Some(status) = filter {
-        let poll_indexer: ReadonlyBucket<'a, bool> = ReadonlyBucket::multilevel(
+    // both the poll bucket and the status indexer are keyed by big endian poll_id,
+    // so iterating either one yields the same poll_id ordering
+    // drafts are skipped before paging unless draft_viewer is the creator
+    // and so are polls of other type if poll_type is given
+    let visible = |poll: &Poll| {
+        (poll.status != PollStatus::Draft || Some(poll.creator.as_str()) == draft_viewer)
+            && poll_type.as_ref().map_or(true, |t| *t == poll.poll_type)
+    };
+
+    let poll_indexer: Option<ReadonlyBucket<'a, bool>> = filter.map(|status| {
+        ReadonlyBucket::multilevel(
             storage,
             &[PREFIX_POLL_INDEXER, status.to_string().as_bytes()],
-        );
-        poll_indexer
-            .range(start.as_deref(), end.as_deref(), order_by.into())
-            .take(limit)
-            .map(|item| {
-                let (k, _) = item?;
-                poll_read(storage).load(&k)
-            })
-            .collect()
-    } else {
-        let polls: ReadonlyBucket<'a, Poll> = ReadonlyBucket::new(storage, PREFIX_POLL);
+        )
+    });
+    let entries: Box<dyn Iterator<Item = StdResult<Poll>> + '_> = match &poll_indexer {
+        Some(poll_indexer) => Box::new(
+            poll_indexer
+                .range(start.as_deref(), end.as_deref(), order_by.into())
+                .map(|item| {
+                    let (k, _) = item?;
+                    load_poll(storage, poll_id_from_key(&k)?)
+                }),
+        ),
+        None => range_polls(storage, start.as_deref(), end.as_deref(), order_by.into()),
+    };
 
-        polls
-            .range(start.as_deref(), end.as_deref(), order_by.into())
-            .take(limit)
-            .map(|item| {
-                let (_, v) = item?;
-                Ok(v)
-            })
-            .collect()
+    // read one more than limit to know if there is a next page
+    // at most MAX_SCAN entries are examined, so a sparse filter returns a short
+    // (possibly empty) page with next_cursor at the last examined poll to resume from
+    let mut polls: Vec<Poll> = vec![];
+    let mut scanned = 0;
+    let mut last_scanned: Option<u64> = None;
+    for poll in entries.take(MAX_SCAN) {
+        let poll = poll?;
+        scanned += 1;
+        last_scanned = Some(poll.id);
+        if visible(&poll) {
+            polls.push(poll);
+            if polls.len() > limit {
+                break;
+            }
+        }
     }
+
+    let next_cursor = if polls.len() > limit {
+        polls.truncate(limit);
+        polls.last().map(|poll| poll.id)
+    } else if scanned == MAX_SCAN {
+        last_scanned
+    } else {
+        None
+    };
+
+    Ok((polls, next_cursor))
 }
@@ -0,0 +1,470 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Api;
+use cosmwasm_std::{CanonicalAddr, Decimal, StdError};
+use cosmwasm_storage::singleton;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+use crate::contract::migrate;
+use crate::msg::{AdminAction, MigrateMsg};
+use crate::state::{config_read, Config};
+use crate::utils::{
+    ConfigResponse, EndPollPermission, IbcConfigResponse, OwnerResponse, PollResponse, PollStatus,
+    StakerResponse, MIN_VOTING_PERIOD,
+};
+
+const ADMIN2: &str = "admin0001";
+
+fn setup_two_admins() -> MockDeps {
+    let mut msg = init_msg();
+    msg.admins = Some(vec![ADMIN.to_string(), ADMIN2.to_string()]);
+    msg.admin_threshold = Some(2);
+    setup_with(msg)
+}
+
+fn propose(
+    deps: &mut MockDeps,
+    height: u64,
+    action: AdminAction,
+    expires: u64,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::ProposeAdminAction { action, expires };
+    exec_as(deps, env_at(height), ADMIN, msg)
+}
+
+fn suspended_until(deps: &MockDeps, member_id: &str) -> Option<u64> {
+    let member: StakerResponse = query_at(
+        deps,
+        mock_env(),
+        QueryMsg::Member {
+            member_id: member_id.to_string(),
+        },
+    );
+    member.suspended_until
+}
+
+#[test]
+fn legacy_owner_becomes_only_admin() {
+    #[derive(Serialize, Deserialize)]
+    struct LegacyConfig {
+        owner: CanonicalAddr,
+        cw721_token: CanonicalAddr,
+        quorum: Decimal,
+        threshold: Decimal,
+        voting_period: u64,
+    }
+
+    let mut deps = setup();
+    let owner = deps.api.addr_canonicalize(ADMIN2).unwrap();
+    let legacy = LegacyConfig {
+        owner: owner.clone(),
+        cw721_token: deps.api.addr_canonicalize(NFT).unwrap(),
+        quorum: Decimal::percent(10),
+        threshold: Decimal::percent(50),
+        voting_period: VOTING_PERIOD,
+    };
+    singleton(&mut deps.storage, b"config")
+        .save(&legacy)
+        .unwrap();
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(attr(&res, "owner_migrated"), "true");
+    let config: Config = config_read(&deps.storage).load().unwrap();
+    assert_eq!(config.admins, vec![owner]);
+    assert_eq!(config.admin_threshold, 1);
+
+    // converted config is left alone by later migrations
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(attr(&res, "owner_migrated"), "false");
+}
+
+#[test]
+fn fresh_config_is_not_migrated() {
+    let mut deps = mock_deps();
+    instantiate(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), init_msg()).unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(attr(&res, "owner_migrated"), "false");
+}
+
+#[test]
+fn privileged_actions_need_approval_above_threshold() {
+    let mut deps = setup_two_admins();
+    let height = mock_env().block.height;
+    let mint_msg = AdminAction::Mint {
+        recipient: "1".to_string(),
+        amount: Uint128::from(100u128),
+    };
+    propose(&mut deps, height, mint_msg, height + 10).unwrap();
+    let approve = ExecuteMsg::ApproveAdminAction { id: 1 };
+    exec_as(&mut deps, mock_env(), ADMIN2, approve).unwrap();
+
+    let direct = vec![
+        ExecuteMsg::SuspendMember {
+            member_id: "1".to_string(),
+            until_height: height + 50,
+        },
+        ExecuteMsg::UnsuspendMember {
+            member_id: "1".to_string(),
+        },
+        ExecuteMsg::BreakDelegation {
+            member_id: "1".to_string(),
+        },
+        ExecuteMsg::RecomputeSupply {},
+        ExecuteMsg::ExtendPoll {
+            poll_id: 1,
+            additional_blocks: 10,
+        },
+        ExecuteMsg::SetBlocked {
+            member_id: "1".to_string(),
+            blocked: true,
+        },
+        ExecuteMsg::DrawCommittee {
+            size: 1,
+            seed_poll_id: 1,
+        },
+        ExecuteMsg::RebuildPollIndex {
+            start_after: None,
+            limit: 10,
+        },
+    ];
+    for msg in direct {
+        let err = exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap_err();
+        assert_eq!(err, ContractError::AdminApprovalRequired(2));
+    }
+
+    let suspend = AdminAction::SuspendMember {
+        member_id: "1".to_string(),
+        until_height: height + 50,
+    };
+    let res = propose(&mut deps, height, suspend, height + 10).unwrap();
+    assert_eq!(attr(&res, "executed"), "false");
+    assert_eq!(suspended_until(&deps, "1"), None);
+
+    let approve = ExecuteMsg::ApproveAdminAction { id: 2 };
+    let res = exec_as(&mut deps, mock_env(), ADMIN2, approve).unwrap();
+    assert_eq!(attr(&res, "executed"), "true");
+    assert_eq!(attr(&res, "action"), "approve_admin_action");
+    assert_eq!(suspended_until(&deps, "1"), Some(height + 50));
+
+    let unsuspend = AdminAction::UnsuspendMember {
+        member_id: "1".to_string(),
+    };
+    propose(&mut deps, height, unsuspend, height + 10).unwrap();
+    let approve = ExecuteMsg::ApproveAdminAction { id: 3 };
+    exec_as(&mut deps, mock_env(), ADMIN2, approve).unwrap();
+    assert_eq!(suspended_until(&deps, "1"), None);
+}
+
+#[test]
+fn extend_poll_and_recompute_supply_as_admin_actions() {
+    let mut msg = init_msg();
+    msg.admins = Some(vec![ADMIN.to_string(), ADMIN2.to_string()]);
+    msg.admin_threshold = Some(2);
+    msg.max_extension = Some(50);
+    let mut deps = setup_with(msg);
+    let height = mock_env().block.height;
+
+    let mint_msg = AdminAction::Mint {
+        recipient: "1".to_string(),
+        amount: Uint128::from(100u128),
+    };
+    propose(&mut deps, height, mint_msg, height + 10).unwrap();
+    exec_as(
+        &mut deps,
+        mock_env(),
+        ADMIN2,
+        ExecuteMsg::ApproveAdminAction { id: 1 },
+    )
+    .unwrap();
+    let poll_id = create_poll(&mut deps, "1", "extended");
+
+    let extend = AdminAction::ExtendPoll {
+        poll_id,
+        additional_blocks: 20,
+    };
+    propose(&mut deps, height, extend, height + 10).unwrap();
+    let res = exec_as(
+        &mut deps,
+        mock_env(),
+        ADMIN2,
+        ExecuteMsg::ApproveAdminAction { id: 2 },
+    )
+    .unwrap();
+    assert_eq!(
+        attr(&res, "end_height"),
+        (height + VOTING_PERIOD + 20).to_string()
+    );
+    assert_eq!(attr(&res, "extended_by"), ADMIN2);
+
+    propose(
+        &mut deps,
+        height,
+        AdminAction::RecomputeSupply {},
+        height + 10,
+    )
+    .unwrap();
+    let res = exec_as(
+        &mut deps,
+        mock_env(),
+        ADMIN2,
+        ExecuteMsg::ApproveAdminAction { id: 3 },
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "done"), "true");
+    assert_eq!(attr(&res, "total_supply"), "100");
+}
+
+#[test]
+fn single_admin_executes_directly() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let height = mock_env().block.height;
+    let msg = ExecuteMsg::SuspendMember {
+        member_id: "1".to_string(),
+        until_height: height + 5,
+    };
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+    assert_eq!(suspended_until(&deps, "1"), Some(height + 5));
+
+    let msg = ExecuteMsg::SuspendMember {
+        member_id: "1".to_string(),
+        until_height: height + 5,
+    };
+    let err = exec_as(&mut deps, mock_env(), "stranger", msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn any_admin_acts_when_one_approval_is_enough() {
+    let mut msg = init_msg();
+    msg.admins = Some(vec![ADMIN.to_string(), ADMIN2.to_string()]);
+    msg.end_poll_permission = Some(EndPollPermission::OwnerOnly);
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    let poll_id = create_poll(&mut deps, "1", "ended by admin");
+    let mut draft = create_poll_msg("draft");
+    if let Cw721HookMsg::CreatePoll { draft, .. } = &mut draft {
+        *draft = true;
+    }
+    let res = hook(&mut deps, mock_env(), "1", draft).unwrap();
+    let draft_id: u64 = attr(&res, "poll_id").parse().unwrap();
+
+    let block = ExecuteMsg::SetBlocked {
+        member_id: "1".to_string(),
+        blocked: true,
+    };
+    exec_as(&mut deps, mock_env(), ADMIN2, block).unwrap();
+    // blocked creators can't publish their drafts either
+    let publish = Cw721HookMsg::PublishPoll { poll_id: draft_id };
+    let err = hook(&mut deps, mock_env(), "1", publish).unwrap_err();
+    assert_eq!(err, ContractError::MemberBlocked {});
+
+    let end = ExecuteMsg::EndPoll { poll_id };
+    let after_voting = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    let err = exec_as(&mut deps, after_voting.clone(), "stranger", end.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    exec_as(&mut deps, after_voting, ADMIN2, end).unwrap();
+}
+
+#[test]
+fn expired_proposals_are_pruned() {
+    let mut deps = setup_two_admins();
+    let height = mock_env().block.height;
+    let action = AdminAction::RecomputeSupply {};
+    propose(&mut deps, height, action.clone(), height + 5).unwrap();
+    propose(&mut deps, height, action.clone(), height + 100).unwrap();
+
+    // first proposal expired, second still open
+    let res = propose(&mut deps, height + 10, action, height + 100).unwrap();
+    assert_eq!(attr(&res, "pruned"), "1");
+    let expired = query(
+        deps.as_ref(),
+        env_at(height + 10),
+        QueryMsg::AdminAction { id: 1 },
+    );
+    assert!(expired.is_err());
+    let open = query(
+        deps.as_ref(),
+        env_at(height + 10),
+        QueryMsg::AdminAction { id: 2 },
+    );
+    assert!(open.is_ok());
+
+    let approve = ExecuteMsg::ApproveAdminAction { id: 2 };
+    let res = exec_as(&mut deps, env_at(height + 10), ADMIN2, approve).unwrap();
+    assert_eq!(attr(&res, "pruned"), "0");
+}
+
+#[test]
+fn force_end_poll_needs_admin_approvals() {
+    let mut deps = setup_two_admins();
+    let height = mock_env().block.height;
+    let mint_msg = AdminAction::Mint {
+        recipient: "1".to_string(),
+        amount: Uint128::from(100u128),
+    };
+    propose(&mut deps, height, mint_msg, height + 10).unwrap();
+    exec_as(
+        &mut deps,
+        mock_env(),
+        ADMIN2,
+        ExecuteMsg::ApproveAdminAction { id: 1 },
+    )
+    .unwrap();
+    let poll_id = create_poll(&mut deps, "1", "forced");
+
+    // the first admin has no say of its own
+    let msg = ExecuteMsg::ForceEndPoll {
+        poll_id,
+        outcome: Some(PollStatus::Passed),
+    };
+    let err = exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap_err();
+    assert_eq!(err, ContractError::AdminApprovalRequired(2));
+
+    let force = AdminAction::ForceEndPoll {
+        poll_id,
+        outcome: None,
+    };
+    let msg = ExecuteMsg::ProposeAdminAction {
+        action: force,
+        expires: height + 10,
+    };
+    exec_as(&mut deps, mock_env(), ADMIN2, msg).unwrap();
+    let res = exec_as(
+        &mut deps,
+        mock_env(),
+        ADMIN,
+        ExecuteMsg::ApproveAdminAction { id: 2 },
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "status"), PollStatus::Rejected.to_string());
+    assert_eq!(attr(&res, "ended_by"), ADMIN);
+
+    let poll: PollResponse = query_at(
+        &deps,
+        mock_env(),
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    assert!(poll.force_ended);
+    assert_eq!(poll.status, PollStatus::Rejected);
+}
+
+#[test]
+fn suspension_outlives_removed_member() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let height = mock_env().block.height;
+    let msg = ExecuteMsg::SuspendMember {
+        member_id: "1".to_string(),
+        until_height: height + 50,
+    };
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+
+    let res = hook(&mut deps, mock_env(), "1", Cw721HookMsg::Exit {}).unwrap();
+    assert_eq!(attr(&res, "removed"), "true");
+    assert_eq!(suspended_until(&deps, "1"), Some(height + 50));
+
+    // coming back doesn't lift the suspension
+    mint(&mut deps, "1", 100);
+    assert_eq!(suspended_until(&deps, "1"), Some(height + 50));
+    let err = hook(&mut deps, mock_env(), "1", create_poll_msg("back")).unwrap_err();
+    assert_eq!(err, ContractError::MemberSuspended { until: height + 50 });
+}
+
+#[test]
+fn optional_settings_can_be_cleared() {
+    let mut deps = setup();
+    let msg = update_config_msg(
+        r#"{"decay_after":500,"draft_ttl":50,"season_length":1000,
+            "ibc_channel":"channel-3","ibc_receiver":"sister","ibc_denom":"uluna"}"#,
+    );
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+
+    // left out fields are kept
+    let msg = update_config_msg(r#"{"quorum":"0.2"}"#);
+    // a message built in code doesn't turn into nulls when sent
+    let msg: ExecuteMsg = from_binary(&to_binary(&msg).unwrap()).unwrap();
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+    let config: ConfigResponse = query_as(&deps, QueryMsg::Config {});
+    assert_eq!(config.decay_after, Some(500));
+    assert_eq!(config.draft_ttl, Some(50));
+    assert_eq!(config.season_length, Some(1000));
+
+    let msg = update_config_msg(
+        r#"{"decay_after":null,"draft_ttl":null,"season_length":null,"ibc_channel":null}"#,
+    );
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+    let config: ConfigResponse = query_as(&deps, QueryMsg::Config {});
+    assert_eq!(config.decay_after, None);
+    assert_eq!(config.draft_ttl, None);
+    assert_eq!(config.season_length, None);
+    let ibc: IbcConfigResponse = query_as(&deps, QueryMsg::IbcConfig {});
+    assert_eq!(ibc.ibc_channel, None);
+    assert_eq!(ibc.ibc_receiver, Some("sister".to_string()));
+    assert_eq!(ibc.ibc_denom, Some("uluna".to_string()));
+
+    // cleared through an approved admin action as well
+    let mut deps = setup_two_admins();
+    let height = mock_env().block.height;
+    let actions = [
+        br#"{"update_config":{"ibc_denom":"uluna"}}"#.to_vec(),
+        br#"{"update_config":{"ibc_denom":null}}"#.to_vec(),
+    ];
+    let mut denoms = vec![];
+    for (id, action) in actions.iter().enumerate() {
+        let action: AdminAction = from_slice(action).unwrap();
+        propose(&mut deps, height, action, height + 10).unwrap();
+        let approve = ExecuteMsg::ApproveAdminAction { id: id as u64 + 1 };
+        exec_as(&mut deps, mock_env(), ADMIN2, approve).unwrap();
+        let ibc: IbcConfigResponse = query_as(&deps, QueryMsg::IbcConfig {});
+        denoms.push(ibc.ibc_denom);
+    }
+    assert_eq!(denoms, vec![Some("uluna".to_string()), None]);
+}
+
+#[test]
+fn voting_period_has_a_floor() {
+    let mut msg = init_msg();
+    msg.voting_period = MIN_VOTING_PERIOD - 1;
+    let mut deps = mock_deps();
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "voting_period must be at least 100 blocks"
+        ))
+    );
+
+    let mut deps = setup();
+    let below = update_config_msg(&format!(r#"{{"voting_period":{}}}"#, MIN_VOTING_PERIOD - 1));
+    exec_as(&mut deps, mock_env(), ADMIN, below).unwrap_err();
+    let config: ConfigResponse = query_as(&deps, QueryMsg::Config {});
+    assert_eq!(config.voting_period, VOTING_PERIOD);
+
+    // the floor itself is allowed
+    let longer = update_config_msg(&format!(r#"{{"voting_period":{}}}"#, 2 * MIN_VOTING_PERIOD));
+    exec_as(&mut deps, mock_env(), ADMIN, longer).unwrap();
+    let at_floor = update_config_msg(&format!(r#"{{"voting_period":{}}}"#, MIN_VOTING_PERIOD));
+    exec_as(&mut deps, mock_env(), ADMIN, at_floor).unwrap();
+    let config: ConfigResponse = query_as(&deps, QueryMsg::Config {});
+    assert_eq!(config.voting_period, MIN_VOTING_PERIOD);
+}
+
+#[test]
+fn owner_is_instantiate_sender() {
+    let deps = setup();
+    let res: OwnerResponse = query_as(&deps, QueryMsg::Owner {});
+    assert_eq!(res.owner, ADMIN);
+    assert_eq!(res.admins, vec![ADMIN]);
+    assert_eq!(res.admin_threshold, 1);
+
+    // first listed admin with several admins
+    let deps = setup_two_admins();
+    let res: OwnerResponse = query_as(&deps, QueryMsg::Owner {});
+    assert_eq!(res.owner, ADMIN);
+    assert_eq!(res.admins, vec![ADMIN, ADMIN2]);
+    assert_eq!(res.admin_threshold, 2);
+}
@@ -0,0 +1,92 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::PollResponse;
+
+fn cast_votes(
+    deps: &mut MockDeps,
+    member_id: &str,
+    votes: Vec<(u64, VoteOption)>,
+) -> Result<Response, ContractError> {
+    hook(
+        deps,
+        mock_env(),
+        member_id,
+        Cw721HookMsg::CastVotes { votes },
+    )
+}
+
+fn tally(deps: &MockDeps, poll_id: u64) -> (u128, u128) {
+    let poll: PollResponse = query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    (poll.yes_votes.u128(), poll.no_votes.u128())
+}
+
+/// "1" holding share 10 and the delegated share 20 of "2", three open polls
+fn setup_batch() -> (MockDeps, Vec<u64>) {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 400);
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: "1".to_string(),
+    };
+    hook(&mut deps, mock_env(), "2", msg).unwrap();
+    let poll_ids = ["first", "second", "third"]
+        .iter()
+        .map(|title| create_poll(&mut deps, "1", title))
+        .collect();
+    (deps, poll_ids)
+}
+
+#[test]
+fn votes_on_three_polls_at_once() {
+    let (mut deps, poll_ids) = setup_batch();
+    let votes = vec![
+        (poll_ids[0], VoteOption::Yes),
+        (poll_ids[1], VoteOption::No),
+        (poll_ids[2], VoteOption::Yes),
+    ];
+    let res = cast_votes(&mut deps, "1", votes).unwrap();
+    assert_eq!(attr(&res, "action"), "cast_votes");
+    assert_eq!(attr(&res, &format!("poll_{}", poll_ids[0])), "yes:30");
+    assert_eq!(attr(&res, &format!("poll_{}", poll_ids[1])), "no:30");
+    assert_eq!(attr(&res, &format!("poll_{}", poll_ids[2])), "yes:30");
+    let all = poll_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    assert_eq!(attr(&res, "quorum_reached_polls"), all);
+
+    assert_eq!(tally(&deps, poll_ids[0]), (30, 0));
+    assert_eq!(tally(&deps, poll_ids[1]), (0, 30));
+    assert_eq!(tally(&deps, poll_ids[2]), (30, 0));
+}
+
+#[test]
+fn failing_vote_names_its_poll() {
+    let (mut deps, poll_ids) = setup_batch();
+    cast_vote(&mut deps, mock_env(), "1", poll_ids[1], VoteOption::Yes).unwrap();
+
+    let votes = vec![
+        (poll_ids[0], VoteOption::Yes),
+        (poll_ids[1], VoteOption::No),
+        (poll_ids[2], VoteOption::Yes),
+    ];
+    let err = cast_votes(&mut deps, "1", votes).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BatchVoteFailed {
+            poll_id: poll_ids[1],
+            reason: ContractError::AlreadyVoted {}.to_string(),
+        }
+    );
+
+    let err = cast_votes(&mut deps, "1", vec![]).unwrap_err();
+    assert_eq!(err, ContractError::DataShouldBeGiven {});
+}
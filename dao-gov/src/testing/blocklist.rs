@@ -0,0 +1,82 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{IsBlockedResponse, PollResponse, StakerResponse};
+
+fn set_blocked(deps: &mut MockDeps, member_id: &str, blocked: bool) {
+    let msg = ExecuteMsg::SetBlocked {
+        member_id: member_id.to_string(),
+        blocked,
+    };
+    exec_as(deps, mock_env(), ADMIN, msg).unwrap();
+}
+
+fn is_blocked(deps: &MockDeps, member_id: &str) -> bool {
+    let res: IsBlockedResponse = query_as(
+        deps,
+        QueryMsg::IsBlocked {
+            member_id: member_id.to_string(),
+        },
+    );
+    res.blocked
+}
+
+#[test]
+fn only_admin_sets_blocked() {
+    let mut deps = setup();
+    let msg = ExecuteMsg::SetBlocked {
+        member_id: "1".to_string(),
+        blocked: true,
+    };
+    let err = exec_as(&mut deps, mock_env(), "stranger", msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    assert!(!is_blocked(&deps, "1"));
+
+    set_blocked(&mut deps, "1", true);
+    assert!(is_blocked(&deps, "1"));
+    set_blocked(&mut deps, "1", false);
+    assert!(!is_blocked(&deps, "1"));
+}
+
+#[test]
+fn blocked_member_is_inert_for_governance() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 400);
+    mint(&mut deps, "3", 900);
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: "1".to_string(),
+    };
+    hook(&mut deps, mock_env(), "3", msg).unwrap();
+    set_blocked(&mut deps, "2", true);
+    set_blocked(&mut deps, "3", true);
+
+    let err = hook(&mut deps, mock_env(), "2", create_poll_msg("blocked")).unwrap_err();
+    assert_eq!(err, ContractError::MemberBlocked {});
+    let poll_id = create_poll(&mut deps, "1", "open");
+    let err = cast_vote(&mut deps, mock_env(), "2", poll_id, VoteOption::Yes).unwrap_err();
+    assert_eq!(err, ContractError::MemberBlocked {});
+
+    // delegated share of a blocked member is skipped
+    let res = cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    assert_eq!(attr(&res, "skipped_blocked"), "3");
+    assert_eq!(attr(&res, "total_amount"), "10");
+    let a_poll: PollResponse = query_as(
+        &deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    assert_eq!(a_poll.yes_votes, Uint128::from(10u128));
+
+    // tokens are kept and still minted
+    mint(&mut deps, "2", 100);
+    let member: StakerResponse = query_as(
+        &deps,
+        QueryMsg::Member {
+            member_id: "2".to_string(),
+        },
+    );
+    assert_eq!(member.balance, Uint128::from(500u128));
+}
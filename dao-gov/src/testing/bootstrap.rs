@@ -0,0 +1,111 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::Decimal;
+
+use super::*;
+use crate::utils::{PollResponse, PollStatus, PollsResponse};
+
+const INSTANTIATE_HEIGHT: u64 = 12345;
+
+/// member "small" holds under 1% of the share, "large" the rest
+fn setup_bootstrap(bootstrap_period: u64) -> MockDeps {
+    let mut msg = init_msg();
+    msg.bootstrap_period = Some(bootstrap_period);
+    msg.bootstrap_quorum = Some(Decimal::permille(5));
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "small", 1);
+    mint(&mut deps, "large", 10000);
+    deps
+}
+
+/// poll voted yes by "small" only, ended right after its voting period
+fn end_small_turnout_poll(deps: &mut MockDeps) -> PollStatus {
+    let poll_id = create_poll(deps, "small", "early");
+    cast_vote(deps, mock_env(), "small", poll_id, VoteOption::Yes).unwrap();
+    let end = env_at(INSTANTIATE_HEIGHT + VOTING_PERIOD + 1);
+    hook(deps, end, "small", Cw721HookMsg::EndPoll { poll_id }).unwrap();
+    let poll: PollResponse = query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    poll.status
+}
+
+#[test]
+fn bootstrap_quorum_applies_inside_window() {
+    let mut deps = setup_bootstrap(VOTING_PERIOD * 2);
+    assert_eq!(end_small_turnout_poll(&mut deps), PollStatus::Passed);
+}
+
+#[test]
+fn normal_quorum_applies_outside_window() {
+    let mut deps = setup_bootstrap(VOTING_PERIOD / 2);
+    assert_eq!(end_small_turnout_poll(&mut deps), PollStatus::Rejected);
+}
+
+#[test]
+fn bootstrap_period_requires_quorum() {
+    let mut msg = init_msg();
+    msg.bootstrap_period = Some(VOTING_PERIOD);
+    let mut deps = mock_deps();
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::BootstrapQuorumRequired {});
+
+    let mut deps = setup();
+    let msg = update_config_msg(r#"{"bootstrap_period":100}"#);
+    let err = exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap_err();
+    assert_eq!(err, ContractError::BootstrapQuorumRequired {});
+    // a zero period needs no quorum
+    let msg = update_config_msg(r#"{"bootstrap_period":0}"#);
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+}
+
+#[test]
+fn bootstrap_period_is_not_extended_once_over() {
+    let mut deps = setup_bootstrap(VOTING_PERIOD);
+    let extend = r#"{"bootstrap_period":200,"bootstrap_quorum":"0.005"}"#;
+
+    // still open: extending is fine
+    let open = env_at(INSTANTIATE_HEIGHT + VOTING_PERIOD - 1);
+    exec_as(&mut deps, open, ADMIN, update_config_msg(extend)).unwrap();
+
+    // closed: no reopening, shortening is still allowed
+    let closed = env_at(INSTANTIATE_HEIGHT + 200);
+    let reopen = r#"{"bootstrap_period":300,"bootstrap_quorum":"0.005"}"#;
+    let err = exec_as(&mut deps, closed.clone(), ADMIN, update_config_msg(reopen)).unwrap_err();
+    assert_eq!(err, ContractError::BootstrapEnded {});
+    let msg = update_config_msg(r#"{"bootstrap_period":0}"#);
+    exec_as(&mut deps, closed, ADMIN, msg).unwrap();
+}
+
+#[test]
+fn poll_lists_show_the_bootstrap_quorum() {
+    let mut deps = setup_bootstrap(VOTING_PERIOD * 2);
+    create_poll(&mut deps, "small", "first");
+    create_poll(&mut deps, "small", "second");
+    let list = |deps: &MockDeps, height: u64| -> PollsResponse {
+        let msg = QueryMsg::Polls {
+            filter: None,
+            start_after: None,
+            limit: None,
+            order_by: None,
+            include_drafts: None,
+            member_id: None,
+            poll_type: None,
+            viewer: None,
+        };
+        query_at(deps, env_at(height), msg)
+    };
+
+    let inside = list(&deps, INSTANTIATE_HEIGHT + 1);
+    assert_eq!(inside.polls.len(), 2);
+    for poll in inside.polls.iter() {
+        assert_eq!(poll.applied_quorum, Decimal::permille(5));
+    }
+    let outside = list(&deps, INSTANTIATE_HEIGHT + VOTING_PERIOD * 2);
+    for poll in outside.polls.iter() {
+        assert_eq!(poll.applied_quorum, Decimal::percent(10));
+    }
+}
@@ -0,0 +1,126 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{PollKind, PollResponse, PollStatus};
+
+fn choice_poll_msg(options: &[&str]) -> Cw721HookMsg {
+    let mut msg = create_poll_msg("candidates");
+    if let Cw721HookMsg::CreatePoll { options: o, .. } = &mut msg {
+        *o = Some(options.iter().map(|option| option.to_string()).collect());
+    }
+    msg
+}
+
+fn choose(deps: &mut MockDeps, member_id: &str, poll_id: u64, option_index: u32) {
+    let msg = Cw721HookMsg::CastChoiceVote {
+        poll_id,
+        option_index,
+    };
+    hook(deps, mock_env(), member_id, msg).unwrap();
+}
+
+fn poll(deps: &MockDeps, poll_id: u64) -> PollResponse {
+    query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    )
+}
+
+/// "1", "2" and "3" with shares 10, 20 and 30, a poll choosing among A, B and C
+fn setup_choice() -> (MockDeps, u64) {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 400);
+    mint(&mut deps, "3", 900);
+    let res = hook(
+        &mut deps,
+        mock_env(),
+        "1",
+        choice_poll_msg(&["A", "B", "C"]),
+    )
+    .unwrap();
+    (deps, attr(&res, "poll_id").parse().unwrap())
+}
+
+fn end(deps: &mut MockDeps, poll_id: u64) -> Response {
+    let after_voting = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    hook(deps, after_voting, "1", Cw721HookMsg::EndPoll { poll_id }).unwrap()
+}
+
+#[test]
+fn option_with_most_votes_wins() {
+    let (mut deps, poll_id) = setup_choice();
+    let a_poll = poll(&deps, poll_id);
+    assert_eq!(
+        a_poll.kind,
+        PollKind::MultipleChoice {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+        }
+    );
+    assert_eq!(a_poll.votes_per_option, vec![Uint128::zero(); 3]);
+
+    choose(&mut deps, "1", poll_id, 0);
+    choose(&mut deps, "2", poll_id, 2);
+    choose(&mut deps, "3", poll_id, 1);
+    let votes: Vec<u128> = poll(&deps, poll_id)
+        .votes_per_option
+        .iter()
+        .map(|votes| votes.u128())
+        .collect();
+    assert_eq!(votes, vec![10, 30, 20]);
+
+    let res = end(&mut deps, poll_id);
+    assert_eq!(attr(&res, "passed"), "true");
+    assert_eq!(attr(&res, "winning_option"), "1");
+    let a_poll = poll(&deps, poll_id);
+    assert_eq!(a_poll.status, PollStatus::Passed);
+    assert_eq!(a_poll.winning_option, Some(1));
+}
+
+#[test]
+fn tied_options_reject_the_poll() {
+    let (mut deps, poll_id) = setup_choice();
+    mint(&mut deps, "4", 100);
+    choose(&mut deps, "1", poll_id, 0);
+    choose(&mut deps, "4", poll_id, 2);
+
+    let res = end(&mut deps, poll_id);
+    assert_eq!(attr(&res, "passed"), "false");
+    assert_eq!(attr(&res, "rejected_reason"), "Tie between options");
+    assert_eq!(attr(&res, "winning_option"), "");
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Rejected);
+}
+
+#[test]
+fn vote_must_match_poll_kind() {
+    let (mut deps, poll_id) = setup_choice();
+    let msg = Cw721HookMsg::CastChoiceVote {
+        poll_id,
+        option_index: 3,
+    };
+    let err = hook(&mut deps, mock_env(), "1", msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidVoteOption {});
+    let err = cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap_err();
+    assert_eq!(err, ContractError::InvalidVoteOption {});
+
+    let binary = create_poll(&mut deps, "1", "binary");
+    let msg = Cw721HookMsg::CastChoiceVote {
+        poll_id: binary,
+        option_index: 0,
+    };
+    let err = hook(&mut deps, mock_env(), "1", msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidVoteOption {});
+}
+
+#[test]
+fn poll_options_are_validated() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    for options in [vec!["A"], vec!["A", ""], vec!["A", "B", "A"]].iter() {
+        let err = hook(&mut deps, mock_env(), "1", choice_poll_msg(options)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPollOptions(_)));
+    }
+}
@@ -0,0 +1,143 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::state::{Committee, DrawnMember};
+use crate::utils::{keep_drawn, sample_key, VoteOption};
+
+/// poll 1 ended (seed of draws), returns env after it ended
+fn ended_seed_poll(deps: &mut MockDeps) -> Env {
+    mint(deps, "seeder", 100);
+    let poll_id = create_poll(deps, "seeder", "seed");
+    cast_vote(deps, mock_env(), "seeder", poll_id, VoteOption::Yes).unwrap();
+    let end = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    exec_as(deps, end.clone(), ADMIN, ExecuteMsg::EndPoll { poll_id }).unwrap();
+    end
+}
+
+fn draw(deps: &mut MockDeps, env: Env, size: u8) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::DrawCommittee {
+        size,
+        seed_poll_id: 1,
+    };
+    exec_as(deps, env, ADMIN, msg)
+}
+
+/// bank of 150 members (two pages) with shares 1 to 15
+fn large_bank() -> (MockDeps, Env) {
+    let mut deps = setup();
+    let env = ended_seed_poll(&mut deps);
+    for i in 0..150u128 {
+        mint(&mut deps, &format!("member{:03}", i), (i % 15 + 1).pow(2));
+    }
+    (deps, env)
+}
+
+fn draw_all(deps: &mut MockDeps, env: Env, size: u8) -> Vec<String> {
+    let res = draw(deps, env.clone(), size).unwrap();
+    assert_eq!(attr(&res, "done"), "false");
+    let res = draw(deps, env, size).unwrap();
+    assert_eq!(attr(&res, "done"), "true");
+    let committee: Committee = query_as(
+        deps,
+        QueryMsg::Committee {
+            id: attr(&res, "committee_id").parse().unwrap(),
+        },
+    );
+    committee.members
+}
+
+#[test]
+fn zero_share_entries_are_not_drawn() {
+    let mut deps = setup();
+    let env = ended_seed_poll(&mut deps);
+    // voting without balance leaves an empty bank entry
+    let poll_id = create_poll(&mut deps, "seeder", "second");
+    cast_vote(&mut deps, mock_env(), "empty", poll_id, VoteOption::Yes).unwrap();
+
+    let err = draw(&mut deps, env.clone(), 2).unwrap_err();
+    assert_eq!(err, ContractError::InvalidCommitteeSize(1));
+    let res = draw(&mut deps, env, 1).unwrap();
+    assert_eq!(attr(&res, "members"), "seeder");
+}
+
+#[test]
+fn draws_from_banks_over_a_page() {
+    let (mut deps, env) = large_bank();
+    let msg = ExecuteMsg::SetBlocked {
+        member_id: "member014".to_string(),
+        blocked: true,
+    };
+    exec_as(&mut deps, env.clone(), ADMIN, msg).unwrap();
+
+    let members = draw_all(&mut deps, env, 20);
+    assert_eq!(members.len(), 20);
+    let mut distinct = members.clone();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(distinct.len(), 20);
+    assert!(!members.contains(&"member014".to_string()));
+}
+
+#[test]
+fn draw_is_reproducible_from_seed() {
+    let (mut deps, env) = large_bank();
+    let members = draw_all(&mut deps, env.clone(), 5);
+
+    let (mut other, _) = large_bank();
+    assert_eq!(draw_all(&mut other, env.clone(), 5), members);
+
+    // the seed is taken when the draw starts
+    let (mut later, _) = large_bank();
+    let res = draw(&mut later, env.clone(), 5).unwrap();
+    assert_eq!(attr(&res, "done"), "false");
+    let next = env_at(env.block.height + 1);
+    let res = draw(&mut later, next.clone(), 5).unwrap();
+    assert_eq!(attr(&res, "members"), members.join(","));
+    assert_ne!(draw_all(&mut later, next, 5), members);
+}
+
+#[test]
+fn other_parameters_start_over() {
+    let (mut deps, env) = large_bank();
+    let res = draw(&mut deps, env.clone(), 5).unwrap();
+    assert_eq!(attr(&res, "done"), "false");
+    let members = draw_all(&mut deps, env, 4);
+    assert_eq!(members.len(), 4);
+}
+
+fn draw_from(weights: &[(&str, u128)], size: usize, seed: u32) -> Vec<String> {
+    let seed = seed.to_be_bytes();
+    let mut drawn: Vec<DrawnMember> = vec![];
+    for (member_id, share) in weights.iter() {
+        let candidate = DrawnMember {
+            member_id: member_id.to_string(),
+            key: sample_key(&seed, member_id),
+            share: Uint128::from(*share),
+        };
+        keep_drawn(&mut drawn, candidate, size);
+    }
+    drawn.into_iter().map(|entry| entry.member_id).collect()
+}
+
+#[test]
+fn draw_frequencies_follow_share() {
+    let weights = [("a", 1), ("b", 2), ("c", 3)];
+    let rounds = 4000;
+    let mut first = [0u32; 3];
+    let mut with_a = 0u32;
+    for seed in 0..rounds {
+        let drawn = draw_from(&weights, 2, seed);
+        let index = weights.iter().position(|(id, _)| *id == drawn[0]).unwrap();
+        first[index] += 1;
+        if drawn.iter().any(|id| id == "a") {
+            with_a += 1;
+        }
+    }
+    let ratio = |count: u32| count as f64 / rounds as f64;
+    // first drawn proportionally to share: 1/6, 2/6, 3/6
+    for (index, (_, share)) in weights.iter().enumerate() {
+        assert!((ratio(first[index]) - *share as f64 / 6.0).abs() < 0.03);
+    }
+    // then out of the rest: 1/6 + 2/6 * 1/4 + 3/6 * 1/3
+    assert!((ratio(with_a) - 5.0 / 12.0).abs() < 0.03);
+}
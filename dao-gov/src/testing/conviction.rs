@@ -0,0 +1,91 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{conviction_weight, PollResponse, StakerResponse, StateResponse};
+
+fn convicted_vote(
+    deps: &mut MockDeps,
+    member_id: &str,
+    poll_id: u64,
+    conviction: u128,
+) -> Result<Response, ContractError> {
+    let msg = Cw721HookMsg::CastVote {
+        poll_id,
+        vote: VoteOption::Yes,
+        conviction: Some(Uint128::from(conviction)),
+    };
+    hook(deps, mock_env(), member_id, msg)
+}
+
+fn yes_votes(deps: &MockDeps, poll_id: u64) -> u128 {
+    let poll: PollResponse = query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    poll.yes_votes.u128()
+}
+
+fn balance_and_total_share(deps: &MockDeps, member_id: &str) -> (u128, u128) {
+    let member: StakerResponse = query_as(
+        deps,
+        QueryMsg::Member {
+            member_id: member_id.to_string(),
+        },
+    );
+    let state: StateResponse = query_as(deps, QueryMsg::State {});
+    (member.balance.u128(), state.total_share.u128())
+}
+
+#[test]
+fn conviction_weight_is_twice_its_share() {
+    assert_eq!(conviction_weight(Uint128::from(1u128)).u128(), 2);
+    assert_eq!(conviction_weight(Uint128::from(99u128)).u128(), 18);
+    assert_eq!(conviction_weight(Uint128::from(100u128)).u128(), 20);
+}
+
+#[test]
+fn cancel_does_not_refund_conviction() {
+    // "1" burns 100 of 500 (share 22 -> 20) for 20 extra weight, "2" delegates share 10
+    let mut deps = setup();
+    mint(&mut deps, "1", 500);
+    mint(&mut deps, "2", 100);
+    let delegate = Cw721HookMsg::DelegateVote {
+        delegator: "1".to_string(),
+    };
+    hook(&mut deps, mock_env(), "2", delegate).unwrap();
+    let poll_id = create_poll(&mut deps, "1", "convinced");
+    assert_eq!(balance_and_total_share(&deps, "1"), (500, 32));
+
+    let res = convicted_vote(&mut deps, "1", poll_id, 100).unwrap();
+    assert_eq!(attr(&res, "conviction_burned"), "100");
+    assert_eq!(attr(&res, "conviction_weight"), "20");
+    // delegated share is not amplified
+    assert_eq!(attr(&res, "total_amount"), "50");
+    assert_eq!(yes_votes(&deps, poll_id), 50);
+    assert_eq!(balance_and_total_share(&deps, "1"), (400, 30));
+
+    hook(
+        &mut deps,
+        mock_env(),
+        "1",
+        Cw721HookMsg::CancelVote { poll_id },
+    )
+    .unwrap();
+    assert_eq!(yes_votes(&deps, poll_id), 0);
+    assert_eq!(balance_and_total_share(&deps, "1"), (400, 30));
+}
+
+#[test]
+fn conviction_must_be_within_balance() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let poll_id = create_poll(&mut deps, "1", "bounded");
+    for conviction in [0, 101].iter() {
+        let err = convicted_vote(&mut deps, "1", poll_id, *conviction).unwrap_err();
+        assert_eq!(err, ContractError::InvalidConviction {});
+    }
+    assert_eq!(balance_and_total_share(&deps, "1"), (100, 10));
+}
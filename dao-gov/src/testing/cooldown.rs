@@ -0,0 +1,78 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{PollOutcome, StakerResponse};
+
+const COOLDOWN: u64 = 50;
+// first height the poll created at mock_env height can be ended
+const END: u64 = 12345 + VOTING_PERIOD + 1;
+
+/// total share 110 ("1" 10, "2" 10, "3" 90), quorum needs 11
+fn setup_cooldown() -> MockDeps {
+    let mut msg = init_msg();
+    msg.failed_poll_cooldown = Some(COOLDOWN);
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    mint(&mut deps, "3", 8100);
+    deps
+}
+
+/// poll of "1" with the given votes, ended at END
+fn end_voted(deps: &mut MockDeps, votes: &[(&str, VoteOption)]) -> PollOutcome {
+    let poll_id = create_poll(deps, "1", "cooled");
+    for (member_id, vote) in votes.iter() {
+        cast_vote(deps, mock_env(), member_id, poll_id, vote.clone()).unwrap();
+    }
+    hook(deps, env_at(END), "2", Cw721HookMsg::EndPoll { poll_id }).unwrap();
+    let member: StakerResponse = query_as(
+        deps,
+        QueryMsg::Member {
+            member_id: "1".to_string(),
+        },
+    );
+    let last = member.last_ended_poll.unwrap();
+    assert_eq!(last.poll_id, poll_id);
+    assert_eq!(last.ended_at_height, END);
+    last.outcome
+}
+
+fn create_at(deps: &mut MockDeps, height: u64) -> Result<Response, ContractError> {
+    hook(deps, env_at(height), "1", create_poll_msg("next"))
+}
+
+#[test]
+fn failed_quorum_starts_cooldown() {
+    let mut deps = setup_cooldown();
+    assert_eq!(end_voted(&mut deps, &[]), PollOutcome::QuorumNotReached);
+
+    let err = create_at(&mut deps, END + COOLDOWN - 1).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CooldownActive {
+            until_height: END + COOLDOWN
+        }
+    );
+    // other members are not affected
+    hook(&mut deps, env_at(END), "2", create_poll_msg("other")).unwrap();
+    create_at(&mut deps, END + COOLDOWN).unwrap();
+}
+
+#[test]
+fn passed_poll_has_no_cooldown() {
+    let mut deps = setup_cooldown();
+    let votes = [("1", VoteOption::Yes), ("2", VoteOption::Yes)];
+    assert_eq!(end_voted(&mut deps, &votes), PollOutcome::Passed);
+    create_at(&mut deps, END).unwrap();
+}
+
+#[test]
+fn threshold_rejection_has_no_cooldown() {
+    let mut deps = setup_cooldown();
+    let votes = [("1", VoteOption::Yes), ("3", VoteOption::No)];
+    assert_eq!(
+        end_voted(&mut deps, &votes),
+        PollOutcome::ThresholdNotReached
+    );
+    create_at(&mut deps, END).unwrap();
+}
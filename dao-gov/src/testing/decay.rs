@@ -0,0 +1,90 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Decimal;
+
+use super::*;
+use crate::state::{config_read, Config, TokenManager};
+use crate::utils::{effective_share, PollResponse};
+
+// height of mock_env, where create_poll opens the poll
+const START: u64 = 12345;
+const DECAY_AFTER: u64 = 100;
+
+fn setup_decay() -> MockDeps {
+    let mut msg = init_msg();
+    msg.decay_after = Some(DECAY_AFTER);
+    msg.decay_rate = Some(Decimal::percent(10));
+    msg.decay_floor = Some(Decimal::percent(50));
+    setup_with(msg)
+}
+
+fn voted_at(height: u64) -> TokenManager {
+    TokenManager {
+        share: Uint128::from(100u128),
+        last_vote_height: Some(height),
+        ..TokenManager::default()
+    }
+}
+
+#[test]
+fn share_decays_per_voting_period_down_to_floor() {
+    let deps = setup_decay();
+    let config: Config = config_read(&deps.storage).load().unwrap();
+    let member = voted_at(1000);
+    let share_at = |height: u64| effective_share(&config, &member, height).u128();
+
+    assert_eq!(share_at(1000), 100);
+    assert_eq!(share_at(1000 + DECAY_AFTER), 100);
+    // a started period doesn't count yet
+    assert_eq!(share_at(1000 + DECAY_AFTER + VOTING_PERIOD - 1), 100);
+    assert_eq!(share_at(1000 + DECAY_AFTER + VOTING_PERIOD), 90);
+    assert_eq!(share_at(1000 + DECAY_AFTER + 3 * VOTING_PERIOD), 70);
+    assert_eq!(share_at(1000 + DECAY_AFTER + 5 * VOTING_PERIOD), 50);
+    assert_eq!(share_at(u64::MAX), 50);
+
+    // never voted, or decay turned off
+    let fresh = TokenManager {
+        last_vote_height: None,
+        ..member.clone()
+    };
+    assert_eq!(effective_share(&config, &fresh, u64::MAX).u128(), 100);
+    let config = Config {
+        decay_after: None,
+        ..config
+    };
+    assert_eq!(effective_share(&config, &member, u64::MAX).u128(), 100);
+}
+
+#[test]
+fn inactive_vote_weighs_less() {
+    let mut deps = setup_decay();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    let first = create_poll(&mut deps, "1", "first");
+    cast_vote(&mut deps, mock_env(), "1", first, VoteOption::Yes).unwrap();
+    // last block of the first poll
+    cast_vote(
+        &mut deps,
+        env_at(START + VOTING_PERIOD),
+        "2",
+        first,
+        VoteOption::Yes,
+    )
+    .unwrap();
+
+    // one voting period past the grace window for "1", still within it for "2"
+    let later = env_at(START + DECAY_AFTER + VOTING_PERIOD);
+    let res = hook(&mut deps, later.clone(), "1", create_poll_msg("second")).unwrap();
+    let second: u64 = attr(&res, "poll_id").parse().unwrap();
+    cast_vote(&mut deps, later.clone(), "1", second, VoteOption::Yes).unwrap();
+    cast_vote(&mut deps, later, "2", second, VoteOption::No).unwrap();
+
+    let a_poll: PollResponse = query_as(
+        &deps,
+        QueryMsg::Poll {
+            poll_id: second,
+            member_id: None,
+        },
+    );
+    assert_eq!(a_poll.yes_votes, Uint128::from(9u128));
+    assert_eq!(a_poll.no_votes, Uint128::from(10u128));
+}
@@ -0,0 +1,496 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::state::{load_member, save_member};
+use crate::utils::{DelegationStatusResponse, PollResponse, VoteOption};
+
+const TIMEOUT: u64 = 50;
+// first height "d" (joined at mock_env height) counts as inactive
+const INACTIVE_AT: u64 = 12345 + TIMEOUT + 1;
+
+fn delegate(deps: &mut MockDeps, member_id: &str, delegatee: &str) {
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: delegatee.to_string(),
+    };
+    hook(deps, mock_env(), member_id, msg).unwrap();
+}
+
+fn poll(deps: &MockDeps, poll_id: u64) -> PollResponse {
+    query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    )
+}
+
+#[test]
+fn cancel_withdraws_votes_cast_for_delegators() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 400);
+    delegate(&mut deps, "2", "1");
+    let poll_id = create_poll(&mut deps, "1", "delegated");
+
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    assert_eq!(poll(&deps, poll_id).yes_votes, Uint128::from(30u128));
+
+    let msg = Cw721HookMsg::CancelVote { poll_id };
+    let res = hook(&mut deps, mock_env(), "1", msg).unwrap();
+    assert_eq!(attr(&res, "withdrawn_delegators"), "2");
+    assert_eq!(poll(&deps, poll_id).yes_votes, Uint128::zero());
+
+    // voting again casts the delegated vote with the new option
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::No).unwrap();
+    let a_poll = poll(&deps, poll_id);
+    assert_eq!(a_poll.yes_votes, Uint128::zero());
+    assert_eq!(a_poll.no_votes, Uint128::from(30u128));
+}
+
+#[test]
+fn undelegated_share_is_not_counted_twice() {
+    let mut deps = setup();
+    mint(&mut deps, "a", 100);
+    mint(&mut deps, "b", 400);
+    delegate(&mut deps, "a", "b");
+    let poll_id = create_poll(&mut deps, "b", "twice");
+
+    cast_vote(&mut deps, mock_env(), "b", poll_id, VoteOption::Yes).unwrap();
+    assert_eq!(poll(&deps, poll_id).yes_votes, Uint128::from(30u128));
+    // the vote cast on my behalf counts as voted
+    let err = cast_vote(&mut deps, mock_env(), "a", poll_id, VoteOption::No).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyVoted {});
+
+    let res = hook(&mut deps, mock_env(), "a", Cw721HookMsg::UnDelegateVote {}).unwrap();
+    assert_eq!(attr(&res, "withdrawn_polls"), poll_id.to_string());
+    cast_vote(&mut deps, mock_env(), "a", poll_id, VoteOption::No).unwrap();
+
+    // a.share + b.share, not 2 * a.share + b.share
+    let a_poll = poll(&deps, poll_id);
+    assert_eq!(a_poll.yes_votes, Uint128::from(20u128));
+    assert_eq!(a_poll.no_votes, Uint128::from(10u128));
+}
+
+/// "d" with delegators "a", "b" and "c", delegations void after TIMEOUT blocks
+fn setup_timeout() -> MockDeps {
+    let mut msg = init_msg();
+    msg.delegate_inactivity_timeout = Some(TIMEOUT);
+    let mut deps = setup_with(msg);
+    for member_id in ["d", "a", "b", "c"].iter() {
+        mint(&mut deps, member_id, 100);
+    }
+    for member_id in ["a", "b", "c"].iter() {
+        delegate(&mut deps, member_id, "d");
+    }
+    deps
+}
+
+fn delegate_to(deps: &MockDeps, member_id: &str) -> Option<String> {
+    load_member(&deps.storage, member_id.as_bytes())
+        .unwrap()
+        .delegate_to
+}
+
+fn delegated_from(deps: &MockDeps, member_id: &str) -> Vec<String> {
+    load_member(&deps.storage, member_id.as_bytes())
+        .unwrap()
+        .delegated_from
+}
+
+fn sweep(deps: &mut MockDeps, height: u64, limit: u32) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::SweepInactiveDelegations {
+        delegate_id: "d".to_string(),
+        limit,
+    };
+    exec_as(deps, env_at(height), "anyone", msg)
+}
+
+#[test]
+fn delegator_action_voids_inactive_delegation() {
+    let mut deps = setup_timeout();
+
+    // still active: nothing happens
+    let res = hook(
+        &mut deps,
+        env_at(INACTIVE_AT - 1),
+        "a",
+        create_poll_msg("kept"),
+    )
+    .unwrap();
+    assert!(!res
+        .attributes
+        .iter()
+        .any(|a| a.key == "voided_delegation_to"));
+    assert_eq!(delegate_to(&deps, "a"), Some("d".to_string()));
+
+    let res = hook(
+        &mut deps,
+        env_at(INACTIVE_AT),
+        "a",
+        create_poll_msg("voided"),
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "voided_delegation_to"), "d");
+    assert_eq!(delegate_to(&deps, "a"), None);
+    let remaining = delegated_from(&deps, "d");
+    assert_eq!(remaining.len(), 2);
+    assert!(!remaining.contains(&"a".to_string()));
+}
+
+#[test]
+fn sweep_releases_delegators_over_two_pages() {
+    let mut deps = setup_timeout();
+    let err = sweep(&mut deps, INACTIVE_AT - 1, 2).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DelegateActive {
+            last_vote_height: 12345
+        }
+    );
+
+    let res = sweep(&mut deps, INACTIVE_AT, 2).unwrap();
+    assert_eq!(attr(&res, "released_delegators"), "a,b");
+    assert_eq!(attr(&res, "remaining"), "1");
+    assert_eq!(attr(&res, "done"), "false");
+    assert_eq!(delegated_from(&deps, "d"), vec!["c"]);
+
+    let res = sweep(&mut deps, INACTIVE_AT, 2).unwrap();
+    assert_eq!(attr(&res, "released_delegators"), "c");
+    assert_eq!(attr(&res, "done"), "true");
+    assert!(delegated_from(&deps, "d").is_empty());
+    for member_id in ["a", "b", "c"].iter() {
+        assert_eq!(delegate_to(&deps, member_id), None);
+    }
+}
+
+#[test]
+fn delegate_voting_again_keeps_delegations() {
+    let mut deps = setup_timeout();
+    let poll_id = create_poll(&mut deps, "a", "comeback");
+
+    // delegations are void for the vote that makes "d" active again
+    cast_vote(
+        &mut deps,
+        env_at(INACTIVE_AT),
+        "d",
+        poll_id,
+        VoteOption::Yes,
+    )
+    .unwrap();
+    assert_eq!(poll(&deps, poll_id).yes_votes, Uint128::from(10u128));
+
+    let err = sweep(&mut deps, INACTIVE_AT + 1, 10).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DelegateActive {
+            last_vote_height: INACTIVE_AT
+        }
+    );
+    let res = hook(
+        &mut deps,
+        env_at(INACTIVE_AT + 1),
+        "b",
+        create_poll_msg("kept"),
+    )
+    .unwrap();
+    assert!(!res
+        .attributes
+        .iter()
+        .any(|a| a.key == "voided_delegation_to"));
+    assert_eq!(delegated_from(&deps, "d"), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn zero_timeout_turns_expiry_off() {
+    let mut deps = setup_timeout();
+    let msg = update_config_msg(r#"{"delegate_inactivity_timeout":0}"#);
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+
+    let err = sweep(&mut deps, INACTIVE_AT, 10).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DelegateActive {
+            last_vote_height: 12345
+        }
+    );
+    hook(&mut deps, env_at(INACTIVE_AT), "a", create_poll_msg("kept")).unwrap();
+    assert_eq!(delegate_to(&deps, "a"), Some("d".to_string()));
+}
+
+// MAX_DELEGATION_DEPTH in execute
+const MAX_DELEGATION_DEPTH: usize = 8;
+
+/// members "d1".."d<len>" where each delegates to the next, written straight to storage
+/// as older versions could leave them (hooks only allow a single hop)
+fn chain(deps: &mut MockDeps, len: usize) {
+    for i in 1..=len {
+        mint(deps, &format!("d{}", i), 100);
+    }
+    for i in 1..len {
+        let key = format!("d{}", i);
+        let mut token_manager = load_member(&deps.storage, key.as_bytes()).unwrap();
+        token_manager.delegate_to = Some(format!("d{}", i + 1));
+        save_member(&mut deps.storage, key.as_bytes(), &token_manager).unwrap();
+    }
+}
+
+fn try_delegate(
+    deps: &mut MockDeps,
+    member_id: &str,
+    delegatee: &str,
+) -> Result<Response, ContractError> {
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: delegatee.to_string(),
+    };
+    hook(deps, mock_env(), member_id, msg)
+}
+
+#[test]
+fn delegation_cycle_is_rejected() {
+    let mut deps = setup();
+    mint(&mut deps, "v", 100);
+    let err = try_delegate(&mut deps, "v", "v").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DelegationCycle {
+            path: vec!["v".to_string(), "v".to_string()]
+        }
+    );
+
+    chain(&mut deps, 2);
+    let mut last = load_member(&deps.storage, b"d2").unwrap();
+    last.delegate_to = Some("v".to_string());
+    save_member(&mut deps.storage, b"d2", &last).unwrap();
+    let err = try_delegate(&mut deps, "v", "d1").unwrap_err();
+    let path = ["v", "d1", "d2", "v"].iter().map(|id| id.to_string());
+    assert_eq!(
+        err,
+        ContractError::DelegationCycle {
+            path: path.collect()
+        }
+    );
+}
+
+#[test]
+fn delegation_chain_depth_is_capped() {
+    // v -> d1 .. d<MAX> is MAX_DELEGATION_DEPTH hops, only the single hop rule refuses it
+    let mut deps = setup();
+    mint(&mut deps, "v", 100);
+    chain(&mut deps, MAX_DELEGATION_DEPTH);
+    let err = try_delegate(&mut deps, "v", "d1").unwrap_err();
+    assert_eq!(err, ContractError::ConflictingDelegation {});
+
+    // one hop more is too long
+    let mut deps = setup();
+    mint(&mut deps, "v", 100);
+    chain(&mut deps, MAX_DELEGATION_DEPTH + 1);
+    let err = try_delegate(&mut deps, "v", "d1").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DelegationChainTooLong(MAX_DELEGATION_DEPTH)
+    );
+}
+
+fn delegation_status(deps: &MockDeps, member_id: &str) -> DelegationStatusResponse {
+    query_as(
+        deps,
+        QueryMsg::DelegationStatus {
+            member_id: member_id.to_string(),
+        },
+    )
+}
+
+#[test]
+fn delegation_status_is_one_sided() {
+    let mut deps = setup();
+    mint(&mut deps, "a", 100);
+    mint(&mut deps, "b", 400);
+    mint(&mut deps, "c", 900);
+    mint(&mut deps, "d", 100);
+    delegate(&mut deps, "a", "b");
+    delegate(&mut deps, "c", "b");
+
+    let delegatee = delegation_status(&deps, "b");
+    assert_eq!(delegatee.delegate_to, None);
+    assert!(!delegatee.is_delegating);
+    assert_eq!(delegatee.delegated_from, vec!["a", "c"]);
+    assert_eq!(delegatee.incoming_share, Uint128::from(40u128));
+
+    let delegator = delegation_status(&deps, "a");
+    assert_eq!(delegator.delegate_to, Some("b".to_string()));
+    assert!(delegator.is_delegating);
+    assert!(delegator.delegated_from.is_empty());
+    assert_eq!(delegator.incoming_share, Uint128::zero());
+
+    // nobody ends up both delegating out and receiving
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: "d".to_string(),
+    };
+    let err = hook(&mut deps, mock_env(), "b", msg).unwrap_err();
+    assert_eq!(err, ContractError::ConflictingDelegation {});
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: "a".to_string(),
+    };
+    let err = hook(&mut deps, mock_env(), "d", msg).unwrap_err();
+    assert_eq!(err, ContractError::ConflictingDelegation {});
+    assert!(delegation_status(&deps, "a").delegated_from.is_empty());
+    assert!(!delegation_status(&deps, "b").is_delegating);
+
+    // unknown member has no delegations
+    let unknown = delegation_status(&deps, "z");
+    assert_eq!(unknown.delegate_to, None);
+    assert!(unknown.delegated_from.is_empty());
+}
+
+#[test]
+fn delegated_voter_is_told_its_delegatee() {
+    let mut deps = setup();
+    mint(&mut deps, "a", 100);
+    mint(&mut deps, "b", 400);
+    delegate(&mut deps, "a", "b");
+    let poll_id = create_poll(&mut deps, "b", "reported");
+
+    let err = cast_vote(&mut deps, mock_env(), "a", poll_id, VoteOption::Yes).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::AlreadyDelegatedTo {
+            delegatee: "b".to_string()
+        }
+    );
+
+    // voting is back once undelegated
+    hook(&mut deps, mock_env(), "a", Cw721HookMsg::UnDelegateVote {}).unwrap();
+    cast_vote(&mut deps, mock_env(), "a", poll_id, VoteOption::Yes).unwrap();
+}
+
+#[test]
+fn double_delegation_is_rejected() {
+    let mut deps = setup();
+    mint(&mut deps, "a", 100);
+    mint(&mut deps, "b", 400);
+    delegate(&mut deps, "a", "b");
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: "b".to_string(),
+    };
+    let err = hook(&mut deps, mock_env(), "a", msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyDelegated {});
+
+    // stale entry left with delegate_to cleared
+    let mut delegator = load_member(&deps.storage, b"a").unwrap();
+    delegator.delegate_to = None;
+    save_member(&mut deps.storage, b"a", &delegator).unwrap();
+    let err = hook(&mut deps, mock_env(), "a", msg).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyDelegated {});
+    let delegatee = load_member(&deps.storage, b"b").unwrap();
+    assert_eq!(delegatee.delegated_from, vec!["a"]);
+}
+
+#[test]
+fn delegatee_must_hold_tokens() {
+    let mut deps = setup();
+    mint(&mut deps, "a", 100);
+    mint(&mut deps, "b", 100);
+    let to = |delegatee: &str| Cw721HookMsg::DelegateVote {
+        delegator: delegatee.to_string(),
+    };
+
+    let err = hook(&mut deps, mock_env(), "a", to("stranger")).unwrap_err();
+    assert_eq!(err, ContractError::DelegateeNotMember {});
+
+    // nor is a member who exited
+    hook(&mut deps, mock_env(), "b", Cw721HookMsg::Exit {}).unwrap();
+    let err = hook(&mut deps, mock_env(), "a", to("b")).unwrap_err();
+    assert_eq!(err, ContractError::DelegateeNotMember {});
+    assert_eq!(load_member(&deps.storage, b"a").unwrap().delegate_to, None);
+}
+
+#[test]
+fn delegation_conflicts_are_rejected_both_ways() {
+    let mut deps = setup();
+    for member_id in ["a", "b", "c"].iter() {
+        mint(&mut deps, member_id, 100);
+    }
+    delegate(&mut deps, "a", "b");
+    let to = |delegatee: &str| Cw721HookMsg::DelegateVote {
+        delegator: delegatee.to_string(),
+    };
+
+    // to a member delegating out
+    let err = hook(&mut deps, mock_env(), "c", to("a")).unwrap_err();
+    assert_eq!(err, ContractError::ConflictingDelegation {});
+    // out of a member receiving delegations
+    let err = hook(&mut deps, mock_env(), "b", to("c")).unwrap_err();
+    assert_eq!(err, ContractError::ConflictingDelegation {});
+
+    // both are allowed once "a" undelegates
+    hook(&mut deps, mock_env(), "a", Cw721HookMsg::UnDelegateVote {}).unwrap();
+    hook(&mut deps, mock_env(), "b", to("c")).unwrap();
+    hook(&mut deps, mock_env(), "a", to("c")).unwrap();
+    let delegatee = load_member(&deps.storage, b"c").unwrap();
+    assert_eq!(delegatee.delegated_from, vec!["b", "a"]);
+}
+
+#[test]
+fn two_node_cycle_is_rejected_and_repaired() {
+    let mut deps = setup();
+    mint(&mut deps, "a", 100);
+    mint(&mut deps, "b", 400);
+    delegate(&mut deps, "a", "b");
+    // the single hop rule refuses the back edge before the chain is walked
+    let err = try_delegate(&mut deps, "b", "a").unwrap_err();
+    assert_eq!(err, ContractError::ConflictingDelegation {});
+
+    // an older version could leave a -> b without "b" knowing about it
+    let mut token_manager = load_member(&deps.storage, b"b").unwrap();
+    token_manager.delegated_from.clear();
+    save_member(&mut deps.storage, b"b", &token_manager).unwrap();
+    let err = try_delegate(&mut deps, "b", "a").unwrap_err();
+    let path = ["b", "a", "b"].iter().map(|id| id.to_string());
+    assert_eq!(
+        err,
+        ContractError::DelegationCycle {
+            path: path.collect()
+        }
+    );
+
+    let break_msg = ExecuteMsg::BreakDelegation {
+        member_id: "a".to_string(),
+    };
+    let err = exec_as(&mut deps, mock_env(), "stranger", break_msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = exec_as(&mut deps, mock_env(), ADMIN, break_msg).unwrap();
+    assert_eq!(attr(&res, "from"), "a");
+    assert_eq!(attr(&res, "to"), "b");
+
+    assert_eq!(delegation_status(&deps, "a").delegate_to, None);
+    assert_eq!(
+        delegation_status(&deps, "b").incoming_share,
+        Uint128::zero()
+    );
+    // either direction can be delegated again
+    try_delegate(&mut deps, "b", "a").unwrap();
+    assert_eq!(delegation_status(&deps, "a").delegated_from, vec!["b"]);
+}
+
+#[test]
+fn three_node_cycle_is_rejected() {
+    let mut deps = setup();
+    for member_id in ["a", "b", "c"].iter() {
+        mint(&mut deps, member_id, 100);
+    }
+    delegate(&mut deps, "a", "b");
+    let mut token_manager = load_member(&deps.storage, b"b").unwrap();
+    token_manager.delegate_to = Some("c".to_string());
+    save_member(&mut deps.storage, b"b", &token_manager).unwrap();
+
+    let err = try_delegate(&mut deps, "c", "a").unwrap_err();
+    let path = ["c", "a", "b", "c"].iter().map(|id| id.to_string());
+    assert_eq!(
+        err,
+        ContractError::DelegationCycle {
+            path: path.collect()
+        }
+    );
+    assert_eq!(delegation_status(&deps, "c").delegate_to, None);
+}
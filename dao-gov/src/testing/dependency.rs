@@ -0,0 +1,156 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{DepositInfoResponse, DepositStatus, PollResponse, PollStatus};
+
+fn poll(deps: &MockDeps, poll_id: u64) -> PollResponse {
+    query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    )
+}
+
+fn create_dependent(deps: &mut MockDeps, depends_on: u64) -> Result<Response, ContractError> {
+    let mut msg = create_poll_msg("dependent");
+    if let Cw721HookMsg::CreatePoll {
+        depends_on: ref mut dependency,
+        ..
+    } = msg
+    {
+        *dependency = Some(depends_on);
+    }
+    hook(deps, mock_env(), "1", msg)
+}
+
+#[test]
+fn missing_dependency_is_rejected_at_creation() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let err = create_dependent(&mut deps, 7).unwrap_err();
+    assert_eq!(err, ContractError::PollNotFound {});
+}
+
+#[test]
+fn pruned_dependency_rejects_pending_poll() {
+    let mut msg = init_msg();
+    msg.draft_ttl = Some(10);
+    msg.execute_deposit = Some(Uint128::from(50u128));
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+
+    let mut draft = create_poll_msg("draft");
+    if let Cw721HookMsg::CreatePoll {
+        draft: ref mut d, ..
+    } = draft
+    {
+        *d = true;
+    }
+    let res = hook(&mut deps, mock_env(), "1", draft).unwrap();
+    let draft_id: u64 = attr(&res, "poll_id").parse().unwrap();
+
+    let res = create_dependent(&mut deps, draft_id).unwrap();
+    let poll_id: u64 = attr(&res, "poll_id").parse().unwrap();
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Pending);
+
+    let later = env_at(mock_env().block.height + 10);
+    let res = exec_as(
+        &mut deps,
+        later.clone(),
+        "anyone0000",
+        ExecuteMsg::PruneDrafts {},
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "pruned_polls"), draft_id.to_string());
+
+    let msg = ExecuteMsg::ResolveDependency { poll_id };
+    let res = exec_as(&mut deps, later, "anyone0000", msg).unwrap();
+    assert_eq!(attr(&res, "status"), "Rejected");
+    assert_eq!(attr(&res, "rejected_reason"), "dependency failed");
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Rejected);
+
+    let deposit: DepositInfoResponse = query_as(&deps, QueryMsg::DepositInfo { poll_id });
+    assert_eq!(deposit.amount, Uint128::from(50u128));
+    assert!(matches!(deposit.status, DepositStatus::Refunded));
+}
+
+fn end_poll(deps: &mut MockDeps, height: u64, poll_id: u64) {
+    let env = env_at(height);
+    exec_as(deps, env, "ender0000", ExecuteMsg::EndPoll { poll_id }).unwrap();
+}
+
+fn resolve(deps: &mut MockDeps, height: u64, poll_id: u64) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::ResolveDependency { poll_id };
+    exec_as(deps, env_at(height), "anyone0000", msg)
+}
+
+#[test]
+fn passed_dependency_opens_pending_poll() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let dependency = create_poll(&mut deps, "1", "dependency");
+    let res = create_dependent(&mut deps, dependency).unwrap();
+    let poll_id: u64 = attr(&res, "poll_id").parse().unwrap();
+
+    let pending = poll(&deps, poll_id);
+    assert_eq!(pending.status, PollStatus::Pending);
+    assert_eq!(pending.depends_on, Some(dependency));
+    assert_eq!(pending.dependency_status, Some(PollStatus::InProgress));
+    assert_eq!(poll(&deps, dependency).depends_on, None);
+    assert_eq!(poll(&deps, dependency).dependency_status, None);
+
+    // still open dependency leaves it pending
+    let height = mock_env().block.height + 1;
+    let res = resolve(&mut deps, height, poll_id).unwrap();
+    assert_eq!(attr(&res, "resolved"), "false");
+    assert_eq!(attr(&res, "status"), "Pending");
+    let err = cast_vote(&mut deps, env_at(height), "1", poll_id, VoteOption::Yes).unwrap_err();
+    assert_eq!(err, ContractError::PollNotInProgress {});
+
+    cast_vote(&mut deps, mock_env(), "1", dependency, VoteOption::Yes).unwrap();
+    let ended = mock_env().block.height + VOTING_PERIOD;
+    end_poll(&mut deps, ended, dependency);
+    assert_eq!(
+        poll(&deps, poll_id).dependency_status,
+        Some(PollStatus::Passed)
+    );
+
+    // the next vote resolves it and its voting period starts there
+    let height = ended + 5;
+    cast_vote(&mut deps, env_at(height), "1", poll_id, VoteOption::Yes).unwrap();
+    let opened = poll(&deps, poll_id);
+    assert_eq!(opened.status, PollStatus::InProgress);
+    assert_eq!(opened.end_height, height + VOTING_PERIOD);
+    assert_eq!(opened.yes_votes, Uint128::from(10u128));
+    let err = resolve(&mut deps, height, poll_id).unwrap_err();
+    assert_eq!(err, ContractError::PollNotPending {});
+}
+
+#[test]
+fn failed_dependency_rejects_pending_poll() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    // "2" creates it so the creator cooldown doesn't get in the way of "1"
+    let dependency = create_poll(&mut deps, "2", "dependency");
+    let res = create_dependent(&mut deps, dependency).unwrap();
+    let poll_id: u64 = attr(&res, "poll_id").parse().unwrap();
+
+    // nobody votes, so it ends without passing
+    let ended = mock_env().block.height + VOTING_PERIOD;
+    end_poll(&mut deps, ended, dependency);
+    assert_eq!(poll(&deps, dependency).status, PollStatus::Rejected);
+
+    let res = resolve(&mut deps, ended + 1, poll_id).unwrap();
+    assert_eq!(attr(&res, "resolved"), "true");
+    assert_eq!(attr(&res, "status"), "Rejected");
+    assert_eq!(attr(&res, "rejected_reason"), "dependency failed");
+    let rejected = poll(&deps, poll_id);
+    assert_eq!(rejected.status, PollStatus::Rejected);
+    assert_eq!(rejected.dependency_status, Some(PollStatus::Rejected));
+
+    let err = create_dependent(&mut deps, dependency).unwrap_err();
+    assert_eq!(err, ContractError::DependencyFailed {});
+}
@@ -0,0 +1,242 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::contract::query;
+use crate::state::load_member;
+use crate::utils::{
+    DepositInfoResponse, DepositStatus, PollOutcome, PollResponse, PollStatus, PollType,
+    StakerResponse, StateResponse,
+};
+
+const TEXT_DEPOSIT: u128 = 10;
+const EXECUTE_DEPOSIT: u128 = 50;
+
+fn setup_deposits() -> MockDeps {
+    let mut msg = init_msg();
+    msg.text_deposit = Some(Uint128::from(TEXT_DEPOSIT));
+    msg.execute_deposit = Some(Uint128::from(EXECUTE_DEPOSIT));
+    setup_with(msg)
+}
+
+fn typed_poll_msg(title: &str, poll_type: PollType) -> Cw721HookMsg {
+    let mut msg = create_poll_msg(title);
+    if let Cw721HookMsg::CreatePoll {
+        poll_type: msg_type,
+        ..
+    } = &mut msg
+    {
+        *msg_type = poll_type;
+    }
+    msg
+}
+
+fn balance(deps: &MockDeps, member_id: &str) -> u128 {
+    let member: StakerResponse = query_as(
+        deps,
+        QueryMsg::Member {
+            member_id: member_id.to_string(),
+        },
+    );
+    member.balance.u128()
+}
+
+#[test]
+fn deposit_follows_poll_type() {
+    let mut deps = setup_deposits();
+    mint(&mut deps, "1", 100);
+
+    let msg = typed_poll_msg("signal", PollType::Signal);
+    let res = hook(&mut deps, mock_env(), "1", msg).unwrap();
+    assert_eq!(attr(&res, "deposit"), TEXT_DEPOSIT.to_string());
+    assert_eq!(balance(&deps, "1"), 100 - TEXT_DEPOSIT);
+
+    let msg = typed_poll_msg("binding", PollType::Binding);
+    let res = hook(&mut deps, mock_env(), "1", msg).unwrap();
+    assert_eq!(attr(&res, "deposit"), EXECUTE_DEPOSIT.to_string());
+    assert_eq!(balance(&deps, "1"), 100 - TEXT_DEPOSIT - EXECUTE_DEPOSIT);
+}
+
+#[test]
+fn underfunded_creator_is_rejected() {
+    let mut deps = setup_deposits();
+    mint(&mut deps, "1", EXECUTE_DEPOSIT - 1);
+
+    let msg = typed_poll_msg("binding", PollType::Binding);
+    let err = hook(&mut deps, mock_env(), "1", msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientProposalDeposit(EXECUTE_DEPOSIT)
+    );
+    // enough for a signal poll
+    let msg = typed_poll_msg("signal", PollType::Signal);
+    hook(&mut deps, mock_env(), "1", msg).unwrap();
+
+    // balance locked by a vote doesn't count
+    let mut deps = setup_deposits();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    let poll_id = create_poll(&mut deps, "2", "locking");
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    let msg = typed_poll_msg("binding", PollType::Binding);
+    let err = hook(&mut deps, mock_env(), "1", msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientProposalDeposit(EXECUTE_DEPOSIT)
+    );
+}
+
+fn deposit_info(deps: &MockDeps, poll_id: u64) -> (String, u128, DepositStatus) {
+    let info: DepositInfoResponse = query_as(deps, QueryMsg::DepositInfo { poll_id });
+    assert_eq!(info.poll_id, poll_id);
+    (info.creator, info.amount.u128(), info.status)
+}
+
+/// binding poll of member 1 (holding 100), voted by member 2 as given
+fn setup_deposited(vote: Option<VoteOption>) -> (MockDeps, u64) {
+    let mut deps = setup_deposits();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 400);
+    let msg = typed_poll_msg("deposited", PollType::Binding);
+    let res = hook(&mut deps, mock_env(), "1", msg).unwrap();
+    let poll_id = attr(&res, "poll_id").parse().unwrap();
+    if let Some(vote) = vote {
+        cast_vote(&mut deps, mock_env(), "2", poll_id, vote).unwrap();
+    }
+    (deps, poll_id)
+}
+
+fn locked_deposit() -> (String, u128, DepositStatus) {
+    ("1".to_string(), EXECUTE_DEPOSIT, DepositStatus::Locked)
+}
+
+fn refunded_deposit() -> (String, u128, DepositStatus) {
+    ("1".to_string(), EXECUTE_DEPOSIT, DepositStatus::Refunded)
+}
+
+#[test]
+fn deposit_is_refunded_when_poll_ends() {
+    for vote in [VoteOption::Yes, VoteOption::No].iter() {
+        let (mut deps, poll_id) = setup_deposited(Some(vote.clone()));
+        assert_eq!(deposit_info(&deps, poll_id), locked_deposit());
+        assert_eq!(balance(&deps, "1"), 100 - EXECUTE_DEPOSIT);
+
+        let env = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+        let res = exec_as(&mut deps, env, "ender0000", ExecuteMsg::EndPoll { poll_id }).unwrap();
+        assert_eq!(attr(&res, "passed"), (*vote == VoteOption::Yes).to_string());
+        assert_eq!(attr(&res, "refunded_deposit"), EXECUTE_DEPOSIT.to_string());
+        assert_eq!(attr(&res, "deposit_slashed"), "false");
+        assert_eq!(deposit_info(&deps, poll_id), refunded_deposit());
+        assert_eq!(balance(&deps, "1"), 100);
+    }
+}
+
+#[test]
+fn deposit_is_slashed_without_quorum() {
+    let (mut deps, poll_id) = setup_deposited(None);
+    let state: StateResponse = query_as(&deps, QueryMsg::State {});
+    let total_supply = state.total_supply;
+
+    let env = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    let res = exec_as(&mut deps, env, "ender0000", ExecuteMsg::EndPoll { poll_id }).unwrap();
+    assert_eq!(attr(&res, "rejected_reason"), "Quorum not reached");
+    assert_eq!(attr(&res, "refunded_deposit"), "0");
+    assert_eq!(attr(&res, "deposit_slashed"), "true");
+    assert_eq!(
+        deposit_info(&deps, poll_id),
+        ("1".to_string(), EXECUTE_DEPOSIT, DepositStatus::Slashed)
+    );
+    // stays burned
+    assert_eq!(balance(&deps, "1"), 100 - EXECUTE_DEPOSIT);
+    let state: StateResponse = query_as(&deps, QueryMsg::State {});
+    assert_eq!(state.total_supply, total_supply);
+}
+
+const RECLAIM_GRACE_PERIOD: u64 = 100_000;
+
+fn reclaim(deps: &mut MockDeps, env: Env, poll_id: u64) -> Result<Response, ContractError> {
+    exec_as(deps, env, "anyone", ExecuteMsg::ReclaimDeposit { poll_id })
+}
+
+#[test]
+fn abandoned_poll_deposit_is_reclaimed() {
+    let (mut deps, poll_id) = setup_deposited(Some(VoteOption::Yes));
+    let grace_ends = mock_env().block.height + VOTING_PERIOD + RECLAIM_GRACE_PERIOD;
+
+    let err = reclaim(&mut deps, env_at(grace_ends - 1), poll_id).unwrap_err();
+    assert_eq!(err, ContractError::ReclaimGracePeriod {});
+    assert_eq!(deposit_info(&deps, poll_id), locked_deposit());
+
+    let res = reclaim(&mut deps, env_at(grace_ends), poll_id).unwrap();
+    assert_eq!(attr(&res, "deposit"), EXECUTE_DEPOSIT.to_string());
+    assert_eq!(attr(&res, "rejected_reason"), "Inactivity");
+    assert_eq!(deposit_info(&deps, poll_id), refunded_deposit());
+    assert_eq!(balance(&deps, "1"), 100);
+
+    // closed like end_poll closes polls
+    let a_poll: PollResponse = query_as(
+        &deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    assert_eq!(a_poll.status, PollStatus::Rejected);
+    assert_eq!(a_poll.ended_by, Some("reclaim".to_string()));
+    assert_eq!(a_poll.ended_at_height, grace_ends);
+    assert!(a_poll.raw_quorum.is_some());
+    assert!(a_poll.quorum_used.is_some());
+    let creator = load_member(deps.as_ref().storage, b"1").unwrap();
+    let last = creator.last_ended_poll.unwrap();
+    assert_eq!(last.poll_id, poll_id);
+    assert_eq!(last.outcome, PollOutcome::Abandoned);
+
+    let err = reclaim(&mut deps, env_at(grace_ends + 1), poll_id).unwrap_err();
+    assert_eq!(err, ContractError::PollNotInProgress {});
+    assert_eq!(balance(&deps, "1"), 100);
+}
+
+#[test]
+fn reclaim_grace_runs_past_end_time() {
+    let mut msg = init_msg();
+    msg.text_deposit = Some(Uint128::from(TEXT_DEPOSIT));
+    msg.execute_deposit = Some(Uint128::from(EXECUTE_DEPOSIT));
+    msg.voting_time = Some(1_000);
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    let res = hook(&mut deps, mock_env(), "1", create_poll_msg("timed")).unwrap();
+    let poll_id = attr(&res, "poll_id").parse().unwrap();
+
+    // grace in seconds follows voting_time / voting_period
+    let grace_seconds = RECLAIM_GRACE_PERIOD * 1_000 / VOTING_PERIOD;
+    let height = mock_env().block.height + VOTING_PERIOD + RECLAIM_GRACE_PERIOD;
+    let env_after = |seconds: u64| {
+        let mut env = env_at(height);
+        env.block.time = mock_env().block.time.plus_seconds(seconds);
+        env
+    };
+    let err = reclaim(&mut deps, env_after(0), poll_id).unwrap_err();
+    assert_eq!(err, ContractError::ReclaimGracePeriod {});
+    let err = reclaim(&mut deps, env_after(1_000 + grace_seconds - 1), poll_id).unwrap_err();
+    assert_eq!(err, ContractError::ReclaimGracePeriod {});
+
+    reclaim(&mut deps, env_after(1_000 + grace_seconds), poll_id).unwrap();
+    assert_eq!(balance(&deps, "1"), 100);
+}
+
+#[test]
+fn poll_without_deposit_has_none() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let poll_id = create_poll(&mut deps, "1", "free");
+    assert_eq!(
+        deposit_info(&deps, poll_id),
+        ("1".to_string(), 0, DepositStatus::None)
+    );
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::DepositInfo { poll_id: 9 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PollNotFound {});
+}
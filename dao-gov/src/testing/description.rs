@@ -0,0 +1,147 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::to_vec;
+
+use super::*;
+use crate::state::{
+    may_load_poll_description, remove_poll_description, save_poll_description,
+    DESCRIPTION_CHUNK_SIZE, MAX_DESCRIPTION_CHUNKS,
+};
+use crate::utils::{PollDescriptionResponse, PollResponse, PollsResponse, TextLimits, SUMMARY_MAX};
+
+fn setup_long_descriptions() -> MockDeps {
+    let mut msg = init_msg();
+    msg.text_limits = Some(TextLimits {
+        desc_max: (DESCRIPTION_CHUNK_SIZE * MAX_DESCRIPTION_CHUNKS) as u32,
+        ..TextLimits::default()
+    });
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    deps
+}
+
+/// poll with description and no summary, like clients from before summaries
+fn create_described_poll(deps: &mut MockDeps, description: &str) -> u64 {
+    let msg = Cw721HookMsg::CreatePoll {
+        title: "long".to_string(),
+        description: description.to_string(),
+        summary: None,
+        link: None,
+        links: None,
+        draft: false,
+        options: None,
+        poll_type: Default::default(),
+        depends_on: None,
+    };
+    let res = hook(deps, mock_env(), "1", msg).unwrap();
+    attr(&res, "poll_id").parse().unwrap()
+}
+
+/// multi-byte characters with one split across each chunk boundary
+fn long_description() -> String {
+    let mut description = "a".to_string();
+    while description.len() + "é".len() < 3 * DESCRIPTION_CHUNK_SIZE {
+        description.push('é');
+    }
+    description.push_str("end");
+    description
+}
+
+#[test]
+fn multi_chunk_description_round_trips_exactly() {
+    let mut deps = setup_long_descriptions();
+    let description = long_description();
+    assert!(description.len() > 2 * DESCRIPTION_CHUNK_SIZE);
+    let poll_id = create_described_poll(&mut deps, &description);
+
+    let res: PollDescriptionResponse = query_as(
+        &deps,
+        QueryMsg::PollDescription {
+            poll_id,
+            member_id: None,
+        },
+    );
+    assert_eq!(res.description, description);
+
+    let poll: PollResponse = query_as(
+        &deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    assert_eq!(poll.description, Some(description.clone()));
+    // summary is the first SUMMARY_MAX characters, not bytes
+    assert_eq!(
+        poll.summary,
+        description.chars().take(SUMMARY_MAX).collect::<String>()
+    );
+}
+
+#[test]
+fn poll_lists_leave_out_description() {
+    let mut deps = setup_long_descriptions();
+    mint(&mut deps, "2", 100);
+    let description = long_description();
+    create_described_poll(&mut deps, &description);
+    // the tail of the description is past the summary
+    let tail = &description[description.len() - 101..];
+
+    let polls: PollsResponse = query_as(
+        &deps,
+        QueryMsg::Polls {
+            filter: None,
+            start_after: None,
+            limit: None,
+            order_by: None,
+            include_drafts: None,
+            member_id: None,
+            poll_type: None,
+            viewer: None,
+        },
+    );
+    let eligible: PollsResponse = query_as(
+        &deps,
+        QueryMsg::EligiblePolls {
+            member_id: "2".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    for list in [polls, eligible].iter() {
+        assert_eq!(list.polls.len(), 1);
+        assert_eq!(list.polls[0].description, None);
+        assert_eq!(list.polls[0].summary.chars().count(), SUMMARY_MAX);
+        let raw = String::from_utf8(to_vec(list).unwrap()).unwrap();
+        assert!(!raw.contains(tail));
+    }
+}
+
+#[test]
+fn description_chunks_are_bounded_and_removed() {
+    let mut deps = mock_deps();
+    let full = "x".repeat(DESCRIPTION_CHUNK_SIZE * MAX_DESCRIPTION_CHUNKS);
+    save_poll_description(deps.as_mut().storage, 1, &full).unwrap();
+    assert_eq!(
+        may_load_poll_description(deps.as_ref().storage, 1).unwrap(),
+        Some(full.clone())
+    );
+
+    let too_long = format!("{}x", full);
+    save_poll_description(deps.as_mut().storage, 2, &too_long).unwrap_err();
+    assert_eq!(
+        may_load_poll_description(deps.as_ref().storage, 2).unwrap(),
+        None
+    );
+
+    // a shorter description replaces every chunk of the longer one
+    save_poll_description(deps.as_mut().storage, 1, "short").unwrap();
+    assert_eq!(
+        may_load_poll_description(deps.as_ref().storage, 1).unwrap(),
+        Some("short".to_string())
+    );
+    remove_poll_description(deps.as_mut().storage, 1);
+    assert_eq!(
+        may_load_poll_description(deps.as_ref().storage, 1).unwrap(),
+        None
+    );
+}
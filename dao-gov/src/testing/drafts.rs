@@ -0,0 +1,65 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{PollResponse, PollStatus};
+
+const DRAFT_TTL: u64 = 10;
+
+fn setup_draft(draft_ttl: Option<u64>) -> (MockDeps, u64) {
+    let mut msg = init_msg();
+    msg.draft_ttl = draft_ttl;
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    let res = hook(&mut deps, mock_env(), "1", create_draft_msg("draft")).unwrap();
+    let poll_id = attr(&res, "poll_id").parse().unwrap();
+    (deps, poll_id)
+}
+
+fn publish(deps: &mut MockDeps, height: u64, poll_id: u64) -> Result<Response, ContractError> {
+    hook(
+        deps,
+        env_at(height),
+        "1",
+        Cw721HookMsg::PublishPoll { poll_id },
+    )
+}
+
+#[test]
+fn draft_publishes_until_its_ttl() {
+    let (mut deps, poll_id) = setup_draft(Some(DRAFT_TTL));
+    let expired_at = mock_env().block.height + DRAFT_TTL;
+    publish(&mut deps, expired_at - 1, poll_id).unwrap();
+
+    let poll: PollResponse = query_as(
+        &deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    assert_eq!(poll.status, PollStatus::InProgress);
+}
+
+#[test]
+fn expired_draft_cannot_be_published() {
+    let (mut deps, poll_id) = setup_draft(Some(DRAFT_TTL));
+    let expired_at = mock_env().block.height + DRAFT_TTL;
+    let err = publish(&mut deps, expired_at, poll_id).unwrap_err();
+    assert_eq!(err, ContractError::DraftExpired { expired_at });
+
+    // it is pruned at the same height instead
+    let res = exec_as(
+        &mut deps,
+        env_at(expired_at),
+        ADMIN,
+        ExecuteMsg::PruneDrafts {},
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "pruned_polls"), poll_id.to_string());
+}
+
+#[test]
+fn draft_without_ttl_never_expires() {
+    let (mut deps, poll_id) = setup_draft(None);
+    publish(&mut deps, mock_env().block.height + 1_000_000, poll_id).unwrap();
+}
@@ -0,0 +1,41 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::PollsResponse;
+
+fn eligible_ids(deps: &MockDeps, member_id: &str) -> Vec<u64> {
+    let res: PollsResponse = query_as(
+        deps,
+        QueryMsg::EligiblePolls {
+            member_id: member_id.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    res.polls.iter().map(|poll| poll.id).collect()
+}
+
+#[test]
+fn voted_poll_is_not_eligible() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    mint(&mut deps, "3", 100);
+    let first = create_poll(&mut deps, "2", "first");
+    let second = create_poll(&mut deps, "2", "second");
+    let third = create_poll(&mut deps, "2", "third");
+    assert_eq!(eligible_ids(&deps, "1"), vec![first, second, third]);
+
+    cast_vote(&mut deps, mock_env(), "1", second, VoteOption::Yes).unwrap();
+    assert_eq!(eligible_ids(&deps, "1"), vec![first, third]);
+
+    // a delegating member votes through its delegatee, it has nothing to vote on
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: "1".to_string(),
+    };
+    hook(&mut deps, mock_env(), "3", msg).unwrap();
+    cast_vote(&mut deps, mock_env(), "1", third, VoteOption::No).unwrap();
+    assert_eq!(eligible_ids(&deps, "1"), vec![first]);
+    assert!(eligible_ids(&deps, "3").is_empty());
+    assert_eq!(eligible_ids(&deps, "2"), vec![first, second, third]);
+}
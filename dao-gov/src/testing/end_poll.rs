@@ -0,0 +1,261 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{Order, Storage};
+
+use super::*;
+use crate::state::{move_poll_indexer, poll_indexer_read, poll_indexer_store, state_read};
+use crate::utils::{EndPollPermission, PollResponse, PollStatus, StakerResponse};
+
+const REWARD: u128 = 7;
+
+fn setup_rewarded(permission: Option<EndPollPermission>) -> (MockDeps, u64) {
+    let mut msg = init_msg();
+    msg.end_poll_reward = Some(Uint128::from(REWARD));
+    msg.end_poll_permission = permission;
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    let poll_id = create_poll(&mut deps, "1", "rewarded");
+    (deps, poll_id)
+}
+
+fn after_voting() -> Env {
+    env_at(mock_env().block.height + VOTING_PERIOD + 1)
+}
+
+fn balance(deps: &MockDeps, member_id: &str) -> Uint128 {
+    let member: StakerResponse = query_as(
+        deps,
+        QueryMsg::Member {
+            member_id: member_id.to_string(),
+        },
+    );
+    member.balance
+}
+
+fn status(deps: &MockDeps, poll_id: u64) -> PollStatus {
+    let poll: PollResponse = query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    poll.status
+}
+
+#[test]
+fn default_permission_is_anyone() {
+    assert_eq!(EndPollPermission::default(), EndPollPermission::Anyone);
+}
+
+#[test]
+fn member_with_share_is_rewarded() {
+    let (mut deps, poll_id) = setup_rewarded(None);
+    let res = hook(
+        &mut deps,
+        after_voting(),
+        "1",
+        Cw721HookMsg::EndPoll { poll_id },
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "end_poll_reward"), REWARD.to_string());
+    assert_eq!(balance(&deps, "1"), Uint128::from(100 + REWARD));
+}
+
+#[test]
+fn token_without_share_ends_poll_unrewarded() {
+    let (mut deps, poll_id) = setup_rewarded(None);
+    let res = hook(
+        &mut deps,
+        after_voting(),
+        "9",
+        Cw721HookMsg::EndPoll { poll_id },
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "end_poll_reward"), "0");
+    assert_eq!(balance(&deps, "9"), Uint128::zero());
+    assert_eq!(status(&deps, poll_id), PollStatus::Rejected);
+}
+
+#[test]
+fn address_end_is_unrewarded() {
+    let (mut deps, poll_id) = setup_rewarded(None);
+    let res = exec_as(
+        &mut deps,
+        after_voting(),
+        "anyone0000",
+        ExecuteMsg::EndPoll { poll_id },
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "end_poll_reward"), "0");
+}
+
+#[test]
+fn token_holders_permission_requires_share() {
+    let (mut deps, poll_id) = setup_rewarded(Some(EndPollPermission::TokenHolders));
+    let err = hook(
+        &mut deps,
+        after_voting(),
+        "9",
+        Cw721HookMsg::EndPoll { poll_id },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotAMember {});
+
+    let err = exec_as(
+        &mut deps,
+        after_voting(),
+        "anyone0000",
+        ExecuteMsg::EndPoll { poll_id },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = hook(
+        &mut deps,
+        after_voting(),
+        "1",
+        Cw721HookMsg::EndPoll { poll_id },
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "end_poll_reward"), REWARD.to_string());
+}
+
+fn recent_turnout(deps: &MockDeps) -> Vec<Decimal> {
+    state_read(&deps.storage).load().unwrap().recent_turnout
+}
+
+#[test]
+fn turnout_history_skips_unvoted_and_force_ended_polls() {
+    let (mut deps, unvoted) = setup_rewarded(None);
+    let voted = create_poll(&mut deps, "1", "voted");
+    let forced = create_poll(&mut deps, "1", "forced");
+    cast_vote(&mut deps, mock_env(), "1", voted, VoteOption::Yes).unwrap();
+    cast_vote(&mut deps, mock_env(), "1", forced, VoteOption::Yes).unwrap();
+
+    let msg = ExecuteMsg::ForceEndPoll {
+        poll_id: forced,
+        outcome: None,
+    };
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+    for poll_id in [unvoted, voted].iter() {
+        let msg = Cw721HookMsg::EndPoll { poll_id: *poll_id };
+        hook(&mut deps, after_voting(), "1", msg).unwrap();
+    }
+    assert_eq!(status(&deps, unvoted), PollStatus::Rejected);
+    assert_eq!(status(&deps, forced), PollStatus::Rejected);
+
+    // only the voted poll (all of the share turned out) is remembered
+    assert_eq!(recent_turnout(&deps), vec![Decimal::one()]);
+}
+
+#[test]
+fn owner_only_permission_rejects_others() {
+    let (mut deps, poll_id) = setup_rewarded(Some(EndPollPermission::OwnerOnly));
+    let err = exec_as(
+        &mut deps,
+        after_voting(),
+        "anyone0000",
+        ExecuteMsg::EndPoll { poll_id },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the creator's token isn't held by an admin
+    let err = hook(
+        &mut deps,
+        after_voting(),
+        "1",
+        Cw721HookMsg::EndPoll { poll_id },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    assert_eq!(status(&deps, poll_id), PollStatus::InProgress);
+
+    let res = exec_as(
+        &mut deps,
+        after_voting(),
+        ADMIN,
+        ExecuteMsg::EndPoll { poll_id },
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "ended_by"), ADMIN);
+    assert_eq!(status(&deps, poll_id), PollStatus::Rejected);
+}
+
+#[test]
+fn second_end_poll_is_a_clean_error() {
+    let (mut deps, poll_id) = setup_rewarded(None);
+    let end = Cw721HookMsg::EndPoll { poll_id };
+    hook(&mut deps, after_voting(), "1", end.clone()).unwrap();
+    let before: Vec<_> = deps.storage.range(None, None, Order::Ascending).collect();
+
+    let err = hook(&mut deps, after_voting(), "1", end).unwrap_err();
+    assert_eq!(err, ContractError::PollNotInProgress {});
+    let after: Vec<_> = deps.storage.range(None, None, Order::Ascending).collect();
+    assert_eq!(after, before);
+    assert_eq!(balance(&deps, "1"), Uint128::from(100 + REWARD));
+
+    // indexed under the final status only
+    let key = poll_id.to_be_bytes();
+    for status in [PollStatus::InProgress, PollStatus::Rejected].iter() {
+        let indexed = poll_indexer_read(&deps.storage, status)
+            .may_load(&key)
+            .unwrap()
+            .is_some();
+        assert_eq!(indexed, *status == PollStatus::Rejected);
+    }
+}
+
+#[test]
+fn moving_poll_indexer_twice_is_a_no_op() {
+    let mut deps = setup();
+    let key = 7u64.to_be_bytes();
+    poll_indexer_store(&mut deps.storage, &PollStatus::InProgress)
+        .save(&key, &true)
+        .unwrap();
+    for _ in 0..2 {
+        move_poll_indexer(
+            &mut deps.storage,
+            7,
+            &PollStatus::InProgress,
+            &PollStatus::Passed,
+        )
+        .unwrap();
+        let in_progress = poll_indexer_read(&deps.storage, &PollStatus::InProgress)
+            .may_load(&key)
+            .unwrap();
+        assert_eq!(in_progress, None);
+        let passed = poll_indexer_read(&deps.storage, &PollStatus::Passed)
+            .may_load(&key)
+            .unwrap();
+        assert_eq!(passed, Some(true));
+    }
+}
+
+#[test]
+fn expired_and_ended_polls_fail_differently() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    let poll_id = create_poll(&mut deps, "1", "closing");
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    let cancel = Cw721HookMsg::CancelVote { poll_id };
+
+    // past end_height but not ended yet
+    let err = cast_vote(&mut deps, after_voting(), "2", poll_id, VoteOption::No).unwrap_err();
+    assert_eq!(err, ContractError::PollEnded {});
+    let err = hook(&mut deps, after_voting(), "1", cancel.clone()).unwrap_err();
+    assert_eq!(err, ContractError::PollEnded {});
+
+    hook(
+        &mut deps,
+        after_voting(),
+        "2",
+        Cw721HookMsg::EndPoll { poll_id },
+    )
+    .unwrap();
+    let err = cast_vote(&mut deps, after_voting(), "2", poll_id, VoteOption::No).unwrap_err();
+    assert_eq!(err, ContractError::PollNotInProgress {});
+    let err = hook(&mut deps, after_voting(), "1", cancel).unwrap_err();
+    assert_eq!(err, ContractError::PollNotInProgress {});
+}
@@ -0,0 +1,94 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{PollResponse, PollStatus};
+
+const START: u64 = 12345;
+
+fn env_after(blocks: u64, seconds: u64) -> Env {
+    let mut env = env_at(START + blocks);
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env
+}
+
+/// poll of member 1 created at mock_env, member 2 votes later
+fn setup_poll(voting_time: Option<u64>) -> (MockDeps, u64) {
+    let mut msg = init_msg();
+    msg.voting_time = voting_time;
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    let poll_id = create_poll(&mut deps, "1", "timed");
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    (deps, poll_id)
+}
+
+fn poll(deps: &MockDeps, poll_id: u64) -> PollResponse {
+    query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    )
+}
+
+fn end_poll(deps: &mut MockDeps, env: Env, poll_id: u64) -> Result<Response, ContractError> {
+    exec_as(deps, env, "ender0000", ExecuteMsg::EndPoll { poll_id })
+}
+
+#[test]
+fn height_only_poll_ignores_time() {
+    let (mut deps, poll_id) = setup_poll(None);
+    assert_eq!(poll(&deps, poll_id).end_time, None);
+
+    let a_year = 365 * 24 * 60 * 60;
+    let err = end_poll(&mut deps, env_after(VOTING_PERIOD - 1, a_year), poll_id).unwrap_err();
+    assert_eq!(err, ContractError::PollVotingPeriod {});
+    cast_vote(
+        &mut deps,
+        env_after(VOTING_PERIOD, a_year),
+        "2",
+        poll_id,
+        VoteOption::Yes,
+    )
+    .unwrap();
+    end_poll(&mut deps, env_after(VOTING_PERIOD + 1, 0), poll_id).unwrap();
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Passed);
+}
+
+#[test]
+fn time_ends_voting_before_height() {
+    let (mut deps, poll_id) = setup_poll(Some(60));
+    let end_time = mock_env().block.time.plus_seconds(60);
+    assert_eq!(poll(&deps, poll_id).end_time, Some(end_time));
+
+    let err = end_poll(&mut deps, env_after(10, 59), poll_id).unwrap_err();
+    assert_eq!(err, ContractError::PollVotingPeriod {});
+    let err = cast_vote(&mut deps, env_after(10, 61), "2", poll_id, VoteOption::No).unwrap_err();
+    assert_eq!(err, ContractError::PollEnded {});
+    end_poll(&mut deps, env_after(10, 61), poll_id).unwrap();
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Passed);
+}
+
+#[test]
+fn height_ends_hybrid_voting_before_time() {
+    let day = 24 * 60 * 60;
+    let (mut deps, poll_id) = setup_poll(Some(day));
+
+    cast_vote(
+        &mut deps,
+        env_after(VOTING_PERIOD, 10),
+        "2",
+        poll_id,
+        VoteOption::No,
+    )
+    .unwrap();
+    let err = end_poll(&mut deps, env_after(VOTING_PERIOD - 1, 10), poll_id).unwrap_err();
+    assert_eq!(err, ContractError::PollVotingPeriod {});
+    let msg = Cw721HookMsg::CancelVote { poll_id };
+    let err = hook(&mut deps, env_after(VOTING_PERIOD + 1, 10), "2", msg).unwrap_err();
+    assert_eq!(err, ContractError::PollEnded {});
+    end_poll(&mut deps, env_after(VOTING_PERIOD + 1, 10), poll_id).unwrap();
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Rejected);
+}
@@ -0,0 +1,131 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::state::{load_member, may_load_member, save_member, TokenManager};
+use crate::utils::MembersResponse;
+
+fn exit(deps: &mut MockDeps, env: Env, member_id: &str) -> Result<Response, ContractError> {
+    hook(deps, env, member_id, Cw721HookMsg::Exit {})
+}
+
+#[test]
+fn clean_exit_removes_bank_entry() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+
+    let res = exit(&mut deps, mock_env(), "1").unwrap();
+    assert_eq!(attr(&res, "amount"), "100");
+    assert_eq!(attr(&res, "removed"), "true");
+    assert!(may_load_member(deps.as_ref().storage, b"1")
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn active_lock_blocks_exit() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let poll_id = create_poll(&mut deps, "1", "locked");
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+
+    let err = exit(&mut deps, mock_env(), "1").unwrap_err();
+    assert_eq!(err, ContractError::InvalidWithdrawAmount {});
+    let token_manager = may_load_member(deps.as_ref().storage, b"1").unwrap();
+    assert_eq!(token_manager.unwrap().balance, Uint128::from(100u128));
+
+    let ended = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    hook(
+        &mut deps,
+        ended.clone(),
+        "1",
+        Cw721HookMsg::EndPoll { poll_id },
+    )
+    .unwrap();
+    let res = exit(&mut deps, ended, "1").unwrap();
+    assert_eq!(attr(&res, "removed"), "true");
+    assert!(may_load_member(deps.as_ref().storage, b"1")
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn delegated_out_member_must_undelegate_first() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 400);
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: "2".to_string(),
+    };
+    hook(&mut deps, mock_env(), "1", msg).unwrap();
+
+    let err = exit(&mut deps, mock_env(), "1").unwrap_err();
+    assert_eq!(err, ContractError::UndelegateBeforeExit {});
+    assert!(may_load_member(deps.as_ref().storage, b"1")
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn exit_releases_delegators() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 400);
+    for member_id in ["2", "3"].iter() {
+        mint(&mut deps, member_id, 100);
+        let msg = Cw721HookMsg::DelegateVote {
+            delegator: "1".to_string(),
+        };
+        hook(&mut deps, mock_env(), member_id, msg).unwrap();
+    }
+
+    let res = exit(&mut deps, mock_env(), "1").unwrap();
+    assert_eq!(attr(&res, "removed"), "true");
+    assert_eq!(attr(&res, "released_delegators"), "2,3");
+    for member_id in ["2", "3"].iter() {
+        let token_manager = load_member(deps.as_ref().storage, member_id.as_bytes()).unwrap();
+        assert_eq!(token_manager.delegate_to, None);
+    }
+}
+
+fn members(deps: &MockDeps) -> Vec<String> {
+    let res: MembersResponse = query_as(
+        deps,
+        QueryMsg::Members {
+            start_after: None,
+            limit: None,
+        },
+    );
+    res.members.into_iter().map(|(id, _, _)| id).collect()
+}
+
+#[test]
+fn anyone_prunes_an_empty_record() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    // zero balance record left behind by an exit before records were removed,
+    // still receiving the delegation of "1"
+    let mut delegator = load_member(deps.as_ref().storage, b"1").unwrap();
+    delegator.delegate_to = Some("old".to_string());
+    save_member(&mut deps.storage, b"1", &delegator).unwrap();
+    let mut token_manager = TokenManager::default();
+    token_manager.delegated_from.push("1".to_string());
+    save_member(&mut deps.storage, b"old", &token_manager).unwrap();
+    assert_eq!(members(&deps), vec!["1", "2", "old"]);
+
+    let prune = |member_id: &str| ExecuteMsg::PruneMember {
+        member_id: member_id.to_string(),
+    };
+    let err = exec_as(&mut deps, mock_env(), "stranger", prune("2")).unwrap_err();
+    assert_eq!(err, ContractError::MemberNotEmpty {});
+    let err = exec_as(&mut deps, mock_env(), "stranger", prune("none")).unwrap_err();
+    assert_eq!(err, ContractError::NothingStaked {});
+
+    let res = exec_as(&mut deps, mock_env(), "stranger", prune("old")).unwrap();
+    assert_eq!(attr(&res, "released_delegators"), "1");
+    assert!(may_load_member(deps.as_ref().storage, b"old")
+        .unwrap()
+        .is_none());
+    assert_eq!(members(&deps), vec!["1", "2"]);
+    let delegator = load_member(deps.as_ref().storage, b"1").unwrap();
+    assert_eq!(delegator.delegate_to, None);
+}
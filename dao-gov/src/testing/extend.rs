@@ -0,0 +1,100 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::PollResponse;
+
+const MAX_EXTENSION: u64 = 30;
+
+/// poll of member 1, member 2 holds as much share
+fn setup_extendable() -> (MockDeps, u64) {
+    let mut msg = init_msg();
+    msg.max_extension = Some(MAX_EXTENSION);
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    let poll_id = create_poll(&mut deps, "1", "extendable");
+    (deps, poll_id)
+}
+
+fn extend(
+    deps: &mut MockDeps,
+    member_id: &str,
+    poll_id: u64,
+    additional_blocks: u64,
+) -> Result<Response, ContractError> {
+    let msg = Cw721HookMsg::ExtendPoll {
+        poll_id,
+        additional_blocks,
+    };
+    hook(deps, mock_env(), member_id, msg)
+}
+
+fn end_height(deps: &MockDeps, poll_id: u64) -> u64 {
+    let a_poll: PollResponse = query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    a_poll.end_height
+}
+
+#[test]
+fn creator_extends_until_quorum() {
+    let (mut deps, poll_id) = setup_extendable();
+    let end = mock_env().block.height + VOTING_PERIOD;
+
+    let err = extend(&mut deps, "2", poll_id, 5).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = extend(&mut deps, "1", poll_id, 5).unwrap();
+    assert_eq!(attr(&res, "extended_by"), "1");
+    assert_eq!(end_height(&deps, poll_id), end + 5);
+
+    cast_vote(&mut deps, mock_env(), "2", poll_id, VoteOption::Yes).unwrap();
+    let err = extend(&mut deps, "1", poll_id, 5).unwrap_err();
+    assert_eq!(err, ContractError::QuorumAlreadyReached {});
+
+    // admin still can
+    let msg = ExecuteMsg::ExtendPoll {
+        poll_id,
+        additional_blocks: 5,
+    };
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+    assert_eq!(end_height(&deps, poll_id), end + 10);
+}
+
+#[test]
+fn extensions_are_capped_in_total() {
+    let (mut deps, poll_id) = setup_extendable();
+    let end = mock_env().block.height + VOTING_PERIOD;
+
+    extend(&mut deps, "1", poll_id, 20).unwrap();
+    let err = extend(&mut deps, "1", poll_id, MAX_EXTENSION - 20 + 1).unwrap_err();
+    assert_eq!(err, ContractError::ExtensionExceeded(MAX_EXTENSION));
+    assert_eq!(end_height(&deps, poll_id), end + 20);
+
+    extend(&mut deps, "1", poll_id, MAX_EXTENSION - 20).unwrap();
+    assert_eq!(end_height(&deps, poll_id), end + MAX_EXTENSION);
+    let err = extend(&mut deps, "1", poll_id, 1).unwrap_err();
+    assert_eq!(err, ContractError::ExtensionExceeded(MAX_EXTENSION));
+}
+
+#[test]
+fn zero_extension_is_rejected() {
+    let (mut deps, poll_id) = setup_extendable();
+    let err = extend(&mut deps, "1", poll_id, 0).unwrap_err();
+    assert_eq!(err, ContractError::ZeroExtension {});
+
+    let msg = ExecuteMsg::ExtendPoll {
+        poll_id,
+        additional_blocks: 0,
+    };
+    let err = exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap_err();
+    assert_eq!(err, ContractError::ZeroExtension {});
+    assert_eq!(
+        end_height(&deps, poll_id),
+        mock_env().block.height + VOTING_PERIOD
+    );
+}
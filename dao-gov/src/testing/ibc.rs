@@ -0,0 +1,243 @@
+use cosmwasm_std::testing::{
+    mock_env, mock_ibc_channel_connect_ack, mock_ibc_channel_open_init, mock_ibc_channel_open_try,
+    mock_ibc_packet_recv,
+};
+use cosmwasm_std::{
+    coins, ContractResult, CosmosMsg, IbcMsg, IbcOrder, Reply, ReplyOn, StdError, SubMsg,
+    SubMsgExecutionResponse,
+};
+
+use super::*;
+use crate::contract::reply;
+use crate::execute::IBC_NOTIFY_REPLY_ID;
+use crate::ibc::{ibc_channel_connect, ibc_channel_open, ibc_packet_receive, IBC_VERSION};
+use crate::msg::IbcNotifyPacket;
+use crate::utils::{IbcConfigResponse, PollResponse, PollStatus};
+
+const CHANNEL: &str = "channel-7";
+const RECEIVER: &str = "sister1dao";
+const DENOM: &str = "uluna";
+
+fn setup_ibc(notify: bool) -> MockDeps {
+    let mut msg = init_msg();
+    msg.ibc_channel = Some(CHANNEL.to_string());
+    msg.ibc_notify = Some(notify);
+    msg.ibc_receiver = Some(RECEIVER.to_string());
+    msg.ibc_denom = Some(DENOM.to_string());
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 400);
+    deps
+}
+
+/// ends a poll member 1 voted yes on
+fn end_voted_poll(deps: &mut MockDeps) -> (u64, Response) {
+    let poll_id = create_poll(deps, "1", "mirrored");
+    cast_vote(deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    let env = env_at(mock_env().block.height + VOTING_PERIOD);
+    let res = exec_as(deps, env, "ender0000", ExecuteMsg::EndPoll { poll_id }).unwrap();
+    (poll_id, res)
+}
+
+fn status(deps: &MockDeps, poll_id: u64) -> PollStatus {
+    let a_poll: PollResponse = query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    a_poll.status
+}
+
+#[test]
+fn end_poll_sends_result_packet() {
+    let mut deps = setup_ibc(true);
+    let contract = mock_env().contract.address;
+    deps.querier.set_balance(contract.as_str(), coins(1, DENOM));
+
+    let (poll_id, res) = end_voted_poll(&mut deps);
+    assert_eq!(res.messages.len(), 1);
+    let SubMsg {
+        id, msg, reply_on, ..
+    } = &res.messages[0];
+    assert_eq!((*id, reply_on), (IBC_NOTIFY_REPLY_ID, &ReplyOn::Error));
+    let (channel_id, data, timeout) = match msg {
+        CosmosMsg::Ibc(IbcMsg::SendPacket {
+            channel_id,
+            data,
+            timeout,
+        }) => (channel_id, data, timeout),
+        msg => panic!("unexpected message {:?}", msg),
+    };
+    assert_eq!(channel_id, CHANNEL);
+    assert_eq!(
+        timeout.timestamp(),
+        Some(mock_env().block.time.plus_seconds(600))
+    );
+    let packet: IbcNotifyPacket = from_binary(data).unwrap();
+    assert_eq!(
+        packet,
+        IbcNotifyPacket {
+            receiver: RECEIVER.to_string(),
+            poll_id,
+            passed: true,
+            yes: Uint128::from(20u128),
+            no: Uint128::zero(),
+            height: mock_env().block.height + VOTING_PERIOD,
+        }
+    );
+}
+
+#[test]
+fn disabled_notification_sends_nothing() {
+    let mut deps = setup_ibc(false);
+    let contract = mock_env().contract.address;
+    deps.querier.set_balance(contract.as_str(), coins(1, DENOM));
+
+    let (poll_id, res) = end_voted_poll(&mut deps);
+    assert!(res.messages.is_empty());
+    assert!(res.attributes.iter().all(|a| a.key != "ibc_notify_error"));
+    assert_eq!(status(&deps, poll_id), PollStatus::Passed);
+
+    let ibc: IbcConfigResponse = query_as(&deps, QueryMsg::IbcConfig {});
+    assert!(!ibc.ibc_notify);
+    assert_eq!(ibc.ibc_channel, Some(CHANNEL.to_string()));
+}
+
+#[test]
+fn unfunded_notification_is_an_attribute() {
+    let mut deps = setup_ibc(true);
+    let (poll_id, res) = end_voted_poll(&mut deps);
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        attr(&res, "ibc_notify_error"),
+        ContractError::InsufficientIbcFee {
+            denom: DENOM.to_string()
+        }
+        .to_string()
+    );
+    assert_eq!(status(&deps, poll_id), PollStatus::Passed);
+}
+
+#[test]
+fn failing_balance_query_is_an_attribute() {
+    let mut deps = setup_ibc(true);
+    deps.querier.set_bank_down();
+    let (poll_id, res) = end_voted_poll(&mut deps);
+    assert!(res.messages.is_empty());
+    assert!(attr(&res, "ibc_notify_error").contains("bank"));
+    assert_eq!(status(&deps, poll_id), PollStatus::Passed);
+}
+
+#[test]
+fn failed_packet_is_recorded_by_reply() {
+    let mut deps = setup_ibc(true);
+    let msg = Reply {
+        id: IBC_NOTIFY_REPLY_ID,
+        result: ContractResult::Err("channel closed".to_string()),
+    };
+    let res = reply(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(attr(&res, "action"), "ibc_notify_failed");
+    assert_eq!(attr(&res, "error"), "channel closed");
+
+    let msg = Reply {
+        id: 9,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let err = reply(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidReplyId {});
+}
+
+#[test]
+fn channel_must_be_unordered_notify_channel() {
+    let mut deps = setup_ibc(true);
+    let msg = mock_ibc_channel_open_init(CHANNEL, IbcOrder::Unordered, IBC_VERSION);
+    ibc_channel_open(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let msg = mock_ibc_channel_open_init(CHANNEL, IbcOrder::Ordered, IBC_VERSION);
+    let err = ibc_channel_open(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::IbcChannelOrder {});
+
+    let msg = mock_ibc_channel_open_try(CHANNEL, IbcOrder::Unordered, "ics20-1");
+    let err = ibc_channel_open(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::IbcChannelVersion {
+            expected: IBC_VERSION.to_string(),
+            version: "ics20-1".to_string(),
+        }
+    );
+
+    let msg = mock_ibc_channel_connect_ack(CHANNEL, IbcOrder::Unordered, IBC_VERSION);
+    let res = ibc_channel_connect(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(
+        res.attributes
+            .iter()
+            .find(|a| a.key == "channel_id")
+            .unwrap()
+            .value,
+        CHANNEL
+    );
+
+    // nothing comes back over the channel
+    let msg = mock_ibc_packet_recv(CHANNEL, &"hello").unwrap();
+    let err = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::IbcReceiveUnsupported {});
+}
+
+#[test]
+fn channel_id_is_validated() {
+    let invalid = ContractError::Std(StdError::generic_err("ibc_channel must be channel-N"));
+    let mut msg = init_msg();
+    msg.ibc_channel = Some("channel-x".to_string());
+    let mut deps = mock_deps();
+    let info = mock_info(ADMIN, &[]);
+    let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, invalid);
+
+    // update_config checks it too and keeps the old channel
+    let mut deps = setup_ibc(true);
+    for channel in ["channel-", "chan-7", "channel-7a"].iter() {
+        let msg = update_config_msg(&format!(r#"{{"ibc_channel":"{}"}}"#, channel));
+        let err = exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap_err();
+        assert_eq!(err, invalid);
+    }
+    let msg = update_config_msg(r#"{"ibc_channel":"channel-12"}"#);
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+    let ibc: IbcConfigResponse = query_as(&deps, QueryMsg::IbcConfig {});
+    assert_eq!(ibc.ibc_channel, Some("channel-12".to_string()));
+}
+
+#[test]
+fn ibc_config_reflects_instantiate() {
+    let deps = setup_ibc(true);
+    let ibc: IbcConfigResponse = query_as(&deps, QueryMsg::IbcConfig {});
+    assert_eq!(
+        ibc,
+        IbcConfigResponse {
+            ibc_channel: Some(CHANNEL.to_string()),
+            ibc_notify: true,
+            ibc_receiver: Some(RECEIVER.to_string()),
+            ibc_denom: Some(DENOM.to_string()),
+        }
+    );
+
+    // nothing configured means nothing is sent
+    let mut deps = setup();
+    mint(&mut deps, "1", 400);
+    let ibc: IbcConfigResponse = query_as(&deps, QueryMsg::IbcConfig {});
+    assert_eq!(
+        ibc,
+        IbcConfigResponse {
+            ibc_channel: None,
+            ibc_notify: false,
+            ibc_receiver: None,
+            ibc_denom: None,
+        }
+    );
+    let (_, res) = end_voted_poll(&mut deps);
+    assert!(res.messages.is_empty());
+}
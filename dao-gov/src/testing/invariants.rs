@@ -0,0 +1,157 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Uint128;
+
+use super::*;
+use crate::execute::assert_member_consistent;
+use crate::state::{
+    in_flight_read, in_flight_store, load_member, remove_poll, save_member, state_store,
+};
+use crate::utils::VerifyInvariantsResponse;
+
+fn inconsistent_reason(err: ContractError) -> String {
+    match err {
+        ContractError::InconsistentMember { member_id, reason } => {
+            assert_eq!(member_id, "member1");
+            reason
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+}
+
+#[test]
+fn hooks_keep_members_consistent() {
+    let mut deps = setup();
+    mint(&mut deps, "member1", 400);
+    let poll_id = create_poll(&mut deps, "member1", "first");
+    cast_vote(&mut deps, mock_env(), "member1", poll_id, VoteOption::Yes).unwrap();
+    assert_member_consistent(deps.as_ref().storage, "member1").unwrap();
+    // unknown members have nothing to check
+    assert_member_consistent(deps.as_ref().storage, "member2").unwrap();
+}
+
+#[test]
+fn share_not_matching_balance_is_caught() {
+    let mut deps = setup();
+    mint(&mut deps, "member1", 400);
+    let mut token_manager = load_member(deps.as_ref().storage, b"member1").unwrap();
+    token_manager.share = Uint128::from(21u128);
+    save_member(deps.as_mut().storage, b"member1", &token_manager).unwrap();
+
+    let err = assert_member_consistent(deps.as_ref().storage, "member1").unwrap_err();
+    assert_eq!(inconsistent_reason(err), "share doesn't match balance");
+
+    // the next hook of the member fails instead of building on the corrupt entry
+    let err = hook(&mut deps, mock_env(), "member1", create_poll_msg("second")).unwrap_err();
+    assert_eq!(inconsistent_reason(err), "share doesn't match balance");
+}
+
+#[test]
+fn lock_on_missing_poll_is_caught() {
+    let mut deps = setup();
+    mint(&mut deps, "member1", 400);
+    let poll_id = create_poll(&mut deps, "member1", "first");
+    cast_vote(&mut deps, mock_env(), "member1", poll_id, VoteOption::Yes).unwrap();
+    remove_poll(deps.as_mut().storage, poll_id);
+
+    let err = assert_member_consistent(deps.as_ref().storage, "member1").unwrap_err();
+    assert_eq!(
+        inconsistent_reason(err),
+        format!("lock on missing poll {}", poll_id)
+    );
+}
+
+/// sums member shares page by page, returns the last response and the number of pages
+fn verify_invariants(deps: &MockDeps, limit: u32) -> (VerifyInvariantsResponse, usize) {
+    let mut start_after = None;
+    let mut carried = None;
+    let mut pages = 0;
+    loop {
+        let msg = QueryMsg::VerifyInvariants {
+            start_after,
+            limit: Some(limit),
+            carried,
+        };
+        let res: VerifyInvariantsResponse = query_as(deps, msg);
+        pages += 1;
+        if res.next_cursor.is_none() {
+            return (res, pages);
+        }
+        assert_eq!(res.matches, None);
+        start_after = res.next_cursor;
+        carried = Some(res.computed);
+    }
+}
+
+#[test]
+fn total_share_matches_member_shares_over_pages() {
+    let mut deps = setup();
+    for (member_id, amount) in [("1", 100), ("2", 400), ("3", 900), ("4", 1600), ("5", 2500)].iter()
+    {
+        mint(&mut deps, member_id, *amount);
+    }
+    let msg = Cw721HookMsg::Withdraw {
+        amount: Uint128::from(300u128),
+    };
+    hook(&mut deps, mock_env(), "2", msg).unwrap();
+    hook(&mut deps, mock_env(), "3", Cw721HookMsg::Exit {}).unwrap();
+    mint(&mut deps, "1", 44);
+
+    // shares 12 + 10 + 40 + 50 over pages of two members
+    // a full page can't tell it is the last one, an empty page follows
+    let (res, pages) = verify_invariants(&deps, 2);
+    assert_eq!(pages, 3);
+    assert_eq!(res.matches, Some(true));
+    assert_eq!(res.computed, Uint128::from(112u128));
+    assert_eq!(res.stored, res.computed);
+
+    let mut state = state_store(&mut deps.storage).load().unwrap();
+    state.total_share += Uint128::from(1u128);
+    state_store(&mut deps.storage).save(&state).unwrap();
+    let (res, _) = verify_invariants(&deps, 3);
+    assert_eq!(res.matches, Some(false));
+    assert_eq!(res.computed, Uint128::from(112u128));
+}
+
+#[test]
+fn hook_of_member_in_flight_is_rejected() {
+    let mut deps = setup();
+    mint(&mut deps, "member1", 400);
+    in_flight_store(deps.as_mut().storage)
+        .save(b"member1", &1u8)
+        .unwrap();
+
+    let msg = create_poll_msg("blocked");
+    match hook(&mut deps, mock_env(), "member1", msg).unwrap_err() {
+        ContractError::MemberInFlight { member_id } => assert_eq!(member_id, "member1"),
+        err => panic!("unexpected error {:?}", err),
+    }
+    // other members are not held up
+    mint(&mut deps, "member2", 100);
+    create_poll(&mut deps, "member2", "other");
+}
+
+#[test]
+fn in_flight_is_cleared_after_hooks() {
+    let mut deps = setup();
+    mint(&mut deps, "member1", 400);
+    let poll_id = create_poll(&mut deps, "member1", "first");
+    assert!(in_flight_read(deps.as_ref().storage)
+        .may_load(b"member1")
+        .unwrap()
+        .is_none());
+
+    // a failing hook clears it as well
+    cast_vote(
+        &mut deps,
+        mock_env(),
+        "member1",
+        poll_id + 1,
+        VoteOption::Yes,
+    )
+    .unwrap_err();
+    assert!(in_flight_read(deps.as_ref().storage)
+        .may_load(b"member1")
+        .unwrap()
+        .is_none());
+    cast_vote(&mut deps, mock_env(), "member1", poll_id, VoteOption::Yes).unwrap();
+}
@@ -0,0 +1,111 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::StdError;
+
+use super::*;
+use crate::state::{load_poll, save_poll};
+use crate::utils::{validate_links, PollLink, PollResponse};
+
+fn link(label: &str, url: &str) -> PollLink {
+    PollLink {
+        label: label.to_string(),
+        url: url.to_string(),
+    }
+}
+
+/// https url of exactly len bytes
+fn url_of(len: usize) -> String {
+    format!("https://{}", "a".repeat(len - "https://".len()))
+}
+
+fn links_err(links: &[PollLink]) -> String {
+    match validate_links(links).unwrap_err() {
+        StdError::GenericErr { msg, .. } => msg,
+        err => panic!("unexpected error {:?}", err),
+    }
+}
+
+#[test]
+fn link_labels_are_limited() {
+    let url = url_of(20);
+    assert_eq!(links_err(&[link("", &url)]), "Link label too short");
+    validate_links(&[link(&"l".repeat(32), &url)]).unwrap();
+    assert_eq!(
+        links_err(&[link(&"l".repeat(33), &url)]),
+        "Link label too long"
+    );
+}
+
+#[test]
+fn link_urls_are_limited() {
+    assert_eq!(links_err(&[link("spec", &url_of(11))]), "Link too short");
+    validate_links(&[link("spec", &url_of(12))]).unwrap();
+    validate_links(&[link("spec", &url_of(256))]).unwrap();
+    assert_eq!(links_err(&[link("spec", &url_of(257))]), "Link too long");
+
+    validate_links(&[link("spec", "http://spec.example")]).unwrap();
+    validate_links(&[link("spec", "ipfs://bafyspec00")]).unwrap();
+    assert_eq!(
+        links_err(&[link("spec", "ftp://spec.example")]),
+        "Link must be http, https or ipfs"
+    );
+}
+
+#[test]
+fn at_most_five_links() {
+    let links: Vec<PollLink> = (0..6)
+        .map(|i| link(&format!("link {}", i), &url_of(20)))
+        .collect();
+    validate_links(&links[..5]).unwrap();
+    assert_eq!(links_err(&links), "Too many links");
+}
+
+fn poll(deps: &MockDeps, poll_id: u64) -> PollResponse {
+    query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    )
+}
+
+#[test]
+fn legacy_link_becomes_first_link() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let mut msg = create_poll_msg("linked");
+    if let Cw721HookMsg::CreatePoll { link, links, .. } = &mut msg {
+        *link = Some("https://forum.example/1".to_string());
+        *links = Some(vec![self::link("budget", "https://sheet.example/1")]);
+    }
+    let res = hook(&mut deps, mock_env(), "1", msg).unwrap();
+    let poll_id: u64 = attr(&res, "poll_id").parse().unwrap();
+
+    let a_poll = poll(&deps, poll_id);
+    assert_eq!(
+        a_poll.links,
+        vec![
+            link("link", "https://forum.example/1"),
+            link("budget", "https://sheet.example/1"),
+        ]
+    );
+    assert_eq!(a_poll.link, Some("https://forum.example/1".to_string()));
+}
+
+#[test]
+fn stored_poll_with_only_link_surfaces_it() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let poll_id = create_poll(&mut deps, "1", "legacy");
+    let mut stored = load_poll(&deps.storage, poll_id).unwrap();
+    stored.link = Some("https://forum.example/old".to_string());
+    stored.links = vec![];
+    save_poll(&mut deps.storage, &stored).unwrap();
+
+    let a_poll = poll(&deps, poll_id);
+    assert_eq!(
+        a_poll.links,
+        vec![link("link", "https://forum.example/old")]
+    );
+    assert_eq!(a_poll.link, Some("https://forum.example/old".to_string()));
+}
@@ -0,0 +1,76 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::state::load_member;
+
+// MAX_LOCKED_POLLS in execute
+const MAX_LOCKED_POLLS: usize = 50;
+
+/// member 1 voted on the first MAX_LOCKED_POLLS of the polls created by "c"
+fn setup_at_cap() -> (MockDeps, Vec<u64>) {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "c", 100);
+    let poll_ids: Vec<u64> = (0..=MAX_LOCKED_POLLS)
+        .map(|i| create_poll(&mut deps, "c", &format!("poll {}", i)))
+        .collect();
+    for poll_id in poll_ids.iter().take(MAX_LOCKED_POLLS) {
+        cast_vote(&mut deps, mock_env(), "1", *poll_id, VoteOption::Yes).unwrap();
+    }
+    (deps, poll_ids)
+}
+
+fn locked_polls(deps: &MockDeps, member_id: &str) -> usize {
+    load_member(&deps.storage, member_id.as_bytes())
+        .unwrap()
+        .locked_share
+        .len()
+}
+
+#[test]
+fn votes_stop_at_locked_poll_cap() {
+    let (mut deps, poll_ids) = setup_at_cap();
+    let last = poll_ids[MAX_LOCKED_POLLS];
+    let err = cast_vote(&mut deps, mock_env(), "1", last, VoteOption::Yes).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TooManyLockedPolls {
+            max: MAX_LOCKED_POLLS
+        }
+    );
+
+    // ending a poll frees its lock for the next vote
+    let env = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    let msg = ExecuteMsg::EndPoll {
+        poll_id: poll_ids[0],
+    };
+    exec_as(&mut deps, env, "ender0000", msg).unwrap();
+    cast_vote(&mut deps, mock_env(), "1", last, VoteOption::Yes).unwrap();
+    assert_eq!(locked_polls(&deps, "1"), MAX_LOCKED_POLLS);
+}
+
+#[test]
+fn delegator_at_cap_is_skipped() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: "2".to_string(),
+    };
+    hook(&mut deps, mock_env(), "1", msg).unwrap();
+    let poll_ids: Vec<u64> = (0..=MAX_LOCKED_POLLS)
+        .map(|i| create_poll(&mut deps, "2", &format!("poll {}", i)))
+        .collect();
+    for poll_id in poll_ids.iter().take(MAX_LOCKED_POLLS) {
+        cast_vote(&mut deps, mock_env(), "2", *poll_id, VoteOption::Yes).unwrap();
+    }
+    assert_eq!(locked_polls(&deps, "1"), MAX_LOCKED_POLLS);
+    // the delegate's own votes are withdrawn, the delegated ones stay
+    hook(&mut deps, mock_env(), "2", Cw721HookMsg::Rebind {}).unwrap();
+    assert_eq!(locked_polls(&deps, "2"), 0);
+
+    let last = poll_ids[MAX_LOCKED_POLLS];
+    let res = cast_vote(&mut deps, mock_env(), "2", last, VoteOption::Yes).unwrap();
+    assert_eq!(attr(&res, "skipped_lock_cap"), "1");
+    assert_eq!(attr(&res, "total_amount"), "10");
+}
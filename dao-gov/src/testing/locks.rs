@@ -0,0 +1,61 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{StdError, Storage};
+use cosmwasm_storage::to_length_prefixed;
+
+use super::*;
+use crate::state::{load_member, remove_poll};
+
+/// member1 voted yes on a poll, member2 keeps another share in the bank
+fn setup_locked() -> (MockDeps, u64) {
+    let mut deps = setup();
+    mint(&mut deps, "member1", 400);
+    mint(&mut deps, "member2", 100);
+    let poll_id = create_poll(&mut deps, "member2", "locked");
+    cast_vote(&mut deps, mock_env(), "member1", poll_id, VoteOption::Yes).unwrap();
+    (deps, poll_id)
+}
+
+fn is_parse_err(err: ContractError) -> bool {
+    matches!(err, ContractError::Std(StdError::ParseErr { .. }))
+}
+
+#[test]
+fn unreadable_poll_keeps_its_lock() {
+    let (mut deps, poll_id) = setup_locked();
+    let key = [
+        to_length_prefixed(b"poll").as_slice(),
+        &poll_id.to_be_bytes(),
+    ]
+    .concat();
+    deps.storage.set(&key, br#"{"v2":{}}"#);
+
+    let withdraw = Cw721HookMsg::Withdraw {
+        amount: Uint128::from(1u128),
+    };
+    let err = hook(&mut deps, mock_env(), "member1", withdraw).unwrap_err();
+    assert!(is_parse_err(err));
+    let err = hook(&mut deps, mock_env(), "member1", Cw721HookMsg::Exit {}).unwrap_err();
+    assert!(is_parse_err(err));
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: "member2".to_string(),
+    };
+    let err = hook(&mut deps, mock_env(), "member1", msg).unwrap_err();
+    assert!(is_parse_err(err));
+
+    let member = QueryMsg::Member {
+        member_id: "member1".to_string(),
+    };
+    query(deps.as_ref(), mock_env(), member).unwrap_err();
+    let token_manager = load_member(deps.as_ref().storage, b"member1").unwrap();
+    assert_eq!(token_manager.locked_share.len(), 1);
+}
+
+#[test]
+fn lock_of_missing_poll_is_released_as_orphan() {
+    let (mut deps, poll_id) = setup_locked();
+    remove_poll(deps.as_mut().storage, poll_id);
+
+    let res = hook(&mut deps, mock_env(), "member1", Cw721HookMsg::Exit {}).unwrap();
+    assert_eq!(attr(&res, "orphaned_polls"), poll_id.to_string());
+    assert_eq!(attr(&res, "removed"), "true");
+}
@@ -0,0 +1,98 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{MembersBatchResponse, MembersResponse, StakerResponse};
+
+fn members(deps: &MockDeps, start_after: Option<String>, limit: Option<u32>) -> MembersResponse {
+    query_as(deps, QueryMsg::Members { start_after, limit })
+}
+
+#[test]
+fn members_are_paginated() {
+    let mut deps = setup();
+    let member_ids: Vec<String> = (0..35).map(|i| format!("m{:02}", i)).collect();
+    for member_id in member_ids.iter() {
+        mint(&mut deps, member_id, 400);
+    }
+
+    let mut listed = vec![];
+    let mut start_after = None;
+    loop {
+        let page = members(&deps, start_after, Some(10)).members;
+        if page.is_empty() {
+            break;
+        }
+        assert!(page.len() <= 10);
+        for (_, balance, share) in page.iter() {
+            assert_eq!(*balance, Uint128::from(400u128));
+            assert_eq!(*share, Uint128::from(20u128));
+        }
+        start_after = page.last().map(|(member_id, _, _)| member_id.clone());
+        listed.extend(page.into_iter().map(|(member_id, _, _)| member_id));
+    }
+    assert_eq!(listed, member_ids);
+
+    // limit is capped at MAX_LIMIT
+    assert_eq!(members(&deps, None, Some(100)).members.len(), 30);
+    assert_eq!(members(&deps, None, None).members.len(), 10);
+}
+
+fn members_batch(
+    deps: &MockDeps,
+    member_ids: &[&str],
+) -> Result<MembersBatchResponse, ContractError> {
+    let msg = QueryMsg::MembersBatch {
+        member_ids: member_ids.iter().map(|id| id.to_string()).collect(),
+    };
+    Ok(from_binary(&query(deps.as_ref(), mock_env(), msg)?)?)
+}
+
+#[test]
+fn members_batch_keeps_input_order() {
+    let mut deps = setup();
+    mint(&mut deps, "plain", 100);
+    mint(&mut deps, "locked", 400);
+    let mut poll_ids = vec![];
+    for i in 0..21 {
+        let poll_id = create_poll(&mut deps, "plain", &format!("poll {}", i));
+        cast_vote(&mut deps, mock_env(), "locked", poll_id, VoteOption::Yes).unwrap();
+        poll_ids.push(poll_id);
+    }
+
+    let res = members_batch(&deps, &["locked", "unknown", "plain"]).unwrap();
+    let ids: Vec<&str> = res.members.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids, vec!["locked", "unknown", "plain"]);
+
+    // locks listed up to 20
+    let locked = res.members[0].1.as_ref().unwrap();
+    assert_eq!(locked.balance, Uint128::from(400u128));
+    let listed: Vec<u64> = locked.locked_balance.iter().map(|(id, _)| *id).collect();
+    assert_eq!(listed, poll_ids[..20].to_vec());
+    assert!(locked.locked_truncated);
+
+    assert!(res.members[1].1.is_none());
+    let plain = res.members[2].1.as_ref().unwrap();
+    assert_eq!(plain.share, Uint128::from(10u128));
+    assert!(plain.locked_balance.is_empty());
+    assert!(!plain.locked_truncated);
+
+    // the single member query lists every lock
+    let member: StakerResponse = query_as(
+        &deps,
+        QueryMsg::Member {
+            member_id: "locked".to_string(),
+        },
+    );
+    assert_eq!(member.locked_balance.len(), 21);
+    assert!(!member.locked_truncated);
+}
+
+#[test]
+fn members_batch_is_capped() {
+    let deps = setup();
+    let ids: Vec<String> = (0..31).map(|i| i.to_string()).collect();
+    let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+    members_batch(&deps, &ids[..30]).unwrap();
+    let err = members_batch(&deps, &ids).unwrap_err();
+    assert_eq!(err, ContractError::TooManyMembers(30));
+}
@@ -0,0 +1,124 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Decimal;
+
+use super::*;
+use crate::contract::migrate;
+use crate::msg::MigrateMsg;
+use crate::state::{load_member, load_poll, save_member, save_poll};
+use crate::utils::StorageVersionResponse;
+
+fn snapshot_page(deps: &mut MockDeps, start_after: Option<u64>) -> Response {
+    let msg = ExecuteMsg::SnapshotPollParameters {
+        start_after,
+        limit: 2,
+    };
+    exec_as(deps, mock_env(), "anyone", msg).unwrap()
+}
+
+fn recompute_page(deps: &mut MockDeps, start_after: Option<String>) -> Response {
+    let msg = ExecuteMsg::RecomputeDelegatedShare {
+        start_after,
+        limit: 1,
+    };
+    exec_as(deps, mock_env(), "anyone", msg).unwrap()
+}
+
+#[test]
+fn poll_parameters_are_snapshotted_in_pages() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    for title in ["first", "second", "third"].iter() {
+        let poll_id = create_poll(&mut deps, "1", title);
+        // as stored before snapshotting
+        let mut poll = load_poll(deps.as_ref().storage, poll_id).unwrap();
+        poll.quorum = None;
+        poll.threshold = None;
+        poll.threshold_inclusive = None;
+        save_poll(deps.as_mut().storage, &poll).unwrap();
+    }
+
+    // migrate itself leaves polls to the paged step
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(load_poll(deps.as_ref().storage, 1).unwrap().quorum, None);
+
+    let res = snapshot_page(&mut deps, None);
+    assert_eq!(attr(&res, "snapshotted_polls"), "2");
+    assert_eq!(attr(&res, "next_cursor"), "2");
+    let res = snapshot_page(&mut deps, Some(2));
+    assert_eq!(attr(&res, "snapshotted_polls"), "1");
+    assert_eq!(attr(&res, "next_cursor"), "");
+    for poll_id in 1..=3 {
+        let poll = load_poll(deps.as_ref().storage, poll_id).unwrap();
+        assert_eq!(poll.quorum, Some(Decimal::percent(10)));
+        assert_eq!(poll.threshold, Some(Decimal::percent(50)));
+        assert_eq!(poll.threshold_inclusive, Some(false));
+    }
+
+    // snapshotted polls are left alone
+    let res = snapshot_page(&mut deps, None);
+    assert_eq!(attr(&res, "snapshotted_polls"), "0");
+}
+
+#[test]
+fn delegated_share_is_recomputed_in_pages() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 400);
+    mint(&mut deps, "3", 900);
+    for delegator in ["2", "3"].iter() {
+        let msg = Cw721HookMsg::DelegateVote {
+            delegator: "1".to_string(),
+        };
+        hook(&mut deps, mock_env(), delegator, msg).unwrap();
+    }
+    assert_eq!(
+        load_member(deps.as_ref().storage, b"1")
+            .unwrap()
+            .delegated_in_share,
+        Uint128::from(50u128)
+    );
+
+    // as stored before delegated_in_share was maintained, with a stale delegated_from entry
+    let mut delegatee = load_member(deps.as_ref().storage, b"1").unwrap();
+    delegatee.delegated_in_share = Uint128::zero();
+    delegatee.delegated_from.push("4".to_string());
+    save_member(deps.as_mut().storage, b"1", &delegatee).unwrap();
+    mint(&mut deps, "4", 100);
+
+    let mut start_after = None;
+    let mut recomputed = 0;
+    for _ in 0..10 {
+        let res = recompute_page(&mut deps, start_after);
+        recomputed += attr(&res, "recomputed_members").parse::<u64>().unwrap();
+        let next_cursor = attr(&res, "next_cursor");
+        if next_cursor.is_empty() {
+            break;
+        }
+        start_after = Some(next_cursor);
+    }
+    assert_eq!(recomputed, 1);
+    // 4 is listed but does not delegate to 1
+    let delegatee = load_member(deps.as_ref().storage, b"1").unwrap();
+    assert_eq!(delegatee.delegated_in_share, Uint128::from(50u128));
+}
+
+#[test]
+fn storage_version_matches_contract_version() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 400);
+    create_poll(&mut deps, "1", "counted");
+
+    let res: StorageVersionResponse = query_as(&deps, QueryMsg::StorageVersion {});
+    assert_eq!(res.contract, "crates.io:dao-gov");
+    assert_eq!(res.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(res.poll_count, 1);
+    assert_eq!(res.stored_poll_count, 1);
+    assert_eq!(res.member_count, 2);
+    assert_eq!(res.total_share, Uint128::from(30u128));
+    assert_eq!(res.member_share_sum, res.total_share);
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    let migrated: StorageVersionResponse = query_as(&deps, QueryMsg::StorageVersion {});
+    assert_eq!(migrated, res);
+}
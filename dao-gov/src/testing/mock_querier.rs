@@ -0,0 +1,76 @@
+use cosmwasm_std::testing::MockQuerier;
+use cosmwasm_std::{
+    from_slice, to_binary, Coin, ContractResult, Decimal, Empty, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult, WasmQuery,
+};
+use cw721::{Cw721QueryMsg, NftInfoResponse};
+use dao_common::VotingExtension;
+use std::collections::HashMap;
+
+use super::NFT;
+
+/// answers NftInfo of the voting NFT contract with the multipliers set by tests
+/// and bank queries with the balances set by tests (or an error once bank_down)
+pub struct NftQuerier {
+    base: MockQuerier,
+    multipliers: HashMap<String, Decimal>,
+    bank_down: bool,
+}
+
+impl NftQuerier {
+    pub fn new() -> Self {
+        NftQuerier {
+            base: MockQuerier::new(&[]),
+            multipliers: HashMap::new(),
+            bank_down: false,
+        }
+    }
+
+    pub fn set_balance(&mut self, addr: &str, balance: Vec<Coin>) {
+        self.base.update_balance(addr, balance);
+    }
+
+    pub fn set_bank_down(&mut self) {
+        self.bank_down = true;
+    }
+
+    pub fn set_multiplier(&mut self, token_id: &str, multiplier: Decimal) {
+        self.multipliers.insert(token_id.to_string(), multiplier);
+    }
+
+    fn query_nft(&self, msg: &[u8]) -> QuerierResult {
+        let token_id = match from_slice(msg) {
+            Ok(Cw721QueryMsg::NftInfo { token_id }) => token_id,
+            _ => {
+                return SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "cw721 query".to_string(),
+                })
+            }
+        };
+        let response = NftInfoResponse {
+            token_uri: None,
+            extension: Some(VotingExtension {
+                multiplier: self.multipliers.get(&token_id).cloned(),
+            }),
+        };
+        SystemResult::Ok(ContractResult::from(to_binary(&response)))
+    }
+}
+
+impl Querier for NftQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        match from_slice(bin_request) {
+            Ok(QueryRequest::<Empty>::Wasm(WasmQuery::Smart { contract_addr, msg }))
+                if contract_addr == NFT =>
+            {
+                self.query_nft(msg.as_slice())
+            }
+            Ok(QueryRequest::<Empty>::Bank(_)) if self.bank_down => {
+                SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "bank".to_string(),
+                })
+            }
+            _ => self.base.raw_query(bin_request),
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! unit tests against mock dependencies
+//! members are registered by minting, hooks are sent as the cw721 contract would
+
+mod admin;
+mod batch;
+mod blocklist;
+mod bootstrap;
+mod choice;
+mod committee;
+mod conviction;
+mod cooldown;
+mod decay;
+mod delegation;
+mod dependency;
+mod deposit;
+mod description;
+mod drafts;
+mod eligible;
+mod end_poll;
+mod end_time;
+mod exit;
+mod extend;
+mod ibc;
+mod invariants;
+mod links;
+mod lock_cap;
+mod locks;
+mod members;
+mod migration;
+mod mock_querier;
+mod model;
+mod multiplier;
+mod paging;
+mod participation;
+mod poll_index;
+mod poll_type;
+mod quorum;
+mod rebind;
+mod receipts;
+mod responses;
+mod rounding;
+mod season;
+mod signed;
+mod stored;
+mod supply;
+mod suspension;
+mod text_limits;
+mod threshold;
+mod tie;
+mod top_delegates;
+mod transitions;
+mod update_config;
+mod validate_hook;
+mod vesting;
+mod vote_cap;
+mod vote_option;
+mod voters;
+mod wallet;
+mod withdrawable;
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Decimal, Env, OwnedDeps, Response, Uint128,
+};
+use cw721::Cw721ReceiveMsg;
+use serde::de::DeserializeOwned;
+
+use crate::contract::{execute, instantiate, query};
+use crate::msg::{Cw721HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::utils::VoteOption;
+use crate::ContractError;
+
+pub const ADMIN: &str = "admin0000";
+pub const NFT: &str = "nft0000";
+pub const VOTING_PERIOD: u64 = 100;
+
+pub use mock_querier::NftQuerier;
+
+pub type MockDeps = OwnedDeps<MockStorage, MockApi, NftQuerier>;
+
+pub fn mock_deps() -> MockDeps {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: NftQuerier::new(),
+    }
+}
+
+pub fn init_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        cw721_token: NFT.to_string(),
+        quorum: Decimal::percent(10),
+        threshold: Decimal::percent(50),
+        voting_period: VOTING_PERIOD,
+        share_rounding: None,
+        voting_model: None,
+        season_length: None,
+        season_blackout: None,
+        end_poll_reward: None,
+        admins: None,
+        admin_threshold: None,
+        decay_after: None,
+        decay_rate: None,
+        decay_floor: None,
+        bind_wallet: None,
+        text_limits: None,
+        draft_ttl: None,
+        threshold_inclusive: None,
+        failed_poll_cooldown: None,
+        signal_quorum: None,
+        signal_threshold: None,
+        max_extension: None,
+        ibc_channel: None,
+        ibc_notify: None,
+        ibc_receiver: None,
+        ibc_denom: None,
+        tie_policy: None,
+        auto_finalize: None,
+        max_supply: None,
+        text_deposit: None,
+        execute_deposit: None,
+        end_poll_permission: None,
+        adaptive_quorum: None,
+        vote_freeze_window: None,
+        freeze_new_votes: None,
+        voting_time: None,
+        use_token_multiplier: None,
+        max_multiplier: None,
+        bootstrap_period: None,
+        bootstrap_quorum: None,
+        delegate_inactivity_timeout: None,
+        max_vote_share: None,
+    }
+}
+
+pub fn setup_with(msg: InstantiateMsg) -> MockDeps {
+    let mut deps = mock_deps();
+    instantiate(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap();
+    deps
+}
+
+pub fn setup() -> MockDeps {
+    setup_with(init_msg())
+}
+
+pub fn env_at(height: u64) -> Env {
+    let mut env = mock_env();
+    env.block.height = height;
+    env
+}
+
+pub fn exec_as(
+    deps: &mut MockDeps,
+    env: Env,
+    sender: &str,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    execute(deps.as_mut(), env, mock_info(sender, &[]), msg)
+}
+
+/// UpdateConfig setting only the given fields, e.g. r#"{"quorum":"0.2"}"#
+pub fn update_config_msg(fields: &str) -> ExecuteMsg {
+    from_slice(format!(r#"{{"update_config":{}}}"#, fields).as_bytes()).unwrap()
+}
+
+pub fn mint(deps: &mut MockDeps, member_id: &str, amount: u128) {
+    let msg = ExecuteMsg::Mint {
+        recipient: member_id.to_string(),
+        amount: Uint128::from(amount),
+    };
+    exec_as(deps, mock_env(), ADMIN, msg).unwrap();
+}
+
+/// wallet holding the NFT of member_id in tests
+pub fn wallet_of(member_id: &str) -> String {
+    format!("wallet{}", member_id)
+}
+
+/// hook sent by cw721 on behalf of the holder of member_id
+pub fn hook(
+    deps: &mut MockDeps,
+    env: Env,
+    member_id: &str,
+    hook_msg: Cw721HookMsg,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+        sender: wallet_of(member_id),
+        token_id: member_id.to_string(),
+        msg: to_binary(&hook_msg).unwrap(),
+    });
+    exec_as(deps, env, NFT, msg)
+}
+
+pub fn create_poll_msg(title: &str) -> Cw721HookMsg {
+    Cw721HookMsg::CreatePoll {
+        title: title.to_string(),
+        description: format!("{} description", title),
+        summary: None,
+        link: None,
+        links: None,
+        draft: false,
+        options: None,
+        poll_type: Default::default(),
+        depends_on: None,
+    }
+}
+
+pub fn create_draft_msg(title: &str) -> Cw721HookMsg {
+    let mut msg = create_poll_msg(title);
+    if let Cw721HookMsg::CreatePoll { draft, .. } = &mut msg {
+        *draft = true;
+    }
+    msg
+}
+
+/// creates a poll at mock_env height, returns its id
+pub fn create_poll(deps: &mut MockDeps, member_id: &str, title: &str) -> u64 {
+    let res = hook(deps, mock_env(), member_id, create_poll_msg(title)).unwrap();
+    attr(&res, "poll_id").parse().unwrap()
+}
+
+pub fn cast_vote(
+    deps: &mut MockDeps,
+    env: Env,
+    member_id: &str,
+    poll_id: u64,
+    vote: VoteOption,
+) -> Result<Response, ContractError> {
+    let msg = Cw721HookMsg::CastVote {
+        poll_id,
+        vote,
+        conviction: None,
+    };
+    hook(deps, env, member_id, msg)
+}
+
+pub fn query_at<T: DeserializeOwned>(deps: &MockDeps, env: Env, msg: QueryMsg) -> T {
+    from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap()
+}
+
+pub fn query_as<T: DeserializeOwned>(deps: &MockDeps, msg: QueryMsg) -> T {
+    query_at(deps, mock_env(), msg)
+}
+
+/// value of the first attribute named key
+pub fn attr(res: &Response, key: &str) -> String {
+    res.attributes
+        .iter()
+        .find(|attr| attr.key == key)
+        .unwrap_or_else(|| panic!("missing attribute {}", key))
+        .value
+        .clone()
+}
@@ -0,0 +1,505 @@
+// model-based test of the mint/burn/vote/delegate state machine
+// random operation sequences run against the contract and a reference model,
+// tallies, total_share, member shares, delegations and locks must match after every step
+// cases default to 256, override with PROPTEST_CASES
+use std::collections::BTreeMap;
+
+use cosmwasm_std::testing::{mock_env, MockStorage};
+use cosmwasm_std::{Isqrt, Order, Storage};
+use proptest::prelude::*;
+
+use super::*;
+use crate::state::{may_load_member, may_load_poll, poll_voter_read, state_read, State};
+use crate::utils::{PollStatus, VoteOption};
+
+const MEMBERS: usize = 4;
+const POLLS: u64 = 6;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Mint {
+        member: usize,
+        amount: u128,
+    },
+    Withdraw {
+        member: usize,
+        amount: u128,
+    },
+    Exit {
+        member: usize,
+    },
+    CreatePoll {
+        member: usize,
+    },
+    CastVote {
+        member: usize,
+        poll_id: u64,
+        yes: bool,
+    },
+    CancelVote {
+        member: usize,
+        poll_id: u64,
+    },
+    Delegate {
+        member: usize,
+        to: usize,
+    },
+    Undelegate {
+        member: usize,
+    },
+    EndPoll {
+        poll_id: u64,
+    },
+    Advance {
+        blocks: u64,
+    },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let member = 0..MEMBERS;
+    let poll_id = 1..=POLLS;
+    prop_oneof![
+        3 => (member.clone(), 1..2_000u128).prop_map(|(member, amount)| Op::Mint { member, amount }),
+        1 => (member.clone(), 1..2_000u128)
+            .prop_map(|(member, amount)| Op::Withdraw { member, amount }),
+        1 => member.clone().prop_map(|member| Op::Exit { member }),
+        2 => member.clone().prop_map(|member| Op::CreatePoll { member }),
+        4 => (member.clone(), poll_id.clone(), any::<bool>())
+            .prop_map(|(member, poll_id, yes)| Op::CastVote { member, poll_id, yes }),
+        1 => (member.clone(), poll_id.clone())
+            .prop_map(|(member, poll_id)| Op::CancelVote { member, poll_id }),
+        2 => (member.clone(), member.clone()).prop_map(|(member, to)| Op::Delegate { member, to }),
+        1 => member.prop_map(|member| Op::Undelegate { member }),
+        1 => poll_id.prop_map(|poll_id| Op::EndPoll { poll_id }),
+        2 => (1..=60u64).prop_map(|blocks| Op::Advance { blocks }),
+    ]
+}
+
+fn member_id(member: usize) -> String {
+    (member + 1).to_string()
+}
+
+#[derive(Clone, Debug, Default)]
+struct ModelMember {
+    exists: bool,
+    balance: u128,
+    delegate_to: Option<usize>,
+    delegated_from: Vec<usize>,
+}
+
+impl ModelMember {
+    // default voting model: floor(sqrt(balance))
+    fn share(&self) -> u128 {
+        Uint128::from(self.balance).isqrt().u128()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct ModelVote {
+    yes: bool,
+    weight: u128,
+    via: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+struct ModelPoll {
+    end_height: u64,
+    passed: Option<bool>, // None while in progress
+    total_share_at_start: u128,
+    votes: BTreeMap<usize, ModelVote>,
+}
+
+impl ModelPoll {
+    fn voting_open(&self, height: u64) -> bool {
+        self.passed.is_none() && height <= self.end_height
+    }
+
+    fn tally(&self, yes: bool) -> u128 {
+        self.votes
+            .values()
+            .filter(|vote| vote.yes == yes)
+            .map(|vote| vote.weight)
+            .sum()
+    }
+}
+
+/// reference model, each operation returns whether the contract must accept it
+#[derive(Clone, Debug)]
+struct Model {
+    height: u64,
+    members: Vec<ModelMember>,
+    polls: Vec<ModelPoll>,
+}
+
+impl Model {
+    fn new() -> Self {
+        Model {
+            height: mock_env().block.height,
+            members: vec![ModelMember::default(); MEMBERS],
+            polls: vec![],
+        }
+    }
+
+    fn total_share(&self) -> u128 {
+        self.members.iter().map(|member| member.share()).sum()
+    }
+
+    fn poll_mut(&mut self, poll_id: u64) -> Option<&mut ModelPoll> {
+        self.polls.get_mut(poll_id as usize - 1)
+    }
+
+    /// largest vote of member in polls not ended yet (0 without any)
+    fn locked(&self, member: usize) -> Option<u128> {
+        self.polls
+            .iter()
+            .filter(|poll| poll.passed.is_none())
+            .filter_map(|poll| poll.votes.get(&member).map(|vote| vote.weight))
+            .max()
+    }
+
+    /// withdraw votes cast for member by its delegate from polls still open
+    fn release_delegation(&mut self, member: usize) {
+        let delegate = self.members[member].delegate_to.take();
+        let height = self.height;
+        for poll in self
+            .polls
+            .iter_mut()
+            .filter(|poll| poll.voting_open(height))
+        {
+            if poll
+                .votes
+                .get(&member)
+                .map_or(false, |vote| vote.via == delegate)
+            {
+                poll.votes.remove(&member);
+            }
+        }
+        if let Some(delegate) = delegate {
+            self.members[delegate]
+                .delegated_from
+                .retain(|id| *id != member);
+        }
+    }
+
+    fn apply(&mut self, op: &Op) -> bool {
+        let height = self.height;
+        match *op {
+            Op::Mint { member, amount } => {
+                self.members[member].exists = true;
+                self.members[member].balance += amount;
+                true
+            }
+            Op::Withdraw { member, amount } => {
+                let locked = self.locked(member).unwrap_or_default();
+                let m = &mut self.members[member];
+                if !m.exists || amount >= m.balance || locked * locked + amount > m.balance {
+                    return false;
+                }
+                m.balance -= amount;
+                true
+            }
+            Op::Exit { member } => {
+                let locked = self.locked(member);
+                let m = &self.members[member];
+                if !m.exists || m.delegate_to.is_some() || locked.unwrap_or_default() > 0 {
+                    return false;
+                }
+                self.members[member].balance = 0;
+                // entry stays while it holds a (zero) vote in a poll not ended
+                if locked.is_none() {
+                    for delegator in self.members[member].delegated_from.clone() {
+                        self.release_delegation(delegator);
+                    }
+                    self.members[member] = ModelMember::default();
+                }
+                true
+            }
+            Op::CreatePoll { .. } => {
+                let total_share_at_start = self.total_share();
+                self.polls.push(ModelPoll {
+                    end_height: height + VOTING_PERIOD,
+                    passed: None,
+                    total_share_at_start,
+                    votes: BTreeMap::new(),
+                });
+                true
+            }
+            Op::CastVote {
+                member,
+                poll_id,
+                yes,
+            } => {
+                let members = self.members.clone();
+                let poll = match self.poll_mut(poll_id) {
+                    Some(poll) if poll.voting_open(height) => poll,
+                    _ => return false,
+                };
+                let voter = &members[member];
+                if poll.votes.contains_key(&member) || voter.delegate_to.is_some() {
+                    return false;
+                }
+                let vote = ModelVote {
+                    yes,
+                    weight: voter.share(),
+                    via: None,
+                };
+                poll.votes.insert(member, vote);
+                for delegator in voter.delegated_from.iter() {
+                    poll.votes.entry(*delegator).or_insert(ModelVote {
+                        yes,
+                        weight: members[*delegator].share(),
+                        via: Some(member),
+                    });
+                }
+                self.members[member].exists = true;
+                true
+            }
+            Op::CancelVote { member, poll_id } => {
+                let poll = match self.poll_mut(poll_id) {
+                    Some(poll) if poll.voting_open(height) => poll,
+                    _ => return false,
+                };
+                if poll.votes.remove(&member).is_none() {
+                    return false;
+                }
+                // votes cast for delegators go with the delegate's vote
+                poll.votes.retain(|_, vote| vote.via != Some(member));
+                true
+            }
+            Op::Delegate { member, to } => {
+                let m = &self.members[member];
+                let delegatee = &self.members[to];
+                if self.locked(member).is_some()
+                    || m.delegate_to.is_some()
+                    || !m.delegated_from.is_empty()
+                    || !delegatee.exists
+                    || delegatee.balance == 0
+                    || member == to
+                    || delegatee.delegate_to.is_some()
+                {
+                    return false;
+                }
+                self.members[member].exists = true;
+                self.members[member].delegate_to = Some(to);
+                self.members[to].delegated_from.push(member);
+                true
+            }
+            Op::Undelegate { member } => {
+                if self.members[member].delegate_to.is_none() {
+                    return false;
+                }
+                self.release_delegation(member);
+                true
+            }
+            Op::EndPoll { poll_id } => {
+                let total_share = self.total_share();
+                let poll = match self.poll_mut(poll_id) {
+                    Some(poll) if poll.passed.is_none() && poll.end_height <= height => poll,
+                    _ => return false,
+                };
+                let yes = poll.tally(true);
+                let tallied = yes + poll.tally(false);
+                let staked = poll.total_share_at_start.max(total_share);
+                // quorum 10%, threshold 50% (exclusive)
+                let quorum = tallied > 0 && tallied * 10 >= staked;
+                poll.passed = Some(quorum && yes * 2 > tallied);
+                true
+            }
+            Op::Advance { blocks } => {
+                self.height += blocks;
+                true
+            }
+        }
+    }
+}
+
+fn execute_op(deps: &mut MockDeps, height: u64, op: &Op) -> Result<Response, ContractError> {
+    let env = env_at(height);
+    match *op {
+        Op::Mint { member, amount } => {
+            let msg = ExecuteMsg::Mint {
+                recipient: member_id(member),
+                amount: Uint128::from(amount),
+            };
+            exec_as(deps, env, ADMIN, msg)
+        }
+        Op::Withdraw { member, amount } => {
+            let msg = Cw721HookMsg::Withdraw {
+                amount: Uint128::from(amount),
+            };
+            hook(deps, env, &member_id(member), msg)
+        }
+        Op::Exit { member } => hook(deps, env, &member_id(member), Cw721HookMsg::Exit {}),
+        Op::CreatePoll { member } => hook(deps, env, &member_id(member), create_poll_msg("model")),
+        Op::CastVote {
+            member,
+            poll_id,
+            yes,
+        } => {
+            let vote = if yes { VoteOption::Yes } else { VoteOption::No };
+            cast_vote(deps, env, &member_id(member), poll_id, vote)
+        }
+        Op::CancelVote { member, poll_id } => {
+            let msg = Cw721HookMsg::CancelVote { poll_id };
+            hook(deps, env, &member_id(member), msg)
+        }
+        Op::Delegate { member, to } => {
+            let msg = Cw721HookMsg::DelegateVote {
+                delegator: member_id(to),
+            };
+            hook(deps, env, &member_id(member), msg)
+        }
+        Op::Undelegate { member } => hook(
+            deps,
+            env,
+            &member_id(member),
+            Cw721HookMsg::UnDelegateVote {},
+        ),
+        Op::EndPoll { poll_id } => exec_as(deps, env, "ender0000", ExecuteMsg::EndPoll { poll_id }),
+        Op::Advance { .. } => Ok(Response::new()),
+    }
+}
+
+/// failed transactions leave no state behind, mock storage is restored by hand
+fn execute_atomic(deps: &mut MockDeps, height: u64, op: &Op) -> Result<Response, ContractError> {
+    let snapshot: Vec<(Vec<u8>, Vec<u8>)> =
+        deps.storage.range(None, None, Order::Ascending).collect();
+    let result = execute_op(deps, height, op);
+    if result.is_err() {
+        let mut storage = MockStorage::new();
+        for (key, value) in snapshot.iter() {
+            storage.set(key, value);
+        }
+        deps.storage = storage;
+    }
+    result
+}
+
+fn assert_matches(deps: &MockDeps, model: &Model) -> Result<(), TestCaseError> {
+    let storage = &deps.storage;
+    let state: State = state_read(storage).load().unwrap();
+    prop_assert_eq!(state.total_share.u128(), model.total_share());
+
+    for (member, expected) in model.members.iter().enumerate() {
+        let id = member_id(member);
+        let token_manager = match may_load_member(storage, id.as_bytes()).unwrap() {
+            Some(token_manager) => token_manager,
+            None => {
+                prop_assert!(!expected.exists, "member {} is missing", id);
+                continue;
+            }
+        };
+        prop_assert!(expected.exists, "member {} wasn't removed", id);
+        prop_assert_eq!(token_manager.balance.u128(), expected.balance);
+        prop_assert_eq!(token_manager.share.u128(), expected.share());
+        prop_assert_eq!(
+            token_manager.delegate_to,
+            expected.delegate_to.map(member_id)
+        );
+        let mut delegated_from = token_manager.delegated_from.clone();
+        delegated_from.sort();
+        let mut expected_from: Vec<String> = expected
+            .delegated_from
+            .iter()
+            .map(|id| member_id(*id))
+            .collect();
+        expected_from.sort();
+        prop_assert_eq!(delegated_from, expected_from);
+        let delegated_in_share: u128 = expected
+            .delegated_from
+            .iter()
+            .map(|id| model.members[*id].share())
+            .sum();
+        prop_assert_eq!(token_manager.delegated_in_share.u128(), delegated_in_share);
+
+        // locks held in polls not ended
+        let mut locks: Vec<(u64, u128)> = token_manager
+            .locked_share
+            .iter()
+            .filter(|(poll_id, _)| model.polls[*poll_id as usize - 1].passed.is_none())
+            .map(|(poll_id, vote_info)| (*poll_id, vote_info.balance.u128()))
+            .collect();
+        locks.sort_unstable();
+        let expected_locks: Vec<(u64, u128)> = model
+            .polls
+            .iter()
+            .enumerate()
+            .filter(|(_, poll)| poll.passed.is_none())
+            .filter_map(|(index, poll)| {
+                poll.votes
+                    .get(&member)
+                    .map(|vote| (index as u64 + 1, vote.weight))
+            })
+            .collect();
+        prop_assert_eq!(locks, expected_locks, "locks of member {}", id);
+    }
+
+    for (index, expected) in model.polls.iter().enumerate() {
+        let poll_id = index as u64 + 1;
+        let a_poll = may_load_poll(storage, poll_id).unwrap().unwrap();
+        prop_assert_eq!(
+            a_poll.yes_votes.u128(),
+            expected.tally(true),
+            "yes of poll {}",
+            poll_id
+        );
+        prop_assert_eq!(
+            a_poll.no_votes.u128(),
+            expected.tally(false),
+            "no of poll {}",
+            poll_id
+        );
+        let status = match expected.passed {
+            None => PollStatus::InProgress,
+            Some(true) => PollStatus::Passed,
+            Some(false) => PollStatus::Rejected,
+        };
+        prop_assert_eq!(&a_poll.status, &status, "status of poll {}", poll_id);
+        if expected.passed.is_some() {
+            continue;
+        }
+        for member in 0..MEMBERS {
+            let vote = poll_voter_read(storage, poll_id)
+                .may_load(member_id(member).as_bytes())
+                .unwrap()
+                .map(|vote_info| ModelVote {
+                    yes: vote_info.vote == VoteOption::Yes,
+                    weight: vote_info.weight().u128(),
+                    via: vote_info
+                        .via_delegate
+                        .map(|id| id.parse::<usize>().unwrap() - 1),
+                });
+            prop_assert_eq!(
+                vote.as_ref(),
+                expected.votes.get(&member),
+                "vote of member {} in poll {}",
+                member + 1,
+                poll_id
+            );
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn contract_follows_model(ops in proptest::collection::vec(op(), 1..60)) {
+        let mut deps = setup();
+        let mut model = Model::new();
+        for op in ops.iter() {
+            let expected = model.clone().apply(op);
+            let result = execute_atomic(&mut deps, model.height, op);
+            prop_assert_eq!(
+                result.is_ok(),
+                expected,
+                "{:?} returned {:?}",
+                op,
+                result.err()
+            );
+            if expected {
+                model.apply(op);
+            }
+            assert_matches(&deps, &model)?;
+        }
+    }
+}
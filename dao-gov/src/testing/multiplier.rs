@@ -0,0 +1,129 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Decimal;
+
+use super::*;
+use crate::utils::{PollResponse, PollStatus, VoteOption};
+
+fn poll(deps: &MockDeps, poll_id: u64) -> PollResponse {
+    query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    )
+}
+
+#[test]
+fn auto_finalize_leaves_room_for_larger_multipliers() {
+    let mut msg = init_msg();
+    msg.use_token_multiplier = Some(true);
+    msg.max_multiplier = Some(Decimal::percent(500));
+    msg.auto_finalize = Some(true);
+    let mut deps = setup_with(msg);
+    deps.querier.set_multiplier("1", Decimal::percent(200));
+    deps.querier.set_multiplier("2", Decimal::percent(500));
+
+    // shares 20 and 10
+    mint(&mut deps, "1", 400);
+    mint(&mut deps, "2", 100);
+    let poll_id = create_poll(&mut deps, "1", "weighted");
+
+    // 40 yes would decide the poll if the 10 share left were unweighted
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    let a_poll = poll(&deps, poll_id);
+    assert_eq!(a_poll.status, PollStatus::InProgress);
+    assert_eq!(a_poll.yes_votes, Uint128::from(40u128));
+
+    let res = cast_vote(&mut deps, mock_env(), "2", poll_id, VoteOption::No).unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .all(|attr| attr.key != "auto_finalized"));
+    let a_poll = poll(&deps, poll_id);
+    assert_eq!(a_poll.no_votes, Uint128::from(50u128));
+
+    let end = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    exec_as(&mut deps, end, ADMIN, ExecuteMsg::EndPoll { poll_id }).unwrap();
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Rejected);
+}
+
+#[test]
+fn auto_finalize_without_multiplier_is_unchanged() {
+    let mut msg = init_msg();
+    msg.auto_finalize = Some(true);
+    // 20 yes against 21 share left and up to 42 conviction weight
+    msg.threshold = Decimal::percent(20);
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 400);
+    mint(&mut deps, "2", 1);
+    let poll_id = create_poll(&mut deps, "1", "unweighted");
+
+    let res = cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    assert_eq!(attr(&res, "auto_finalized"), "true");
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Passed);
+}
+
+#[test]
+fn auto_finalize_leaves_room_for_conviction() {
+    let mut msg = init_msg();
+    msg.auto_finalize = Some(true);
+    msg.threshold = Decimal::percent(30);
+    let mut deps = setup_with(msg);
+    // shares 20 and 25
+    mint(&mut deps, "1", 400);
+    mint(&mut deps, "2", 625);
+    let poll_id = create_poll(&mut deps, "1", "conviction");
+
+    // 20 yes would decide the poll if only the 25 share left could vote no
+    let res = cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .all(|attr| attr.key != "auto_finalized"));
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::InProgress);
+
+    // burning the whole balance weighs twice the share
+    let msg = Cw721HookMsg::CastVote {
+        poll_id,
+        vote: VoteOption::No,
+        conviction: Some(Uint128::from(625u128)),
+    };
+    hook(&mut deps, mock_env(), "2", msg).unwrap();
+    assert_eq!(poll(&deps, poll_id).no_votes, Uint128::from(50u128));
+
+    let end = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    exec_as(&mut deps, end, ADMIN, ExecuteMsg::EndPoll { poll_id }).unwrap();
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Rejected);
+}
+
+#[test]
+fn votes_are_weighted_by_each_token() {
+    let mut msg = init_msg();
+    msg.use_token_multiplier = Some(true);
+    msg.max_multiplier = Some(Decimal::percent(500));
+    let mut deps = setup_with(msg);
+    deps.querier.set_multiplier("1", Decimal::percent(150));
+    deps.querier.set_multiplier("2", Decimal::percent(250));
+    deps.querier.set_multiplier("4", Decimal::percent(800));
+
+    // shares 20, 30, 10 and 10
+    mint(&mut deps, "1", 400);
+    mint(&mut deps, "2", 900);
+    mint(&mut deps, "3", 100);
+    mint(&mut deps, "4", 100);
+    let poll_id = create_poll(&mut deps, "1", "weighted");
+
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    cast_vote(&mut deps, mock_env(), "2", poll_id, VoteOption::No).unwrap();
+    let a_poll = poll(&deps, poll_id);
+    assert_eq!(a_poll.yes_votes, Uint128::from(30u128));
+    assert_eq!(a_poll.no_votes, Uint128::from(75u128));
+
+    // no multiplier counts once, one above max_multiplier is capped
+    cast_vote(&mut deps, mock_env(), "3", poll_id, VoteOption::Yes).unwrap();
+    cast_vote(&mut deps, mock_env(), "4", poll_id, VoteOption::No).unwrap();
+    let a_poll = poll(&deps, poll_id);
+    assert_eq!(a_poll.yes_votes, Uint128::from(40u128));
+    assert_eq!(a_poll.no_votes, Uint128::from(125u128));
+}
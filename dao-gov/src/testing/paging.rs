@@ -0,0 +1,159 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{OrderBy, PollStatus, PollsResponse};
+
+/// ids of every page of in progress Polls at limit, following next_cursor
+fn paged_ids(deps: &MockDeps, limit: u32, order_by: OrderBy) -> Vec<Vec<u64>> {
+    filtered_pages(deps, PollStatus::InProgress, limit, order_by)
+}
+
+/// ids of every page of Polls with status at limit, following next_cursor
+fn filtered_pages(
+    deps: &MockDeps,
+    status: PollStatus,
+    limit: u32,
+    order_by: OrderBy,
+) -> Vec<Vec<u64>> {
+    let mut pages: Vec<Vec<u64>> = vec![];
+    let mut start_after = None;
+    loop {
+        let res: PollsResponse = query_as(
+            deps,
+            QueryMsg::Polls {
+                filter: Some(status.clone()),
+                start_after,
+                limit: Some(limit),
+                order_by: Some(order_by.clone()),
+                include_drafts: None,
+                member_id: None,
+                poll_type: None,
+                viewer: None,
+            },
+        );
+        pages.push(res.polls.iter().map(|poll| poll.id).collect());
+        start_after = res.next_cursor;
+        if start_after.is_none() {
+            return pages;
+        }
+    }
+}
+
+fn setup_polls() -> MockDeps {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    for title in ["first", "second", "third"].iter() {
+        create_poll(&mut deps, "1", title);
+    }
+    deps
+}
+
+#[test]
+fn zero_limit_pages_one_poll_at_a_time() {
+    let deps = setup_polls();
+    assert_eq!(
+        paged_ids(&deps, 0, OrderBy::Desc),
+        vec![vec![3], vec![2], vec![1]]
+    );
+    assert_eq!(
+        paged_ids(&deps, 0, OrderBy::Asc),
+        vec![vec![1], vec![2], vec![3]]
+    );
+    assert_eq!(paged_ids(&deps, 2, OrderBy::Asc), vec![vec![1, 2], vec![3]]);
+}
+
+#[test]
+fn zero_limit_pages_eligible_polls() {
+    let deps = setup_polls();
+    let res: PollsResponse = query_as(
+        &deps,
+        QueryMsg::EligiblePolls {
+            member_id: "1".to_string(),
+            start_after: None,
+            limit: Some(0),
+        },
+    );
+    assert_eq!(res.polls.len(), 1);
+    assert_eq!(res.next_cursor, Some(1));
+}
+
+#[test]
+fn filtered_pages_cover_each_status_once() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let poll_ids: Vec<u64> = (0..50)
+        .map(|i| create_poll(&mut deps, "1", &format!("poll {}", i)))
+        .collect();
+    // every third poll ends rejected (nobody voted)
+    let end = env_at(mock_env().block.height + VOTING_PERIOD);
+    let mut rejected = vec![];
+    for poll_id in poll_ids.iter().filter(|poll_id| *poll_id % 3 == 0) {
+        let msg = ExecuteMsg::EndPoll { poll_id: *poll_id };
+        exec_as(&mut deps, end.clone(), ADMIN, msg).unwrap();
+        rejected.push(*poll_id);
+    }
+    let in_progress: Vec<u64> = poll_ids
+        .iter()
+        .filter(|poll_id| !rejected.contains(poll_id))
+        .cloned()
+        .collect();
+
+    for (status, expected) in [
+        (PollStatus::InProgress, in_progress),
+        (PollStatus::Rejected, rejected),
+    ]
+    .iter()
+    {
+        for limit in [1, 7, 10, 30].iter() {
+            let forward = filtered_pages(&deps, status.clone(), *limit, OrderBy::Asc);
+            let backward = filtered_pages(&deps, status.clone(), *limit, OrderBy::Desc);
+            for pages in [&forward, &backward].iter() {
+                assert!(pages.iter().all(|page| page.len() <= *limit as usize));
+                assert!(pages[..pages.len() - 1]
+                    .iter()
+                    .all(|page| page.len() == *limit as usize));
+            }
+
+            // no duplicates or gaps, in order both ways
+            let forward: Vec<u64> = forward.concat();
+            assert_eq!(&forward, expected);
+            let mut backward: Vec<u64> = backward.concat();
+            backward.reverse();
+            assert_eq!(&backward, expected);
+        }
+    }
+}
+
+#[test]
+fn sparse_filter_reads_are_bounded() {
+    // polls 1 to 3 are published, drafts 4 to 313 are invisible to others
+    let mut deps = setup_polls();
+    for i in 0..310 {
+        let msg = create_draft_msg(&format!("draft {}", i));
+        hook(&mut deps, mock_env(), "1", msg).unwrap();
+    }
+    let page = |start_after: Option<u64>| -> PollsResponse {
+        query_as(
+            &deps,
+            QueryMsg::Polls {
+                filter: None,
+                start_after,
+                limit: Some(10),
+                order_by: Some(OrderBy::Desc),
+                include_drafts: None,
+                member_id: None,
+                poll_type: None,
+                viewer: None,
+            },
+        )
+    };
+
+    // drafts 313 down to 14 are examined, empty page resuming after the last of them
+    let first = page(None);
+    assert!(first.polls.is_empty());
+    assert_eq!(first.next_cursor, Some(14));
+    let second = page(first.next_cursor);
+    let ids: Vec<u64> = second.polls.iter().map(|poll| poll.id).collect();
+    assert_eq!(ids, vec![3, 2, 1]);
+    assert_eq!(second.next_cursor, None);
+}
@@ -0,0 +1,60 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Decimal;
+
+use super::*;
+use crate::utils::{PollResponse, PollStatus};
+
+fn end_and_load(deps: &mut MockDeps, poll_id: u64) -> PollResponse {
+    let env = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    exec_as(deps, env, "ender0000", ExecuteMsg::EndPoll { poll_id }).unwrap();
+    query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    )
+}
+
+#[test]
+fn participation_above_staked_share_is_clamped() {
+    let mut deps = setup();
+    // total_share at start is 10
+    mint(&mut deps, "a", 100);
+    let poll_id = create_poll(&mut deps, "a", "anomaly");
+
+    // "v" joins and votes with share 20 and conviction weight 2 * isqrt(500) = 44
+    mint(&mut deps, "v", 900);
+    let msg = Cw721HookMsg::CastVote {
+        poll_id,
+        vote: VoteOption::Yes,
+        conviction: Some(Uint128::from(500u128)),
+    };
+    hook(&mut deps, mock_env(), "v", msg).unwrap();
+    // "b" joins, doesn't vote and exits after the vote
+    mint(&mut deps, "b", 400);
+    hook(&mut deps, mock_env(), "b", Cw721HookMsg::Exit {}).unwrap();
+
+    let a_poll = end_and_load(&mut deps, poll_id);
+    assert_eq!(a_poll.yes_votes, Uint128::from(64u128));
+    assert_eq!(a_poll.total_share_at_end_poll, Some(Uint128::from(30u128)));
+    assert_eq!(a_poll.raw_quorum, Some(Decimal::from_ratio(64u128, 30u128)));
+    assert_eq!(a_poll.quorum_used, Some(Decimal::one()));
+    assert!(a_poll.participation_anomaly);
+    assert_eq!(a_poll.status, PollStatus::Passed);
+}
+
+#[test]
+fn regular_participation_is_not_flagged() {
+    let mut deps = setup();
+    mint(&mut deps, "a", 100);
+    mint(&mut deps, "v", 900);
+    let poll_id = create_poll(&mut deps, "a", "regular");
+    cast_vote(&mut deps, mock_env(), "v", poll_id, VoteOption::Yes).unwrap();
+
+    let a_poll = end_and_load(&mut deps, poll_id);
+    let participation = Decimal::from_ratio(30u128, 40u128);
+    assert_eq!(a_poll.raw_quorum, Some(participation));
+    assert_eq!(a_poll.quorum_used, Some(participation));
+    assert!(!a_poll.participation_anomaly);
+}
@@ -0,0 +1,114 @@
+use super::*;
+use crate::state::poll_indexer_store;
+use crate::utils::{IndexConsistencyResponse, OrderBy, PollStatus, PollsResponse};
+
+fn in_progress_ids(deps: &MockDeps) -> Vec<u64> {
+    let res: PollsResponse = query_as(
+        deps,
+        QueryMsg::Polls {
+            filter: Some(PollStatus::InProgress),
+            start_after: None,
+            limit: None,
+            order_by: Some(OrderBy::Asc),
+            include_drafts: None,
+            member_id: None,
+            poll_type: None,
+            viewer: None,
+        },
+    );
+    res.polls.iter().map(|poll| poll.id).collect()
+}
+
+fn consistency(deps: &MockDeps, start_after: Option<u64>) -> IndexConsistencyResponse {
+    query_as(
+        deps,
+        QueryMsg::IndexConsistency {
+            start_after,
+            limit: Some(2),
+        },
+    )
+}
+
+fn rebuild(deps: &mut MockDeps, start_after: Option<u64>) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::RebuildPollIndex {
+        start_after,
+        limit: 2,
+    };
+    exec_as(deps, mock_env(), ADMIN, msg)
+}
+
+/// five polls in progress, indexers of polls 2, 4 and 5 drifted
+fn setup_drifted() -> MockDeps {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    for title in ["first", "second", "third", "fourth", "fifth"].iter() {
+        create_poll(&mut deps, "1", title);
+    }
+    let key = |poll_id: u64| poll_id.to_be_bytes();
+    // missing
+    poll_indexer_store(&mut deps.storage, &PollStatus::InProgress).remove(&key(2));
+    // stale
+    poll_indexer_store(&mut deps.storage, &PollStatus::Passed)
+        .save(&key(4), &true)
+        .unwrap();
+    // missing and stale
+    poll_indexer_store(&mut deps.storage, &PollStatus::InProgress).remove(&key(5));
+    poll_indexer_store(&mut deps.storage, &PollStatus::Rejected)
+        .save(&key(5), &true)
+        .unwrap();
+    deps
+}
+
+#[test]
+fn drift_is_detected_page_by_page() {
+    let deps = setup_drifted();
+    assert_eq!(in_progress_ids(&deps), vec![1, 3, 4]);
+
+    let page = consistency(&deps, None);
+    assert_eq!(page.mismatched, vec![2]);
+    assert_eq!(page.next_cursor, Some(2));
+    let page = consistency(&deps, Some(2));
+    assert_eq!(page.mismatched, vec![4]);
+    assert_eq!(page.next_cursor, Some(4));
+    let page = consistency(&deps, Some(4));
+    assert_eq!(page.mismatched, vec![5]);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn drift_is_repaired_page_by_page() {
+    let mut deps = setup_drifted();
+    let err = exec_as(
+        &mut deps,
+        mock_env(),
+        "anyone",
+        ExecuteMsg::RebuildPollIndex {
+            start_after: None,
+            limit: 2,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = rebuild(&mut deps, None).unwrap();
+    assert_eq!(attr(&res, "fixed_polls"), "2");
+    assert_eq!(attr(&res, "next_cursor"), "2");
+    // the rest is still drifted
+    assert_eq!(in_progress_ids(&deps), vec![1, 2, 3, 4]);
+    assert_eq!(consistency(&deps, Some(2)).mismatched, vec![4]);
+
+    let res = rebuild(&mut deps, Some(2)).unwrap();
+    assert_eq!(attr(&res, "fixed_polls"), "4");
+    assert_eq!(attr(&res, "next_cursor"), "4");
+    let res = rebuild(&mut deps, Some(4)).unwrap();
+    assert_eq!(attr(&res, "fixed_polls"), "5");
+    assert_eq!(attr(&res, "next_cursor"), "");
+
+    assert_eq!(in_progress_ids(&deps), vec![1, 2, 3, 4, 5]);
+    for start_after in [None, Some(2), Some(4)].iter() {
+        assert!(consistency(&deps, *start_after).mismatched.is_empty());
+    }
+    // nothing left to fix
+    let res = rebuild(&mut deps, None).unwrap();
+    assert_eq!(attr(&res, "fixed_polls"), "");
+}
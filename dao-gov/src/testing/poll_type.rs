@@ -0,0 +1,88 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Decimal;
+
+use super::*;
+use crate::utils::{PollResponse, PollType, PollsResponse};
+
+fn typed_poll(deps: &mut MockDeps, title: &str, poll_type: PollType) -> u64 {
+    let mut msg = create_poll_msg(title);
+    if let Cw721HookMsg::CreatePoll { poll_type: t, .. } = &mut msg {
+        *t = poll_type;
+    }
+    let res = hook(deps, mock_env(), "1", msg).unwrap();
+    attr(&res, "poll_id").parse().unwrap()
+}
+
+/// "1" has 9 of total share 99: below the 10% quorum, above the 5% signal quorum
+fn setup_typed() -> MockDeps {
+    let mut msg = init_msg();
+    msg.signal_quorum = Some(Decimal::percent(5));
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 81);
+    mint(&mut deps, "2", 8100);
+    deps
+}
+
+#[test]
+fn signal_poll_passes_with_lower_quorum() {
+    let mut deps = setup_typed();
+    let signal = typed_poll(&mut deps, "temperature", PollType::Signal);
+    let binding = typed_poll(&mut deps, "binding", PollType::Binding);
+    for poll_id in [signal, binding].iter() {
+        cast_vote(&mut deps, mock_env(), "1", *poll_id, VoteOption::Yes).unwrap();
+    }
+
+    let ended = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    let res = hook(
+        &mut deps,
+        ended.clone(),
+        "1",
+        Cw721HookMsg::EndPoll { poll_id: signal },
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "passed"), "true");
+    let res = hook(
+        &mut deps,
+        ended,
+        "1",
+        Cw721HookMsg::EndPoll { poll_id: binding },
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "passed"), "false");
+    assert_eq!(attr(&res, "quorum_met"), "false");
+}
+
+#[test]
+fn polls_are_filtered_by_type() {
+    let mut deps = setup_typed();
+    let signal = typed_poll(&mut deps, "temperature", PollType::Signal);
+    let binding = create_poll(&mut deps, "1", "default");
+
+    let poll: PollResponse = query_as(
+        &deps,
+        QueryMsg::Poll {
+            poll_id: binding,
+            member_id: None,
+        },
+    );
+    assert_eq!(poll.poll_type, PollType::Binding);
+
+    let of_type = |poll_type: PollType| -> Vec<u64> {
+        let res: PollsResponse = query_as(
+            &deps,
+            QueryMsg::Polls {
+                filter: None,
+                start_after: None,
+                limit: None,
+                order_by: None,
+                include_drafts: None,
+                member_id: None,
+                poll_type: Some(poll_type),
+                viewer: None,
+            },
+        );
+        res.polls.iter().map(|poll| poll.id).collect()
+    };
+    assert_eq!(of_type(PollType::Signal), vec![signal]);
+    assert_eq!(of_type(PollType::Binding), vec![binding]);
+}
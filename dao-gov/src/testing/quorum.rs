@@ -0,0 +1,52 @@
+use super::*;
+use crate::utils::PollResponse;
+
+// height of mock_env, where create_poll opens the poll
+const START: u64 = 12345;
+
+fn quorum_reached_height(deps: &MockDeps, poll_id: u64) -> Option<u64> {
+    let poll: PollResponse = query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    poll.quorum_reached_height
+}
+
+fn flags_quorum(res: &Response) -> bool {
+    res.attributes
+        .iter()
+        .any(|attr| attr.key == "quorum_reached")
+}
+
+#[test]
+fn quorum_reached_height_is_recorded_once() {
+    // total share 110, quorum 10% needs 11
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    mint(&mut deps, "3", 8100);
+    let poll_id = create_poll(&mut deps, "1", "quorum");
+
+    let res = cast_vote(&mut deps, env_at(START + 1), "1", poll_id, VoteOption::Yes).unwrap();
+    assert!(!flags_quorum(&res));
+    assert_eq!(quorum_reached_height(&deps, poll_id), None);
+
+    let res = cast_vote(&mut deps, env_at(START + 2), "2", poll_id, VoteOption::No).unwrap();
+    assert_eq!(attr(&res, "quorum_reached"), "true");
+    assert_eq!(quorum_reached_height(&deps, poll_id), Some(START + 2));
+
+    // a later vote doesn't move it
+    let res = cast_vote(&mut deps, env_at(START + 3), "3", poll_id, VoteOption::Yes).unwrap();
+    assert!(!flags_quorum(&res));
+    assert_eq!(quorum_reached_height(&deps, poll_id), Some(START + 2));
+
+    // nor does cancelling the votes that reached it
+    for member_id in ["1", "2"] {
+        let msg = Cw721HookMsg::CancelVote { poll_id };
+        hook(&mut deps, env_at(START + 4), member_id, msg).unwrap();
+    }
+    assert_eq!(quorum_reached_height(&deps, poll_id), Some(START + 2));
+}
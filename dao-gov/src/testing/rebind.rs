@@ -0,0 +1,81 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{PollResponse, VoteOption};
+
+const FREEZE_WINDOW: u64 = 10;
+
+/// member 1 voted yes on a poll with a vote_freeze_window
+fn setup_voted() -> (MockDeps, u64) {
+    let mut msg = init_msg();
+    msg.vote_freeze_window = Some(FREEZE_WINDOW);
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    let poll_id = create_poll(&mut deps, "1", "frozen");
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    (deps, poll_id)
+}
+
+fn yes_votes(deps: &MockDeps, poll_id: u64) -> Uint128 {
+    let poll: PollResponse = query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    poll.yes_votes
+}
+
+fn end_height() -> u64 {
+    mock_env().block.height + VOTING_PERIOD
+}
+
+#[test]
+fn rebind_before_freeze_withdraws_vote() {
+    let (mut deps, poll_id) = setup_voted();
+    // last height before the window
+    let height = end_height() - FREEZE_WINDOW;
+    let res = hook(&mut deps, env_at(height), "1", Cw721HookMsg::Rebind {}).unwrap();
+    assert_eq!(attr(&res, "withdrawn_polls"), poll_id.to_string());
+    assert_eq!(yes_votes(&deps, poll_id), Uint128::zero());
+}
+
+#[test]
+fn rebind_within_freeze_keeps_vote() {
+    let (mut deps, poll_id) = setup_voted();
+    // first height of the window
+    let height = end_height() - FREEZE_WINDOW + 1;
+    let res = hook(&mut deps, env_at(height), "1", Cw721HookMsg::Rebind {}).unwrap();
+    assert_eq!(attr(&res, "withdrawn_polls"), "");
+    assert_eq!(yes_votes(&deps, poll_id), Uint128::from(10u128));
+
+    // at end_height the vote is still counted
+    let res = hook(
+        &mut deps,
+        env_at(end_height()),
+        "1",
+        Cw721HookMsg::Rebind {},
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "withdrawn_polls"), "");
+    assert_eq!(yes_votes(&deps, poll_id), Uint128::from(10u128));
+}
+
+#[test]
+fn rebind_without_window_withdraws_until_end() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let poll_id = create_poll(&mut deps, "1", "open");
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+
+    let res = hook(
+        &mut deps,
+        env_at(end_height()),
+        "1",
+        Cw721HookMsg::Rebind {},
+    )
+    .unwrap();
+    assert_eq!(attr(&res, "withdrawn_polls"), poll_id.to_string());
+    assert_eq!(yes_votes(&deps, poll_id), Uint128::zero());
+}
@@ -0,0 +1,95 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::VoteReceiptResponse;
+
+fn delegate(deps: &mut MockDeps, member_id: &str, delegatee: &str) {
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: delegatee.to_string(),
+    };
+    hook(deps, mock_env(), member_id, msg).unwrap();
+}
+
+fn receipt(deps: &MockDeps, poll_id: u64, member_id: &str) -> VoteReceiptResponse {
+    query_as(
+        deps,
+        QueryMsg::VoteReceipt {
+            poll_id,
+            member_id: member_id.to_string(),
+        },
+    )
+}
+
+#[test]
+fn attributes_and_receipt_agree() {
+    let mut deps = setup();
+    mint(&mut deps, "d", 100);
+    for (member_id, amount) in [("x", 100), ("y", 400), ("z", 900)].iter() {
+        mint(&mut deps, member_id, *amount);
+        delegate(&mut deps, member_id, "d");
+    }
+    let poll_id = create_poll(&mut deps, "d", "receipt");
+
+    let res = cast_vote(&mut deps, mock_env(), "d", poll_id, VoteOption::Yes).unwrap();
+    assert_eq!(attr(&res, "my_share"), "10");
+    assert_eq!(attr(&res, "total_amount"), "70");
+    let delegated: Vec<(String, Uint128)> = ["x", "y", "z"]
+        .iter()
+        .map(|id| {
+            let amount = attr(&res, &format!("delegated_from_{}", id));
+            (id.to_string(), amount.parse::<u128>().unwrap().into())
+        })
+        .collect();
+    assert!(res
+        .attributes
+        .iter()
+        .all(|a| a.key != "delegated_truncated"));
+
+    let delegate_receipt = receipt(&deps, poll_id, "d");
+    assert_eq!(delegate_receipt.vote, VoteOption::Yes);
+    assert_eq!(delegate_receipt.my_share, Uint128::from(10u128));
+    assert_eq!(delegate_receipt.delegated_from, delegated);
+    assert_eq!(delegate_receipt.total_amount, Uint128::from(70u128));
+
+    // a delegator's receipt holds only its own share
+    let delegator = receipt(&deps, poll_id, "x");
+    assert_eq!(delegator.my_share, Uint128::from(10u128));
+    assert!(delegator.delegated_from.is_empty());
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::VoteReceipt {
+            poll_id,
+            member_id: "w".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotYetVoted {});
+}
+
+#[test]
+fn delegated_attributes_are_truncated() {
+    let mut deps = setup();
+    mint(&mut deps, "d", 100);
+    for i in 0..21 {
+        let member_id = format!("m{:02}", i);
+        mint(&mut deps, &member_id, 100);
+        delegate(&mut deps, &member_id, "d");
+    }
+    let poll_id = create_poll(&mut deps, "d", "truncated");
+
+    let res = cast_vote(&mut deps, mock_env(), "d", poll_id, VoteOption::No).unwrap();
+    let listed = res
+        .attributes
+        .iter()
+        .filter(|a| a.key.starts_with("delegated_from_"))
+        .count();
+    assert_eq!(listed, 20);
+    assert_eq!(attr(&res, "delegated_truncated"), "true");
+
+    // the receipt has the full breakdown
+    let full = receipt(&deps, poll_id, "d");
+    assert_eq!(full.delegated_from.len(), 21);
+    assert_eq!(full.total_amount, Uint128::from(220u128));
+}
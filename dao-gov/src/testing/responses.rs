@@ -0,0 +1,187 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{from_slice, Binary, Uint128};
+use serde::Deserialize;
+
+use super::*;
+use crate::utils::{
+    PollResponse, PollStatus, PollsResponse, StakerResponse, StateResponse, TextLimits,
+};
+
+fn polls_msg() -> QueryMsg {
+    QueryMsg::Polls {
+        filter: None,
+        start_after: None,
+        limit: None,
+        order_by: None,
+        include_drafts: None,
+        member_id: None,
+        poll_type: None,
+        viewer: None,
+    }
+}
+
+#[test]
+fn meta_is_nested_field_of_responses() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let poll_id = create_poll(&mut deps, "1", "first");
+    let env = env_at(mock_env().block.height + 3);
+
+    let state: StateResponse = query_at(&deps, env.clone(), QueryMsg::State {});
+    assert_eq!(state.meta.height, env.block.height);
+    assert_eq!(state.poll_count, 1);
+
+    let poll: PollResponse = query_at(
+        &deps,
+        env.clone(),
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    assert_eq!(poll.meta.height, env.block.height);
+
+    let polls: PollsResponse = query_at(&deps, env.clone(), polls_msg());
+    assert_eq!(polls.meta.height, env.block.height);
+    assert_eq!(polls.polls.len(), 1);
+
+    let member: StakerResponse = query_at(
+        &deps,
+        env.clone(),
+        QueryMsg::Member {
+            member_id: "1".to_string(),
+        },
+    );
+    assert_eq!(member.meta.time, env.block.time);
+    assert_eq!(member.balance, Uint128::from(100u128));
+}
+
+#[test]
+fn meta_serializes_as_object() {
+    #[derive(Deserialize)]
+    struct Height {
+        height: u64,
+    }
+    #[derive(Deserialize)]
+    struct WithMeta {
+        meta: Height,
+    }
+
+    let deps = setup();
+    let raw = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+    let with_meta: WithMeta = from_slice(raw.as_slice()).unwrap();
+    assert_eq!(with_meta.meta.height, mock_env().block.height);
+}
+
+#[test]
+fn hook_kind_and_poll_title_attributes() {
+    let mut msg = init_msg();
+    msg.text_limits = Some(TextLimits {
+        title_max: 100,
+        ..TextLimits::default()
+    });
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    let title = "t".repeat(70);
+    let poll_id = create_poll(&mut deps, "1", &title);
+
+    let res = cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    assert_eq!(attr(&res, "hook"), "cast_vote");
+    assert_eq!(attr(&res, "hook_kind"), "cast_vote");
+    assert_eq!(attr(&res, "poll_title"), "t".repeat(64));
+
+    let res = hook(&mut deps, mock_env(), "1", create_poll_msg("second")).unwrap();
+    assert_eq!(attr(&res, "hook"), "create_poll");
+    assert!(!res.attributes.iter().any(|a| a.key == "poll_title"));
+}
+
+#[test]
+fn hooks_encoded_by_cw721_are_accepted() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 400);
+    let poll_id = create_poll(&mut deps, "1", "raw json");
+    assert_eq!(poll_id, 1);
+    // same json as the execute_dao helpers of dao-cw721 produce
+    let receive = |json: &str| {
+        ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: wallet_of("1"),
+            token_id: "1".to_string(),
+            msg: Binary::from(json.as_bytes()),
+        })
+    };
+    let poll = |deps: &MockDeps| -> PollResponse {
+        query_as(
+            deps,
+            QueryMsg::Poll {
+                poll_id,
+                member_id: None,
+            },
+        )
+    };
+
+    let msg = receive(r#"{"cast_vote":{"poll_id":1,"vote":"no","conviction":null}}"#);
+    let res = exec_as(&mut deps, mock_env(), NFT, msg).unwrap();
+    assert_eq!(attr(&res, "hook_kind"), "cast_vote");
+    assert_eq!(poll(&deps).no_votes, Uint128::from(20u128));
+
+    let msg = receive(r#"{"cancel_vote":{"poll_id":1}}"#);
+    exec_as(&mut deps, mock_env(), NFT, msg).unwrap();
+    assert!(poll(&deps).no_votes.is_zero());
+
+    // conviction may be left out
+    let msg = receive(r#"{"cast_vote":{"poll_id":1,"vote":"yes"}}"#);
+    exec_as(&mut deps, mock_env(), NFT, msg).unwrap();
+
+    let msg = receive(r#"{"end_poll":{"poll_id":1}}"#);
+    let after_voting = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    exec_as(&mut deps, after_voting, NFT, msg).unwrap();
+    assert_eq!(poll(&deps).status, PollStatus::Passed);
+}
+
+fn polls_for(deps: &MockDeps, viewer: Option<&str>) -> Vec<PollResponse> {
+    let mut msg = polls_msg();
+    if let QueryMsg::Polls {
+        viewer: ref mut v, ..
+    } = msg
+    {
+        *v = viewer.map(|viewer| viewer.to_string());
+    }
+    let mut polls: PollsResponse = query_as(deps, msg);
+    polls.polls.sort_by_key(|poll| poll.id);
+    polls.polls
+}
+
+#[test]
+fn polls_are_enriched_for_a_viewer() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 400);
+    let voted = create_poll(&mut deps, "1", "voted");
+    create_poll(&mut deps, "1", "not voted");
+    cast_vote(&mut deps, mock_env(), "2", voted, VoteOption::No).unwrap();
+
+    let polls = polls_for(&deps, Some("2"));
+    assert_eq!(polls[0].viewer_vote, Some(VoteOption::No));
+    assert_eq!(polls[0].viewer_locked, Some(Uint128::from(20u128)));
+    assert_eq!(polls[0].creator_share, Some(Uint128::from(10u128)));
+    assert_eq!(polls[1].viewer_vote, None);
+    assert_eq!(polls[1].viewer_locked, None);
+    assert_eq!(polls[1].creator_share, Some(Uint128::from(10u128)));
+
+    // exited creator has zero share
+    hook(&mut deps, mock_env(), "1", Cw721HookMsg::Exit {}).unwrap();
+    let polls = polls_for(&deps, Some("2"));
+    assert_eq!(polls[0].creator_share, Some(Uint128::zero()));
+
+    // no viewer, no fields
+    for poll in polls_for(&deps, None) {
+        assert_eq!(poll.viewer_vote, None);
+        assert_eq!(poll.viewer_locked, None);
+        assert_eq!(poll.creator_share, None);
+    }
+    let raw = query(deps.as_ref(), mock_env(), polls_msg()).unwrap();
+    let json = String::from_utf8(raw.to_vec()).unwrap();
+    for key in ["viewer_vote", "viewer_locked", "creator_share"].iter() {
+        assert!(!json.contains(key));
+    }
+}
@@ -0,0 +1,87 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{RoundingMode, ShareToMinBalanceResponse, StakerResponse};
+
+fn share(deps: &MockDeps, member_id: &str) -> u128 {
+    let member: StakerResponse = query_as(
+        deps,
+        QueryMsg::Member {
+            member_id: member_id.to_string(),
+        },
+    );
+    member.share.u128()
+}
+
+fn setup_rounding(rounding: Option<RoundingMode>) -> MockDeps {
+    let mut msg = init_msg();
+    msg.share_rounding = rounding;
+    let mut deps = setup_with(msg);
+    for (member_id, balance) in [("6", 6), ("7", 7), ("8", 8)].iter() {
+        mint(&mut deps, member_id, *balance);
+    }
+    deps
+}
+
+#[test]
+fn floor_is_the_default() {
+    let deps = setup_rounding(None);
+    assert_eq!(share(&deps, "6"), 2);
+    assert_eq!(share(&deps, "7"), 2);
+    assert_eq!(share(&deps, "8"), 2);
+}
+
+#[test]
+fn nearest_rounds_shares_at_the_midpoint() {
+    // 6 is closer to 4, 7 and 8 are closer to 9
+    let mut deps = setup_rounding(Some(RoundingMode::Nearest));
+    assert_eq!(share(&deps, "6"), 2);
+    assert_eq!(share(&deps, "7"), 3);
+    assert_eq!(share(&deps, "8"), 3);
+
+    // burning is rounded the same way
+    let withdraw = Cw721HookMsg::Withdraw {
+        amount: Uint128::from(2u128),
+    };
+    hook(&mut deps, mock_env(), "8", withdraw).unwrap();
+    assert_eq!(share(&deps, "8"), 2);
+}
+
+#[test]
+fn nearest_min_balance_is_first_balance_rounding_up() {
+    let rounding = RoundingMode::Nearest;
+    for share in 1..50u128 {
+        let min_balance = rounding.min_balance(share);
+        assert_eq!(rounding.share(Uint128::from(min_balance)).u128(), share);
+        assert_eq!(
+            rounding.share(Uint128::from(min_balance - 1)).u128(),
+            share - 1
+        );
+    }
+    assert_eq!(rounding.min_balance(0), 0);
+}
+
+fn min_balance(deps: &MockDeps, share: u128) -> Result<u128, ContractError> {
+    let msg = QueryMsg::ShareToMinBalance {
+        share: Uint128::from(share),
+    };
+    let res: ShareToMinBalanceResponse = from_binary(&query(deps.as_ref(), mock_env(), msg)?)?;
+    Ok(res.min_balance.u128())
+}
+
+#[test]
+fn share_to_min_balance_inverts_share() {
+    let deps = setup_rounding(None);
+    for (share, balance) in [(0, 0), (1, 1), (2, 4), (10, 100), (20, 400)].iter() {
+        assert_eq!(min_balance(&deps, *share).unwrap(), *balance);
+    }
+    // above the share of the largest balance
+    let max_share = u64::MAX as u128;
+    assert!(min_balance(&deps, max_share).is_ok());
+    assert!(min_balance(&deps, max_share + 1).is_err());
+
+    let deps = setup_rounding(Some(RoundingMode::Nearest));
+    for (share, balance) in [(0, 0), (1, 1), (2, 3), (3, 7), (10, 91)].iter() {
+        assert_eq!(min_balance(&deps, *share).unwrap(), *balance);
+    }
+}
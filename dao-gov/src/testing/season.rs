@@ -0,0 +1,128 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::StdError;
+
+use super::*;
+use crate::state::SeasonSnapshot;
+use crate::utils::SeasonsResponse;
+
+fn season_length_err(err: ContractError) {
+    match err {
+        ContractError::Std(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "season_length must be at least 1 block")
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+}
+
+#[test]
+fn zero_season_length_is_rejected() {
+    let mut msg = init_msg();
+    msg.season_length = Some(0);
+    let mut deps = mock_deps();
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap_err();
+    season_length_err(err);
+
+    let mut msg = init_msg();
+    msg.season_length = Some(1);
+    let mut deps = setup_with(msg);
+    let update = update_config_msg(r#"{"season_length":0}"#);
+    season_length_err(exec_as(&mut deps, mock_env(), ADMIN, update).unwrap_err());
+    let update = update_config_msg(r#"{"season_length":50}"#);
+    exec_as(&mut deps, mock_env(), ADMIN, update).unwrap();
+}
+
+const START: u64 = 12345;
+const SEASON: u64 = 1000;
+const BLACKOUT: u64 = 100;
+
+fn setup_season() -> MockDeps {
+    let mut msg = init_msg();
+    msg.season_length = Some(SEASON);
+    msg.season_blackout = Some(BLACKOUT);
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    deps
+}
+
+fn create_at(deps: &mut MockDeps, height: u64) -> Result<Response, ContractError> {
+    hook(deps, env_at(height), "1", create_poll_msg("seasonal"))
+}
+
+fn roll_at(deps: &mut MockDeps, height: u64) -> Result<Response, ContractError> {
+    exec_as(deps, env_at(height), "anyone", ExecuteMsg::RollSeason {})
+}
+
+#[test]
+fn create_poll_is_blocked_in_blackout() {
+    let mut deps = setup_season();
+    let ends_at = START + SEASON;
+    create_at(&mut deps, ends_at - BLACKOUT - 1).unwrap();
+    let err = create_at(&mut deps, ends_at - BLACKOUT).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SeasonBlackout {
+            season_ends_at: ends_at
+        }
+    );
+    // still blocked once the season is over but not rolled
+    let err = create_at(&mut deps, ends_at + 10).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SeasonBlackout {
+            season_ends_at: ends_at
+        }
+    );
+
+    roll_at(&mut deps, ends_at + 10).unwrap();
+    create_at(&mut deps, ends_at + 10).unwrap();
+}
+
+#[test]
+fn season_rolls_once_it_has_elapsed() {
+    let mut deps = setup_season();
+    let ends_at = START + SEASON;
+    create_at(&mut deps, START).unwrap();
+
+    let err = roll_at(&mut deps, ends_at - 1).unwrap_err();
+    assert_eq!(err, ContractError::SeasonNotEnded {});
+    let res = roll_at(&mut deps, ends_at).unwrap();
+    assert_eq!(attr(&res, "ended_season"), "0");
+    assert_eq!(attr(&res, "season"), "1");
+
+    // next season starts at the rollover
+    let err = roll_at(&mut deps, ends_at).unwrap_err();
+    assert_eq!(err, ContractError::SeasonNotEnded {});
+    roll_at(&mut deps, ends_at + SEASON).unwrap();
+
+    let res: SeasonsResponse = query_as(
+        &deps,
+        QueryMsg::Seasons {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(
+        res.seasons,
+        vec![
+            SeasonSnapshot {
+                season: 0,
+                total_share: Uint128::from(10u128),
+                poll_count: 1,
+                height: ends_at,
+            },
+            SeasonSnapshot {
+                season: 1,
+                total_share: Uint128::from(10u128),
+                poll_count: 1,
+                height: ends_at + SEASON,
+            },
+        ]
+    );
+}
+
+#[test]
+fn season_does_not_roll_without_season_length() {
+    let mut deps = setup();
+    let err = roll_at(&mut deps, START + SEASON).unwrap_err();
+    assert_eq!(err, ContractError::SeasonNotEnded {});
+}
@@ -0,0 +1,276 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{Binary, Order, Storage};
+use k256::ecdsa::signature::Signer;
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+
+use super::*;
+use crate::msg::SignedBallot;
+use crate::state::load_member;
+use crate::utils::{ballot_message, PollResponse, StakerResponse, VoteOption};
+
+fn signing_key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32]).unwrap()
+}
+
+fn register_key(deps: &mut MockDeps, member_id: &str, key: &SigningKey) {
+    let pubkey = Binary::from(VerifyingKey::from(key).to_bytes().as_slice());
+    hook(
+        deps,
+        mock_env(),
+        member_id,
+        Cw721HookMsg::RegisterVoteKey { pubkey },
+    )
+    .unwrap();
+}
+
+fn signed(
+    key: &SigningKey,
+    member_id: &str,
+    poll_id: u64,
+    vote: VoteOption,
+    nonce: u64,
+) -> SignedBallot {
+    let mut ballot = SignedBallot {
+        token_id: member_id.to_string(),
+        poll_id,
+        vote,
+        nonce,
+        signature: Binary::default(),
+    };
+    let message = ballot_message(mock_env().contract.address.as_str(), &ballot);
+    let signature: Signature = key.sign(message.as_bytes());
+    ballot.signature = Binary::from(signature.as_ref());
+    ballot
+}
+
+fn cast_signed(
+    deps: &mut MockDeps,
+    env: Env,
+    ballot: SignedBallot,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::CastVoteSigned {
+        poll_id: ballot.poll_id,
+        voter: ballot.token_id,
+        vote: ballot.vote,
+        nonce: ballot.nonce,
+        signature: ballot.signature,
+    };
+    exec_as(deps, env, "relayer0000", msg)
+}
+
+fn vote_nonce(deps: &MockDeps, member_id: &str) -> u64 {
+    let member: StakerResponse = query_as(
+        deps,
+        QueryMsg::Member {
+            member_id: member_id.to_string(),
+        },
+    );
+    member.vote_nonce
+}
+
+fn yes_votes(deps: &MockDeps, poll_id: u64) -> Uint128 {
+    let poll: PollResponse = query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    poll.yes_votes
+}
+
+fn setup_signer() -> (MockDeps, SigningKey, u64) {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let key = signing_key(1);
+    register_key(&mut deps, "1", &key);
+    let poll_id = create_poll(&mut deps, "1", "signed");
+    (deps, key, poll_id)
+}
+
+#[test]
+fn valid_signature_casts_vote() {
+    let (mut deps, key, poll_id) = setup_signer();
+    let ballot = signed(&key, "1", poll_id, VoteOption::Yes, 0);
+    let res = cast_signed(&mut deps, mock_env(), ballot).unwrap();
+    assert_eq!(attr(&res, "voter"), "1");
+    assert_eq!(yes_votes(&deps, poll_id), Uint128::from(10u128));
+    assert_eq!(vote_nonce(&deps, "1"), 1);
+}
+
+#[test]
+fn invalid_signatures_are_rejected() {
+    let (mut deps, key, poll_id) = setup_signer();
+
+    // signed by a key other than the registered one
+    let ballot = signed(&signing_key(2), "1", poll_id, VoteOption::Yes, 0);
+    let err = cast_signed(&mut deps, mock_env(), ballot).unwrap_err();
+    assert_eq!(err, ContractError::InvalidBallotSignature {});
+
+    // vote changed after signing
+    let mut ballot = signed(&key, "1", poll_id, VoteOption::Yes, 0);
+    ballot.vote = VoteOption::No;
+    let err = cast_signed(&mut deps, mock_env(), ballot).unwrap_err();
+    assert_eq!(err, ContractError::InvalidBallotSignature {});
+
+    // malformed signature
+    let mut ballot = signed(&key, "1", poll_id, VoteOption::Yes, 0);
+    ballot.signature = Binary::from(vec![0u8; 10]);
+    let err = cast_signed(&mut deps, mock_env(), ballot).unwrap_err();
+    assert_eq!(err, ContractError::InvalidBallotSignature {});
+
+    assert_eq!(vote_nonce(&deps, "1"), 0);
+    assert_eq!(yes_votes(&deps, poll_id), Uint128::zero());
+}
+
+#[test]
+fn replayed_ballot_is_rejected() {
+    let (mut deps, key, poll_id) = setup_signer();
+    let ballot = signed(&key, "1", poll_id, VoteOption::Yes, 0);
+    cast_signed(&mut deps, mock_env(), ballot.clone()).unwrap();
+
+    let err = cast_signed(&mut deps, mock_env(), ballot.clone()).unwrap_err();
+    assert_eq!(err, ContractError::InvalidNonce {});
+
+    // replay through relay_vote is skipped as well
+    let msg = ExecuteMsg::RelayVote {
+        ballots: vec![ballot],
+    };
+    let res = exec_as(&mut deps, mock_env(), "relayer0000", msg).unwrap();
+    assert_eq!(attr(&res, "applied"), "0");
+    assert_eq!(
+        attr(&res, "skipped_0"),
+        ContractError::InvalidNonce {}.to_string()
+    );
+    assert_eq!(yes_votes(&deps, poll_id), Uint128::from(10u128));
+}
+
+#[test]
+fn skipped_ballot_keeps_nonce() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let key = signing_key(1);
+    register_key(&mut deps, "1", &key);
+
+    // fill up the member's locks
+    for i in 0..50 {
+        let poll_id = create_poll(&mut deps, "1", &format!("poll {}", i));
+        cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    }
+    let later = env_at(mock_env().block.height + VOTING_PERIOD / 2);
+    let res = hook(&mut deps, later, "1", create_poll_msg("one too many")).unwrap();
+    let poll_id: u64 = attr(&res, "poll_id").parse().unwrap();
+
+    let ballot = signed(&key, "1", poll_id, VoteOption::Yes, 0);
+    let msg = ExecuteMsg::RelayVote {
+        ballots: vec![ballot.clone()],
+    };
+    let res = exec_as(&mut deps, mock_env(), "relayer0000", msg).unwrap();
+    assert_eq!(attr(&res, "applied"), "0");
+    assert_eq!(
+        attr(&res, "skipped_0"),
+        ContractError::TooManyLockedPolls { max: 50 }.to_string()
+    );
+    assert_eq!(vote_nonce(&deps, "1"), 0);
+
+    // once the locked polls ended, the same ballot goes through
+    let after = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    for ended in 1..=50 {
+        let msg = ExecuteMsg::EndPoll { poll_id: ended };
+        exec_as(&mut deps, after.clone(), ADMIN, msg).unwrap();
+    }
+    let msg = ExecuteMsg::RelayVote {
+        ballots: vec![ballot],
+    };
+    let res = exec_as(&mut deps, after, "relayer0000", msg).unwrap();
+    assert_eq!(attr(&res, "applied"), "1");
+    assert_eq!(vote_nonce(&deps, "1"), 1);
+    assert_eq!(yes_votes(&deps, poll_id), Uint128::from(10u128));
+}
+
+#[test]
+fn nonce_outlives_removed_member() {
+    let (mut deps, key, poll_id) = setup_signer();
+    let ballot = signed(&key, "1", poll_id, VoteOption::Yes, 0);
+    cast_signed(&mut deps, mock_env(), ballot).unwrap();
+
+    // exit once the vote no longer locks the balance
+    let after = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    exec_as(
+        &mut deps,
+        after.clone(),
+        ADMIN,
+        ExecuteMsg::EndPoll { poll_id },
+    )
+    .unwrap();
+    let res = hook(&mut deps, after.clone(), "1", Cw721HookMsg::Exit {}).unwrap();
+    assert_eq!(attr(&res, "removed"), "true");
+    assert_eq!(vote_nonce(&deps, "1"), 1);
+
+    // the same key registered again can't replay ballots signed before
+    mint(&mut deps, "1", 100);
+    register_key(&mut deps, "1", &key);
+    let res = hook(&mut deps, after.clone(), "1", create_poll_msg("again")).unwrap();
+    let poll_id: u64 = attr(&res, "poll_id").parse().unwrap();
+    let replayed = signed(&key, "1", poll_id, VoteOption::Yes, 0);
+    let err = cast_signed(&mut deps, after.clone(), replayed).unwrap_err();
+    assert_eq!(err, ContractError::InvalidNonce {});
+    let ballot = signed(&key, "1", poll_id, VoteOption::Yes, 1);
+    cast_signed(&mut deps, after, ballot).unwrap();
+    assert_eq!(vote_nonce(&deps, "1"), 2);
+}
+
+fn storage_snapshot(deps: &MockDeps) -> Vec<(Vec<u8>, Vec<u8>)> {
+    deps.storage.range(None, None, Order::Ascending).collect()
+}
+
+#[test]
+fn skipped_ballots_leave_no_state() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 400);
+    let key = signing_key(1);
+    register_key(&mut deps, "1", &key);
+    let other_key = signing_key(2);
+    register_key(&mut deps, "2", &other_key);
+
+    // member 1 holds the most locks a member can
+    for i in 0..50 {
+        let poll_id = create_poll(&mut deps, "2", &format!("poll {}", i));
+        cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    }
+    let poll_id = create_poll(&mut deps, "2", "one too many");
+
+    let before = storage_snapshot(&deps);
+    let msg = ExecuteMsg::RelayVote {
+        ballots: vec![
+            signed(&key, "1", poll_id, VoteOption::Yes, 0),
+            signed(&other_key, "2", poll_id, VoteOption::Yes, 7),
+        ],
+    };
+    let res = exec_as(&mut deps, mock_env(), "relayer0000", msg).unwrap();
+    assert_eq!(attr(&res, "applied"), "0");
+    assert_eq!(
+        attr(&res, "skipped_0"),
+        ContractError::TooManyLockedPolls { max: 50 }.to_string()
+    );
+    assert_eq!(
+        attr(&res, "skipped_1"),
+        ContractError::InvalidNonce {}.to_string()
+    );
+    assert_eq!(storage_snapshot(&deps), before);
+
+    // the refused ballot doesn't hold up the others
+    let member = load_member(&deps.storage, b"1").unwrap();
+    let msg = ExecuteMsg::RelayVote {
+        ballots: vec![
+            signed(&key, "1", poll_id, VoteOption::Yes, 0),
+            signed(&other_key, "2", poll_id, VoteOption::No, 0),
+        ],
+    };
+    let res = exec_as(&mut deps, mock_env(), "relayer0000", msg).unwrap();
+    assert_eq!(attr(&res, "applied"), "1");
+    assert_eq!(load_member(&deps.storage, b"1").unwrap(), member);
+    assert_eq!(yes_votes(&deps, poll_id), Uint128::zero());
+    assert_eq!(vote_nonce(&deps, "2"), 1);
+}
@@ -0,0 +1,178 @@
+use cosmwasm_std::testing::mock_dependencies;
+use cosmwasm_std::{Decimal, Storage, Uint128};
+use cosmwasm_storage::to_length_prefixed;
+
+use crate::state::{load_member, load_poll, save_member, save_poll, EndedPoll, Poll, TokenManager};
+use crate::utils::{PollKind, PollLink, PollOutcome, PollStatus, PollType, VoteInfo, VoteOption};
+
+// records as written by the build that introduced the versioned envelope
+const POLL_V1: &str = r#"{"v1":{"id":7,"creator":"member1","status":"passed","yes_votes":"30","no_votes":"10","end_height":12445,"title":"fixture poll","description":"fixture description","link":null,"links":[{"label":"forum","url":"https://forum.example/7"}],"total_share_at_end_poll":"120","total_share_at_start_poll":"100","deposit":"5","ended_by":"member2","ended_at_height":12446,"quorum":"0.1","threshold":"0.5","threshold_inclusive":true,"creator_wallet":"walletmember1","quorum_reached_height":12350,"created_at_height":12345,"extended_blocks":0,"tie_extended":false,"poll_type":"signal","kind":"binary","votes_per_option":[],"winning_option":null,"depends_on":3}}"#;
+const MEMBER_V1: &str = r#"{"v1":{"share":"20","balance":"400","locked_share":[[7,{"vote":"yes","balance":"20","via_delegate":null,"wallet":"walletmember1","conviction_weight":"4"}]],"delegate_to":null,"delegated_from":["member3"],"aliases":[["evm","0xabc"]],"suspended_until":13000,"last_vote_height":12350,"wallet":"walletmember1","vote_key":null,"last_ended_poll":{"poll_id":7,"outcome":"passed","ended_at_height":12446},"delegated_in_share":"10"}}"#;
+// records as written before versioning (untagged, before any defaulted field)
+const POLL_UNTAGGED: &str = r#"{"id":2,"creator":"member1","status":"in_progress","yes_votes":"0","no_votes":"0","end_height":12445,"title":"legacy poll","description":"legacy description","link":"https://forum.example/2","total_share_at_end_poll":null,"total_share_at_start_poll":"100"}"#;
+const MEMBER_UNTAGGED: &str = r#"{"share":"20","balance":"400","locked_share":[[2,{"vote":"yes","balance":"20"}]],"delegate_to":null,"delegated_from":[]}"#;
+
+fn poll_key(poll_id: u64) -> Vec<u8> {
+    [
+        to_length_prefixed(b"poll").as_slice(),
+        &poll_id.to_be_bytes(),
+    ]
+    .concat()
+}
+
+fn member_key(member_id: &str) -> Vec<u8> {
+    [to_length_prefixed(b"bank").as_slice(), member_id.as_bytes()].concat()
+}
+
+fn v1_poll() -> Poll {
+    Poll {
+        id: 7,
+        creator: "member1".to_string(),
+        status: PollStatus::Passed,
+        yes_votes: Uint128::from(30u128),
+        no_votes: Uint128::from(10u128),
+        end_height: 12445,
+        title: "fixture poll".to_string(),
+        description: "fixture description".to_string(),
+        summary: String::new(),
+        link: None,
+        links: vec![PollLink {
+            label: "forum".to_string(),
+            url: "https://forum.example/7".to_string(),
+        }],
+        total_share_at_end_poll: Some(Uint128::from(120u128)),
+        total_share_at_start_poll: Uint128::from(100u128),
+        deposit: Uint128::from(5u128),
+        deposit_claimed: false,
+        deposit_slashed: false,
+        ended_by: Some("member2".to_string()),
+        ended_at_height: 12446,
+        quorum: Some(Decimal::percent(10)),
+        threshold: Some(Decimal::percent(50)),
+        threshold_inclusive: Some(true),
+        creator_wallet: "walletmember1".to_string(),
+        quorum_reached_height: Some(12350),
+        created_at_height: 12345,
+        extended_blocks: 0,
+        tie_extended: false,
+        poll_type: PollType::Signal,
+        kind: PollKind::Binary,
+        votes_per_option: vec![],
+        winning_option: None,
+        depends_on: Some(3),
+        force_ended: false,
+        raw_quorum: None,
+        quorum_used: None,
+        participation_anomaly: false,
+        end_time: None,
+    }
+}
+
+fn vote(balance: u128, wallet: &str, conviction_weight: u128) -> VoteInfo {
+    VoteInfo {
+        vote: VoteOption::Yes,
+        balance: Uint128::from(balance),
+        via_delegate: None,
+        wallet: wallet.to_string(),
+        conviction_weight: Uint128::from(conviction_weight),
+        multiplier: None,
+        capped_balance: None,
+    }
+}
+
+#[test]
+fn v1_poll_upgrades_on_load() {
+    let mut deps = mock_dependencies(&[]);
+    deps.storage.set(&poll_key(7), POLL_V1.as_bytes());
+    assert_eq!(load_poll(&deps.storage, 7).unwrap(), v1_poll());
+}
+
+#[test]
+fn untagged_poll_upgrades_on_load() {
+    let mut deps = mock_dependencies(&[]);
+    deps.storage.set(&poll_key(2), POLL_UNTAGGED.as_bytes());
+    let poll = load_poll(&deps.storage, 2).unwrap();
+    assert_eq!(poll.status, PollStatus::InProgress);
+    assert_eq!(poll.link, Some("https://forum.example/2".to_string()));
+    assert_eq!(poll.quorum, None);
+    assert_eq!(poll.poll_type, PollType::Binding);
+    assert_eq!(poll.summary, "");
+}
+
+#[test]
+fn v1_member_upgrades_on_load() {
+    let mut deps = mock_dependencies(&[]);
+    deps.storage
+        .set(&member_key("member1"), MEMBER_V1.as_bytes());
+    let expected = TokenManager {
+        share: Uint128::from(20u128),
+        balance: Uint128::from(400u128),
+        locked_share: vec![(7, vote(20, "walletmember1", 4))],
+        delegated_from: vec!["member3".to_string()],
+        aliases: vec![("evm".to_string(), "0xabc".to_string())],
+        suspended_until: Some(13000),
+        last_vote_height: Some(12350),
+        wallet: Some("walletmember1".to_string()),
+        last_ended_poll: Some(EndedPoll {
+            poll_id: 7,
+            outcome: PollOutcome::Passed,
+            ended_at_height: 12446,
+        }),
+        delegated_in_share: Uint128::from(10u128),
+        ..TokenManager::default()
+    };
+    assert_eq!(load_member(&deps.storage, b"member1").unwrap(), expected);
+}
+
+#[test]
+fn untagged_member_upgrades_on_load() {
+    let mut deps = mock_dependencies(&[]);
+    deps.storage
+        .set(&member_key("member1"), MEMBER_UNTAGGED.as_bytes());
+    let token_manager = load_member(&deps.storage, b"member1").unwrap();
+    assert_eq!(token_manager.share, Uint128::from(20u128));
+    assert_eq!(token_manager.locked_share, vec![(2, vote(20, "", 0))]);
+    assert_eq!(token_manager.vote_nonce, 0);
+}
+
+#[test]
+fn upgraded_records_are_saved_as_latest() {
+    let mut deps = mock_dependencies(&[]);
+    deps.storage.set(&poll_key(7), POLL_V1.as_bytes());
+    deps.storage
+        .set(&member_key("member1"), MEMBER_V1.as_bytes());
+
+    let mut poll = load_poll(&deps.storage, 7).unwrap();
+    poll.force_ended = true;
+    save_poll(&mut deps.storage, &poll).unwrap();
+    let mut token_manager = load_member(&deps.storage, b"member1").unwrap();
+    token_manager.vote_nonce = 3;
+    save_member(&mut deps.storage, b"member1", &token_manager).unwrap();
+
+    let data = deps.storage.get(&poll_key(7)).unwrap();
+    assert!(data.starts_with(br#"{"v2":"#));
+    assert_eq!(load_poll(&deps.storage, 7).unwrap(), poll);
+    let data = deps.storage.get(&member_key("member1")).unwrap();
+    assert!(data.starts_with(br#"{"v2":"#));
+    assert_eq!(
+        load_member(&deps.storage, b"member1").unwrap(),
+        token_manager
+    );
+}
+
+#[test]
+fn corrupt_tagged_record_is_an_error() {
+    let mut deps = mock_dependencies(&[]);
+    // a V2 record missing its newer fields is not read as an older layout
+    let truncated = POLL_V1.replacen(r#"{"v1":"#, r#"{"v2":"#, 1);
+    deps.storage.set(&poll_key(7), truncated.as_bytes());
+    let err = load_poll(&deps.storage, 7).unwrap_err().to_string();
+    assert!(err.contains("missing field"), "{}", err);
+
+    deps.storage
+        .set(&member_key("member1"), br#"{"v1":{"share":"20"}}"#);
+    let err = load_member(&deps.storage, b"member1")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("missing field"), "{}", err);
+}
@@ -0,0 +1,203 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::state::{state_read, state_store, State};
+use crate::utils::{MembersResponse, StakerResponse, StateResponse, TotalSupplyResponse};
+
+fn member(deps: &MockDeps, member_id: &str) -> StakerResponse {
+    query_as(
+        deps,
+        QueryMsg::Member {
+            member_id: member_id.to_string(),
+        },
+    )
+}
+
+fn transfer(
+    deps: &mut MockDeps,
+    owner: &str,
+    recipient: &str,
+    amount: u128,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::TransferFrom {
+        owner: owner.to_string(),
+        recipient: recipient.to_string(),
+        amount: Uint128::from(amount),
+    };
+    exec_as(deps, mock_env(), ADMIN, msg)
+}
+
+#[test]
+fn transfer_to_self_is_rejected() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 400);
+    let err = transfer(&mut deps, "1", "1", 150).unwrap_err();
+    assert_eq!(err, ContractError::TransferToSelf {});
+    let one = member(&deps, "1");
+    assert_eq!(one.balance, Uint128::from(400u128));
+    assert_eq!(one.share, Uint128::from(20u128));
+    let state: StateResponse = query_as(&deps, QueryMsg::State {});
+    assert_eq!(state.total_share, Uint128::from(20u128));
+
+    // transfer to another member moves balance and share
+    transfer(&mut deps, "1", "2", 300).unwrap();
+    assert_eq!(member(&deps, "1").share, Uint128::from(10u128));
+    assert_eq!(member(&deps, "2").share, Uint128::from(17u128));
+}
+
+fn state(deps: &MockDeps) -> StateResponse {
+    query_as(deps, QueryMsg::State {})
+}
+
+fn try_mint(deps: &mut MockDeps, member_id: &str, amount: u128) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Mint {
+        recipient: member_id.to_string(),
+        amount: Uint128::from(amount),
+    };
+    exec_as(deps, mock_env(), ADMIN, msg)
+}
+
+fn exceeded(max: u128, attempted_total: u128) -> ContractError {
+    ContractError::MaxSupplyExceeded {
+        max: Uint128::from(max),
+        attempted_total: Uint128::from(attempted_total),
+    }
+}
+
+fn setup_capped() -> MockDeps {
+    let mut msg = init_msg();
+    msg.max_supply = Some(Uint128::from(1000u128));
+    setup_with(msg)
+}
+
+#[test]
+fn mint_up_to_max_supply() {
+    let mut deps = setup_capped();
+    mint(&mut deps, "1", 600);
+    assert_eq!(state(&deps).supply_headroom, Some(Uint128::from(400u128)));
+    // exactly the cap
+    mint(&mut deps, "2", 400);
+    let capped = state(&deps);
+    assert_eq!(capped.total_supply, Uint128::from(1000u128));
+    assert_eq!(capped.max_supply, Some(Uint128::from(1000u128)));
+    assert_eq!(capped.supply_headroom, Some(Uint128::zero()));
+
+    // one unit over
+    let err = try_mint(&mut deps, "2", 1).unwrap_err();
+    assert_eq!(err, exceeded(1000, 1001));
+    let msg = ExecuteMsg::MintLocked {
+        recipient: "3".to_string(),
+        amount: Uint128::from(1u128),
+        until_height: None,
+    };
+    let err = exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap_err();
+    assert_eq!(err, exceeded(1000, 1001));
+
+    // transfers are unaffected, burns make room again
+    transfer(&mut deps, "1", "3", 100).unwrap();
+    let msg = Cw721HookMsg::Withdraw {
+        amount: Uint128::from(40u128),
+    };
+    hook(&mut deps, mock_env(), "3", msg).unwrap();
+    assert_eq!(state(&deps).supply_headroom, Some(Uint128::from(40u128)));
+    mint(&mut deps, "3", 40);
+}
+
+#[test]
+fn max_supply_is_only_lowered() {
+    let mut deps = setup_capped();
+    mint(&mut deps, "1", 600);
+    let err = exec_as(
+        &mut deps,
+        mock_env(),
+        ADMIN,
+        update_config_msg(r#"{"max_supply":"1001"}"#),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CannotRaiseMaxSupply {});
+    let err = exec_as(
+        &mut deps,
+        mock_env(),
+        ADMIN,
+        update_config_msg(r#"{"max_supply":"599"}"#),
+    )
+    .unwrap_err();
+    assert_eq!(err, exceeded(599, 600));
+    exec_as(
+        &mut deps,
+        mock_env(),
+        ADMIN,
+        update_config_msg(r#"{"max_supply":"600"}"#),
+    )
+    .unwrap();
+    assert_eq!(state(&deps).supply_headroom, Some(Uint128::zero()));
+
+    // without a cap there is no headroom to report
+    let deps = setup();
+    assert_eq!(state(&deps).max_supply, None);
+    assert_eq!(state(&deps).supply_headroom, None);
+}
+
+/// TotalSupply and the sum of member balances
+fn supply(deps: &MockDeps) -> (u128, u128) {
+    let total: TotalSupplyResponse = query_as(deps, QueryMsg::TotalSupply {});
+    assert_eq!(state(deps).total_supply, total.total_supply);
+    let members: MembersResponse = query_as(
+        deps,
+        QueryMsg::Members {
+            start_after: None,
+            limit: Some(30),
+        },
+    );
+    let balances = members
+        .members
+        .iter()
+        .map(|(_, balance, _)| balance.u128())
+        .sum();
+    (total.total_supply.u128(), balances)
+}
+
+#[test]
+fn total_supply_follows_balances() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 400);
+    mint(&mut deps, "2", 100);
+    assert_eq!(supply(&deps), (500, 500));
+
+    let withdraw = Cw721HookMsg::Withdraw {
+        amount: Uint128::from(150u128),
+    };
+    hook(&mut deps, mock_env(), "1", withdraw).unwrap();
+    assert_eq!(supply(&deps), (350, 350));
+
+    transfer(&mut deps, "1", "3", 50).unwrap();
+    assert_eq!(supply(&deps), (350, 350));
+
+    hook(&mut deps, mock_env(), "2", Cw721HookMsg::Exit {}).unwrap();
+    assert_eq!(supply(&deps), (250, 250));
+}
+
+#[test]
+fn supply_is_recomputed_in_pages() {
+    let mut deps = setup();
+    for i in 0..45 {
+        mint(&mut deps, &format!("m{:02}", i), 10);
+    }
+    let mut drifted: State = state_read(&deps.storage).load().unwrap();
+    drifted.total_supply = Uint128::from(1u128);
+    state_store(&mut deps.storage).save(&drifted).unwrap();
+
+    let recompute = ExecuteMsg::RecomputeSupply {};
+    let err = exec_as(&mut deps, mock_env(), "stranger", recompute.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // first page of 30 members leaves the stored value alone
+    let res = exec_as(&mut deps, mock_env(), ADMIN, recompute.clone()).unwrap();
+    assert_eq!(attr(&res, "done"), "false");
+    assert_eq!(attr(&res, "total_supply"), "300");
+    assert_eq!(state(&deps).total_supply, Uint128::from(1u128));
+
+    let res = exec_as(&mut deps, mock_env(), ADMIN, recompute).unwrap();
+    assert_eq!(attr(&res, "done"), "true");
+    assert_eq!(state(&deps).total_supply, Uint128::from(450u128));
+}
@@ -0,0 +1,109 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::StakerResponse;
+
+// height of mock_env, where create_poll opens the poll
+const START: u64 = 12345;
+const UNTIL: u64 = START + 10;
+
+fn suspend(deps: &mut MockDeps, member_id: &str) {
+    let msg = ExecuteMsg::SuspendMember {
+        member_id: member_id.to_string(),
+        until_height: UNTIL,
+    };
+    exec_as(deps, mock_env(), ADMIN, msg).unwrap();
+}
+
+fn suspended() -> ContractError {
+    ContractError::MemberSuspended { until: UNTIL }
+}
+
+#[test]
+fn suspension_ends_at_until_height() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    let poll_id = create_poll(&mut deps, "1", "suspended");
+    let msg = ExecuteMsg::SuspendMember {
+        member_id: "2".to_string(),
+        until_height: UNTIL,
+    };
+    let err = exec_as(&mut deps, mock_env(), "stranger", msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    suspend(&mut deps, "2");
+    let member: StakerResponse = query_as(
+        &deps,
+        QueryMsg::Member {
+            member_id: "2".to_string(),
+        },
+    );
+    assert_eq!(member.suspended_until, Some(UNTIL));
+
+    let err = hook(
+        &mut deps,
+        env_at(UNTIL - 1),
+        "2",
+        create_poll_msg("blocked"),
+    )
+    .unwrap_err();
+    assert_eq!(err, suspended());
+    let delegate = Cw721HookMsg::DelegateVote {
+        delegator: "1".to_string(),
+    };
+    let err = hook(&mut deps, env_at(UNTIL - 1), "2", delegate).unwrap_err();
+    assert_eq!(err, suspended());
+    let err = cast_vote(&mut deps, env_at(UNTIL - 1), "2", poll_id, VoteOption::Yes).unwrap_err();
+    assert_eq!(err, suspended());
+
+    cast_vote(&mut deps, env_at(UNTIL), "2", poll_id, VoteOption::Yes).unwrap();
+}
+
+#[test]
+fn suspended_delegator_share_is_skipped() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "3", 900);
+    let delegate = Cw721HookMsg::DelegateVote {
+        delegator: "1".to_string(),
+    };
+    hook(&mut deps, mock_env(), "3", delegate).unwrap();
+    suspend(&mut deps, "3");
+    let poll_id = create_poll(&mut deps, "1", "skipping");
+
+    let res = cast_vote(&mut deps, env_at(UNTIL - 1), "1", poll_id, VoteOption::Yes).unwrap();
+    assert_eq!(attr(&res, "skipped_suspended"), "3");
+    assert_eq!(attr(&res, "total_amount"), "10");
+
+    // counted again once the suspension is over
+    let second = create_poll(&mut deps, "1", "counting");
+    let res = cast_vote(&mut deps, env_at(UNTIL), "1", second, VoteOption::Yes).unwrap();
+    assert_eq!(attr(&res, "total_amount"), "40");
+}
+
+#[test]
+fn suspended_member_can_unwind() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    mint(&mut deps, "3", 100);
+    let poll_id = create_poll(&mut deps, "1", "unwinding");
+    cast_vote(&mut deps, mock_env(), "2", poll_id, VoteOption::No).unwrap();
+    let delegate = Cw721HookMsg::DelegateVote {
+        delegator: "1".to_string(),
+    };
+    hook(&mut deps, mock_env(), "3", delegate).unwrap();
+    suspend(&mut deps, "2");
+    suspend(&mut deps, "3");
+
+    let cancel = Cw721HookMsg::CancelVote { poll_id };
+    hook(&mut deps, mock_env(), "2", cancel).unwrap();
+    hook(&mut deps, mock_env(), "3", Cw721HookMsg::UnDelegateVote {}).unwrap();
+    hook(&mut deps, mock_env(), "3", Cw721HookMsg::Exit {}).unwrap();
+
+    let unsuspend = ExecuteMsg::UnsuspendMember {
+        member_id: "2".to_string(),
+    };
+    exec_as(&mut deps, mock_env(), ADMIN, unsuspend).unwrap();
+    cast_vote(&mut deps, mock_env(), "2", poll_id, VoteOption::Yes).unwrap();
+}
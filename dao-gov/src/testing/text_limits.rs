@@ -0,0 +1,110 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{
+    validate_description, validate_link, validate_text_limits, validate_title, PollResponse,
+    TextLimits,
+};
+
+fn too_long(field: &str, max: u32, got: usize) -> ContractError {
+    ContractError::TextTooLong {
+        field: field.to_string(),
+        max,
+        got,
+    }
+}
+
+fn link_of(len: usize) -> Option<String> {
+    Some(format!("https://{}", "a".repeat(len - 8)))
+}
+
+#[test]
+fn default_limits_at_the_boundary() {
+    let limits = TextLimits::default();
+    validate_title(&"t".repeat(64), &limits).unwrap();
+    assert_eq!(
+        validate_title(&"t".repeat(65), &limits).unwrap_err(),
+        too_long("title", 64, 65)
+    );
+    validate_description(&"d".repeat(1024), &limits).unwrap();
+    assert_eq!(
+        validate_description(&"d".repeat(1025), &limits).unwrap_err(),
+        too_long("description", 1024, 1025)
+    );
+    validate_link(&link_of(128), &limits).unwrap();
+    assert_eq!(
+        validate_link(&link_of(129), &limits).unwrap_err(),
+        too_long("link", 128, 129)
+    );
+}
+
+#[test]
+fn configured_limits_apply_to_new_polls_only() {
+    let mut msg = init_msg();
+    msg.text_limits = Some(TextLimits {
+        title_max: 10,
+        desc_max: 40,
+        link_max: 30,
+    });
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    let poll_id = create_poll(&mut deps, "1", &"t".repeat(10));
+    let err = hook(&mut deps, mock_env(), "1", create_poll_msg(&"t".repeat(11))).unwrap_err();
+    assert_eq!(err, too_long("title", 10, 11));
+
+    let msg = update_config_msg(r#"{"text_limits":{"title_max":4,"desc_max":40,"link_max":30}}"#);
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+    let err = hook(&mut deps, mock_env(), "1", create_poll_msg("title")).unwrap_err();
+    assert_eq!(err, too_long("title", 4, 5));
+    let a_poll: PollResponse = query_as(
+        &deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    assert_eq!(a_poll.title, "t".repeat(10));
+}
+
+#[test]
+fn limits_above_ceiling_are_rejected() {
+    let limits = |title_max, desc_max, link_max| TextLimits {
+        title_max,
+        desc_max,
+        link_max,
+    };
+    let invalid = |field: &str, min, ceiling, got| ContractError::InvalidTextLimit {
+        field: field.to_string(),
+        min,
+        ceiling,
+        got,
+    };
+    validate_text_limits(&limits(128, 16384, 512)).unwrap();
+    validate_text_limits(&limits(4, 4, 12)).unwrap();
+    assert_eq!(
+        validate_text_limits(&limits(129, 1024, 128)).unwrap_err(),
+        invalid("title_max", 4, 128, 129)
+    );
+    assert_eq!(
+        validate_text_limits(&limits(64, 16385, 128)).unwrap_err(),
+        invalid("desc_max", 4, 16384, 16385)
+    );
+    assert_eq!(
+        validate_text_limits(&limits(64, 1024, 513)).unwrap_err(),
+        invalid("link_max", 12, 512, 513)
+    );
+    assert_eq!(
+        validate_text_limits(&limits(64, 1024, 11)).unwrap_err(),
+        invalid("link_max", 12, 512, 11)
+    );
+
+    // update_config checks the same ceilings
+    let mut deps = setup();
+    let msg =
+        update_config_msg(r#"{"text_limits":{"title_max":129,"desc_max":1024,"link_max":128}}"#);
+    let err = exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap_err();
+    assert_eq!(err, invalid("title_max", 4, 128, 129));
+    let msg =
+        update_config_msg(r#"{"text_limits":{"title_max":128,"desc_max":16384,"link_max":512}}"#);
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+}
@@ -0,0 +1,71 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Decimal;
+
+use super::*;
+use crate::utils::{threshold_reached, ConfigResponse, PollResponse, PollStatus};
+
+fn reached(yes: u128, tallied: u128, inclusive: bool) -> bool {
+    threshold_reached(
+        Uint128::from(yes),
+        Uint128::from(tallied),
+        Decimal::percent(50),
+        inclusive,
+    )
+}
+
+#[test]
+fn boundary_depends_on_inclusive() {
+    for tallied in [2u128, 2_000, 200_000_000_000_000_000_000].iter() {
+        let tallied = *tallied;
+        let half = tallied / 2;
+        // exact equality
+        assert!(!reached(half, tallied, false));
+        assert!(reached(half, tallied, true));
+        // one unit above
+        assert!(reached(half + 1, tallied, false));
+        assert!(reached(half + 1, tallied, true));
+        // one unit below
+        assert!(!reached(half - 1, tallied, false));
+        assert!(!reached(half - 1, tallied, true));
+    }
+
+    // a third is not rounded: 1 of 3 is above 0.333333333333333333
+    let third = Decimal::from_ratio(1u128, 3u128);
+    let (yes, tallied) = (Uint128::from(1u128), Uint128::from(3u128));
+    assert!(threshold_reached(yes, tallied, third, false));
+}
+
+/// members 1 and 2 vote yes and no with equal share
+fn end_even_poll(threshold_inclusive: Option<bool>) -> PollStatus {
+    let mut msg = init_msg();
+    msg.threshold_inclusive = threshold_inclusive;
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    let config: ConfigResponse = query_as(&deps, QueryMsg::Config {});
+    assert_eq!(
+        config.threshold_inclusive,
+        threshold_inclusive.unwrap_or_default()
+    );
+
+    let poll_id = create_poll(&mut deps, "1", "even");
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    cast_vote(&mut deps, mock_env(), "2", poll_id, VoteOption::No).unwrap();
+    let env = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    exec_as(&mut deps, env, "ender0000", ExecuteMsg::EndPoll { poll_id }).unwrap();
+    let a_poll: PollResponse = query_as(
+        &deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    a_poll.status
+}
+
+#[test]
+fn exactly_half_passes_only_when_inclusive() {
+    assert_eq!(end_even_poll(None), PollStatus::Rejected);
+    assert_eq!(end_even_poll(Some(false)), PollStatus::Rejected);
+    assert_eq!(end_even_poll(Some(true)), PollStatus::Passed);
+}
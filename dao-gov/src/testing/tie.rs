@@ -0,0 +1,84 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{PollResponse, PollStatus, TiePolicy};
+
+const START: u64 = 12345;
+
+/// members 1 and 2 tie yes and no, member 3 has not voted
+fn setup_tied(tie_policy: Option<TiePolicy>) -> (MockDeps, u64) {
+    let mut msg = init_msg();
+    msg.tie_policy = tie_policy;
+    let mut deps = setup_with(msg);
+    for member_id in ["1", "2", "3"].iter() {
+        mint(&mut deps, member_id, 100);
+    }
+    let poll_id = create_poll(&mut deps, "1", "tied");
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    cast_vote(&mut deps, mock_env(), "2", poll_id, VoteOption::No).unwrap();
+    (deps, poll_id)
+}
+
+fn end_poll(deps: &mut MockDeps, height: u64, poll_id: u64) -> Result<Response, ContractError> {
+    exec_as(
+        deps,
+        env_at(height),
+        "ender0000",
+        ExecuteMsg::EndPoll { poll_id },
+    )
+}
+
+fn poll(deps: &MockDeps, poll_id: u64) -> PollResponse {
+    query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    )
+}
+
+#[test]
+fn tie_is_rejected_by_default() {
+    assert_eq!(TiePolicy::default(), TiePolicy::Reject);
+    let (mut deps, poll_id) = setup_tied(None);
+    let res = end_poll(&mut deps, START + VOTING_PERIOD + 1, poll_id).unwrap();
+    assert_eq!(attr(&res, "rejected_reason"), "Threshold not reached");
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Rejected);
+}
+
+#[test]
+fn tie_passes_with_pass() {
+    let (mut deps, poll_id) = setup_tied(Some(TiePolicy::Pass));
+    end_poll(&mut deps, START + VOTING_PERIOD + 1, poll_id).unwrap();
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Passed);
+}
+
+#[test]
+fn tie_extends_once_then_rejects() {
+    let (mut deps, poll_id) = setup_tied(Some(TiePolicy::ExtendOnce));
+    let extended_end = START + 2 * VOTING_PERIOD;
+    let res = end_poll(&mut deps, START + VOTING_PERIOD + 1, poll_id).unwrap();
+    assert_eq!(attr(&res, "tie_extended"), "true");
+    assert_eq!(attr(&res, "end_height"), extended_end.to_string());
+    let a_poll = poll(&deps, poll_id);
+    assert_eq!(a_poll.status, PollStatus::InProgress);
+    assert_eq!(a_poll.end_height, extended_end);
+
+    let err = end_poll(&mut deps, extended_end - 1, poll_id).unwrap_err();
+    assert_eq!(err, ContractError::PollVotingPeriod {});
+    // still tied after the extension
+    let res = end_poll(&mut deps, extended_end, poll_id).unwrap();
+    assert_eq!(attr(&res, "rejected_reason"), "Threshold not reached");
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Rejected);
+}
+
+#[test]
+fn tie_broken_during_extension() {
+    let (mut deps, poll_id) = setup_tied(Some(TiePolicy::ExtendOnce));
+    end_poll(&mut deps, START + VOTING_PERIOD + 1, poll_id).unwrap();
+    let env = env_at(START + VOTING_PERIOD + 2);
+    cast_vote(&mut deps, env, "3", poll_id, VoteOption::Yes).unwrap();
+    end_poll(&mut deps, START + 2 * VOTING_PERIOD + 1, poll_id).unwrap();
+    assert_eq!(poll(&deps, poll_id).status, PollStatus::Passed);
+}
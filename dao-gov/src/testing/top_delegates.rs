@@ -0,0 +1,89 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::state::load_member;
+use crate::utils::TopDelegatesResponse;
+
+fn delegate(deps: &mut MockDeps, member_id: &str, delegatee: &str) {
+    let msg = Cw721HookMsg::DelegateVote {
+        delegator: delegatee.to_string(),
+    };
+    hook(deps, mock_env(), member_id, msg).unwrap();
+}
+
+fn delegated_in_share(deps: &MockDeps, member_id: &str) -> u128 {
+    load_member(&deps.storage, member_id.as_bytes())
+        .unwrap()
+        .delegated_in_share
+        .u128()
+}
+
+fn top_delegates(deps: &MockDeps, limit: Option<u32>) -> Vec<(String, u128, u128)> {
+    let res: TopDelegatesResponse = query_as(deps, QueryMsg::TopDelegates { limit });
+    res.delegates
+        .into_iter()
+        .map(|(member_id, delegated_in, share)| (member_id, delegated_in.u128(), share.u128()))
+        .collect()
+}
+
+fn top(entries: &[(&str, u128, u128)]) -> Vec<(String, u128, u128)> {
+    entries
+        .iter()
+        .map(|(member_id, delegated_in, share)| (member_id.to_string(), *delegated_in, *share))
+        .collect()
+}
+
+/// "a" (share 10) delegates to "d1", "b" (share 20) to "d2"
+fn setup_delegated() -> MockDeps {
+    let mut deps = setup();
+    mint(&mut deps, "a", 100);
+    mint(&mut deps, "b", 400);
+    mint(&mut deps, "d1", 100);
+    mint(&mut deps, "d2", 400);
+    delegate(&mut deps, "a", "d1");
+    delegate(&mut deps, "b", "d2");
+    deps
+}
+
+#[test]
+fn delegator_mint_and_burn_update_delegate() {
+    let mut deps = setup_delegated();
+    assert_eq!(
+        top_delegates(&deps, None),
+        top(&[("d2", 20, 20), ("d1", 10, 10)])
+    );
+
+    // share of "a" grows to 30
+    mint(&mut deps, "a", 800);
+    assert_eq!(delegated_in_share(&deps, "d1"), 30);
+    assert_eq!(
+        top_delegates(&deps, None),
+        top(&[("d1", 30, 10), ("d2", 20, 20)])
+    );
+    assert_eq!(top_delegates(&deps, Some(1)), top(&[("d1", 30, 10)]));
+
+    // and shrinks back to 20
+    let msg = Cw721HookMsg::Withdraw {
+        amount: Uint128::from(500u128),
+    };
+    hook(&mut deps, mock_env(), "a", msg).unwrap();
+    assert_eq!(delegated_in_share(&deps, "d1"), 20);
+}
+
+#[test]
+fn undelegation_reverses_exactly() {
+    let mut deps = setup_delegated();
+    mint(&mut deps, "a", 800);
+    hook(&mut deps, mock_env(), "b", Cw721HookMsg::UnDelegateVote {}).unwrap();
+    delegate(&mut deps, "b", "d1");
+    // "b" moved from "d2" to "d1"
+    assert_eq!(delegated_in_share(&deps, "d1"), 50);
+    assert_eq!(delegated_in_share(&deps, "d2"), 0);
+    assert_eq!(top_delegates(&deps, None), top(&[("d1", 50, 10)]));
+
+    hook(&mut deps, mock_env(), "a", Cw721HookMsg::UnDelegateVote {}).unwrap();
+    assert_eq!(delegated_in_share(&deps, "d1"), 20);
+    hook(&mut deps, mock_env(), "b", Cw721HookMsg::UnDelegateVote {}).unwrap();
+    assert_eq!(delegated_in_share(&deps, "d1"), 0);
+    assert!(top_delegates(&deps, None).is_empty());
+}
@@ -0,0 +1,183 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{from_slice, to_vec};
+
+use super::*;
+use crate::utils::{PollStatus, StateTransition, STATE_TRANSITION_VERSION};
+
+fn transitions(res: &Response) -> Vec<StateTransition> {
+    res.attributes
+        .iter()
+        .filter(|a| a.key == "state_transition")
+        .map(|a| from_slice(a.value.as_bytes()).unwrap())
+        .collect()
+}
+
+/// the only transition of res, checked against poll_id, from and to
+fn single(
+    res: &Response,
+    poll_id: u64,
+    from: Option<PollStatus>,
+    to: Option<PollStatus>,
+) -> StateTransition {
+    let mut all = transitions(res);
+    assert_eq!(all.len(), 1);
+    let transition = all.remove(0);
+    assert_eq!(transition.v, STATE_TRANSITION_VERSION);
+    assert_eq!(transition.poll_id, poll_id);
+    assert_eq!(transition.from, from);
+    assert_eq!(transition.to, to);
+    transition
+}
+
+#[test]
+fn state_transition_json_is_pinned() {
+    // indexers parse this value, any change here needs STATE_TRANSITION_VERSION bumped
+    let transition = StateTransition {
+        v: 1,
+        poll_id: 7,
+        from: Some(PollStatus::InProgress),
+        to: Some(PollStatus::Passed),
+        yes: Uint128::from(30u128),
+        no: Uint128::from(4u128),
+        height: 12345,
+    };
+    let json = r#"{"v":1,"poll_id":7,"from":"in_progress","to":"passed","yes":"30","no":"4","height":12345}"#;
+    assert_eq!(
+        String::from_utf8(to_vec(&transition).unwrap()).unwrap(),
+        json
+    );
+    assert_eq!(transition.attribute().unwrap().value, json);
+    assert_eq!(transition.attribute().unwrap().key, "state_transition");
+    assert_eq!(STATE_TRANSITION_VERSION, 1);
+
+    let created = StateTransition {
+        from: None,
+        to: Some(PollStatus::Draft),
+        ..transition.clone()
+    };
+    let removed = StateTransition {
+        from: Some(PollStatus::Draft),
+        to: None,
+        ..transition
+    };
+    assert_eq!(
+        String::from_utf8(to_vec(&created).unwrap()).unwrap(),
+        r#"{"v":1,"poll_id":7,"from":null,"to":"draft","yes":"30","no":"4","height":12345}"#
+    );
+    assert_eq!(
+        String::from_utf8(to_vec(&removed).unwrap()).unwrap(),
+        r#"{"v":1,"poll_id":7,"from":"draft","to":null,"yes":"30","no":"4","height":12345}"#
+    );
+}
+
+#[test]
+fn created_published_and_pruned_polls() {
+    let mut msg = init_msg();
+    msg.draft_ttl = Some(10);
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+
+    let res = hook(&mut deps, mock_env(), "1", create_poll_msg("open")).unwrap();
+    let open: u64 = attr(&res, "poll_id").parse().unwrap();
+    let created = single(&res, open, None, Some(PollStatus::InProgress));
+    assert_eq!(created.height, mock_env().block.height);
+    assert!(created.yes.is_zero() && created.no.is_zero());
+
+    let res = hook(&mut deps, mock_env(), "1", create_draft_msg("published")).unwrap();
+    let published: u64 = attr(&res, "poll_id").parse().unwrap();
+    single(&res, published, None, Some(PollStatus::Draft));
+    let msg = Cw721HookMsg::PublishPoll { poll_id: published };
+    let res = hook(&mut deps, mock_env(), "1", msg).unwrap();
+    single(
+        &res,
+        published,
+        Some(PollStatus::Draft),
+        Some(PollStatus::InProgress),
+    );
+
+    let res = hook(&mut deps, mock_env(), "1", create_draft_msg("pruned")).unwrap();
+    let pruned: u64 = attr(&res, "poll_id").parse().unwrap();
+    let later = env_at(mock_env().block.height + 10);
+    let res = exec_as(&mut deps, later.clone(), ADMIN, ExecuteMsg::PruneDrafts {}).unwrap();
+    let removed = single(&res, pruned, Some(PollStatus::Draft), None);
+    assert_eq!(removed.height, later.block.height);
+}
+
+#[test]
+fn votes_update_the_tally() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 400);
+    mint(&mut deps, "2", 100);
+    let first = create_poll(&mut deps, "1", "first");
+    let second = create_poll(&mut deps, "1", "second");
+    let in_progress = Some(PollStatus::InProgress);
+
+    let res = cast_vote(&mut deps, mock_env(), "1", first, VoteOption::Yes).unwrap();
+    let voted = single(&res, first, in_progress.clone(), in_progress.clone());
+    assert_eq!(voted.yes, Uint128::from(20u128));
+    assert!(voted.no.is_zero());
+
+    let msg = Cw721HookMsg::CancelVote { poll_id: first };
+    let res = hook(&mut deps, mock_env(), "1", msg).unwrap();
+    let cancelled = single(&res, first, in_progress.clone(), in_progress.clone());
+    assert!(cancelled.yes.is_zero());
+
+    // one transition per poll of CastVotes
+    let msg = Cw721HookMsg::CastVotes {
+        votes: vec![(first, VoteOption::No), (second, VoteOption::Yes)],
+    };
+    let res = hook(&mut deps, mock_env(), "2", msg).unwrap();
+    let all = transitions(&res);
+    assert_eq!(
+        all.iter().map(|t| t.poll_id).collect::<Vec<u64>>(),
+        vec![first, second]
+    );
+    assert_eq!(all[0].no, Uint128::from(10u128));
+    assert_eq!(all[1].yes, Uint128::from(10u128));
+}
+
+#[test]
+fn withdrawn_and_ended_polls() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 400);
+    let withdrawn = create_poll(&mut deps, "1", "withdrawn");
+    let ended = create_poll(&mut deps, "1", "ended");
+
+    let msg = Cw721HookMsg::WithdrawPoll { poll_id: withdrawn };
+    let res = hook(&mut deps, mock_env(), "1", msg).unwrap();
+    single(
+        &res,
+        withdrawn,
+        Some(PollStatus::InProgress),
+        Some(PollStatus::Cancelled),
+    );
+
+    cast_vote(&mut deps, mock_env(), "1", ended, VoteOption::Yes).unwrap();
+    let after_voting = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    let msg = Cw721HookMsg::EndPoll { poll_id: ended };
+    let res = hook(&mut deps, after_voting.clone(), "1", msg).unwrap();
+    let transition = single(
+        &res,
+        ended,
+        Some(PollStatus::InProgress),
+        Some(PollStatus::Passed),
+    );
+    assert_eq!(transition.yes, Uint128::from(20u128));
+    assert_eq!(transition.height, after_voting.block.height);
+}
+
+#[test]
+fn rebind_withdraws_votes_of_open_polls() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 400);
+    mint(&mut deps, "2", 100);
+    let poll_id = create_poll(&mut deps, "2", "open");
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    cast_vote(&mut deps, mock_env(), "2", poll_id, VoteOption::No).unwrap();
+
+    let res = hook(&mut deps, mock_env(), "1", Cw721HookMsg::Rebind {}).unwrap();
+    let in_progress = Some(PollStatus::InProgress);
+    let transition = single(&res, poll_id, in_progress.clone(), in_progress);
+    assert!(transition.yes.is_zero());
+    assert_eq!(transition.no, Uint128::from(10u128));
+}
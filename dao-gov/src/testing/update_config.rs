@@ -0,0 +1,71 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{Order, StdError, Storage};
+
+use super::*;
+use crate::utils::ConfigResponse;
+
+fn storage_snapshot(deps: &MockDeps) -> Vec<(Vec<u8>, Vec<u8>)> {
+    deps.storage.range(None, None, Order::Ascending).collect()
+}
+
+fn end_after_voting(deps: &mut MockDeps, poll_id: u64) -> Response {
+    let ended = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    hook(deps, ended, "1", Cw721HookMsg::EndPoll { poll_id }).unwrap()
+}
+
+#[test]
+fn config_change_mid_poll_keeps_its_outcome() {
+    // "1" has 10 of total share 100, just the 10% quorum
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 8100);
+    let open = create_poll(&mut deps, "1", "snapshotted");
+    cast_vote(&mut deps, mock_env(), "1", open, VoteOption::Yes).unwrap();
+
+    let msg = update_config_msg(r#"{"quorum":"0.5"}"#);
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+    let later = create_poll(&mut deps, "1", "stricter");
+    cast_vote(&mut deps, mock_env(), "1", later, VoteOption::Yes).unwrap();
+
+    let res = end_after_voting(&mut deps, open);
+    assert_eq!(attr(&res, "passed"), "true");
+    let res = end_after_voting(&mut deps, later);
+    assert_eq!(attr(&res, "passed"), "false");
+    assert_eq!(attr(&res, "quorum_met"), "false");
+}
+
+#[test]
+fn quorum_above_one_is_rejected() {
+    let mut deps = setup();
+    for fields in [r#"{"quorum":"1.01"}"#, r#"{"threshold":"1.5"}"#].iter() {
+        let err = exec_as(&mut deps, mock_env(), ADMIN, update_config_msg(fields)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Std(StdError::GenericErr { .. })
+        ));
+    }
+    let config: ConfigResponse = query_as(&deps, QueryMsg::Config {});
+    assert_eq!(config.quorum, init_msg().quorum);
+    assert_eq!(config.threshold, init_msg().threshold);
+}
+
+#[test]
+fn dry_run_leaves_storage_untouched() {
+    let mut deps = setup();
+    let before = storage_snapshot(&deps);
+    let msg = update_config_msg(r#"{"quorum":"0.3","voting_period":500,"dry_run":true}"#);
+    let res = exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+    assert_eq!(attr(&res, "dry_run"), "true");
+    assert_eq!(attr(&res, "quorum"), "0.3");
+    assert_eq!(attr(&res, "voting_period"), "500");
+    assert_eq!(storage_snapshot(&deps), before);
+
+    // validated like a real update
+    let msg = update_config_msg(r#"{"quorum":"2","dry_run":true}"#);
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap_err();
+    assert_eq!(storage_snapshot(&deps), before);
+
+    let msg = update_config_msg(r#"{"quorum":"0.3"}"#);
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+    assert_ne!(storage_snapshot(&deps), before);
+}
@@ -0,0 +1,140 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::ValidateHookResponse;
+
+/// previews hook, then sends it: both must agree on validity and error
+/// returns whether the hook was executed
+fn preview_then_execute(
+    deps: &mut MockDeps,
+    env: Env,
+    member_id: &str,
+    hook_msg: Cw721HookMsg,
+) -> bool {
+    let preview: ValidateHookResponse = query_at(
+        deps,
+        env.clone(),
+        QueryMsg::ValidateHook {
+            member_id: member_id.to_string(),
+            hook: hook_msg.clone(),
+        },
+    );
+    let res = hook(deps, env, member_id, hook_msg.clone());
+    assert_eq!(
+        preview.error,
+        res.as_ref().err().map(|err| err.to_string()),
+        "{:?} by {}",
+        hook_msg,
+        member_id
+    );
+    assert_eq!(preview.valid, res.is_ok());
+    res.is_ok()
+}
+
+fn vote(poll_id: u64) -> Cw721HookMsg {
+    Cw721HookMsg::CastVote {
+        poll_id,
+        vote: VoteOption::Yes,
+        conviction: None,
+    }
+}
+
+fn delegate(delegatee: &str) -> Cw721HookMsg {
+    Cw721HookMsg::DelegateVote {
+        delegator: delegatee.to_string(),
+    }
+}
+
+#[test]
+fn preview_agrees_with_execute() {
+    let mut deps = setup();
+    for member_id in ["1", "2", "3"].iter() {
+        mint(&mut deps, member_id, 100);
+    }
+    let poll_id = create_poll(&mut deps, "1", "preview");
+    let env = mock_env();
+    let after_voting = env_at(env.block.height + VOTING_PERIOD + 1);
+
+    // (member, hook, expected to be executed)
+    let matrix = vec![
+        ("1", vote(poll_id + 1), false),
+        ("1", vote(poll_id), true),
+        ("1", vote(poll_id), false),
+        ("1", Cw721HookMsg::CancelVote { poll_id }, true),
+        ("1", Cw721HookMsg::CancelVote { poll_id }, false),
+        ("2", Cw721HookMsg::UnDelegateVote {}, false),
+        ("2", delegate("2"), false),
+        ("2", delegate("9"), false),
+        ("2", delegate("3"), true),
+        ("2", delegate("1"), false),
+        ("2", vote(poll_id), false),
+        ("2", Cw721HookMsg::UnDelegateVote {}, true),
+        ("3", vote(poll_id), true),
+        ("3", Cw721HookMsg::Exit {}, false),
+        ("2", create_poll_msg("no"), false),
+        ("2", create_poll_msg("second"), true),
+        ("2", Cw721HookMsg::Exit {}, true),
+        ("9", create_poll_msg("stranger"), true),
+    ];
+    for (step, (member_id, hook_msg, executed)) in matrix.into_iter().enumerate() {
+        let res = preview_then_execute(&mut deps, env.clone(), member_id, hook_msg.clone());
+        assert_eq!(
+            res, executed,
+            "step {}: {:?} by {}",
+            step, hook_msg, member_id
+        );
+    }
+
+    // voting period is over
+    assert!(!preview_then_execute(
+        &mut deps,
+        after_voting,
+        "1",
+        vote(poll_id)
+    ));
+}
+
+#[test]
+fn suspended_member_preview_fails_like_execute() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    mint(&mut deps, "2", 100);
+    let poll_id = create_poll(&mut deps, "1", "suspended");
+    let msg = ExecuteMsg::SuspendMember {
+        member_id: "2".to_string(),
+        until_height: mock_env().block.height + 10,
+    };
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+
+    assert!(!preview_then_execute(
+        &mut deps,
+        mock_env(),
+        "2",
+        vote(poll_id)
+    ));
+    assert!(!preview_then_execute(
+        &mut deps,
+        mock_env(),
+        "2",
+        create_poll_msg("suspended")
+    ));
+    let env = env_at(mock_env().block.height + 10);
+    assert!(preview_then_execute(&mut deps, env, "2", vote(poll_id)));
+}
+
+#[test]
+fn other_hooks_are_not_validatable() {
+    let deps = setup();
+    let preview: ValidateHookResponse = query_as(
+        &deps,
+        QueryMsg::ValidateHook {
+            member_id: "1".to_string(),
+            hook: Cw721HookMsg::Rebind {},
+        },
+    );
+    assert!(!preview.valid);
+    assert_eq!(
+        preview.error,
+        Some(ContractError::HookNotValidatable {}.to_string())
+    );
+}
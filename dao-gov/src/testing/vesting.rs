@@ -0,0 +1,146 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{PollResponse, StakerResponse};
+
+const START: u64 = 12345;
+const RELEASE: u64 = START + 2 * VOTING_PERIOD;
+
+fn mint_locked(deps: &mut MockDeps, member_id: &str, amount: u128, until_height: Option<u64>) {
+    let msg = ExecuteMsg::MintLocked {
+        recipient: member_id.to_string(),
+        amount: Uint128::from(amount),
+        until_height,
+    };
+    exec_as(deps, mock_env(), ADMIN, msg).unwrap();
+}
+
+fn member_at(deps: &MockDeps, height: u64, member_id: &str) -> StakerResponse {
+    query_at(
+        deps,
+        env_at(height),
+        QueryMsg::Member {
+            member_id: member_id.to_string(),
+        },
+    )
+}
+
+fn release(deps: &mut MockDeps, height: u64) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::ReleaseVested {
+        member_id: "founder".to_string(),
+    };
+    exec_as(deps, env_at(height), "anyone", msg)
+}
+
+fn vested_locked(until: Option<u64>) -> ContractError {
+    ContractError::VestedLocked {
+        amount: Uint128::from(900u128),
+        until,
+    }
+}
+
+#[test]
+fn vested_balance_votes_but_stays_until_release() {
+    let mut deps = setup();
+    mint_locked(&mut deps, "founder", 900, Some(RELEASE));
+    let founder = member_at(&deps, START, "founder");
+    assert_eq!(founder.share, Uint128::from(30u128));
+    assert_eq!(founder.vested_locked, Uint128::from(900u128));
+    assert_eq!(founder.vested_until, Some(RELEASE));
+
+    let poll_id = create_poll(&mut deps, "founder", "vested");
+    cast_vote(&mut deps, mock_env(), "founder", poll_id, VoteOption::Yes).unwrap();
+    let a_poll: PollResponse = query_as(
+        &deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    assert_eq!(a_poll.yes_votes, Uint128::from(30u128));
+
+    // after the poll ended, only vesting holds the balance
+    let ended = START + VOTING_PERIOD + 1;
+    exec_as(
+        &mut deps,
+        env_at(ended),
+        "ender0000",
+        ExecuteMsg::EndPoll { poll_id },
+    )
+    .unwrap();
+    let err = hook(&mut deps, env_at(ended), "founder", Cw721HookMsg::Exit {}).unwrap_err();
+    assert_eq!(err, vested_locked(Some(RELEASE)));
+    let msg = Cw721HookMsg::Withdraw {
+        amount: Uint128::from(1u128),
+    };
+    let err = hook(&mut deps, env_at(ended), "founder", msg).unwrap_err();
+    assert_eq!(err, vested_locked(Some(RELEASE)));
+    let msg = ExecuteMsg::TransferFrom {
+        owner: "founder".to_string(),
+        recipient: "2".to_string(),
+        amount: Uint128::from(1u128),
+    };
+    let err = exec_as(&mut deps, env_at(ended), ADMIN, msg).unwrap_err();
+    assert_eq!(err, vested_locked(Some(RELEASE)));
+
+    let err = release(&mut deps, RELEASE - 1).unwrap_err();
+    assert_eq!(err, vested_locked(Some(RELEASE)));
+    let res = release(&mut deps, RELEASE).unwrap();
+    assert_eq!(attr(&res, "released"), "900");
+    assert!(member_at(&deps, RELEASE, "founder").vested_locked.is_zero());
+    assert_eq!(
+        release(&mut deps, RELEASE).unwrap_err(),
+        ContractError::NothingVested {}
+    );
+
+    hook(&mut deps, env_at(RELEASE), "founder", Cw721HookMsg::Exit {}).unwrap();
+}
+
+#[test]
+fn permanent_vesting_is_never_released() {
+    let mut deps = setup();
+    mint_locked(&mut deps, "founder", 900, None);
+    mint(&mut deps, "founder", 100);
+    let far = START + 1_000_000;
+    assert_eq!(release(&mut deps, far).unwrap_err(), vested_locked(None));
+
+    // the freely minted part can be withdrawn
+    let withdraw = |amount: u128| Cw721HookMsg::Withdraw {
+        amount: Uint128::from(amount),
+    };
+    let err = hook(&mut deps, env_at(far), "founder", withdraw(101)).unwrap_err();
+    assert_eq!(err, vested_locked(None));
+    hook(&mut deps, env_at(far), "founder", withdraw(100)).unwrap();
+}
+
+#[test]
+fn amount_above_balance_without_vesting_is_invalid() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let msg = Cw721HookMsg::Withdraw {
+        amount: Uint128::from(101u128),
+    };
+    let err = hook(&mut deps, mock_env(), "1", msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidWithdrawAmount {});
+}
+
+#[test]
+fn release_height_must_be_in_the_future() {
+    let mut deps = setup();
+    for until_height in [START - 1, START].iter() {
+        let msg = ExecuteMsg::MintLocked {
+            recipient: "founder".to_string(),
+            amount: Uint128::from(900u128),
+            until_height: Some(*until_height),
+        };
+        let err = exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::VestingInPast {
+                until_height: *until_height
+            }
+        );
+    }
+    mint_locked(&mut deps, "founder", 900, Some(START + 1));
+    assert_eq!(member_at(&deps, START, "founder").balance.u128(), 900);
+}
@@ -0,0 +1,100 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Decimal;
+
+use super::*;
+use crate::state::poll_voter_read;
+use crate::utils::PollResponse;
+
+/// shares: whale 100, small 10, delegate 10 with delegators e and f 30 each (total 180)
+/// a fifth of the total caps each member at 36
+fn setup_capped() -> MockDeps {
+    let mut msg = init_msg();
+    msg.max_vote_share = Some(Decimal::percent(20));
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "whale", 10000);
+    mint(&mut deps, "small", 100);
+    mint(&mut deps, "delegate", 100);
+    mint(&mut deps, "e", 900);
+    mint(&mut deps, "f", 900);
+    for member_id in ["e", "f"].iter() {
+        let msg = Cw721HookMsg::DelegateVote {
+            delegator: "delegate".to_string(),
+        };
+        hook(&mut deps, mock_env(), member_id, msg).unwrap();
+    }
+    deps
+}
+
+fn poll(deps: &MockDeps, poll_id: u64) -> PollResponse {
+    query_as(
+        deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    )
+}
+
+fn voter_capped(deps: &MockDeps, poll_id: u64, member_id: &str) -> bool {
+    poll_voter_read(&deps.storage, poll_id)
+        .load(member_id.as_bytes())
+        .unwrap()
+        .capped_balance
+        .is_some()
+}
+
+fn has_attr(res: &Response, key: &str) -> bool {
+    res.attributes.iter().any(|a| a.key == key)
+}
+
+#[test]
+fn whale_is_capped() {
+    let mut deps = setup_capped();
+    let poll_id = create_poll(&mut deps, "small", "whale");
+    let res = cast_vote(&mut deps, mock_env(), "whale", poll_id, VoteOption::Yes).unwrap();
+    assert_eq!(attr(&res, "capped"), "true");
+    assert_eq!(poll(&deps, poll_id).yes_votes, Uint128::from(36u128));
+    assert!(voter_capped(&deps, poll_id, "whale"));
+
+    // cancel takes back the capped amount
+    let msg = Cw721HookMsg::CancelVote { poll_id };
+    hook(&mut deps, mock_env(), "whale", msg).unwrap();
+    assert_eq!(poll(&deps, poll_id).yes_votes, Uint128::zero());
+}
+
+#[test]
+fn small_holder_is_not_capped() {
+    let mut deps = setup_capped();
+    let poll_id = create_poll(&mut deps, "small", "small");
+    let res = cast_vote(&mut deps, mock_env(), "small", poll_id, VoteOption::No).unwrap();
+    assert!(!has_attr(&res, "capped"));
+    assert_eq!(poll(&deps, poll_id).no_votes, Uint128::from(10u128));
+    assert!(!voter_capped(&deps, poll_id, "small"));
+}
+
+#[test]
+fn delegated_shares_are_capped_per_delegator() {
+    let mut deps = setup_capped();
+    let poll_id = create_poll(&mut deps, "small", "delegated");
+    // 10 + 30 + 30 is over the cap, but every member stays under it
+    let res = cast_vote(&mut deps, mock_env(), "delegate", poll_id, VoteOption::Yes).unwrap();
+    assert!(!has_attr(&res, "capped"));
+    assert!(!has_attr(&res, "capped_delegators"));
+    assert_eq!(poll(&deps, poll_id).yes_votes, Uint128::from(70u128));
+}
+
+#[test]
+fn zero_removes_the_cap() {
+    let mut deps = setup_capped();
+    let msg = update_config_msg(r#"{"max_vote_share":"0"}"#);
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap();
+
+    let poll_id = create_poll(&mut deps, "small", "uncapped");
+    let res = cast_vote(&mut deps, mock_env(), "whale", poll_id, VoteOption::Yes).unwrap();
+    assert!(!has_attr(&res, "capped"));
+    assert_eq!(poll(&deps, poll_id).yes_votes, Uint128::from(100u128));
+
+    // out of range values are still rejected
+    let msg = update_config_msg(r#"{"max_vote_share":"1.5"}"#);
+    exec_as(&mut deps, mock_env(), ADMIN, msg).unwrap_err();
+}
@@ -0,0 +1,38 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::StdError;
+use std::convert::TryFrom;
+
+use super::*;
+
+#[test]
+fn vote_option_round_trips_through_string() {
+    let options = vec![
+        VoteOption::Yes,
+        VoteOption::No,
+        VoteOption::Choice(0),
+        VoteOption::Choice(42),
+    ];
+    for option in options {
+        let text = option.to_string();
+        assert_eq!(text.parse::<VoteOption>().unwrap(), option);
+        assert_eq!(VoteOption::try_from(text.as_str()).unwrap(), option);
+    }
+}
+
+#[test]
+fn unknown_vote_option_is_a_parse_error() {
+    for text in ["", "Yes", "abstain", "choice_", "choice_x", "choice_-1"].iter() {
+        let err = text.parse::<VoteOption>().unwrap_err();
+        assert!(matches!(err, StdError::ParseErr { .. }), "{}", text);
+    }
+}
+
+#[test]
+fn vote_option_attribute_parses_back() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 100);
+    let poll_id = create_poll(&mut deps, "1", "attribute");
+    let res = cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::No).unwrap();
+    let vote: VoteOption = attr(&res, "vote_option").parse().unwrap();
+    assert_eq!(vote, VoteOption::No);
+}
@@ -0,0 +1,111 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{OrderBy, VoterCountResponse, VotersResponse};
+
+/// members 1, 3 and 5 vote yes, 2 and 4 vote no
+fn setup_voted() -> (MockDeps, u64) {
+    let mut deps = setup();
+    for member_id in ["1", "2", "3", "4", "5"].iter() {
+        mint(&mut deps, member_id, 100);
+    }
+    let poll_id = create_poll(&mut deps, "1", "filtered");
+    for (member_id, vote) in [
+        ("1", VoteOption::Yes),
+        ("2", VoteOption::No),
+        ("3", VoteOption::Yes),
+        ("4", VoteOption::No),
+        ("5", VoteOption::Yes),
+    ]
+    .iter()
+    {
+        cast_vote(&mut deps, mock_env(), member_id, poll_id, vote.clone()).unwrap();
+    }
+    (deps, poll_id)
+}
+
+fn voters(
+    deps: &MockDeps,
+    poll_id: u64,
+    start_after: Option<&str>,
+    limit: Option<u32>,
+    vote_filter: Option<VoteOption>,
+) -> Vec<String> {
+    let res: VotersResponse = query_as(
+        deps,
+        QueryMsg::Voters {
+            poll_id,
+            start_after: start_after.map(|voter| voter.to_string()),
+            limit,
+            order_by: Some(OrderBy::Asc),
+            vote_filter,
+        },
+    );
+    res.voters.into_iter().map(|item| item.voter).collect()
+}
+
+#[test]
+fn yes_only_voters() {
+    let (deps, poll_id) = setup_voted();
+    assert_eq!(
+        voters(&deps, poll_id, None, None, None),
+        vec!["1", "2", "3", "4", "5"]
+    );
+    assert_eq!(
+        voters(&deps, poll_id, None, None, Some(VoteOption::Yes)),
+        vec!["1", "3", "5"]
+    );
+    assert_eq!(
+        voters(&deps, poll_id, None, None, Some(VoteOption::No)),
+        vec!["2", "4"]
+    );
+    assert_eq!(
+        voters(&deps, poll_id, Some("1"), None, Some(VoteOption::Yes)),
+        vec!["3", "5"]
+    );
+    // limit counts matching voters
+    assert_eq!(
+        voters(&deps, poll_id, None, Some(2), Some(VoteOption::Yes)),
+        vec!["1", "3"]
+    );
+}
+
+fn voter_count(deps: &MockDeps, poll_id: u64) -> VoterCountResponse {
+    query_as(deps, QueryMsg::VoterCount { poll_id })
+}
+
+#[test]
+fn voter_count_includes_delegated_votes() {
+    let mut deps = setup();
+    for member_id in ["1", "2", "3", "4"].iter() {
+        mint(&mut deps, member_id, 100);
+    }
+    for delegator in ["2", "3"].iter() {
+        let msg = Cw721HookMsg::DelegateVote {
+            delegator: "1".to_string(),
+        };
+        hook(&mut deps, mock_env(), delegator, msg).unwrap();
+    }
+    let poll_id = create_poll(&mut deps, "1", "counted");
+    assert_eq!(voter_count(&deps, poll_id).voter_count, 0);
+
+    // the delegate's vote records one entry per delegator
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    assert_eq!(voter_count(&deps, poll_id).voter_count, 3);
+    cast_vote(&mut deps, mock_env(), "4", poll_id, VoteOption::No).unwrap();
+    let count = voter_count(&deps, poll_id);
+    assert_eq!(count.voter_count, 4);
+    assert!(!count.capped);
+
+    let msg = Cw721HookMsg::CancelVote { poll_id };
+    hook(&mut deps, mock_env(), "1", msg).unwrap();
+    assert_eq!(voter_count(&deps, poll_id).voter_count, 1);
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::VoterCount { poll_id: 99 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PollNotFound {});
+}
@@ -0,0 +1,98 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::state::poll_voter_read;
+use crate::utils::{PollResponse, StakerResponse};
+
+const NEW_HOLDER: &str = "newholder0000";
+
+/// hook sent by cw721 after the NFT of member_id moved to wallet
+fn hook_from(
+    deps: &mut MockDeps,
+    wallet: &str,
+    member_id: &str,
+    hook_msg: Cw721HookMsg,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+        sender: wallet.to_string(),
+        token_id: member_id.to_string(),
+        msg: to_binary(&hook_msg).unwrap(),
+    });
+    exec_as(deps, mock_env(), NFT, msg)
+}
+
+fn vote_msg(poll_id: u64) -> Cw721HookMsg {
+    Cw721HookMsg::CastVote {
+        poll_id,
+        vote: VoteOption::Yes,
+        conviction: None,
+    }
+}
+
+fn member_wallet(deps: &MockDeps, member_id: &str) -> Option<String> {
+    let member: StakerResponse = query_as(
+        deps,
+        QueryMsg::Member {
+            member_id: member_id.to_string(),
+        },
+    );
+    member.wallet
+}
+
+fn voter_wallet(deps: &MockDeps, poll_id: u64, member_id: &str) -> String {
+    poll_voter_read(&deps.storage, poll_id)
+        .load(member_id.as_bytes())
+        .unwrap()
+        .wallet
+}
+
+/// member 1 created a poll and voted on it from its first wallet
+fn setup_voted(bind_wallet: bool) -> (MockDeps, u64) {
+    let mut msg = init_msg();
+    msg.bind_wallet = Some(bind_wallet);
+    let mut deps = setup_with(msg);
+    mint(&mut deps, "1", 100);
+    let poll_id = create_poll(&mut deps, "1", "bound");
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    (deps, poll_id)
+}
+
+#[test]
+fn new_holder_rebinds_before_voting() {
+    let (mut deps, poll_id) = setup_voted(true);
+    let a_poll: PollResponse = query_as(
+        &deps,
+        QueryMsg::Poll {
+            poll_id,
+            member_id: None,
+        },
+    );
+    assert_eq!(a_poll.creator_wallet, wallet_of("1"));
+    assert_eq!(voter_wallet(&deps, poll_id, "1"), wallet_of("1"));
+
+    // the NFT moved to NEW_HOLDER
+    let err = hook_from(&mut deps, NEW_HOLDER, "1", create_poll_msg("other")).unwrap_err();
+    assert_eq!(err, ContractError::WalletMismatch {});
+    assert_eq!(member_wallet(&deps, "1"), Some(wallet_of("1")));
+
+    let res = hook_from(&mut deps, NEW_HOLDER, "1", Cw721HookMsg::Rebind {}).unwrap();
+    assert_eq!(attr(&res, "withdrawn_polls"), poll_id.to_string());
+    assert_eq!(member_wallet(&deps, "1"), Some(NEW_HOLDER.to_string()));
+
+    // the vote of the previous holder was withdrawn, the new holder votes again
+    hook_from(&mut deps, NEW_HOLDER, "1", vote_msg(poll_id)).unwrap();
+    assert_eq!(voter_wallet(&deps, poll_id, "1"), NEW_HOLDER);
+
+    // the previous holder is now the mismatched one
+    let err = hook_from(&mut deps, &wallet_of("1"), "1", create_poll_msg("other")).unwrap_err();
+    assert_eq!(err, ContractError::WalletMismatch {});
+}
+
+#[test]
+fn unbound_wallet_follows_the_holder() {
+    let (mut deps, poll_id) = setup_voted(false);
+    hook_from(&mut deps, NEW_HOLDER, "1", create_poll_msg("other")).unwrap();
+    assert_eq!(member_wallet(&deps, "1"), Some(NEW_HOLDER.to_string()));
+    // the vote keeps the wallet that sent it
+    assert_eq!(voter_wallet(&deps, poll_id, "1"), wallet_of("1"));
+}
@@ -0,0 +1,183 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::utils::{unlock_schedule, FreeShareResponse, WithdrawableResponse};
+
+fn locks(heights_and_balances: &[(u64, u128)]) -> Vec<(u64, Uint128)> {
+    heights_and_balances
+        .iter()
+        .map(|(end_height, balance)| (*end_height, Uint128::from(*balance)))
+        .collect()
+}
+
+fn withdrawable(deps: &MockDeps, member_id: &str, at_height: Option<u64>) -> WithdrawableResponse {
+    query_as(
+        deps,
+        QueryMsg::Withdrawable {
+            member_id: member_id.to_string(),
+            at_height,
+        },
+    )
+}
+
+#[test]
+fn unlock_schedule_releases_above_outliving_locks() {
+    assert_eq!(unlock_schedule(&[]), vec![]);
+    assert_eq!(unlock_schedule(&locks(&[(100, 50)])), locks(&[(100, 50)]));
+    // a larger lock ending later holds everything until it ends
+    assert_eq!(
+        unlock_schedule(&locks(&[(100, 30), (200, 50)])),
+        locks(&[(200, 50)])
+    );
+    // a larger lock ending first frees only its excess
+    assert_eq!(
+        unlock_schedule(&locks(&[(200, 30), (100, 50)])),
+        locks(&[(100, 20), (200, 30)])
+    );
+    // locks ending at the same height are released together
+    assert_eq!(
+        unlock_schedule(&locks(&[(100, 50), (150, 10), (100, 30)])),
+        locks(&[(100, 40), (150, 10)])
+    );
+    assert_eq!(
+        unlock_schedule(&locks(&[(100, 50), (100, 50)])),
+        locks(&[(100, 50)])
+    );
+}
+
+#[test]
+fn withdrawable_follows_locks_of_open_polls() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 400);
+    mint(&mut deps, "2", 100);
+    let height = mock_env().block.height;
+    let early = create_poll(&mut deps, "2", "early");
+    let res = hook(&mut deps, env_at(height + 5), "2", create_poll_msg("late")).unwrap();
+    let late: u64 = attr(&res, "poll_id").parse().unwrap();
+
+    // share 20 locks 400 until late ends
+    cast_vote(&mut deps, env_at(height + 5), "1", late, VoteOption::Yes).unwrap();
+    // share 30 locks 900 until early ends
+    mint(&mut deps, "1", 500);
+    cast_vote(&mut deps, env_at(height + 5), "1", early, VoteOption::Yes).unwrap();
+    mint(&mut deps, "1", 100);
+
+    let early_end = height + VOTING_PERIOD;
+    let late_end = height + 5 + VOTING_PERIOD;
+    assert_eq!(
+        withdrawable(&deps, "1", None),
+        WithdrawableResponse {
+            withdrawable_now: Uint128::from(100u128),
+            locked: Uint128::from(900u128),
+            unlock_schedule: locks(&[(early_end, 500), (late_end, 400)]),
+        }
+    );
+    assert_eq!(
+        withdrawable(&deps, "1", Some(early_end)),
+        WithdrawableResponse {
+            withdrawable_now: Uint128::from(600u128),
+            locked: Uint128::from(400u128),
+            unlock_schedule: locks(&[(late_end, 400)]),
+        }
+    );
+    assert_eq!(
+        withdrawable(&deps, "1", Some(late_end)).withdrawable_now,
+        Uint128::from(1000u128)
+    );
+
+    // withdraw accepts exactly withdrawable_now
+    let withdraw = |amount: u128| Cw721HookMsg::Withdraw {
+        amount: Uint128::from(amount),
+    };
+    let err = hook(&mut deps, env_at(height + 6), "1", withdraw(101)).unwrap_err();
+    assert_eq!(err, ContractError::InvalidWithdrawAmount {});
+    hook(&mut deps, env_at(height + 6), "1", withdraw(100)).unwrap();
+    assert!(withdrawable(&deps, "1", None).withdrawable_now.is_zero());
+}
+
+#[test]
+fn unknown_member_has_nothing_to_withdraw() {
+    let deps = setup();
+    assert_eq!(
+        withdrawable(&deps, "9", None),
+        WithdrawableResponse {
+            withdrawable_now: Uint128::zero(),
+            locked: Uint128::zero(),
+            unlock_schedule: vec![],
+        }
+    );
+}
+
+#[test]
+fn partial_withdraw_reduces_share() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 900);
+    let poll_id = create_poll(&mut deps, "1", "before");
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    mint(&mut deps, "1", 500);
+
+    // the vote keeps 900 locked
+    let msg = Cw721HookMsg::Withdraw {
+        amount: Uint128::from(500u128),
+    };
+    let res = hook(&mut deps, mock_env(), "1", msg).unwrap();
+    assert_eq!(attr(&res, "amount"), "500");
+    assert_eq!(attr(&res, "balance"), "900");
+    assert_eq!(attr(&res, "share"), "30");
+
+    let msg = Cw721HookMsg::Withdraw {
+        amount: Uint128::from(1u128),
+    };
+    let err = hook(&mut deps, mock_env(), "1", msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidWithdrawAmount {});
+
+    // once the poll ended, the share of a later vote follows the smaller balance
+    let ended = mock_env().block.height + VOTING_PERIOD + 1;
+    let msg = ExecuteMsg::EndPoll { poll_id };
+    exec_as(&mut deps, env_at(ended), "ender0000", msg).unwrap();
+    let msg = Cw721HookMsg::Withdraw {
+        amount: Uint128::from(500u128),
+    };
+    let res = hook(&mut deps, env_at(ended), "1", msg).unwrap();
+    assert_eq!(attr(&res, "share"), "20");
+    let res = hook(&mut deps, env_at(ended), "1", create_poll_msg("after")).unwrap();
+    let later: u64 = attr(&res, "poll_id").parse().unwrap();
+    let res = cast_vote(&mut deps, env_at(ended), "1", later, VoteOption::Yes).unwrap();
+    assert_eq!(attr(&res, "my_share"), "20");
+}
+
+fn free_share(deps: &MockDeps, member_id: &str) -> (u128, u128, u128) {
+    let res: FreeShareResponse = query_as(
+        deps,
+        QueryMsg::FreeShare {
+            member_id: member_id.to_string(),
+        },
+    );
+    (
+        res.share.u128(),
+        res.locked_share.u128(),
+        res.free_share.u128(),
+    )
+}
+
+#[test]
+fn active_vote_reduces_free_share() {
+    let mut deps = setup();
+    mint(&mut deps, "1", 400);
+    assert_eq!(free_share(&deps, "1"), (20, 0, 20));
+
+    let poll_id = create_poll(&mut deps, "1", "locking");
+    cast_vote(&mut deps, mock_env(), "1", poll_id, VoteOption::Yes).unwrap();
+    assert_eq!(free_share(&deps, "1"), (20, 20, 0));
+
+    // share gained after voting is free
+    mint(&mut deps, "1", 500);
+    assert_eq!(free_share(&deps, "1"), (30, 20, 10));
+
+    let ended = env_at(mock_env().block.height + VOTING_PERIOD + 1);
+    hook(&mut deps, ended, "1", Cw721HookMsg::EndPoll { poll_id }).unwrap();
+    assert_eq!(free_share(&deps, "1"), (30, 0, 30));
+
+    // unknown member has nothing
+    assert_eq!(free_share(&deps, "2"), (0, 0, 0));
+}
@@ -1,41 +1,283 @@
-use cosmwasm_std::{Decimal, StdError, StdResult, Uint128};
+use cosmwasm_std::{
+    attr, to_vec, Attribute, Binary, Decimal, Env, Fraction, Isqrt, StdError, StdResult, Timestamp,
+    Uint128,
+};
 
-use cosmwasm_std::{to_binary, Addr, Order, QuerierWrapper, QueryRequest, WasmQuery};
+use cosmwasm_std::{to_binary, Addr, QuerierWrapper, QueryRequest, WasmQuery};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use sha2::{Digest, Sha256};
 
+use crate::error::ContractError;
+use crate::msg::{AdminAction, SignedBallot};
+use crate::state::{Config, DrawnMember, EndedPoll, SeasonSnapshot, TokenManager};
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg};
+use cw721::{Cw721QueryMsg, NftInfoResponse};
+// shared with dao-cw721, re-exported so crate::utils paths keep working
+pub use dao_common::{OrderBy, PollLink, PollStatus, PollType, VoteOption, VotingExtension};
 
+/// rounding of share computed from balance (share = sqrt(balance))
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum OrderBy {
-    Asc,
-    Desc,
+pub enum RoundingMode {
+    Floor,
+    Nearest,
 }
 
-impl From<OrderBy> for Order {
-    fn from(o: OrderBy) -> Order {
-        if o == OrderBy::Asc {
-            Order::Ascending
-        } else {
-            Order::Descending
+impl Default for RoundingMode {
+    fn default() -> Self {
+        RoundingMode::Floor
+    }
+}
+
+impl RoundingMode {
+    /// share of balance
+    pub fn share(&self, balance: Uint128) -> Uint128 {
+        let n = balance.isqrt();
+        match self {
+            RoundingMode::Floor => n,
+            // (n+1)^2 is closer than n^2 if balance - n^2 > n
+            RoundingMode::Nearest => {
+                if balance.u128() - n.u128() * n.u128() > n.u128() {
+                    n + Uint128::from(1u128)
+                } else {
+                    n
+                }
+            }
+        }
+    }
+
+    /// minimum balance which has given share
+    pub fn min_balance(&self, share: u128) -> u128 {
+        match self {
+            RoundingMode::Floor => share.pow(2),
+            RoundingMode::Nearest => {
+                if share == 0 {
+                    0
+                } else {
+                    // share^2 - share + 1 without overflowing at share = 2^64
+                    share * (share - 1) + 1
+                }
+            }
+        }
+    }
+}
+
+// scale of DoubleQuadratic before the outer sqrt (share = balance^(1/4) * 1000)
+const DOUBLE_QUADRATIC_SCALE: u128 = 1_000_000;
+
+/// curve of share over balance
+/// fixed at instantiation since existing shares are not recomputed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VotingModel {
+    Linear,    // share = balance
+    Quadratic, // share = sqrt(balance), rounded by share_rounding
+    // share = isqrt(isqrt(balance) * 10^6), always floor
+    // isqrt(balance) < 2^64, so the product stays below 2^84 (no overflow)
+    DoubleQuadratic,
+}
+
+impl Default for VotingModel {
+    fn default() -> Self {
+        VotingModel::Quadratic
+    }
+}
+
+/// who can end polls
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EndPollPermission {
+    Anyone,       // any address (ExecuteMsg::EndPoll) or member (EndPoll hook)
+    OwnerOnly,    // an admin's address or a token held by an admin's wallet (admin_threshold 1)
+    TokenHolders, // members with share through the EndPoll hook only
+}
+
+impl Default for EndPollPermission {
+    fn default() -> Self {
+        EndPollPermission::Anyone
+    }
+}
+
+/// action in end_poll when yes and no votes tie at quorum
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TiePolicy {
+    Reject,     // decided by threshold as any other poll
+    ExtendOnce, // end_height pushed by voting_period once, then Reject
+    Pass,
+}
+
+impl Default for TiePolicy {
+    fn default() -> Self {
+        TiePolicy::Reject
+    }
+}
+
+impl VotingModel {
+    /// share of balance
+    pub fn share(&self, rounding: &RoundingMode, balance: Uint128) -> Uint128 {
+        match self {
+            VotingModel::Linear => balance,
+            VotingModel::Quadratic => rounding.share(balance),
+            VotingModel::DoubleQuadratic => {
+                (balance.isqrt() * Uint128::from(DOUBLE_QUADRATIC_SCALE)).isqrt()
+            }
+        }
+    }
+
+    /// minimum balance which has given share
+    pub fn min_balance(&self, rounding: &RoundingMode, share: u128) -> u128 {
+        match self {
+            VotingModel::Linear => share,
+            VotingModel::Quadratic => rounding.min_balance(share),
+            // share(b) >= s iff isqrt(b) >= ceil(s^2 / scale)
+            VotingModel::DoubleQuadratic => {
+                let root = (share.pow(2) + DOUBLE_QUADRATIC_SCALE - 1) / DOUBLE_QUADRATIC_SCALE;
+                root.pow(2)
+            }
+        }
+    }
+}
+
+/// maximum lengths of poll text (checked at poll creation)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TextLimits {
+    pub title_max: u32,
+    pub desc_max: u32,
+    pub link_max: u32, // legacy link
+}
+
+impl Default for TextLimits {
+    fn default() -> Self {
+        TextLimits {
+            title_max: 64,
+            desc_max: 1024,
+            link_max: 128,
         }
     }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
-    pub owner: String,
+    pub admins: Vec<String>,
+    pub admin_threshold: u32,
     pub quorum: Decimal,
     pub threshold: Decimal,
     pub voting_period: u64,
+    pub share_rounding: RoundingMode,
+    pub voting_model: VotingModel,
+    pub season_length: Option<u64>,
+    pub season_blackout: u64,
+    pub end_poll_reward: Uint128,
+    pub decay_after: Option<u64>,
+    pub decay_rate: Decimal,
+    pub decay_floor: Decimal,
+    pub bind_wallet: bool,
+    pub text_limits: TextLimits,
+    pub draft_ttl: Option<u64>,
+    pub threshold_inclusive: bool,
+    pub failed_poll_cooldown: u64,
+    pub signal_quorum: Option<Decimal>,
+    pub signal_threshold: Option<Decimal>,
+    pub max_extension: u64,
+    pub ibc_notify: bool,
+    pub tie_policy: TiePolicy,
+    pub auto_finalize: bool,
+    pub text_deposit: Uint128,
+    pub execute_deposit: Uint128,
+    pub end_poll_permission: EndPollPermission,
+    pub adaptive_quorum: bool,
+    pub vote_freeze_window: u64,
+    pub freeze_new_votes: bool,
+    pub voting_time: u64,
+    pub use_token_multiplier: bool,
+    pub max_multiplier: Decimal,
+    pub bootstrap_period: u64,
+    pub bootstrap_quorum: Decimal,
+    pub delegate_inactivity_timeout: Option<u64>,
+    pub max_vote_share: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct OwnerResponse {
+    pub owner: String,
+    pub admins: Vec<String>,
+    pub admin_threshold: u32,
+}
+
+/// block at which a query was evaluated (meta field of responses)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct ResponseMeta {
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+impl ResponseMeta {
+    pub fn new(env: &Env) -> Self {
+        ResponseMeta {
+            height: env.block.height,
+            time: env.block.time,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct StateResponse {
     pub poll_count: u64,
     pub total_share: Uint128,
+    pub total_supply: Uint128,
+    pub current_season: u64,
+    pub season_start_height: u64,
+    pub max_supply: Option<Uint128>,
+    pub supply_headroom: Option<Uint128>, // tokens admins can still mint under max_supply
+    pub instantiate_height: u64,
+    pub bootstrap_ends_at: u64, // first height end_poll uses the normal quorum
+    pub meta: ResponseMeta,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct AdminActionResponse {
+    pub id: u64,
+    pub action: AdminAction,
+    pub proposer: String,
+    pub approvals: Vec<String>,
+    pub expires: u64,
+    pub expired: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct AdminActionsResponse {
+    pub admin_actions: Vec<AdminActionResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct SeasonsResponse {
+    pub seasons: Vec<SeasonSnapshot>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct TotalSupplyResponse {
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct StorageVersionResponse {
+    pub contract: String,
+    pub version: String,
+    pub poll_count: u64,
+    pub stored_poll_count: u64, // polls in storage (pruned drafts are missing)
+    pub member_count: u64,
+    pub total_share: Uint128,
+    pub member_share_sum: Uint128, // sum of shares in bank, should equal total_share
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct VerifyInvariantsResponse {
+    pub matches: Option<bool>,       // None until the last page
+    pub computed: Uint128,           // sum of member shares up to this page (carried included)
+    pub stored: Uint128,             // state.total_share
+    pub next_cursor: Option<String>, // start_after for the next page, None if last page
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -44,18 +286,80 @@ pub struct PollResponse {
     pub creator: String,
     pub status: PollStatus,
     pub end_height: u64,
+    pub end_time: Option<Timestamp>, // voting also ends at end_time if set
     pub title: String,
-    pub description: String,
-    pub link: Option<String>,
+    pub summary: String,
+    pub description: Option<String>, // full text, only in single poll queries
+    pub link: Option<String>,        // url of the first link (legacy)
+    pub links: Vec<PollLink>,
     pub yes_votes: Uint128, // balance
     pub no_votes: Uint128,  // balance
     pub total_share_at_start_poll: Uint128,
     pub total_share_at_end_poll: Option<Uint128>,
+    pub deposit: Uint128,
+    pub deposit_claimed: bool, // deposit was refunded to creator
+    pub deposit_slashed: bool, // deposit was burned, poll ended without quorum
+    pub ended_by: Option<String>,
+    pub ended_at_height: u64,
+    pub creator_wallet: String,
+    pub quorum_reached_height: Option<u64>,
+    pub created_at_height: u64,
+    pub extended_blocks: u64,
+    pub tie_extended: bool,
+    pub poll_type: PollType,
+    pub kind: PollKind,
+    pub votes_per_option: Vec<Uint128>, // balance (multiple choice)
+    pub winning_option: Option<u32>,
+    pub depends_on: Option<u64>,
+    pub dependency_status: Option<PollStatus>,
+    pub force_ended: bool,
+    pub raw_quorum: Option<Decimal>, // participation at end before clamping
+    pub quorum_used: Option<Decimal>, // participation at end compared against quorum
+    pub participation_anomaly: bool, // tallied weight exceeded staked share at end
+    pub applied_quorum: Decimal,     // quorum end_poll compares against
+    pub applied_threshold: Decimal,  // threshold end_poll compares against
+    pub blocks_remaining: Option<u64>, // blocks left in voting period (in progress polls)
+    pub is_expired: bool,            // voting period is over but poll is not ended yet
+    pub freeze_starts_at: Option<u64>, // votes can't be cancelled or overridden from this height
+    // set only when Polls is queried with a viewer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub viewer_vote: Option<VoteOption>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub viewer_locked: Option<Uint128>, // share the viewer's vote locked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator_share: Option<Uint128>,
+    pub meta: ResponseMeta,
+}
+
+/// state of the deposit a poll holds
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DepositStatus {
+    None,     // no deposit (or refunded before refunds were recorded)
+    Locked,   // held until the poll is closed
+    Refunded, // returned to the creator
+    Slashed,  // burned, poll ended without quorum
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct DepositInfoResponse {
+    pub poll_id: u64,
+    pub creator: String,
+    pub amount: Uint128,
+    pub status: DepositStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct PollDescriptionResponse {
+    pub poll_id: u64,
+    pub description: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 pub struct PollsResponse {
     pub polls: Vec<PollResponse>,
+    pub next_cursor: Option<u64>, // start_after for the next page, None if last page
+    pub meta: ResponseMeta,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -68,8 +372,63 @@ pub struct StakerResponse {
     pub balance: Uint128,
     pub share: Uint128,
     pub locked_balance: Vec<(u64, VoteInfo)>,
+    pub locked_truncated: bool, // more in-progress locks than listed (MembersBatch)
     pub delegated_to: String,
     pub delegated_from: Vec<String>,
+    pub aliases: Vec<(String, String)>,
+    pub suspended_until: Option<u64>,
+    pub last_vote_height: Option<u64>,
+    pub effective_share: Uint128, // share counted if voting now (after inactivity decay)
+    pub wallet: Option<String>,
+    pub vote_key: Option<Binary>,
+    pub vote_nonce: u64, // nonce of the next relayed ballot
+    pub last_ended_poll: Option<EndedPoll>,
+    pub vested_locked: Uint128, // part of balance locked by MintLocked (0 once matured)
+    pub vested_until: Option<u64>, // release height of vested_locked (None: permanent)
+    pub meta: ResponseMeta,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct MembersResponse {
+    pub members: Vec<(String, Uint128, Uint128)>, // (member_id, balance, share)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct MembersBatchResponse {
+    pub members: Vec<(String, Option<StakerResponse>)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct FreeShareResponse {
+    pub share: Uint128,
+    pub locked_share: Uint128, // largest share voted in in-progress polls
+    pub free_share: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct ShareToMinBalanceResponse {
+    pub share: Uint128,
+    pub min_balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct WithdrawableResponse {
+    pub withdrawable_now: Uint128,            // at at_height if given
+    pub locked: Uint128,                      // balance held by the largest in-progress lock
+    pub unlock_schedule: Vec<(u64, Uint128)>, // (end_height, balance released) of later locks
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct DelegationStatusResponse {
+    pub delegate_to: Option<String>,
+    pub delegated_from: Vec<String>,
+    pub incoming_share: Uint128, // sum of shares of delegated_from
+    pub is_delegating: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct MemberByAliasResponse {
+    pub member_id: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -77,6 +436,7 @@ pub struct VotersResponseItem {
     pub voter: String,
     pub vote: VoteOption,
     pub balance: Uint128,
+    pub capped: bool, // counted balance was cut to max_vote_share
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -84,83 +444,567 @@ pub struct VotersResponse {
     pub voters: Vec<VotersResponseItem>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct TopDelegatesResponse {
+    pub delegates: Vec<(String, Uint128, Uint128)>, // (member_id, delegated_in_share, share)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct IndexConsistencyResponse {
+    pub mismatched: Vec<u64>, // polls missing from the indexer of their status or indexed under another
+    pub next_cursor: Option<u64>, // start_after for the next page, None if last page
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct IsBlockedResponse {
+    pub member_id: String,
+    pub blocked: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct ValidateHookResponse {
+    pub valid: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct IbcConfigResponse {
+    pub ibc_channel: Option<String>,
+    pub ibc_notify: bool,
+    pub ibc_receiver: Option<String>,
+    pub ibc_denom: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct VoterCountResponse {
+    pub poll_id: u64,
+    pub voter_count: u64,
+    pub capped: bool, // more voters than voter_count
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct VoteReceiptResponse {
+    pub poll_id: u64,
+    pub voter: String,
+    pub vote: VoteOption,
+    pub my_share: Uint128,
+    pub delegated_from: Vec<(String, Uint128)>, // votes cast on behalf of delegators
+    pub total_amount: Uint128,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct VoteInfo {
     pub vote: VoteOption,
     pub balance: Uint128,
+    pub via_delegate: Option<String>, // delegatee who cast this vote on behalf of the voter
+    #[serde(default)]
+    pub wallet: String, // wallet that sent the vote (delegatee's wallet for delegated votes)
+    #[serde(default)]
+    pub conviction_weight: Uint128, // extra weight from burned balance (not locked, not refunded)
+    #[serde(default)]
+    pub multiplier: Option<Decimal>, // token multiplier balance was weighted by (None: 1)
+    #[serde(default)]
+    pub capped_balance: Option<Uint128>, // weighted balance cut to max_vote_share (None: not capped)
 }
 
+impl VoteInfo {
+    /// balance weighted by token multiplier
+    pub fn weighted_balance(&self) -> Uint128 {
+        match self.multiplier {
+            Some(multiplier) => multiplier * self.balance,
+            None => self.balance,
+        }
+    }
+
+    /// weighted balance after max_vote_share cap
+    pub fn counted_balance(&self) -> Uint128 {
+        self.capped_balance
+            .unwrap_or_else(|| self.weighted_balance())
+    }
+
+    /// weight counted in poll tally
+    pub fn weight(&self) -> Uint128 {
+        self.counted_balance() + self.conviction_weight
+    }
+}
+
+/// why end_poll passed or rejected a poll
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum PollStatus {
-    InProgress,
+pub enum PollOutcome {
     Passed,
-    Rejected,
-    Executed,
-    Failed,
+    QuorumNotReached,
+    ThresholdNotReached,
+    Tied,
+    ForceEnded,
+    Abandoned, // nobody ended it, rejected by ReclaimDeposit
 }
 
-impl fmt::Display for PollStatus {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollKind {
+    Binary,                                  // yes / no
+    MultipleChoice { options: Vec<String> }, // winner is the option with most votes
+}
+
+impl Default for PollKind {
+    fn default() -> Self {
+        PollKind::Binary
     }
 }
 
+// schema version of StateTransition (bump on any change of its fields)
+pub const STATE_TRANSITION_VERSION: u8 = 1;
+
+/// poll change of an execute, emitted once per changed poll as compact json
+/// in the state_transition attribute for indexers
+/// from is None for created polls, to is None for removed polls
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum VoteOption {
-    Yes,
-    No,
+pub struct StateTransition {
+    pub v: u8,
+    pub poll_id: u64,
+    pub from: Option<PollStatus>,
+    pub to: Option<PollStatus>,
+    pub yes: Uint128,
+    pub no: Uint128,
+    pub height: u64,
 }
 
-impl fmt::Display for VoteOption {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if *self == VoteOption::Yes {
-            write!(f, "yes")
-        } else {
-            write!(f, "no")
-        }
+impl StateTransition {
+    pub fn attribute(&self) -> StdResult<Attribute> {
+        let value = String::from_utf8(to_vec(self)?).map_err(StdError::invalid_utf8)?;
+        Ok(attr("state_transition", value))
     }
 }
 
 const MIN_TITLE_LENGTH: usize = 4;
-const MAX_TITLE_LENGTH: usize = 64;
 const MIN_DESC_LENGTH: usize = 4;
-const MAX_DESC_LENGTH: usize = 1024;
 const MIN_LINK_LENGTH: usize = 12;
-const MAX_LINK_LENGTH: usize = 128;
+// absolute ceilings of configurable text limits
+const TITLE_MAX_CEILING: u32 = 128;
+// description is stored in chunks outside the Poll (MAX_DESCRIPTION_CHUNKS * DESCRIPTION_CHUNK_SIZE)
+const DESC_MAX_CEILING: u32 = 16384;
+// characters of poll summary
+pub const SUMMARY_MAX: usize = 280;
+const LINK_MAX_CEILING: u32 = 512;
+const MAX_LINK_LABEL_LENGTH: usize = 32;
+const MIN_LINKS_URL_LENGTH: usize = 12;
+const MAX_LINKS_URL_LENGTH: usize = 256;
+const MAX_LINKS: usize = 5;
+const MAX_POLL_OPTIONS: usize = 10;
+const MAX_POLL_OPTION_LENGTH: usize = 64;
+const LINK_SCHEMES: [&str; 3] = ["http://", "https://", "ipfs://"];
+const MAX_ALIAS_NAMESPACE_LENGTH: usize = 16;
+const MIN_ALIAS_ADDRESS_LENGTH: usize = 8;
+const MAX_ALIAS_ADDRESS_LENGTH: usize = 128;
 
-pub fn validate_title(title: &str) -> StdResult<()> {
-    if title.len() < MIN_TITLE_LENGTH {
-        Err(StdError::generic_err("Title too short"))
-    } else if title.len() > MAX_TITLE_LENGTH {
-        Err(StdError::generic_err("Title too long"))
+// quorum: 정족수
+// 0~1
+pub fn validate_quorum(quorum: Decimal) -> StdResult<()> {
+    if quorum > Decimal::one() {
+        Err(StdError::generic_err("quorum must be 0 to 1"))
+    } else {
+        Ok(())
+    }
+}
+
+// 0~1
+pub fn validate_threshold(threshold: Decimal) -> StdResult<()> {
+    if threshold > Decimal::one() {
+        Err(StdError::generic_err("threshold must be 0 to 1"))
     } else {
         Ok(())
     }
 }
 
+/// voting_period below this can't be set (guards against a mistaken 1 block period)
+pub const MIN_VOTING_PERIOD: u64 = 100;
+
+pub fn validate_voting_period(voting_period: u64) -> StdResult<()> {
+    if voting_period < MIN_VOTING_PERIOD {
+        Err(StdError::generic_err(format!(
+            "voting_period must be at least {} blocks",
+            MIN_VOTING_PERIOD
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// a season of 0 blocks would end as soon as it starts (and be in blackout all along)
+pub fn validate_season_length(season_length: u64) -> StdResult<()> {
+    if season_length == 0 {
+        Err(StdError::generic_err(
+            "season_length must be at least 1 block",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn validate_decay(decay_rate: Decimal, decay_floor: Decimal) -> StdResult<()> {
+    if decay_rate > Decimal::one() {
+        Err(StdError::generic_err("decay_rate must be 0 to 1"))
+    } else if decay_floor > Decimal::one() {
+        Err(StdError::generic_err("decay_floor must be 0 to 1"))
+    } else {
+        Ok(())
+    }
+}
+
+/// canonical message of a relayed ballot
+pub fn ballot_message(contract_addr: &str, ballot: &SignedBallot) -> String {
+    format!(
+        "dao-gov ballot|{}|{}|{}|{}|{}",
+        contract_addr, ballot.token_id, ballot.poll_id, ballot.vote, ballot.nonce
+    )
+}
+
+/// validate_vote_key returns an error if the key is not a secp256k1 public key
+pub fn validate_vote_key(pubkey: &Binary) -> StdResult<()> {
+    match pubkey.len() {
+        33 | 65 => Ok(()),
+        _ => Err(StdError::generic_err(
+            "Vote key must be 33 (compressed) or 65 (uncompressed) bytes",
+        )),
+    }
+}
+
+/// share counted for votes, decayed for inactivity
+/// after decay_after blocks since last vote, share decays by decay_rate
+/// per voting_period elapsed, but not below decay_floor of share
+pub fn effective_share(config: &Config, member: &TokenManager, current_height: u64) -> Uint128 {
+    let (decay_after, last_vote_height) = match (config.decay_after, member.last_vote_height) {
+        (Some(decay_after), Some(last_vote_height)) => (decay_after, last_vote_height),
+        _ => return member.share,
+    };
+    let inactive = current_height.saturating_sub(last_vote_height);
+    if inactive <= decay_after {
+        return member.share;
+    }
+    let periods = (inactive - decay_after) / config.voting_period.max(1);
+
+    // in atomics (1 = 10^18) to avoid overflow for long inactivity
+    let one = Decimal::one().numerator();
+    let max_decay = one.saturating_sub(config.decay_floor.numerator());
+    let decay = config
+        .decay_rate
+        .numerator()
+        .saturating_mul(periods as u128);
+    let factor = if decay >= max_decay {
+        config.decay_floor
+    } else {
+        Decimal::from_ratio(one - decay, one)
+    };
+    factor * member.share
+}
+
+/// extra vote weight for burning conviction balance: 2 * isqrt(conviction)
+pub fn conviction_weight(conviction: Uint128) -> Uint128 {
+    conviction.isqrt() * Uint128::from(2u128)
+}
+
+/// upper bound of conviction weight members can still add, 2 * isqrt(balance) each:
+/// at most twice the share except for DoubleQuadratic (at most twice the balance)
+pub fn max_conviction_weight(
+    voting_model: &VotingModel,
+    total_share: Uint128,
+    total_supply: Uint128,
+) -> Uint128 {
+    let bound = match voting_model {
+        VotingModel::Linear | VotingModel::Quadratic => total_share,
+        VotingModel::DoubleQuadratic => total_supply,
+    };
+    bound * Uint128::from(2u128)
+}
+
+/// balance released as locks expire, given (end_height, locked balance) of each lock
+/// only the largest lock holds balance, so a lock ending frees the part of it
+/// above the largest lock still running (nothing if a larger one outlives it)
+/// returns (end_height, released balance) by end_height, heights releasing nothing are left out
+pub fn unlock_schedule(locks: &[(u64, Uint128)]) -> Vec<(u64, Uint128)> {
+    let mut locks = locks.to_vec();
+    locks.sort_by_key(|(end_height, _)| *end_height);
+
+    // largest lock ending after index i
+    let mut outliving = vec![Uint128::zero(); locks.len() + 1];
+    for i in (0..locks.len()).rev() {
+        outliving[i] = outliving[i + 1].max(locks[i].1);
+    }
+
+    let mut schedule: Vec<(u64, Uint128)> = vec![];
+    let mut locked = outliving[0];
+    for (i, (end_height, _)) in locks.iter().enumerate() {
+        // locks ending at the same height are released together
+        if locks
+            .get(i + 1)
+            .map_or(false, |(next_end_height, _)| next_end_height == end_height)
+        {
+            continue;
+        }
+        let remaining = outliving[i + 1];
+        if remaining < locked {
+            schedule.push((*end_height, locked - remaining));
+            locked = remaining;
+        }
+    }
+    schedule
+}
+
+// fractional bits of sample_key
+const SAMPLE_KEY_FRACTION_BITS: u32 = 32;
+
+/// weighted draw without replacement over members streamed in pages (Efraimidis-Spirakis):
+/// member drawn with key -log2(u) / share, u uniform in (0, 1] by seed and member id,
+/// the smallest keys win, which draws as if picking one at a time proportionally to share
+/// deterministic from the seed; seed inputs known before the draw (or chosen by the
+/// block proposer) make it fair among members, not against validators
+/// returns -log2(u) with SAMPLE_KEY_FRACTION_BITS fractional bits
+pub fn sample_key(seed: &[u8], member_id: &str) -> Uint128 {
+    let mut hasher = Sha256::new();
+    hasher.update(seed);
+    hasher.update(member_id.as_bytes());
+    let digest = hasher.finalize();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    // u = x / 2^64 with x in [1, 2^64]
+    let x = u128::from(u64::from_be_bytes(bytes)) + 1;
+    Uint128::from((64u128 << SAMPLE_KEY_FRACTION_BITS) - log2_fixed(x))
+}
+
+/// log2(x) with SAMPLE_KEY_FRACTION_BITS fractional bits for 1 <= x <= 2^64
+/// (integer only, bit by bit from repeated squaring)
+fn log2_fixed(x: u128) -> u128 {
+    let int = 127 - x.leading_zeros();
+    // mantissa in [1, 2) with 63 fractional bits
+    let mut y = if int >= 63 {
+        x >> (int - 63)
+    } else {
+        x << (63 - int)
+    };
+    let mut result = u128::from(int) << SAMPLE_KEY_FRACTION_BITS;
+    for bit in (0..SAMPLE_KEY_FRACTION_BITS).rev() {
+        y = (y * y) >> 63;
+        if y >= 1 << 64 {
+            y >>= 1;
+            result |= 1 << bit;
+        }
+    }
+    result
+}
+
+/// add candidate to drawn (kept in draw order) if its key is among the size smallest
+pub fn keep_drawn(drawn: &mut Vec<DrawnMember>, candidate: DrawnMember, size: usize) {
+    // key / share compared by cross multiplication, ties by member id
+    let before = |a: &DrawnMember, b: &DrawnMember| {
+        let lhs = a.key.full_mul(b.share);
+        let rhs = b.key.full_mul(a.share);
+        lhs < rhs || (lhs == rhs && a.member_id < b.member_id)
+    };
+    let index = drawn
+        .iter()
+        .position(|entry| before(&candidate, entry))
+        .unwrap_or(drawn.len());
+    if index < size {
+        drawn.insert(index, candidate);
+        drawn.truncate(size);
+    }
+}
+
+/// compare yes / tallied with threshold by cross multiplication
+/// (yes * 10^18 vs threshold atomics * tallied) so exact boundaries are not rounded
+pub fn threshold_reached(
+    yes: Uint128,
+    tallied: Uint128,
+    threshold: Decimal,
+    inclusive: bool,
+) -> bool {
+    let lhs = yes.full_mul(Decimal::one().numerator());
+    let rhs = tallied.full_mul(threshold.numerator());
+    if inclusive {
+        lhs >= rhs
+    } else {
+        lhs > rhs
+    }
+}
+
+/// floor(amount * ratio) by the same cross multiplication (no Decimal rounding)
+pub fn share_cap(amount: Uint128, ratio: Decimal) -> Uint128 {
+    amount.multiply_ratio(ratio.numerator(), Decimal::one().numerator())
+}
+
+// 0~1 (zero would leave nothing to count)
+pub fn validate_max_vote_share(max_vote_share: Decimal) -> StdResult<()> {
+    if max_vote_share.is_zero() || max_vote_share > Decimal::one() {
+        Err(StdError::generic_err(
+            "max_vote_share must be above 0 and at most 1",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn validate_title(title: &str, limits: &TextLimits) -> Result<(), ContractError> {
+    if title.len() < MIN_TITLE_LENGTH {
+        Err(StdError::generic_err("Title too short").into())
+    } else {
+        validate_text_max("title", title, limits.title_max)
+    }
+}
+
 /// validate_description returns an error if the description is invalid
-pub fn validate_description(description: &str) -> StdResult<()> {
+pub fn validate_description(description: &str, limits: &TextLimits) -> Result<(), ContractError> {
     if description.len() < MIN_DESC_LENGTH {
-        Err(StdError::generic_err("Description too short"))
-    } else if description.len() > MAX_DESC_LENGTH {
-        Err(StdError::generic_err("Description too long"))
+        Err(StdError::generic_err("Description too short").into())
+    } else {
+        validate_text_max("description", description, limits.desc_max)
+    }
+}
+
+/// summary is limited to SUMMARY_MAX characters (not bytes)
+pub fn validate_summary(summary: &str) -> Result<(), ContractError> {
+    let got = summary.chars().count();
+    if got < MIN_DESC_LENGTH {
+        Err(StdError::generic_err("Summary too short").into())
+    } else if got > SUMMARY_MAX {
+        Err(ContractError::TextTooLong {
+            field: "summary".to_string(),
+            max: SUMMARY_MAX as u32,
+            got,
+        })
     } else {
         Ok(())
     }
 }
 
+/// summary of poll created with description only (and of legacy polls)
+pub fn summary_of(description: &str) -> String {
+    description.chars().take(SUMMARY_MAX).collect()
+}
+
 /// validate_link returns an error if the link is invalid
-pub fn validate_link(link: &Option<String>) -> StdResult<()> {
+pub fn validate_link(link: &Option<String>, limits: &TextLimits) -> Result<(), ContractError> {
     if let Some(link) = link {
         if link.len() < MIN_LINK_LENGTH {
-            Err(StdError::generic_err("Link too short"))
-        } else if link.len() > MAX_LINK_LENGTH {
-            Err(StdError::generic_err("Link too long"))
+            Err(StdError::generic_err("Link too short").into())
         } else {
-            Ok(())
+            validate_text_max("link", link, limits.link_max)
+        }
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_text_max(field: &str, text: &str, max: u32) -> Result<(), ContractError> {
+    if text.len() > max as usize {
+        Err(ContractError::TextTooLong {
+            field: field.to_string(),
+            max,
+            got: text.len(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// validate_text_limits returns an error if a limit is below the minimum length
+/// or above its absolute ceiling
+pub fn validate_text_limits(limits: &TextLimits) -> Result<(), ContractError> {
+    for (field, max, min, ceiling) in [
+        (
+            "title_max",
+            limits.title_max,
+            MIN_TITLE_LENGTH,
+            TITLE_MAX_CEILING,
+        ),
+        (
+            "desc_max",
+            limits.desc_max,
+            MIN_DESC_LENGTH,
+            DESC_MAX_CEILING,
+        ),
+        (
+            "link_max",
+            limits.link_max,
+            MIN_LINK_LENGTH,
+            LINK_MAX_CEILING,
+        ),
+    ] {
+        if (max as usize) < min || max > ceiling {
+            return Err(ContractError::InvalidTextLimit {
+                field: field.to_string(),
+                min: min as u32,
+                ceiling,
+                got: max,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// validate_links returns an error if any of the links is invalid
+pub fn validate_links(links: &[PollLink]) -> StdResult<()> {
+    if links.len() > MAX_LINKS {
+        return Err(StdError::generic_err("Too many links"));
+    }
+    for link in links.iter() {
+        if link.label.is_empty() {
+            return Err(StdError::generic_err("Link label too short"));
+        } else if link.label.len() > MAX_LINK_LABEL_LENGTH {
+            return Err(StdError::generic_err("Link label too long"));
+        } else if link.url.len() < MIN_LINKS_URL_LENGTH {
+            return Err(StdError::generic_err("Link too short"));
+        } else if link.url.len() > MAX_LINKS_URL_LENGTH {
+            return Err(StdError::generic_err("Link too long"));
+        } else if !LINK_SCHEMES
+            .iter()
+            .any(|scheme| link.url.starts_with(scheme))
+        {
+            return Err(StdError::generic_err("Link must be http, https or ipfs"));
         }
+    }
+    Ok(())
+}
+
+/// validate_poll_options returns an error if options of multiple choice poll are invalid
+pub fn validate_poll_options(options: &[String]) -> Result<(), ContractError> {
+    let invalid = options.len() < 2
+        || options.len() > MAX_POLL_OPTIONS
+        || options
+            .iter()
+            .any(|option| option.is_empty() || option.len() > MAX_POLL_OPTION_LENGTH)
+        || options
+            .iter()
+            .enumerate()
+            .any(|(i, option)| options[..i].contains(option));
+    if invalid {
+        return Err(ContractError::InvalidPollOptions(MAX_POLL_OPTIONS));
+    }
+    Ok(())
+}
+
+/// validate_ibc_channel returns an error if the channel id is not "channel-N"
+pub fn validate_ibc_channel(channel: &str) -> StdResult<()> {
+    match channel.strip_prefix("channel-") {
+        Some(n) if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => Ok(()),
+        _ => Err(StdError::generic_err("ibc_channel must be channel-N")),
+    }
+}
+
+/// validate_alias returns an error if the alias namespace or address is invalid
+pub fn validate_alias(namespace: &str, address: &str) -> StdResult<()> {
+    if namespace.is_empty() || namespace.len() > MAX_ALIAS_NAMESPACE_LENGTH {
+        Err(StdError::generic_err(
+            "Alias namespace must be 1 to 16 characters",
+        ))
+    } else if !namespace.chars().all(|c| c.is_ascii_lowercase()) {
+        Err(StdError::generic_err(
+            "Alias namespace must be lowercase ascii",
+        ))
+    } else if address.len() < MIN_ALIAS_ADDRESS_LENGTH {
+        Err(StdError::generic_err("Alias address too short"))
+    } else if address.len() > MAX_ALIAS_ADDRESS_LENGTH {
+        Err(StdError::generic_err("Alias address too long"))
     } else {
         Ok(())
     }
@@ -172,7 +1016,7 @@ pub fn validate_link(link: &Option<String>) -> StdResult<()> {
 /// * **querier** is the object of type [`QuerierWrapper`].
 ///
 /// * **contract_addr** is the object of type [`Addr`]. Sets the address of the contract for which
-/// the balance will be requested
+///   the balance will be requested
 ///
 /// * **account_addr** is the object of type [`Addr`].
 pub fn query_token_balance(
@@ -194,3 +1038,29 @@ pub fn query_token_balance(
 
     Ok(res.balance)
 }
+
+/// multiplier in the VotingExtension of token_id, capped at max_multiplier (at least one)
+/// missing or malformed extension (or failing query) counts as one
+pub fn query_token_multiplier(
+    querier: &QuerierWrapper,
+    cw721_addr: &str,
+    token_id: &str,
+    max_multiplier: Decimal,
+) -> Decimal {
+    let res: StdResult<NftInfoResponse<Option<VotingExtension>>> =
+        to_binary(&Cw721QueryMsg::NftInfo {
+            token_id: token_id.to_string(),
+        })
+        .and_then(|msg| {
+            querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: String::from(cw721_addr),
+                msg,
+            }))
+        });
+    let multiplier = res
+        .ok()
+        .and_then(|res| res.extension)
+        .and_then(|extension| extension.multiplier)
+        .unwrap_or_else(Decimal::one);
+    multiplier.min(max_multiplier.max(Decimal::one()))
+}